@@ -0,0 +1,155 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use futures::stream::StreamExt;
+use serde_json::json;
+use tokio::runtime::Runtime;
+
+use mediasoup::producer::ProducerId;
+use mediasoup::rtp_parameters::MediaKind;
+
+use vulcan_relay::relay_server::{ForeignRoomId, ForeignSessionId, RelayServer, SessionOptions};
+use vulcan_relay::session::Session;
+
+#[path = "../tests/fixture/mod.rs"]
+#[allow(dead_code)]
+mod fixture;
+
+/// Register a room with its Vulcast session.
+fn room(relay_server: &RelayServer, name: &str) -> (ForeignRoomId, Session) {
+    let foreign_room_id = ForeignRoomId(name.into());
+    let vulcast_session_id = ForeignSessionId(format!("{}-vulcast", name));
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    (foreign_room_id, vulcast)
+}
+
+fn session_map(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let relay_server = rt.block_on(fixture::relay_server());
+    let (foreign_room_id, _vulcast) = rt.block_on(async { room(&relay_server, "bench") });
+
+    let _guard = rt.enter();
+    let mut i = 0u64;
+    c.bench_function("register and unregister session", |b| {
+        b.iter(|| {
+            i += 1;
+            let fsid = ForeignSessionId(format!("webclient-{}", i));
+            let token = relay_server
+                .register_session(
+                    fsid.clone(),
+                    SessionOptions::WebClient(foreign_room_id.clone()),
+                )
+                .unwrap();
+            let session = relay_server.session_from_token(token).unwrap();
+            drop(session);
+            relay_server.unregister_session(fsid).unwrap();
+        })
+    });
+}
+
+fn token_lookup(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let relay_server = rt.block_on(fixture::relay_server());
+    let (foreign_room_id, _vulcast) = rt.block_on(async { room(&relay_server, "bench") });
+
+    let _guard = rt.enter();
+    let mut group = c.benchmark_group("session from token");
+    for sessions in [1, 100, 1000] {
+        let tokens = (0..sessions)
+            .map(|i| {
+                relay_server
+                    .register_session(
+                        ForeignSessionId(format!("webclient-{}-{}", sessions, i)),
+                        SessionOptions::WebClient(foreign_room_id.clone()),
+                    )
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let mut tokens = tokens.into_iter().cycle();
+        group.bench_with_input(BenchmarkId::from_parameter(sessions), &sessions, |b, _| {
+            b.iter(|| relay_server.session_from_token(tokens.next().unwrap()))
+        });
+    }
+    group.finish();
+}
+
+fn announcement_fan_out(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let relay_server = rt.block_on(fixture::relay_server());
+    let (_foreign_room_id, vulcast) = rt.block_on(async { room(&relay_server, "bench") });
+    let producer_id: ProducerId =
+        serde_json::from_value(json!("5b0f5c4e-8a57-4b0e-9b8a-2f1c3c7d9e01")).unwrap();
+
+    let mut group = c.benchmark_group("announcement fan-out");
+    for subscribers in [1, 10, 100] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(subscribers),
+            &subscribers,
+            |b, &subscribers| {
+                b.iter_batched(
+                    || {
+                        (0..subscribers)
+                            .map(|_| Box::pin(vulcast.get_room().closed_producers()))
+                            .collect::<Vec<_>>()
+                    },
+                    |mut streams| {
+                        rt.block_on(async {
+                            vulcast.get_room().announce_producer_closed(producer_id);
+                            for stream in &mut streams {
+                                stream.next().await.unwrap();
+                            }
+                        })
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+fn stats_aggregation(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let relay_server = rt.block_on(fixture::relay_server());
+    let vulcast = rt.block_on(async {
+        let (_foreign_room_id, vulcast) = room(&relay_server, "bench");
+        let send_transport = vulcast.create_webrtc_transport().await;
+        vulcast
+            .produce(
+                send_transport.id(),
+                MediaKind::Audio,
+                fixture::audio_producer_device_parameters(),
+            )
+            .await
+            .unwrap();
+        vulcast
+            .produce(
+                send_transport.id(),
+                MediaKind::Video,
+                fixture::video_producer_device_parameters(),
+            )
+            .await
+            .unwrap();
+        vulcast
+    });
+
+    c.bench_function("session stats", |b| {
+        b.iter(|| rt.block_on(vulcast.get_stats()).unwrap())
+    });
+}
+
+criterion_group!(
+    benches,
+    session_map,
+    token_lookup,
+    announcement_fan_out,
+    stats_aggregation
+);
+criterion_main!(benches);
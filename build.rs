@@ -1,4 +1,7 @@
 fn main() {
+    // set by developers enabling tokio-console instrumentation and runtime metrics
+    println!("cargo:rustc-check-cfg=cfg(tokio_unstable)");
+
     let mut opts = built::Options::default();
     opts.set_dependencies(true);
     opts.set_git(true);
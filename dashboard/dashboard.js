@@ -0,0 +1,125 @@
+// Operator dashboard, polling the control endpoint it is served from.
+"use strict";
+
+const REFRESH_INTERVAL_MS = 2000;
+
+async function query(source, variables = {}) {
+    const response = await fetch("/", {
+        method: "POST",
+        headers: { "content-type": "application/json" },
+        body: JSON.stringify({ query: source, variables }),
+    });
+    const { data, errors } = await response.json();
+    if (errors) {
+        throw new Error(errors.map((error) => error.message).join("; "));
+    }
+    return data;
+}
+
+function element(tag, properties = {}, children = []) {
+    const node = Object.assign(document.createElement(tag), properties);
+    node.append(...children);
+    return node;
+}
+
+function table(headers, rows) {
+    return element("table", {}, [
+        element("tr", {}, headers.map((header) => element("th", { textContent: header }))),
+        ...rows.map((row) =>
+            element("tr", {}, row.map((cell) =>
+                cell instanceof Node ? element("td", {}, [cell]) : element("td", { textContent: cell })))),
+    ]);
+}
+
+function button(label, confirmation, action) {
+    return element("button", {
+        textContent: label,
+        onclick: async () => {
+            if (confirm(confirmation)) {
+                await action().catch(showError);
+                await refresh();
+            }
+        },
+    });
+}
+
+function showError(error) {
+    document.getElementById("error").textContent = error ? error.message : "";
+}
+
+function kickSession(sessionId) {
+    return query(`mutation ($sessionId: ID!) {
+        unregisterSession(sessionId: $sessionId) { __typename }
+    }`, { sessionId });
+}
+
+function closeRoom(roomId) {
+    return query(`mutation ($roomId: ID!) {
+        unregisterRoom(roomId: $roomId) { __typename }
+    }`, { roomId });
+}
+
+function renderServerStats(stats) {
+    const rows = Object.entries(stats).map(([name, value]) => [name, value]);
+    document.getElementById("server-stats").replaceChildren(...table(["stat", "value"], rows).children);
+}
+
+async function renderRoom(room) {
+    // stats are not essential, so rooms are shown without them while unavailable
+    const { roomStats } = await query(`query ($roomId: ID!) {
+        roomStats(roomId: $roomId) {
+            recvBitrate sendBitrate packetsReceived packetsLost roundTripTimeP50 roundTripTimeP90
+        }
+    }`, { roomId: room.id }).catch(() => ({ roomStats: null }));
+
+    const sessions = table(["session", "role", "connected", ""], room.sessions.map((session) => [
+        session.id,
+        session.role,
+        session.connected ? "yes" : "no",
+        button("kick", `Kick session ${session.id}?`, () => kickSession(session.id)),
+    ]));
+    sessions.querySelectorAll("tr").forEach((row, i) => {
+        if (i > 0 && !room.sessions[i - 1].connected) {
+            row.classList.add("disconnected");
+        }
+    });
+    const stats = roomStats
+        ? table(Object.keys(roomStats), [Object.values(roomStats).map((value) => value ?? "-")])
+        : element("p", { textContent: "stats unavailable" });
+
+    return element("div", { className: "room" }, [
+        element("h3", { textContent: `${room.id} (vulcast ${room.vulcastSessionId}) ` }, [
+            button("close", `Close room ${room.id}?`, () => closeRoom(room.id)),
+        ]),
+        stats,
+        sessions,
+    ]);
+}
+
+async function refresh() {
+    try {
+        const { version, rooms } = await query(`{
+            version
+            rooms { id vulcastSessionId sessions { id role connected } }
+        }`);
+        document.getElementById("version").textContent = version;
+        const serverStats = await query(`{
+            serverStats {
+                rooms sessions producers consumers dataProducers dataConsumers
+                workerRouters workerMaxRssKib rtpBytesReceived rtpBytesSent
+            }
+        }`).then(({ serverStats }) => serverStats, () => null);
+        if (serverStats) {
+            renderServerStats(serverStats);
+        }
+        const roomNodes = await Promise.all(rooms.map(renderRoom));
+        document.getElementById("rooms").replaceChildren(
+            ...(roomNodes.length ? roomNodes : [element("p", { textContent: "no rooms registered" })]));
+        showError(null);
+    } catch (error) {
+        showError(error);
+    }
+}
+
+refresh();
+setInterval(refresh, REFRESH_INTERVAL_MS);
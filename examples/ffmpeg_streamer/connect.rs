@@ -0,0 +1,144 @@
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use futures::stream::{FuturesUnordered, StreamExt};
+use http::Uri;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Delay before racing the next address when connecting (RFC 8305).
+const CONNECTION_ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+/// Open a TCP stream to a host, through a proxy if given. Supported proxies are
+/// `http://host:port` (HTTP CONNECT) and `socks5://host:port`, without authentication.
+pub async fn connect(host: &str, port: u16, proxy: Option<&Uri>) -> anyhow::Result<TcpStream> {
+    let proxy = match proxy {
+        Some(proxy) => proxy,
+        None => return Ok(happy_eyeballs(host, port).await?),
+    };
+    let proxy_host = proxy.host().ok_or_else(|| anyhow!("proxy has no host"))?;
+    match proxy.scheme_str() {
+        Some("http") => {
+            let mut stream = happy_eyeballs(proxy_host, proxy.port_u16().unwrap_or(80)).await?;
+            http_connect(&mut stream, host, port).await?;
+            Ok(stream)
+        }
+        Some("socks5") | Some("socks5h") => {
+            let mut stream = happy_eyeballs(proxy_host, proxy.port_u16().unwrap_or(1080)).await?;
+            socks5_connect(&mut stream, host, port).await?;
+            Ok(stream)
+        }
+        scheme => bail!("unsupported proxy scheme {:?}", scheme),
+    }
+}
+
+/// Connect to the addresses of a host, alternating between IPv6 and IPv4 and starting
+/// the next attempt whenever the previous one fails or takes too long, so that a
+/// broken address family does not stall the connection.
+async fn happy_eyeballs(host: &str, port: u16) -> io::Result<TcpStream> {
+    // IPv6 literals are bracketed in URIs
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let (v6, v4): (Vec<SocketAddr>, Vec<SocketAddr>) = tokio::net::lookup_host((host, port))
+        .await?
+        .partition(|addr| addr.is_ipv6());
+    let mut addrs = Vec::new();
+    let (mut v6, mut v4) = (v6.into_iter(), v4.into_iter());
+    loop {
+        match (v6.next(), v4.next()) {
+            (None, None) => break,
+            (a, b) => addrs.extend(a.into_iter().chain(b)),
+        }
+    }
+
+    let mut addrs = addrs.into_iter();
+    let mut attempts = FuturesUnordered::new();
+    let mut last_err = io::Error::new(io::ErrorKind::NotFound, "host has no addresses");
+    loop {
+        if attempts.is_empty() {
+            match addrs.next() {
+                Some(addr) => attempts.push(attempt(addr)),
+                None => return Err(last_err),
+            }
+        }
+        tokio::select! {
+            Some(result) = attempts.next() => match result {
+                Ok(stream) => return Ok(stream),
+                Err(err) => last_err = err,
+            },
+            _ = tokio::time::sleep(CONNECTION_ATTEMPT_DELAY) => {
+                if let Some(addr) = addrs.next() {
+                    attempts.push(attempt(addr));
+                }
+            }
+        }
+    }
+}
+
+async fn attempt(addr: SocketAddr) -> io::Result<TcpStream> {
+    log::debug!("connecting to {}", addr);
+    let result = TcpStream::connect(addr).await;
+    if let Err(err) = &result {
+        log::debug!("failed to connect to {}: {}", addr, err);
+    }
+    result
+}
+
+async fn http_connect(stream: &mut TcpStream, host: &str, port: u16) -> anyhow::Result<()> {
+    let request = format!(
+        "CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n\r\n",
+        host = host,
+        port = port
+    );
+    stream.write_all(request.as_bytes()).await?;
+
+    // read the response head byte by byte, so no tunnelled bytes are consumed
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() > 8192 {
+            bail!("proxy response head is too long");
+        }
+        head.push(stream.read_u8().await?);
+    }
+    let head = String::from_utf8_lossy(&head);
+    let status = head.lines().next().unwrap_or_default();
+    if status.split_whitespace().nth(1) != Some("200") {
+        bail!("proxy refused to connect: {}", status);
+    }
+    Ok(())
+}
+
+async fn socks5_connect(stream: &mut TcpStream, host: &str, port: u16) -> anyhow::Result<()> {
+    // offer no authentication
+    stream.write_all(&[5, 1, 0]).await?;
+    let mut method = [0; 2];
+    stream.read_exact(&mut method).await?;
+    if method != [5, 0] {
+        bail!("socks5 proxy requires authentication");
+    }
+
+    // connect by domain name, so the proxy resolves the host
+    let host = host.as_bytes();
+    let host_len = u8::try_from(host.len()).map_err(|_| anyhow!("host name is too long"))?;
+    let mut request = vec![5, 1, 0, 3, host_len];
+    request.extend_from_slice(host);
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+
+    let mut reply = [0; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[1] != 0 {
+        bail!("socks5 proxy refused to connect (reply {})", reply[1]);
+    }
+    // skip the bound address
+    let addr_len = match reply[3] {
+        1 => 4,
+        3 => stream.read_u8().await? as usize,
+        4 => 16,
+        atyp => bail!("socks5 proxy replied with unknown address type {}", atyp),
+    };
+    let mut bound = vec![0; addr_len + 2];
+    stream.read_exact(&mut bound).await?;
+    Ok(())
+}
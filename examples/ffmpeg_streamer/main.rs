@@ -11,11 +11,11 @@ use mediasoup::rtp_parameters::{
     RtpCodecParametersParameters, RtpEncodingParameters, RtpParameters,
 };
 use serde::Serialize;
-use tokio::net::TcpStream;
 use tokio_tungstenite::Connector;
 
 use graphql_ws::GraphQLWebSocket;
 
+mod connect;
 mod signal_schema;
 
 #[derive(Serialize)]
@@ -34,6 +34,9 @@ pub struct Opts {
     /// Disable TLS.
     #[clap(long)]
     pub no_tls: bool,
+    /// Proxy to connect through, e.g. `http://proxy:3128` or `socks5://proxy:1080`.
+    #[clap(long)]
+    pub proxy: Option<Uri>,
     /// File to stream, copied verbatim without re-encoding.
     #[clap(long)]
     pub file: String,
@@ -72,7 +75,7 @@ async fn main() -> Result<(), anyhow::Error> {
 
     let host = uri.host().unwrap();
     let port = uri.port_u16().unwrap();
-    let stream = TcpStream::connect((host, port)).await?;
+    let stream = connect::connect(host, port, opts.proxy.as_ref()).await?;
 
     let req = http::Request::builder()
         .uri(uri)
@@ -307,7 +307,13 @@ async function session(role: Role, token: string) {
                 return (await client.mutate({
                     mutation: gql`
                 mutation {
-                    createWebrtcTransport
+                    createWebrtcTransport {
+                        id
+                        dtlsParameters
+                        sctpParameters
+                        iceParameters
+                        iceCandidates { foundation priority ip protocol port type tcpType }
+                    }
                 }
                 `
                 })).data.createWebrtcTransport;
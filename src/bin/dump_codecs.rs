@@ -0,0 +1,8 @@
+use vulcan_relay::codecs;
+
+fn main() {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&codecs::default_codecs()).unwrap()
+    );
+}
@@ -0,0 +1,5 @@
+use vulcan_relay::public_schema::PublicSchema;
+
+fn main() {
+    println!("{}", &PublicSchema::default().sdl());
+}
@@ -0,0 +1,88 @@
+use serde::Serialize;
+
+use crate::relay_server::{RelayServer, Role};
+use crate::resource_policy::ResourcePolicy;
+use crate::session::ResourceType;
+
+/// Highest sustainable CPU usage of the worker, as a fraction of the single core it
+/// runs on, leaving a margin for bursts (e.g. keyframe requests as viewers join).
+pub const MAX_WORKER_CPU_USAGE: f64 = 0.8;
+
+/// Theoretical and current headroom of a relay, for placing rooms across relays.
+/// Counts are taken from the relay's own bookkeeping, so reporting capacity makes no
+/// requests to the worker and is available while the worker is overloaded.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Capacity {
+    /// Ports the worker allocates to transports, if known.
+    pub rtc_ports: Option<usize>,
+    /// Ports not yet allocated to transports, if known. Each transport holds one port
+    /// per protocol, from a separate pool per protocol.
+    pub rtc_ports_remaining: Option<usize>,
+    /// CPU usage of the worker, as a fraction of one core, if sampled.
+    pub worker_cpu_usage: Option<f64>,
+    /// CPU usage the worker may grow by before reaching [`MAX_WORKER_CPU_USAGE`],
+    /// if sampled.
+    pub worker_cpu_headroom: Option<f64>,
+    /// Cap on the incoming bitrate of each transport, if any.
+    pub max_incoming_bitrate: Option<u32>,
+    /// Consumers a web client may create, unless overridden in its room.
+    pub max_consumers_per_web_client: usize,
+    pub sessions: usize,
+    pub transports: usize,
+    pub producers: usize,
+    pub consumers: usize,
+    /// Estimate of the additional consumers the relay supports, limited by whichever of
+    /// CPU and ports runs out first, if either is known. CPU usage is assumed to scale
+    /// linearly with consumers, and each web client to hold one port for up to the
+    /// maximum consumers per web client.
+    pub additional_consumers: Option<usize>,
+}
+
+/// Report the capacity of a relay.
+pub fn report(relay_server: &RelayServer, resource_policy: &ResourcePolicy) -> Capacity {
+    let mut capacity = Capacity {
+        rtc_ports: relay_server
+            .rtc_ports_range()
+            .map(|rtc_ports_range| rtc_ports_range.count()),
+        worker_cpu_usage: relay_server.worker_monitor().cpu_usage(),
+        max_incoming_bitrate: relay_server.max_incoming_bitrate(),
+        max_consumers_per_web_client: resource_policy.limit(
+            Role::WebClient,
+            None,
+            ResourceType::Consumer,
+        ),
+        ..Capacity::default()
+    };
+    for room in relay_server.get_rooms() {
+        for session in room.active_sessions() {
+            capacity.sessions += 1;
+            capacity.transports +=
+                session.get_webrtc_transports().len() + session.get_plain_transports().len();
+            capacity.producers += session.get_producers().len();
+            capacity.consumers += session.get_consumers().len();
+        }
+    }
+    capacity.rtc_ports_remaining = capacity
+        .rtc_ports
+        .map(|rtc_ports| rtc_ports.saturating_sub(capacity.transports));
+    capacity.worker_cpu_headroom = capacity
+        .worker_cpu_usage
+        .map(|cpu_usage| (MAX_WORKER_CPU_USAGE - cpu_usage).max(0.0));
+
+    // consumers cannot be extrapolated from CPU usage until some exist
+    let cpu_bound = match (capacity.worker_cpu_usage, capacity.worker_cpu_headroom) {
+        (Some(cpu_usage), Some(cpu_headroom)) if cpu_usage > 0.0 && capacity.consumers > 0 => {
+            Some((capacity.consumers as f64 * cpu_headroom / cpu_usage) as usize)
+        }
+        _ => None,
+    };
+    let port_bound = capacity
+        .rtc_ports_remaining
+        .map(|rtc_ports_remaining| rtc_ports_remaining * capacity.max_consumers_per_web_client);
+    capacity.additional_consumers = match (cpu_bound, port_bound) {
+        (Some(cpu_bound), Some(port_bound)) => Some(cpu_bound.min(port_bound)),
+        (cpu_bound, port_bound) => cpu_bound.or(port_bound),
+    };
+    capacity
+}
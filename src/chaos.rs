@@ -0,0 +1,122 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_graphql::extensions::{Extension, ExtensionContext, ExtensionFactory, NextExecute};
+use async_graphql::Response;
+use ring::rand::{SecureRandom, SystemRandom};
+use thiserror::Error;
+
+/// Failures injected into the relay for chaos testing, e.g. of client retry logic.
+/// Failures are only injected in debug builds, so that release builds never fail
+/// on purpose.
+#[derive(Debug, Clone, Default)]
+pub struct Chaos {
+    shared: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// percentage of transport creations which fail
+    transport_failure_percent: u8,
+    /// delay before each signal query or mutation is executed, if any
+    resolver_delay: Option<Duration>,
+}
+
+impl Chaos {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail the given percentage of transport creations.
+    pub fn set_transport_failure_percent(&self, percent: u8) -> Result<(), ChaosError> {
+        check_enabled()?;
+        if percent > 100 {
+            return Err(ChaosError::InvalidPercent(percent));
+        }
+        log::warn!(
+            "injecting failures into {}% of transport creations",
+            percent
+        );
+        let mut state = self.shared.lock().unwrap();
+        state.transport_failure_percent = percent;
+        Ok(())
+    }
+
+    /// Delay each signal query and mutation by the given time.
+    pub fn set_resolver_delay(&self, delay: Option<Duration>) -> Result<(), ChaosError> {
+        check_enabled()?;
+        log::warn!("injecting signal resolver delay {:?}", delay);
+        let mut state = self.shared.lock().unwrap();
+        state.resolver_delay = delay;
+        Ok(())
+    }
+
+    /// Fail a transport creation at random, at the configured rate.
+    pub fn check_transport_creation(&self) -> Result<(), InjectedFailure> {
+        let percent = self.shared.lock().unwrap().transport_failure_percent;
+        if percent == 0 {
+            return Ok(());
+        }
+        let mut draw = [0u8; 2];
+        SystemRandom::new()
+            .fill(&mut draw)
+            .expect("system randomness is unavailable");
+        if u16::from_le_bytes(draw) % 100 < percent as u16 {
+            log::debug!("injected transport creation failure");
+            Err(InjectedFailure("transport creation"))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn transport_failure_percent(&self) -> u8 {
+        self.shared.lock().unwrap().transport_failure_percent
+    }
+    pub fn resolver_delay(&self) -> Option<Duration> {
+        self.shared.lock().unwrap().resolver_delay
+    }
+}
+
+fn check_enabled() -> Result<(), ChaosError> {
+    if cfg!(debug_assertions) {
+        Ok(())
+    } else {
+        Err(ChaosError::Disabled)
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ChaosError {
+    #[error("failure injection is only available in debug builds")]
+    Disabled,
+    #[error("invalid failure percentage {0}")]
+    InvalidPercent(u8),
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("injected failure of {0}")]
+pub struct InjectedFailure(&'static str);
+
+/// GraphQL extension delaying the execution of each operation by the resolver delay.
+pub struct ResolverDelay(pub Chaos);
+impl ExtensionFactory for ResolverDelay {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(ResolverDelayExtension(self.0.clone()))
+    }
+}
+
+struct ResolverDelayExtension(Chaos);
+#[async_trait::async_trait]
+impl Extension for ResolverDelayExtension {
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        if let Some(delay) = self.0.resolver_delay() {
+            tokio::time::sleep(delay).await;
+        }
+        next.run(ctx, operation_name).await
+    }
+}
@@ -1,7 +1,16 @@
+use std::num::NonZeroU64;
 use std::str::FromStr;
 
 use clap::Parser;
 
+use crate::codecs::{CodecFilter, OpusConfig};
+use crate::permissions::Permission;
+use crate::relay_server::{DuplicateConnectionPolicy, Role};
+use crate::room::RoomPreset;
+use crate::session::{MidStrategy, ResourceType};
+use crate::stream_policy::StreamRole;
+use crate::version_gate::ClientVersion;
+
 #[derive(Parser, Clone)]
 #[clap(about, version, author)]
 pub struct Opts {
@@ -21,6 +30,12 @@ pub struct Opts {
     #[clap(long, default_value = "127.0.0.1:9443")]
     pub control_addr: String,
 
+    /// Listen address for the public control endpoint, which serves a read-only subset
+    /// of the control schema (e.g. public rooms) without authentication. Not served if
+    /// unset.
+    #[clap(long)]
+    pub public_control_addr: Option<String>,
+
     /// Listen address for RTC protocols.
     #[clap(long, default_value = "127.0.0.1")]
     pub rtc_ip: String,
@@ -29,6 +44,11 @@ pub struct Opts {
     #[clap(long)]
     pub rtc_announce_ip: Option<String>,
 
+    /// Skip probing at startup whether the announce address routes back to the relay,
+    /// e.g. behind NATs without hairpinning. The relay exits if the probe fails.
+    #[clap(long)]
+    pub skip_announce_check: bool,
+
     /// Disable TLS for all endpoints.
     #[clap(long, conflicts_with_all(&["cert-path", "key-path"]))]
     pub no_tls: bool,
@@ -37,6 +57,14 @@ pub struct Opts {
     #[clap(long)]
     pub no_cors: bool,
 
+    /// Serve GraphQL playgrounds for the control schema on the control endpoint and for
+    /// the signal schema on the signal endpoint, for development. The control endpoint
+    /// has no authentication of its own, so its playground is exactly as exposed as the
+    /// endpoint, which listens on localhost by default. Signal playground requests are
+    /// admitted as signal connections, on behalf of the presented session token.
+    #[clap(long)]
+    pub enable_playground: bool,
+
     /// Enable specific log tags for mediasoup.
     #[clap(short, long, possible_values(&["info", "ice", "dtls", "rtp", "srtp",
         "rtcp", "rtx", "bwe", "score", "simulcast", "svc", "sctp", "message"]))]
@@ -49,6 +77,295 @@ pub struct Opts {
     /// RTC ports range maximum.
     #[clap(long, default_value = "59999")]
     pub rtc_ports_range_max: u16,
+
+    /// Path to a JSON file listing the media codecs supported by the relay, replacing
+    /// the built-in codecs. Print the built-in codecs with `dump_codecs`.
+    #[clap(long)]
+    pub codecs_file: Option<String>,
+
+    /// Remove H264 from the supported video codecs.
+    #[clap(long)]
+    pub disable_h264: bool,
+
+    /// Remove VP8 from the supported video codecs.
+    #[clap(long)]
+    pub disable_vp8: bool,
+
+    /// Only support VP8 for video, e.g. for clients without H264 decode.
+    #[clap(long, conflicts_with_all(&["disable-vp8", "force-h264-only"]))]
+    pub force_vp8_only: bool,
+
+    /// Only support H264 for video, e.g. for clients with H264 hardware decode.
+    #[clap(long, conflicts_with("disable-h264"))]
+    pub force_h264_only: bool,
+
+    /// Negotiate stereo Opus audio.
+    #[clap(long)]
+    pub opus_stereo: bool,
+
+    /// Disable Opus inband forward error correction, which is enabled by default.
+    #[clap(long)]
+    pub opus_disable_fec: bool,
+
+    /// Enable Opus discontinuous transmission, saving bandwidth during silence.
+    #[clap(long)]
+    pub opus_dtx: bool,
+
+    /// Maximum average Opus bitrate in bps (6000-510000).
+    #[clap(long)]
+    pub opus_max_average_bitrate: Option<u32>,
+
+    /// Interval in seconds between audits of Mediasoup objects for leaks.
+    /// Auditing is disabled if unspecified.
+    #[clap(long)]
+    pub leak_audit_interval: Option<NonZeroU64>,
+
+    /// Unregister rooms which have had no connected sessions for the given number of
+    /// minutes. Rooms are never collected if unspecified.
+    #[clap(long)]
+    pub idle_room_timeout: Option<u64>,
+
+    /// Log an hourly report of the objects and memory held by the relay, to verify
+    /// that long-running relays do not leak.
+    #[clap(long)]
+    pub soak_report: bool,
+
+    /// Measure the latency of media from producers to consumers, reported by the
+    /// `latencyReport` control query. Traces every RTP packet, so use for
+    /// performance testing only.
+    #[clap(long)]
+    pub latency_probe: bool,
+
+    /// Latency in milliseconds of requests to the Mediasoup worker above which
+    /// non-essential requests (stats, leak audits) are shed. Never shed if unspecified.
+    #[clap(long)]
+    pub worker_latency_threshold: Option<u64>,
+
+    /// Override a resource limit for sessions, specified as `[role.]resource=limit`
+    /// (e.g. `webclient.consumer=4`). May be specified multiple times.
+    #[clap(long)]
+    pub resource_limit: Vec<ResourceLimitArg>,
+
+    /// Limit the connected duration of web clients in rooms of a preset, specified as
+    /// `preset=minutes` (e.g. `standard=60`), after which they are unregistered.
+    /// Presets are `standard` and `audio-only`. May be specified multiple times.
+    #[clap(long)]
+    pub max_session_duration: Vec<MaxSessionDurationArg>,
+
+    /// Reject clients of a role which declare a lower `clientVersion` on connect (or
+    /// none), specified as `role=version` (e.g. `vulcast=1.4.0`). Roles are `vulcast`,
+    /// `webclient` and `host`. May be specified multiple times.
+    #[clap(long)]
+    pub min_client_version: Vec<MinClientVersionArg>,
+
+    /// Permit clients of a role to perform classes of signal operation, specified as
+    /// `role=permissions` (e.g. `webclient=produce-media`). Permissions are
+    /// `produce-media`, `produce-data`, `consume-media`, `consume-data`, `moderate`,
+    /// `control`, `send-commands` and `receive-commands`.
+    /// May be specified multiple times.
+    #[clap(long)]
+    pub grant: Vec<PermissionsArg>,
+
+    /// Forbid clients of a role from performing classes of signal operation, specified
+    /// as `role=permissions` (e.g. `host=moderate`). May be specified multiple times.
+    #[clap(long)]
+    pub revoke: Vec<PermissionsArg>,
+
+    /// Restrict the roles of stream which clients of a role may produce, specified as
+    /// `role=streams` (e.g. `webclient=camera,voice`). Producers must then declare one
+    /// of the roles of stream. Roles of stream are `game`, `camera`, `voice` and
+    /// `screen`. May be specified multiple times.
+    #[clap(long)]
+    pub produce_streams: Vec<StreamRolesArg>,
+
+    /// Restrict the roles of stream announced to clients of a role for auto-consumption,
+    /// specified as `role=streams` (e.g. `webclient=game`). Other producers may still be
+    /// consumed explicitly. May be specified multiple times.
+    #[clap(long)]
+    pub auto_consume_streams: Vec<StreamRolesArg>,
+
+    /// Enable SRT ingest for Vulcasts, listening on the RTC IP.
+    #[clap(long)]
+    pub srt_ingest: bool,
+
+    /// SRT ingest ports range minimum.
+    #[clap(long, default_value = "9000")]
+    pub srt_ports_range_min: u16,
+
+    /// SRT ingest ports range maximum.
+    #[clap(long, default_value = "9099")]
+    pub srt_ports_range_max: u16,
+
+    /// Path to FFmpeg binary, used to demux SRT ingest.
+    #[clap(long, default_value = "ffmpeg")]
+    pub ffmpeg_path: String,
+
+    /// Enable SRTP on plain transports by default.
+    #[clap(long)]
+    pub plain_transport_srtp: bool,
+
+    /// Cap the incoming bitrate (bps) of each transport, e.g. so that a single Vulcast
+    /// cannot saturate the uplink of the relay. Sessions may only lower their cap.
+    #[clap(long)]
+    pub max_incoming_bitrate: Option<u32>,
+
+    /// Default MID assignment of consumers, either `sequential` (assigned by the
+    /// transport) or `producer-id` (the ID of the consumed producer).
+    #[clap(long, default_value = "sequential")]
+    pub consumer_mid_strategy: MidStrategyArg,
+
+    /// Disable retransmission over RTX for consumers by default.
+    #[clap(long)]
+    pub consumer_disable_rtx: bool,
+
+    /// Forward all simulcast/SVC layers to consumers by default.
+    #[clap(long)]
+    pub consumer_pipe: bool,
+
+    /// Drop DTX packets of audio sent to consumers by default.
+    #[clap(long)]
+    pub consumer_ignore_dtx: bool,
+
+    /// ICE server URL of a TURN server sharing a secret with the relay (e.g. coturn with
+    /// `use-auth-secret`), offered to sessions with minted credentials. May be specified
+    /// multiple times.
+    #[clap(long, requires("turn-secret"))]
+    pub turn_url: Vec<String>,
+
+    /// Secret shared with the TURN server (`static-auth-secret` in coturn).
+    #[clap(long, requires("turn-url"))]
+    pub turn_secret: Option<String>,
+
+    /// Lifetime in seconds of minted TURN credentials.
+    #[clap(long, default_value = "86400")]
+    pub turn_ttl: u64,
+
+    /// Only permit SRTP crypto suites approved for constrained (e.g. FIPS) deployments.
+    /// Requires TLS, and enables SRTP on plain transports. TLS versions and ciphers
+    /// are not restricted.
+    #[clap(long)]
+    pub constrained_crypto: bool,
+
+    /// Relay data (e.g. controller input) through the server rather than directly between
+    /// sessions, limiting each data producer to the given messages per second.
+    #[clap(long)]
+    pub data_rate_limit: Option<u32>,
+
+    /// Relay input from web clients through the server, dropping duplicated and replayed
+    /// messages. Each message must be a JSON object with an increasing `seq` number,
+    /// at most the given window ahead of the previous message.
+    #[clap(long)]
+    pub input_sequence_window: Option<u64>,
+
+    /// Buffer commands to disconnected Vulcasts, and room events of disconnected
+    /// sessions, for the given number of seconds, replaying them when the session
+    /// reconnects.
+    #[clap(long)]
+    pub message_ttl: Option<u64>,
+
+    /// Reuse stats snapshots of each session for the given number of milliseconds, so
+    /// that back-to-back stats queries do not each fan out requests to the worker.
+    #[clap(long)]
+    pub stats_cache_ms: Option<u64>,
+
+    /// Keep sessions alive for the given number of milliseconds once the signal
+    /// connection of their client closes, so that clients reconnecting with the same
+    /// token resume their sessions instead of renegotiating.
+    #[clap(long)]
+    pub reconnect_grace_ms: Option<u64>,
+
+    /// Handling of connections with the token of an already connected session, either
+    /// `evict-old` (replace the existing session), `reject-new`, or `allow-parallel=N`
+    /// (up to N connections per token, each with its own session).
+    #[clap(long, default_value = "evict-old")]
+    pub duplicate_connections: DuplicateConnectionPolicyArg,
+
+    /// Close transports which have had no producers, consumers or traffic for the given
+    /// number of seconds, e.g. after abandoned negotiation attempts.
+    #[clap(long)]
+    pub idle_transport_timeout: Option<u64>,
+
+    /// Admit sessions to each room at most the given number of times per second, e.g. to
+    /// protect a popular room from a thundering herd. Joins beyond the rate are queued.
+    #[clap(long)]
+    pub max_joins_per_second: Option<u32>,
+
+    /// Longest time in milliseconds a join may be queued for before the client is asked
+    /// to retry later.
+    #[clap(long, default_value = "2000")]
+    pub max_join_queue_ms: u64,
+
+    /// Register at most the given number of rooms per Vulcast within the room churn
+    /// window, e.g. to keep buggy firmware from thrashing routers by rapidly
+    /// registering and unregistering rooms.
+    #[clap(long)]
+    pub max_rooms_per_vulcast: Option<u32>,
+
+    /// Length in minutes of the sliding window in which rooms registered per Vulcast
+    /// are limited.
+    #[clap(long, default_value = "60")]
+    pub room_churn_window_minutes: u64,
+
+    /// Serve at most the given number of control operations at once. Operations beyond
+    /// the limit are queued, with registrations served before stats queries.
+    #[clap(long)]
+    pub max_concurrent_control_ops: Option<usize>,
+
+    /// Largest GraphQL request body or websocket message accepted, in bytes.
+    #[clap(long, default_value = "1048576")]
+    pub max_request_bytes: usize,
+
+    /// Largest GraphQL variable accepted, in bytes of serialized JSON, e.g. to reject
+    /// oversized RTP parameters before they are deserialized.
+    #[clap(long, default_value = "65536")]
+    pub max_variable_bytes: usize,
+
+    /// Fail the given percentage of transport creations, for chaos testing of client
+    /// retry logic. Only honoured in debug builds.
+    #[clap(long)]
+    pub chaos_transport_failure_percent: Option<u8>,
+
+    /// Delay each signal query and mutation by the given number of milliseconds, for chaos
+    /// testing.
+    /// Only honoured in debug builds.
+    #[clap(long)]
+    pub chaos_resolver_delay_ms: Option<u64>,
+
+    /// Reject requests to deprecated GraphQL fields, e.g. to check that clients have
+    /// migrated before the fields are removed.
+    #[clap(long)]
+    pub no_deprecated_fields: bool,
+}
+
+impl Opts {
+    /// Get the video codecs permitted by the codec flags.
+    pub fn codec_filter(&self) -> CodecFilter {
+        use mediasoup::rtp_parameters::MimeTypeVideo;
+        let mut filter = CodecFilter::default();
+        if self.disable_h264 {
+            filter.disabled.push(MimeTypeVideo::H264);
+        }
+        if self.disable_vp8 {
+            filter.disabled.push(MimeTypeVideo::Vp8);
+        }
+        if self.force_vp8_only {
+            filter.only = Some(MimeTypeVideo::Vp8);
+        }
+        if self.force_h264_only {
+            filter.only = Some(MimeTypeVideo::H264);
+        }
+        filter
+    }
+
+    /// Get the Opus parameters set by the Opus flags.
+    pub fn opus_config(&self) -> OpusConfig {
+        OpusConfig {
+            stereo: self.opus_stereo.then_some(true),
+            inband_fec: self.opus_disable_fec.then_some(false),
+            dtx: self.opus_dtx.then_some(true),
+            max_average_bitrate: self.opus_max_average_bitrate,
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -77,3 +394,172 @@ impl FromStr for WorkerLogTag {
         }
     }
 }
+
+#[derive(Clone, Copy)]
+pub struct MidStrategyArg(pub MidStrategy);
+
+impl FromStr for MidStrategyArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sequential" => Ok(Self(MidStrategy::Sequential)),
+            "producer-id" => Ok(Self(MidStrategy::ProducerId)),
+            _ => Err(s.to_owned()),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct ResourceLimitArg {
+    pub role: Option<Role>,
+    pub resource: ResourceType,
+    pub limit: usize,
+}
+
+impl FromStr for ResourceLimitArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (scope, limit) = s.split_once('=').ok_or_else(|| s.to_owned())?;
+        let (role, resource) = match scope.split_once('.') {
+            Some((role, resource)) => (Some(role), resource),
+            None => (None, scope),
+        };
+        let role = match role {
+            Some("vulcast") => Some(Role::Vulcast),
+            Some("webclient") => Some(Role::WebClient),
+            Some("host") => Some(Role::Host),
+            Some(role) => return Err(role.to_owned()),
+            None => None,
+        };
+        let resource = match resource {
+            "consumer" => ResourceType::Consumer,
+            "producer" => ResourceType::Producer,
+            "data-consumer" => ResourceType::DataConsumer,
+            "data-producer" => ResourceType::DataProducer,
+            "webrtc-transport" => ResourceType::WebrtcTransport,
+            "plain-transport" => ResourceType::PlainTransport,
+            _ => return Err(resource.to_owned()),
+        };
+        let limit = limit.parse().map_err(|_| limit.to_owned())?;
+        Ok(Self {
+            role,
+            resource,
+            limit,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct MaxSessionDurationArg {
+    pub preset: RoomPreset,
+    pub minutes: u64,
+}
+
+impl FromStr for MaxSessionDurationArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (preset, minutes) = s.split_once('=').ok_or_else(|| s.to_owned())?;
+        let preset = match preset {
+            "standard" => RoomPreset::Standard,
+            "audio-only" => RoomPreset::AudioOnly,
+            _ => return Err(preset.to_owned()),
+        };
+        let minutes = minutes.parse().map_err(|_| minutes.to_owned())?;
+        Ok(Self { preset, minutes })
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct DuplicateConnectionPolicyArg(pub DuplicateConnectionPolicy);
+
+impl FromStr for DuplicateConnectionPolicyArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            None if s == "reject-new" => Ok(Self(DuplicateConnectionPolicy::RejectNew)),
+            None if s == "evict-old" => Ok(Self(DuplicateConnectionPolicy::EvictOld)),
+            Some(("allow-parallel", max_connections)) => match max_connections.parse() {
+                Ok(max_connections) if max_connections > 0 => Ok(Self(
+                    DuplicateConnectionPolicy::AllowParallel(max_connections),
+                )),
+                _ => Err(max_connections.to_owned()),
+            },
+            _ => Err(s.to_owned()),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MinClientVersionArg {
+    pub role: Role,
+    pub min_version: ClientVersion,
+}
+
+impl FromStr for MinClientVersionArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (role, min_version) = s.split_once('=').ok_or_else(|| s.to_owned())?;
+        Ok(Self {
+            role: parse_role(role)?,
+            min_version: min_version.parse()?,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct StreamRolesArg {
+    pub role: Role,
+    pub stream_roles: Vec<StreamRole>,
+}
+
+impl FromStr for StreamRolesArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (role, stream_roles) = s.split_once('=').ok_or_else(|| s.to_owned())?;
+        Ok(Self {
+            role: parse_role(role)?,
+            stream_roles: stream_roles
+                .split(',')
+                .filter(|stream_role| !stream_role.is_empty())
+                .map(StreamRole::from_str)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct PermissionsArg {
+    pub role: Role,
+    pub permissions: Vec<Permission>,
+}
+
+impl FromStr for PermissionsArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (role, permissions) = s.split_once('=').ok_or_else(|| s.to_owned())?;
+        Ok(Self {
+            role: parse_role(role)?,
+            permissions: permissions
+                .split(',')
+                .filter(|permission| !permission.is_empty())
+                .map(Permission::from_str)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+fn parse_role(role: &str) -> Result<Role, String> {
+    match role {
+        "vulcast" => Ok(Role::Vulcast),
+        "webclient" => Ok(Role::WebClient),
+        "host" => Ok(Role::Host),
+        _ => Err(role.to_owned()),
+    }
+}
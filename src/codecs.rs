@@ -0,0 +1,236 @@
+use std::collections::HashSet;
+use std::num::{NonZeroU32, NonZeroU8};
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use mediasoup::rtp_parameters::{
+    MimeTypeAudio, MimeTypeVideo, RtcpFeedback, RtpCodecCapability, RtpCodecParametersParameters,
+};
+use thiserror::Error;
+
+/// Load media codecs from a JSON file containing a list of RTP codec capabilities,
+/// in the format printed by `dump_codecs`.
+pub fn load(path: &Path) -> Result<Vec<RtpCodecCapability>, CodecConfigError> {
+    let codecs = std::fs::read_to_string(path)?;
+    let codecs = serde_json::from_str(&codecs)?;
+    validate(&codecs)?;
+    Ok(codecs)
+}
+
+/// Check that media codecs are acceptable to a router.
+pub fn validate(codecs: &[RtpCodecCapability]) -> Result<(), CodecConfigError> {
+    if codecs.is_empty() {
+        return Err(CodecConfigError::Empty);
+    }
+    let mut payload_types = HashSet::new();
+    for codec in codecs {
+        let (rtx, preferred_payload_type) = match codec {
+            RtpCodecCapability::Audio {
+                preferred_payload_type,
+                ..
+            } => (false, preferred_payload_type),
+            RtpCodecCapability::Video {
+                mime_type,
+                preferred_payload_type,
+                ..
+            } => (*mime_type == MimeTypeVideo::Rtx, preferred_payload_type),
+        };
+        if rtx {
+            return Err(CodecConfigError::Rtx);
+        }
+        if let Some(payload_type) = preferred_payload_type {
+            if !payload_types.insert(*payload_type) {
+                return Err(CodecConfigError::DuplicatePayloadType(*payload_type));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Video codecs permitted to the relay, e.g. for deployments whose clients cannot
+/// decode every built-in codec. Audio codecs are never filtered.
+#[derive(Debug, Clone, Default)]
+pub struct CodecFilter {
+    /// Video codecs which are removed.
+    pub disabled: Vec<MimeTypeVideo>,
+    /// The only video codec which is kept, if any.
+    pub only: Option<MimeTypeVideo>,
+}
+
+impl CodecFilter {
+    pub fn allows(&self, mime_type: MimeTypeVideo) -> bool {
+        !self.disabled.contains(&mime_type) && self.only.map_or(true, |only| only == mime_type)
+    }
+
+    /// Remove the video codecs which are not permitted, failing if no video codec remains.
+    pub fn apply(
+        &self,
+        codecs: Vec<RtpCodecCapability>,
+    ) -> Result<Vec<RtpCodecCapability>, CodecConfigError> {
+        let has_video = codecs
+            .iter()
+            .any(|codec| matches!(codec, RtpCodecCapability::Video { .. }));
+        let codecs: Vec<_> = codecs
+            .into_iter()
+            .filter(|codec| match codec {
+                RtpCodecCapability::Audio { .. } => true,
+                RtpCodecCapability::Video { mime_type, .. } => self.allows(*mime_type),
+            })
+            .collect();
+        if has_video
+            && !codecs
+                .iter()
+                .any(|codec| matches!(codec, RtpCodecCapability::Video { .. }))
+        {
+            return Err(CodecConfigError::NoVideo);
+        }
+        Ok(codecs)
+    }
+}
+
+/// Opus parameters negotiated with producers and consumers, trading audio quality
+/// against bandwidth. Parameters which are unset are left as configured.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpusConfig {
+    pub stereo: Option<bool>,
+    /// Inband forward error correction.
+    pub inband_fec: Option<bool>,
+    /// Discontinuous transmission, which sends no audio during silence.
+    pub dtx: Option<bool>,
+    /// Maximum average bitrate in bps.
+    pub max_average_bitrate: Option<u32>,
+}
+
+impl OpusConfig {
+    /// Apply the parameters to the Opus codecs.
+    pub fn apply(&self, codecs: &mut [RtpCodecCapability]) -> Result<(), CodecConfigError> {
+        if let Some(max_average_bitrate) = self.max_average_bitrate {
+            if !OPUS_BITRATES.contains(&max_average_bitrate) {
+                return Err(CodecConfigError::OpusBitrate(max_average_bitrate));
+            }
+        }
+        let flag = |enabled: bool| u32::from(enabled);
+        for codec in codecs {
+            if let RtpCodecCapability::Audio {
+                mime_type: MimeTypeAudio::Opus,
+                parameters,
+                ..
+            } = codec
+            {
+                if let Some(stereo) = self.stereo {
+                    parameters.insert("stereo", flag(stereo));
+                    parameters.insert("sprop-stereo", flag(stereo));
+                }
+                if let Some(inband_fec) = self.inband_fec {
+                    parameters.insert("useinbandfec", flag(inband_fec));
+                }
+                if let Some(dtx) = self.dtx {
+                    parameters.insert("usedtx", flag(dtx));
+                }
+                if let Some(max_average_bitrate) = self.max_average_bitrate {
+                    parameters.insert("maxaveragebitrate", max_average_bitrate);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bitrates supported by Opus, in bps (RFC 7587).
+const OPUS_BITRATES: RangeInclusive<u32> = 6000..=510000;
+
+/// Get the built-in media codecs supported by the relay.
+pub fn default_codecs() -> Vec<RtpCodecCapability> {
+    vec![
+        RtpCodecCapability::Audio {
+            mime_type: MimeTypeAudio::Opus,
+            preferred_payload_type: None,
+            clock_rate: NonZeroU32::new(48000).unwrap(),
+            channels: NonZeroU8::new(2).unwrap(),
+            parameters: RtpCodecParametersParameters::from([("useinbandfec", 1u32.into())]),
+            rtcp_feedback: vec![RtcpFeedback::TransportCc],
+        },
+        RtpCodecCapability::Video {
+            mime_type: MimeTypeVideo::H264,
+            preferred_payload_type: None,
+            clock_rate: NonZeroU32::new(90000).unwrap(),
+            parameters: RtpCodecParametersParameters::from([
+                ("packetization-mode", 1u32.into()),
+                ("level-asymmetry-allowed", 1u32.into()),
+                ("profile-level-id", "42e01f".into()),
+            ]),
+            rtcp_feedback: vec![
+                RtcpFeedback::Nack,
+                RtcpFeedback::NackPli,
+                RtcpFeedback::CcmFir,
+                RtcpFeedback::GoogRemb,
+                RtcpFeedback::TransportCc,
+            ],
+        },
+        RtpCodecCapability::Video {
+            mime_type: MimeTypeVideo::H264,
+            preferred_payload_type: None,
+            clock_rate: NonZeroU32::new(90000).unwrap(),
+            parameters: RtpCodecParametersParameters::from([
+                ("packetization-mode", 1u32.into()),
+                ("level-asymmetry-allowed", 1u32.into()),
+                ("profile-level-id", "4d0032".into()),
+            ]),
+            rtcp_feedback: vec![
+                RtcpFeedback::Nack,
+                RtcpFeedback::NackPli,
+                RtcpFeedback::CcmFir,
+                RtcpFeedback::GoogRemb,
+                RtcpFeedback::TransportCc,
+            ],
+        },
+        RtpCodecCapability::Video {
+            mime_type: MimeTypeVideo::H264,
+            preferred_payload_type: None,
+            clock_rate: NonZeroU32::new(90000).unwrap(),
+            parameters: RtpCodecParametersParameters::from([
+                ("packetization-mode", 1u32.into()),
+                ("level-asymmetry-allowed", 1u32.into()),
+                ("profile-level-id", "64002a".into()),
+            ]),
+            rtcp_feedback: vec![
+                RtcpFeedback::Nack,
+                RtcpFeedback::NackPli,
+                RtcpFeedback::CcmFir,
+                RtcpFeedback::GoogRemb,
+                RtcpFeedback::TransportCc,
+            ],
+        },
+        RtpCodecCapability::Video {
+            mime_type: MimeTypeVideo::Vp8,
+            preferred_payload_type: None,
+            clock_rate: NonZeroU32::new(90000).unwrap(),
+            parameters: RtpCodecParametersParameters::default(),
+            rtcp_feedback: vec![
+                RtcpFeedback::Nack,
+                RtcpFeedback::NackPli,
+                RtcpFeedback::CcmFir,
+                RtcpFeedback::GoogRemb,
+                RtcpFeedback::TransportCc,
+            ],
+        },
+    ]
+}
+
+#[derive(Debug, Error)]
+pub enum CodecConfigError {
+    #[error("failed to read codecs: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse codecs: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("no codecs specified")]
+    Empty,
+    #[error("RTX codecs are added by the router and must not be specified")]
+    Rtx,
+    #[error("preferred payload type {0} is used by multiple codecs")]
+    DuplicatePayloadType(u8),
+    #[error("no video codecs remain after filtering")]
+    NoVideo,
+    #[error("Opus bitrate {0} is outside 6000-510000 bps")]
+    OpusBitrate(u32),
+}
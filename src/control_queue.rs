@@ -0,0 +1,300 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextResolve, ResolveInfo,
+};
+use async_graphql::{ServerResult, Value};
+use tokio::sync::oneshot;
+
+/// Kind of control operation, by which queued operations are prioritized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperationClass {
+    /// Registration and unregistration of rooms and sessions by the backend.
+    Registration,
+    /// Operations which are cheap to serve.
+    Routine,
+    /// Stats and reports, which are expensive to serve and not essential.
+    Stats,
+}
+
+impl OperationClass {
+    pub const ALL: [OperationClass; 3] = [
+        OperationClass::Registration,
+        OperationClass::Routine,
+        OperationClass::Stats,
+    ];
+
+    /// Classify a top-level field of the control schema.
+    pub fn of_field(name: &str) -> Self {
+        match name {
+            "registerRoom"
+            | "unregisterRoom"
+            | "registerVulcastSession"
+            | "registerClientSession"
+            | "registerHostSession"
+            | "unregisterSession" => OperationClass::Registration,
+            "stats"
+            | "serverStats"
+            | "roomStats"
+            | "soakReport"
+            | "latencyReport"
+            | "checkAnnounceReachability" => OperationClass::Stats,
+            _ => OperationClass::Routine,
+        }
+    }
+
+    /// Weight of the class unless overridden, where heavier operations are served first.
+    pub fn default_weight(self) -> u32 {
+        match self {
+            OperationClass::Registration => 100,
+            OperationClass::Routine => 10,
+            OperationClass::Stats => 1,
+        }
+    }
+}
+
+/// Limits the control operations served concurrently, so that a saturated relay keeps
+/// serving the backend. Operations beyond the limit are queued and served in order of
+/// the weight of their class, then in order of arrival, e.g. so that registrations are
+/// not stuck behind expensive stats queries.
+#[derive(Debug, Clone)]
+pub struct ControlQueue {
+    shared: Arc<Mutex<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    /// operations served concurrently, if limited
+    max_concurrent: Option<usize>,
+    /// operations currently being served
+    running: usize,
+    waiting: BinaryHeap<Waiter>,
+    /// arrival order of the next queued operation
+    next_seq: u64,
+    classes: HashMap<OperationClass, ClassState>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ClassState {
+    weight: u32,
+    depth: usize,
+    peak_depth: usize,
+}
+
+#[derive(Debug)]
+struct Waiter {
+    weight: u32,
+    seq: u64,
+    class: OperationClass,
+    tx: oneshot::Sender<()>,
+}
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for Waiter {}
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Waiter {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // heaviest first, then earliest first
+        self.weight
+            .cmp(&other.weight)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// Queue depth of a class of control operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueDepth {
+    pub class: OperationClass,
+    pub weight: u32,
+    /// Operations currently queued, including those abandoned by their client until
+    /// they reach the front of the queue.
+    pub depth: usize,
+    /// Most operations queued at once since the relay started.
+    pub peak_depth: usize,
+}
+
+impl Default for ControlQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ControlQueue {
+    pub fn new() -> Self {
+        Self {
+            shared: Arc::new(Mutex::new(State {
+                max_concurrent: None,
+                running: 0,
+                waiting: BinaryHeap::new(),
+                next_seq: 0,
+                classes: OperationClass::ALL
+                    .iter()
+                    .map(|class| {
+                        let class_state = ClassState {
+                            weight: class.default_weight(),
+                            depth: 0,
+                            peak_depth: 0,
+                        };
+                        (*class, class_state)
+                    })
+                    .collect(),
+            })),
+        }
+    }
+
+    /// Limit the control operations served concurrently. Operations are never queued
+    /// if unset.
+    pub fn set_max_concurrent(&self, max_concurrent: Option<usize>) {
+        let mut state = self.shared.lock().unwrap();
+        state.max_concurrent = max_concurrent.map(|max_concurrent| max_concurrent.max(1));
+        state.admit_waiting();
+    }
+    pub fn max_concurrent(&self) -> Option<usize> {
+        self.shared.lock().unwrap().max_concurrent
+    }
+
+    /// Set the weight of a class of operations, applying to operations queued from now.
+    pub fn set_weight(&self, class: OperationClass, weight: u32) {
+        let mut state = self.shared.lock().unwrap();
+        state.classes.get_mut(&class).unwrap().weight = weight;
+    }
+
+    /// Get the number of operations currently being served.
+    pub fn running(&self) -> usize {
+        self.shared.lock().unwrap().running
+    }
+
+    /// Get the queue depth of each class of operations.
+    pub fn depths(&self) -> Vec<QueueDepth> {
+        let state = self.shared.lock().unwrap();
+        OperationClass::ALL
+            .iter()
+            .map(|class| {
+                let class_state = state.classes[class];
+                QueueDepth {
+                    class: *class,
+                    weight: class_state.weight,
+                    depth: class_state.depth,
+                    peak_depth: class_state.peak_depth,
+                }
+            })
+            .collect()
+    }
+
+    /// Serve an operation once its turn comes.
+    pub async fn run<F: Future>(&self, class: OperationClass, future: F) -> F::Output {
+        let _permit = self.acquire(class).await;
+        future.await
+    }
+
+    async fn acquire(&self, class: OperationClass) -> Permit {
+        let mut ticket = {
+            let mut state = self.shared.lock().unwrap();
+            if state.max_concurrent.map_or(true, |max| state.running < max) {
+                state.running += 1;
+                return Permit(self.clone());
+            }
+            let (tx, rx) = oneshot::channel();
+            state.next_seq += 1;
+            let waiter = Waiter {
+                weight: state.classes[&class].weight,
+                seq: state.next_seq,
+                class,
+                tx,
+            };
+            state.waiting.push(waiter);
+            let class_state = state.classes.get_mut(&class).unwrap();
+            class_state.depth += 1;
+            class_state.peak_depth = class_state.peak_depth.max(class_state.depth);
+            Ticket {
+                rx,
+                queue: self.clone(),
+            }
+        };
+        // the slot of a finishing operation is handed over, so the sender never drops
+        let _ = (&mut ticket.rx).await;
+        Permit(self.clone())
+    }
+
+    /// Release the slot of a finished operation to the next queued operation, if any.
+    fn release(&self) {
+        let mut state = self.shared.lock().unwrap();
+        state.running -= 1;
+        state.admit_waiting();
+    }
+}
+
+impl State {
+    /// Hand free slots over to queued operations, skipping abandoned operations.
+    fn admit_waiting(&mut self) {
+        while self.max_concurrent.map_or(true, |max| self.running < max) {
+            let waiter = match self.waiting.pop() {
+                Some(waiter) => waiter,
+                None => return,
+            };
+            self.classes.get_mut(&waiter.class).unwrap().depth -= 1;
+            if waiter.tx.send(()).is_ok() {
+                self.running += 1;
+            }
+        }
+    }
+}
+
+/// Slot of an operation being served, released once dropped.
+struct Permit(ControlQueue);
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+/// Place of an operation in the queue.
+struct Ticket {
+    rx: oneshot::Receiver<()>,
+    queue: ControlQueue,
+}
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        // stop slots from being handed over to the abandoned operation, then release
+        // the slot if one was handed over meanwhile
+        self.rx.close();
+        if self.rx.try_recv().is_ok() {
+            self.queue.release();
+        }
+    }
+}
+
+/// GraphQL extension serving each top-level field through the control queue.
+pub struct ControlPriority(pub ControlQueue);
+impl ExtensionFactory for ControlPriority {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(ControlPriorityExtension(self.0.clone()))
+    }
+}
+
+struct ControlPriorityExtension(ControlQueue);
+#[async_trait::async_trait]
+impl Extension for ControlPriorityExtension {
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> ServerResult<Option<Value>> {
+        if info.path_node.parent.is_some() {
+            return next.run(ctx, info).await;
+        }
+        let class = OperationClass::of_field(info.name);
+        self.0.run(class, next.run(ctx, info)).await
+    }
+}
@@ -1,11 +1,38 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
 use anyhow::anyhow;
-use async_graphql::{Context, EmptySubscription, Object, Schema, SimpleObject, Union, ID};
+use async_graphql::{Context, Enum, Object, Schema, SimpleObject, Subscription, Union, ID};
+use futures::stream::{self, Stream, StreamExt};
 
 use crate::built_info;
+use crate::capacity;
+use crate::control_queue::{self, ControlPriority};
+use crate::crypto_policy::CryptoPolicy;
+use crate::data_filter::{DataFilter, RateLimitFilter};
+use crate::payload_limit::PayloadLimitCheck;
+use crate::reachability;
 use crate::relay_server::{
-    ForeignRoomId, ForeignSessionId, RegisterRoomError, RegisterSessionError, RelayServer,
-    SessionOptions, UnregisterRoomError, UnregisterSessionError,
+    self, ForeignRoomId, ForeignSessionId, RegisterRoomError, RegisterSessionError, RelayServer,
+    Role, SessionOptions, SetRoomDataFilterError, SetRoomLockedError, SetRoomPublicError,
+    SetRoomStreamPolicyError, SetRoomTurnConfigError, UnregisterRoomError, UnregisterSessionError,
 };
+use crate::resource_policy::{self, LimitScope, ResourcePolicy};
+use crate::room;
+use crate::room_gc;
+use crate::room_stats;
+use crate::schema_compat::DeprecationPolicy;
+use crate::server_stats;
+use crate::session::{self, ResourceType};
+use crate::soak_report;
+use crate::stream_policy::{self, StreamRules};
+use crate::subsystems;
+use crate::turn::TurnConfig;
+
+/// Label of the server data producer used for room messages.
+const SERVER_DATA_LABEL: &str = "server";
+/// Shortest interval between stats snapshots, in milliseconds.
+const MIN_STATS_INTERVAL_MS: u64 = 100;
 
 #[derive(Default)]
 pub struct QueryRoot;
@@ -13,22 +40,221 @@ pub struct QueryRoot;
 impl QueryRoot {
     /// Get the version and build info of this relay instance.
     async fn version(&self, _ctx: &Context<'_>) -> String {
-        format!(
-            "{}_{}_{}_{}",
-            built_info::PKG_NAME,
-            built_info::PKG_VERSION,
-            built_info::TARGET,
-            built_info::PROFILE
-        )
+        version()
+    }
+
+    /// Get the version and crypto configuration of this relay instance.
+    async fn server_info(&self, ctx: &Context<'_>) -> ServerInfo {
+        let crypto_policy = ctx.data_unchecked::<CryptoPolicy>();
+        ServerInfo {
+            version: version(),
+            tls: crypto_policy.tls(),
+            constrained_crypto: crypto_policy.constrained(),
+            srtp_crypto_suites: crypto_policy
+                .srtp_crypto_suites()
+                .iter()
+                .filter_map(serde_name)
+                .collect(),
+        }
+    }
+
+    /// Get the registered rooms and the sessions registered to each.
+    async fn rooms(&self, ctx: &Context<'_>) -> Vec<RegisteredRoom> {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        let sessions = relay_server.registered_sessions();
+        relay_server
+            .registered_rooms()
+            .into_iter()
+            .map(|(frid, vulcast_fsid)| RegisteredRoom {
+                id: frid.0.clone().into(),
+                sessions: sessions
+                    .iter()
+                    .filter(|(fsid, session_options, _)| match session_options {
+                        SessionOptions::Vulcast => *fsid == vulcast_fsid,
+                        SessionOptions::WebClient(client_frid)
+                        | SessionOptions::Host(client_frid) => *client_frid == frid,
+                    })
+                    .map(|(fsid, session_options, connected)| RegisteredSession {
+                        id: fsid.0.clone().into(),
+                        role: session_options.role().into(),
+                        connected: *connected,
+                    })
+                    .collect(),
+                vulcast_session_id: vulcast_fsid.0.into(),
+            })
+            .collect()
+    }
+
+    /// Get statistics of the producers, consumers and transports of a session.
+    /// Fails while the worker is overloaded, as stats are not essential.
+    async fn stats(&self, ctx: &Context<'_>, session_id: ID) -> Result<Stats, anyhow::Error> {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        relay_server.worker_monitor().check()?;
+        let session = relay_server
+            .get_session(&ForeignSessionId::from(session_id))
+            .ok_or_else(|| anyhow!("unknown fsid"))?;
+        Ok(session.get_stats().await?.into())
+    }
+
+    /// Get why the PHY session of a session last ended, if it ended recently.
+    async fn last_disconnect(&self, ctx: &Context<'_>, session_id: ID) -> Option<Disconnect> {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        relay_server
+            .last_disconnect(&ForeignSessionId::from(session_id))
+            .map(Disconnect::from)
+    }
+
+    /// Get a summary of the load on this relay instance, for capacity planning.
+    /// Fails while the worker is overloaded, as stats are not essential.
+    async fn server_stats(&self, ctx: &Context<'_>) -> Result<ServerStats, anyhow::Error> {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        relay_server.worker_monitor().check()?;
+        Ok(server_stats::report(relay_server).await?.into())
+    }
+
+    /// Get the theoretical and current headroom of this relay instance, e.g. for
+    /// placing rooms across relays. Available while the worker is overloaded.
+    async fn capacity(&self, ctx: &Context<'_>) -> Capacity {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        let resource_policy = ctx.data_unchecked::<ResourcePolicy>();
+        capacity::report(relay_server, resource_policy).into()
+    }
+
+    /// Get stats aggregated across the sessions of a room.
+    /// Fails while the worker is overloaded, as stats are not essential.
+    async fn room_stats(&self, ctx: &Context<'_>, room_id: ID) -> Result<RoomStats, anyhow::Error> {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        relay_server.worker_monitor().check()?;
+        let room = relay_server
+            .get_room(&ForeignRoomId::from(room_id))
+            .ok_or_else(|| anyhow!("unknown frid"))?;
+        Ok(room_stats::report(&room).await?.into())
+    }
+
+    /// Get the moderation actions recently taken in a room, oldest first.
+    async fn moderation_log(
+        &self,
+        ctx: &Context<'_>,
+        room_id: ID,
+    ) -> Result<Vec<ModerationAction>, anyhow::Error> {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        let room = relay_server
+            .get_room(&ForeignRoomId::from(room_id))
+            .ok_or_else(|| anyhow!("unknown frid"))?;
+        Ok(room
+            .moderation_log()
+            .into_iter()
+            .map(ModerationAction::from)
+            .collect())
     }
 
-    /// Get various statistics for a session.
-    async fn stats(&self, ctx: &Context<'_>, session_id: ID) -> Result<String, anyhow::Error> {
+    /// Get the DTLS/SRTP parameters in use on each transport of a session.
+    /// The negotiated DTLS version and cipher suite are not reported by the
+    /// media worker, so only the DTLS state, role and fingerprints are available.
+    async fn transport_crypto(
+        &self,
+        ctx: &Context<'_>,
+        session_id: ID,
+    ) -> Result<Vec<TransportCrypto>, anyhow::Error> {
         let relay_server = ctx.data_unchecked::<RelayServer>();
         let session = relay_server
             .get_session(&ForeignSessionId::from(session_id))
             .ok_or_else(|| anyhow!("unknown fsid"))?;
-        Ok(serde_json::to_string(&session.get_stats().await?)?)
+        Ok(session
+            .get_transport_crypto()
+            .into_iter()
+            .map(TransportCrypto::from)
+            .collect())
+    }
+
+    /// Get the latency of requests to the media worker, which grows as the worker
+    /// channel saturates.
+    async fn worker_load(&self, ctx: &Context<'_>) -> WorkerLoad {
+        let worker_monitor = ctx.data_unchecked::<RelayServer>().worker_monitor();
+        WorkerLoad {
+            latency_ms: worker_monitor
+                .latency()
+                .map(|latency| latency.as_secs_f64() * 1000.0),
+            threshold_ms: worker_monitor
+                .threshold()
+                .map(|threshold| threshold.as_secs_f64() * 1000.0),
+            overloaded: worker_monitor.overloaded(),
+        }
+    }
+
+    /// Get the depth of the queue of control operations, per class of operation.
+    async fn control_queue(&self, ctx: &Context<'_>) -> ControlQueue {
+        let control_queue = ctx.data_unchecked::<RelayServer>().control_queue();
+        ControlQueue {
+            max_concurrent: control_queue.max_concurrent(),
+            running: control_queue.running(),
+            depths: control_queue
+                .depths()
+                .into_iter()
+                .map(QueueDepth::from)
+                .collect(),
+        }
+    }
+
+    /// Get the limits on the size of GraphQL requests, along with the number of
+    /// requests rejected by each.
+    async fn payload_limits(&self, ctx: &Context<'_>) -> PayloadLimits {
+        let payload_limit = ctx.data_unchecked::<RelayServer>().payload_limit();
+        let rejections = payload_limit.rejections();
+        PayloadLimits {
+            max_request_bytes: payload_limit.max_request_bytes(),
+            max_variable_bytes: payload_limit.max_variable_bytes(),
+            requests_rejected: rejections.requests,
+            variables_rejected: rejections.variables,
+        }
+    }
+
+    /// Get the limit on the rooms registered per Vulcast over time, along with the
+    /// rooms registered recently by a Vulcast, e.g. to spot firmware stuck registering
+    /// and unregistering rooms.
+    async fn room_churn(&self, ctx: &Context<'_>, vulcast_id: ID) -> RoomChurn {
+        let room_churn = ctx.data_unchecked::<RelayServer>().room_churn();
+        RoomChurn {
+            max_registrations: room_churn.max_registrations(),
+            window_secs: room_churn.window().as_secs(),
+            registrations: room_churn
+                .registrations(&ForeignSessionId::from(vulcast_id), Instant::now()),
+            rejections: room_churn.rejections(),
+        }
+    }
+
+    /// Get a debug report of the objects and memory held by the relay, as JSON,
+    /// to verify that long-running relays do not leak.
+    async fn soak_report(&self, ctx: &Context<'_>) -> Result<String, anyhow::Error> {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        Ok(serde_json::to_string(
+            &soak_report::report(relay_server).await,
+        )?)
+    }
+
+    /// Get whether each optional subsystem of the relay is enabled.
+    async fn subsystems(&self, ctx: &Context<'_>) -> Vec<SubsystemStatus> {
+        subsystem_statuses(ctx)
+    }
+
+    /// Get the latency of media from each producer to each of its consumers, as JSON.
+    /// Requires the relay to run with `--latency-probe`.
+    async fn latency_report(&self, ctx: &Context<'_>) -> Result<String, anyhow::Error> {
+        let latency_probe = ctx
+            .data_unchecked::<RelayServer>()
+            .latency_probe()
+            .ok_or_else(|| anyhow!("latency probe is disabled"))?;
+        Ok(serde_json::to_string(&latency_probe.report())?)
+    }
+
+    /// Get all configured resource limits for sessions.
+    async fn resource_limits(&self, ctx: &Context<'_>) -> Vec<ResourceLimit> {
+        let resource_policy = ctx.data_unchecked::<ResourcePolicy>();
+        resource_policy
+            .limits()
+            .into_iter()
+            .map(ResourceLimit::from)
+            .collect()
     }
 }
 
@@ -38,16 +264,19 @@ pub struct MutationRoot;
 impl MutationRoot {
     /// Register a room tied to a specific Vulcast, identified by its session ID.
     /// This will fail if the specified Vulcast is already tied to an existing room.
+    /// The room carries audio, video and data unless another preset is specified.
     async fn register_room(
         &self,
         ctx: &Context<'_>,
         room_id: ID,
         vulcast_session_id: ID,
+        preset: Option<RoomPreset>,
     ) -> RegisterRoomResult {
         let relay_server = ctx.data_unchecked::<RelayServer>();
-        match relay_server.register_room(
+        match relay_server.register_room_with_preset(
             ForeignRoomId::from(room_id.clone()),
             ForeignSessionId::from(vulcast_session_id),
+            preset.map_or_else(room::RoomPreset::default, Into::into),
         ) {
             Ok(_) => RegisterRoomResult::Ok(Room { id: room_id }),
             Err(err) => err.into(),
@@ -87,16 +316,19 @@ impl MutationRoot {
     /// The session and corresponding token remains valid until unregistered.
     /// Web clients can present the returned token to connect to the Relay,
     /// which will automatically place them in the correct room.
+    /// The display name, if given, is shown to the other participants of the room.
     async fn register_client_session(
         &self,
         ctx: &Context<'_>,
         room_id: ID,
         session_id: ID,
+        display_name: Option<String>,
     ) -> RegisterSessionResult {
         let relay_server = ctx.data_unchecked::<RelayServer>();
-        match relay_server.register_session(
+        match relay_server.register_session_with_display_name(
             ForeignSessionId::from(session_id.clone()),
             SessionOptions::WebClient(ForeignRoomId::from(room_id)),
+            display_name,
         ) {
             Ok(session_token) => RegisterSessionResult::Ok(SessionWithToken {
                 id: session_id,
@@ -109,16 +341,19 @@ impl MutationRoot {
     /// The session and corresponding token remains valid until unregistered.
     /// Hosts can present the returned token to connect to the Relay,
     /// which will automatically place them in the correct room.
+    /// The display name, if given, is shown to the other participants of the room.
     async fn register_host_session(
         &self,
         ctx: &Context<'_>,
         room_id: ID,
         session_id: ID,
+        display_name: Option<String>,
     ) -> RegisterSessionResult {
         let relay_server = ctx.data_unchecked::<RelayServer>();
-        match relay_server.register_session(
+        match relay_server.register_session_with_display_name(
             ForeignSessionId::from(session_id.clone()),
             SessionOptions::Host(ForeignRoomId::from(room_id)),
+            display_name,
         ) {
             Ok(session_token) => RegisterSessionResult::Ok(SessionWithToken {
                 id: session_id,
@@ -127,6 +362,289 @@ impl MutationRoot {
             Err(err) => err.into(),
         }
     }
+    /// Set the limit of a resource for sessions, taking effect on the next allocation.
+    /// The limit applies to sessions in the given room if specified, otherwise to sessions
+    /// with the given role if specified, otherwise to all sessions.
+    /// Clears the limit if unspecified, deferring to a less specific limit.
+    async fn set_resource_limit(
+        &self,
+        ctx: &Context<'_>,
+        resource: ResourceKind,
+        limit: Option<usize>,
+        role: Option<SessionRole>,
+        room_id: Option<ID>,
+    ) -> Vec<ResourceLimit> {
+        let resource_policy = ctx.data_unchecked::<ResourcePolicy>();
+        let scope = match (room_id, role) {
+            (Some(room_id), _) => LimitScope::Room(ForeignRoomId::from(room_id)),
+            (None, Some(role)) => LimitScope::Role(role.into()),
+            (None, None) => LimitScope::Default,
+        };
+        resource_policy.set_limit(scope, resource.into(), limit);
+        resource_policy
+            .limits()
+            .into_iter()
+            .map(ResourceLimit::from)
+            .collect()
+    }
+
+    /// Limit the rate at which sessions join a room in joins per second, e.g. ahead of
+    /// a mass event. Joins beyond the rate are queued. The default rate is restored if
+    /// unspecified. Returns the rate now in effect in the room, if limited.
+    async fn set_room_join_rate(
+        &self,
+        ctx: &Context<'_>,
+        room_id: ID,
+        joins_per_second: Option<u32>,
+    ) -> Option<u32> {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        let frid = ForeignRoomId::from(room_id);
+        let join_throttle = relay_server.join_throttle();
+        join_throttle.set_room_rate(frid.clone(), joins_per_second);
+        join_throttle.rate(&frid)
+    }
+
+    /// Send a message to all clients in a room, over a data producer owned by the relay.
+    /// Clients receive the message once they consume the server data producer, which
+    /// is announced to the room when the first message is sent.
+    async fn send_room_message(
+        &self,
+        ctx: &Context<'_>,
+        room_id: ID,
+        message: String,
+    ) -> Result<SendRoomMessageResult, anyhow::Error> {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        match relay_server.get_room(&ForeignRoomId::from(room_id.clone())) {
+            Some(room) => {
+                room.send_server_data(SERVER_DATA_LABEL, message).await?;
+                Ok(SendRoomMessageResult::Ok(Room { id: room_id }))
+            }
+            None => Ok(SendRoomMessageResult::UnknownRoom(UnknownRoomError {
+                room: Room { id: room_id },
+            })),
+        }
+    }
+
+    /// Limit the rate (messages per second) and size (bytes) of data messages from each
+    /// data producer in a room, relaying data through the server to enforce the limits.
+    /// Applies to data producers created from now on. Limits are removed if unspecified.
+    async fn set_room_data_limits(
+        &self,
+        ctx: &Context<'_>,
+        room_id: ID,
+        max_messages: Option<u32>,
+        max_message_size: Option<usize>,
+    ) -> SetRoomDataLimitsResult {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        let data_filter: Option<Arc<dyn DataFilter>> =
+            if max_messages.is_none() && max_message_size.is_none() {
+                None
+            } else {
+                let mut filter =
+                    RateLimitFilter::new(max_messages.unwrap_or(u32::MAX), Duration::from_secs(1));
+                if let Some(max_message_size) = max_message_size {
+                    filter = filter.with_max_message_size(max_message_size);
+                }
+                Some(Arc::new(filter))
+            };
+        match relay_server.set_room_data_filter(ForeignRoomId::from(room_id.clone()), data_filter) {
+            Ok(_) => SetRoomDataLimitsResult::Ok(Room { id: room_id }),
+            Err(err) => err.into(),
+        }
+    }
+
+    /// Offer credentials for a TURN server sharing the given secret to sessions in a room,
+    /// instead of the default TURN server. Credentials expire after the given lifetime
+    /// in seconds. The default is restored if no URLs are given.
+    async fn set_room_turn_server(
+        &self,
+        ctx: &Context<'_>,
+        room_id: ID,
+        urls: Option<Vec<String>>,
+        shared_secret: Option<String>,
+        #[graphql(default = 86400)] ttl: u64,
+    ) -> Result<SetRoomTurnServerResult, anyhow::Error> {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        let turn_config = match (urls, shared_secret) {
+            (Some(urls), Some(shared_secret)) => Some(TurnConfig {
+                urls,
+                shared_secret,
+                ttl: Duration::from_secs(ttl),
+            }),
+            (Some(_), None) => return Err(anyhow!("a shared secret is required")),
+            (None, _) => None,
+        };
+        Ok(
+            match relay_server
+                .set_room_turn_config(ForeignRoomId::from(room_id.clone()), turn_config)
+            {
+                Ok(_) => SetRoomTurnServerResult::Ok(Room { id: room_id }),
+                Err(err) => err.into(),
+            },
+        )
+    }
+
+    /// List a room on the public control endpoint, along with its viewer count, or
+    /// unlist it.
+    async fn set_room_public(
+        &self,
+        ctx: &Context<'_>,
+        room_id: ID,
+        public: bool,
+    ) -> SetRoomPublicResult {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        match relay_server.set_room_public(ForeignRoomId::from(room_id.clone()), public) {
+            Ok(_) => SetRoomPublicResult::Ok(Room { id: room_id }),
+            Err(err) => err.into(),
+        }
+    }
+
+    /// Restrict the roles of stream which sessions of a role may produce in a room, and
+    /// which they are announced for auto-consumption, e.g. so that WebClients only
+    /// produce camera and voice streams and only auto-consume the game. Unset lists
+    /// leave the role unrestricted. Applies to producers created and announced from now
+    /// on.
+    async fn set_room_stream_rules(
+        &self,
+        ctx: &Context<'_>,
+        room_id: ID,
+        role: SessionRole,
+        produce: Option<Vec<StreamRole>>,
+        auto_consume: Option<Vec<StreamRole>>,
+    ) -> SetRoomStreamPolicyResult {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        let stream_roles = |stream_roles: Vec<StreamRole>| -> Vec<stream_policy::StreamRole> {
+            stream_roles.into_iter().map(Into::into).collect()
+        };
+        let rules = StreamRules {
+            produce: produce.map(stream_roles),
+            auto_consume: auto_consume.map(stream_roles),
+        };
+        match relay_server.set_room_stream_rules(
+            ForeignRoomId::from(room_id.clone()),
+            role.into(),
+            rules,
+        ) {
+            Ok(_) => SetRoomStreamPolicyResult::Ok(Room { id: room_id }),
+            Err(err) => err.into(),
+        }
+    }
+
+    /// Restore the default stream policy of a room.
+    async fn reset_room_stream_policy(
+        &self,
+        ctx: &Context<'_>,
+        room_id: ID,
+    ) -> SetRoomStreamPolicyResult {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        match relay_server.set_room_stream_policy(ForeignRoomId::from(room_id.clone()), None) {
+            Ok(_) => SetRoomStreamPolicyResult::Ok(Room { id: room_id }),
+            Err(err) => err.into(),
+        }
+    }
+
+    /// Lock a room, so that new WebClient sessions cannot connect to it (e.g. once a
+    /// match starts), leaving connected sessions connected.
+    async fn lock_room(&self, ctx: &Context<'_>, room_id: ID) -> SetRoomLockedResult {
+        set_room_locked(ctx, room_id, true)
+    }
+
+    /// Unlock a room, letting new WebClient sessions connect to it again.
+    async fn unlock_room(&self, ctx: &Context<'_>, room_id: ID) -> SetRoomLockedResult {
+        set_room_locked(ctx, room_id, false)
+    }
+
+    /// Lock input from WebClients in a room, e.g. during cutscenes, leaving media flowing.
+    async fn lock_room_input(&self, ctx: &Context<'_>, room_id: ID) -> RoomInputResult {
+        set_room_input_locked(ctx, room_id, true)
+    }
+
+    /// Unlock input from WebClients in a room.
+    async fn unlock_room_input(&self, ctx: &Context<'_>, room_id: ID) -> RoomInputResult {
+        set_room_input_locked(ctx, room_id, false)
+    }
+
+    /// Undo a moderation action taken in a room, e.g. resume a producer muted by a
+    /// host by mistake. Kicks cannot be undone.
+    async fn undo_moderation(
+        &self,
+        ctx: &Context<'_>,
+        room_id: ID,
+        id: u64,
+    ) -> Result<ModerationAction, anyhow::Error> {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        let room = relay_server
+            .get_room(&ForeignRoomId::from(room_id))
+            .ok_or_else(|| anyhow!("unknown frid"))?;
+        Ok(room.undo_moderation(id, None).await?.into())
+    }
+
+    /// Disable an optional subsystem of the relay, e.g. to shed load during incidents,
+    /// without affecting media.
+    async fn disable_subsystem(
+        &self,
+        ctx: &Context<'_>,
+        subsystem: Subsystem,
+    ) -> Vec<SubsystemStatus> {
+        set_subsystem_enabled(ctx, subsystem, false)
+    }
+
+    /// Enable an optional subsystem of the relay.
+    async fn enable_subsystem(
+        &self,
+        ctx: &Context<'_>,
+        subsystem: Subsystem,
+    ) -> Vec<SubsystemStatus> {
+        set_subsystem_enabled(ctx, subsystem, true)
+    }
+
+    /// Inject failures for chaos testing of client retry logic. Unspecified options are
+    /// unchanged, and a resolver delay of 0 disables the delay. Only available in
+    /// debug builds.
+    async fn inject_failures(
+        &self,
+        ctx: &Context<'_>,
+        transport_failure_percent: Option<u8>,
+        resolver_delay_ms: Option<u64>,
+    ) -> Result<InjectedFailures, anyhow::Error> {
+        let chaos = ctx.data_unchecked::<RelayServer>().chaos();
+        if let Some(transport_failure_percent) = transport_failure_percent {
+            chaos.set_transport_failure_percent(transport_failure_percent)?;
+        }
+        if let Some(resolver_delay_ms) = resolver_delay_ms {
+            chaos.set_resolver_delay(
+                Some(Duration::from_millis(resolver_delay_ms)).filter(|delay| !delay.is_zero()),
+            )?;
+        }
+        Ok(InjectedFailures {
+            transport_failure_percent: chaos.transport_failure_percent(),
+            resolver_delay_ms: chaos
+                .resolver_delay()
+                .map_or(0, |delay| delay.as_millis() as u64),
+        })
+    }
+
+    /// Probe whether the announce IP of this relay routes back to it, e.g. after
+    /// network changes. Media silently fails to flow if it does not.
+    async fn check_announce_reachability(&self, ctx: &Context<'_>) -> AnnounceReachability {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        let announce_ip = relay_server.transport_listen_ip().announced_ip;
+        match reachability::check(relay_server).await {
+            Ok(rtt) => AnnounceReachability {
+                announce_ip: announce_ip.map(|ip| ip.to_string()),
+                reachable: true,
+                rtt_ms: rtt.map(|rtt| rtt.as_secs_f64() * 1000.0),
+                error: None,
+            },
+            Err(err) => AnnounceReachability {
+                announce_ip: announce_ip.map(|ip| ip.to_string()),
+                reachable: false,
+                rtt_ms: None,
+                error: Some(err.to_string()),
+            },
+        }
+    }
+
     /// Unregister a session by its session ID.
     /// This will also terminate all active connections made with this session.
     async fn unregister_session(
@@ -142,6 +660,292 @@ impl MutationRoot {
     }
 }
 
+#[derive(Default)]
+pub struct SubscriptionRoot;
+#[Subscription]
+impl SubscriptionRoot {
+    /// Periodically notify of statistics of the producers, consumers and transports of
+    /// a session. The interval is in milliseconds. Snapshots are skipped while the
+    /// worker is overloaded, as stats are not essential. Completes once the PHY session
+    /// of the session ends.
+    async fn stats_stream(
+        &self,
+        ctx: &Context<'_>,
+        session_id: ID,
+        #[graphql(default = 1000)] interval_ms: u64,
+    ) -> async_graphql::Result<impl Stream<Item = Stats>> {
+        let relay_server = ctx.data_unchecked::<RelayServer>().clone();
+        let session = relay_server
+            .get_session(&ForeignSessionId::from(session_id))
+            .ok_or_else(|| anyhow!("unknown fsid"))?;
+        // hold the session weakly, so the stream does not keep it open
+        let weak_session = session.downgrade();
+        let interval = Duration::from_millis(interval_ms.max(MIN_STATS_INTERVAL_MS));
+        Ok(stream::unfold(weak_session, move |weak_session| {
+            let relay_server = relay_server.clone();
+            async move {
+                loop {
+                    tokio::time::sleep(interval).await;
+                    let session = weak_session.upgrade()?;
+                    if relay_server.worker_monitor().check().is_err() {
+                        continue;
+                    }
+                    match session.get_stats().await {
+                        Ok(stats) => return Some((stats.into(), weak_session)),
+                        Err(err) => log::warn!("failed to get session stats: {}", err),
+                    }
+                }
+            }
+        }))
+    }
+
+    /// Notify of rooms unregistered by garbage collection, as they had no connected
+    /// sessions for the idle timeout.
+    async fn room_collected(&self, ctx: &Context<'_>) -> impl Stream<Item = CollectedRoom> {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        relay_server.room_gc().collected().map(CollectedRoom::from)
+    }
+}
+
+/// Room unregistered as it had no connected sessions for the idle timeout.
+#[derive(SimpleObject)]
+struct CollectedRoom {
+    room_id: ID,
+    /// Time for which the room had no connected sessions, in seconds.
+    idle_secs: u64,
+}
+impl From<room_gc::CollectedRoom> for CollectedRoom {
+    fn from(collected: room_gc::CollectedRoom) -> Self {
+        Self {
+            room_id: collected.frid.0.into(),
+            idle_secs: collected.idle.as_secs(),
+        }
+    }
+}
+
+/// Failures injected for chaos testing.
+#[derive(SimpleObject)]
+struct InjectedFailures {
+    /// Percentage of transport creations which fail.
+    transport_failure_percent: u8,
+    /// Delay before each signal query or mutation is executed, in milliseconds.
+    resolver_delay_ms: u64,
+}
+
+/// Result of probing the announce IP of a relay instance.
+#[derive(SimpleObject)]
+struct AnnounceReachability {
+    /// IP announced to clients, if any. Reachability is not probed otherwise.
+    announce_ip: Option<String>,
+    reachable: bool,
+    /// Round trip time of the probe in milliseconds, if probed and reachable.
+    rtt_ms: Option<f64>,
+    /// Why the probe failed, if unreachable.
+    error: Option<String>,
+}
+
+/// Moderation action taken in a room.
+#[derive(SimpleObject)]
+struct ModerationAction {
+    id: u64,
+    kind: ModerationKind,
+    /// Session ID of the host which took the action, if not taken by the backend.
+    actor_session_id: Option<ID>,
+    /// Session ID of the client the action was taken against, if any.
+    target_session_id: Option<ID>,
+    /// Producer paused by the action, if any.
+    producer_id: Option<String>,
+    /// Time of the action, in milliseconds since the Unix epoch.
+    timestamp: f64,
+    /// Time at which the action was undone, if it was.
+    undone_at: Option<f64>,
+    /// Session ID of the host which undid the action, if not undone by the backend.
+    undone_by: Option<ID>,
+}
+impl From<room::ModerationAction> for ModerationAction {
+    fn from(action: room::ModerationAction) -> Self {
+        Self {
+            id: action.id,
+            kind: action.kind.into(),
+            actor_session_id: action.actor.map(Into::into),
+            target_session_id: action.target.map(Into::into),
+            producer_id: action
+                .producer_id
+                .map(|producer_id| producer_id.to_string()),
+            timestamp: unix_ms(action.time),
+            undone_at: action.undone.as_ref().map(|(time, _)| unix_ms(*time)),
+            undone_by: action.undone.and_then(|(_, actor)| actor).map(Into::into),
+        }
+    }
+}
+
+/// Kind of moderation action.
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+enum ModerationKind {
+    /// A producer was paused.
+    Mute,
+    /// A client was dropped from the room.
+    Kick,
+    LockInput,
+    UnlockInput,
+}
+impl From<room::ModerationKind> for ModerationKind {
+    fn from(kind: room::ModerationKind) -> Self {
+        match kind {
+            room::ModerationKind::Mute => ModerationKind::Mute,
+            room::ModerationKind::Kick => ModerationKind::Kick,
+            room::ModerationKind::LockInput => ModerationKind::LockInput,
+            room::ModerationKind::UnlockInput => ModerationKind::UnlockInput,
+        }
+    }
+}
+
+/// End of the PHY session of a session.
+#[derive(SimpleObject)]
+struct Disconnect {
+    reason: DisconnectReason,
+    /// Time of the disconnect, in milliseconds since the Unix epoch.
+    timestamp: f64,
+}
+impl From<relay_server::Disconnect> for Disconnect {
+    fn from(disconnect: relay_server::Disconnect) -> Self {
+        Self {
+            reason: disconnect.reason.into(),
+            timestamp: unix_ms(disconnect.time),
+        }
+    }
+}
+
+/// Why a PHY session ended.
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+enum DisconnectReason {
+    /// The client closed its connection.
+    ClientClosed,
+    /// The client connected again with the same token, replacing the session.
+    Replaced,
+    /// The session was unregistered, invalidating its token.
+    Unregistered,
+    /// The room of the session was unregistered.
+    RoomUnregistered,
+    /// The session reached its maximum duration, and was unregistered.
+    Expired,
+    /// A host of the room of the session kicked it.
+    Kicked,
+}
+impl From<relay_server::DisconnectReason> for DisconnectReason {
+    fn from(reason: relay_server::DisconnectReason) -> Self {
+        match reason {
+            relay_server::DisconnectReason::ClientClosed => DisconnectReason::ClientClosed,
+            relay_server::DisconnectReason::Replaced => DisconnectReason::Replaced,
+            relay_server::DisconnectReason::Unregistered => DisconnectReason::Unregistered,
+            relay_server::DisconnectReason::RoomUnregistered => DisconnectReason::RoomUnregistered,
+            relay_server::DisconnectReason::Expired => DisconnectReason::Expired,
+            relay_server::DisconnectReason::Kicked => DisconnectReason::Kicked,
+        }
+    }
+}
+
+/// Latency of requests to the media worker.
+#[derive(SimpleObject)]
+struct WorkerLoad {
+    /// Moving average of request latency, if sampled.
+    latency_ms: Option<f64>,
+    /// Latency above which non-essential requests are shed, if any.
+    threshold_ms: Option<f64>,
+    /// Whether non-essential requests are being shed.
+    overloaded: bool,
+}
+
+/// Control operations served and queued while the relay is saturated.
+#[derive(SimpleObject)]
+struct ControlQueue {
+    /// Operations served concurrently, if limited.
+    max_concurrent: Option<usize>,
+    /// Operations currently being served.
+    running: usize,
+    depths: Vec<QueueDepth>,
+}
+
+/// Queue depth of a class of control operations.
+#[derive(SimpleObject)]
+struct QueueDepth {
+    class: OperationClass,
+    /// Heavier classes are served first.
+    weight: u32,
+    /// Operations currently queued.
+    depth: usize,
+    /// Most operations queued at once since the relay started.
+    peak_depth: usize,
+}
+impl From<control_queue::QueueDepth> for QueueDepth {
+    fn from(queue_depth: control_queue::QueueDepth) -> Self {
+        Self {
+            class: queue_depth.class.into(),
+            weight: queue_depth.weight,
+            depth: queue_depth.depth,
+            peak_depth: queue_depth.peak_depth,
+        }
+    }
+}
+
+/// Limits on the size of GraphQL requests.
+#[derive(SimpleObject)]
+struct PayloadLimits {
+    /// Largest request body or websocket message accepted, if limited.
+    max_request_bytes: Option<usize>,
+    /// Largest variable accepted, as serialized JSON, if limited.
+    max_variable_bytes: Option<usize>,
+    /// Requests rejected for exceeding the request limit since the relay started.
+    requests_rejected: u64,
+    /// Requests rejected for exceeding the variable limit since the relay started.
+    variables_rejected: u64,
+}
+
+/// Limit on the rooms registered per Vulcast within a sliding window.
+#[derive(SimpleObject)]
+struct RoomChurn {
+    /// Rooms which may be registered per Vulcast within the window, if limited.
+    max_registrations: Option<u32>,
+    /// Length of the window in seconds.
+    window_secs: u64,
+    /// Rooms registered by the Vulcast within the window.
+    registrations: usize,
+    /// Registrations of all Vulcasts rejected since the relay started.
+    rejections: u64,
+}
+
+/// Class of control operation, by which queued operations are prioritized.
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+enum OperationClass {
+    /// Registration and unregistration of rooms and sessions.
+    Registration,
+    /// Operations which are cheap to serve.
+    Routine,
+    /// Stats and reports.
+    Stats,
+}
+impl From<control_queue::OperationClass> for OperationClass {
+    fn from(class: control_queue::OperationClass) -> Self {
+        match class {
+            control_queue::OperationClass::Registration => OperationClass::Registration,
+            control_queue::OperationClass::Routine => OperationClass::Routine,
+            control_queue::OperationClass::Stats => OperationClass::Stats,
+        }
+    }
+}
+
+/// Version and crypto configuration of the relay.
+#[derive(SimpleObject)]
+struct ServerInfo {
+    version: String,
+    /// Whether the signal and control endpoints are served over TLS.
+    tls: bool,
+    /// Whether only approved crypto suites are offered.
+    constrained_crypto: bool,
+    /// SRTP crypto suites permitted on plain transports.
+    srtp_crypto_suites: Vec<String>,
+}
+
 #[derive(SimpleObject)]
 struct Room {
     id: ID,
@@ -152,6 +956,663 @@ struct Session {
     id: ID,
 }
 
+/// Registered room, with the sessions registered to it.
+#[derive(SimpleObject)]
+struct RegisteredRoom {
+    id: ID,
+    vulcast_session_id: ID,
+    sessions: Vec<RegisteredSession>,
+}
+
+#[derive(SimpleObject)]
+struct RegisteredSession {
+    id: ID,
+    role: SessionRole,
+    /// Whether the PHY session of the session is connected.
+    connected: bool,
+}
+
+/// Limit on the count of a resource allocated by a session.
+#[derive(SimpleObject)]
+struct ResourceLimit {
+    resource: ResourceKind,
+    limit: usize,
+    /// Role the limit applies to, if role-specific.
+    role: Option<SessionRole>,
+    /// Room the limit applies to, if room-specific.
+    room: Option<Room>,
+}
+impl From<resource_policy::ResourceLimit> for ResourceLimit {
+    fn from(resource_limit: resource_policy::ResourceLimit) -> Self {
+        let (role, room) = match resource_limit.scope {
+            LimitScope::Default => (None, None),
+            LimitScope::Role(role) => (Some(role.into()), None),
+            LimitScope::Room(foreign_room_id) => (
+                None,
+                Some(Room {
+                    id: foreign_room_id.into(),
+                }),
+            ),
+        };
+        Self {
+            resource: resource_limit.resource.into(),
+            limit: resource_limit.limit,
+            role,
+            room,
+        }
+    }
+}
+
+/// Crypto parameters in use on a transport.
+#[derive(SimpleObject)]
+struct TransportCrypto {
+    transport_id: ID,
+    /// State of the DTLS handshake, if this is a WebRTC transport.
+    dtls_state: Option<String>,
+    /// Local DTLS role, if this is a WebRTC transport.
+    dtls_role: Option<String>,
+    /// Hash algorithms of the local DTLS certificate fingerprints.
+    fingerprint_algorithms: Vec<String>,
+    /// SRTP crypto suite, if this is a plain transport with SRTP enabled.
+    srtp_crypto_suite: Option<String>,
+}
+impl From<session::TransportCrypto> for TransportCrypto {
+    fn from(crypto: session::TransportCrypto) -> Self {
+        let (dtls_role, fingerprint_algorithms) = match crypto.dtls_parameters {
+            Some(dtls_parameters) => (
+                serde_name(&dtls_parameters.role),
+                dtls_parameters
+                    .fingerprints
+                    .iter()
+                    .filter_map(|fingerprint| {
+                        serde_json::to_value(fingerprint)
+                            .ok()?
+                            .get("algorithm")?
+                            .as_str()
+                            .map(String::from)
+                    })
+                    .collect(),
+            ),
+            None => (None, vec![]),
+        };
+        Self {
+            transport_id: crypto.transport_id.to_string().into(),
+            dtls_state: crypto.dtls_state.as_ref().and_then(serde_name),
+            dtls_role,
+            fingerprint_algorithms,
+            srtp_crypto_suite: crypto.srtp_crypto_suite.as_ref().and_then(serde_name),
+        }
+    }
+}
+
+/// Statistics of the resources of a session.
+#[derive(SimpleObject)]
+struct Stats {
+    consumers: Vec<ConsumerStats>,
+    producers: Vec<ProducerStats>,
+    data_consumers: Vec<DataConsumerStats>,
+    data_producers: Vec<DataProducerStats>,
+    transports: Vec<TransportStats>,
+}
+// integer widths of stats differ between fields and mediasoup versions, so all
+// counts are widened to u64
+#[allow(clippy::unnecessary_cast)]
+impl From<session::Stats> for Stats {
+    fn from(stats: session::Stats) -> Self {
+        let data_relay_stats = stats.data_relay_stats;
+        let webrtc_transports =
+            stats
+                .webrtc_transport_stats
+                .into_iter()
+                .filter_map(|(id, stats)| {
+                    let stat = stats.into_iter().next()?;
+                    Some(TransportStats {
+                        transport_id: id.to_string().into(),
+                        bytes_received: stat.bytes_received as u64,
+                        recv_bitrate: stat.recv_bitrate as u64,
+                        bytes_sent: stat.bytes_sent as u64,
+                        send_bitrate: stat.send_bitrate as u64,
+                        available_outgoing_bitrate: stat
+                            .available_outgoing_bitrate
+                            .map(|bitrate| bitrate as u64),
+                        ice_state: serde_name(&stat.ice_state),
+                        dtls_state: serde_name(&stat.dtls_state),
+                    })
+                });
+        let plain_transports = stats
+            .plain_transport_stats
+            .into_iter()
+            .filter_map(|(id, stats)| {
+                let stat = stats.into_iter().next()?;
+                Some(TransportStats {
+                    transport_id: id.to_string().into(),
+                    bytes_received: stat.bytes_received as u64,
+                    recv_bitrate: stat.recv_bitrate as u64,
+                    bytes_sent: stat.bytes_sent as u64,
+                    send_bitrate: stat.send_bitrate as u64,
+                    available_outgoing_bitrate: stat
+                        .available_outgoing_bitrate
+                        .map(|bitrate| bitrate as u64),
+                    ice_state: None,
+                    dtls_state: None,
+                })
+            });
+        Self {
+            consumers: stats
+                .consumer_stats
+                .into_iter()
+                .map(|(id, stat)| ConsumerStats {
+                    consumer_id: id.to_string().into(),
+                    stream: stat.into(),
+                })
+                .collect(),
+            producers: stats
+                .producer_stats
+                .into_iter()
+                .map(|(id, stats)| ProducerStats {
+                    producer_id: id.to_string().into(),
+                    streams: stats.into_iter().map(RtpStreamStats::from).collect(),
+                })
+                .collect(),
+            data_consumers: stats
+                .data_consumer_stats
+                .into_iter()
+                .filter_map(|(id, stats)| {
+                    let stat = stats.into_iter().next()?;
+                    Some(DataConsumerStats {
+                        data_consumer_id: id.to_string().into(),
+                        label: stat.label,
+                        messages_sent: stat.messages_sent as u64,
+                        bytes_sent: stat.bytes_sent as u64,
+                        buffered_amount: stat.buffered_amount as u64,
+                    })
+                })
+                .collect(),
+            data_producers: stats
+                .data_producer_stats
+                .into_iter()
+                .filter_map(|(id, stats)| {
+                    let stat = stats.into_iter().next()?;
+                    let relay_stat = data_relay_stats.get(&id);
+                    Some(DataProducerStats {
+                        data_producer_id: id.to_string().into(),
+                        label: stat.label,
+                        messages_received: stat.messages_received as u64,
+                        bytes_received: stat.bytes_received as u64,
+                        messages_relayed: relay_stat.map(|relay_stat| relay_stat.relayed),
+                        messages_dropped: relay_stat.map(|relay_stat| relay_stat.dropped),
+                    })
+                })
+                .collect(),
+            transports: webrtc_transports.chain(plain_transports).collect(),
+        }
+    }
+}
+
+/// Theoretical and current headroom of a relay instance.
+#[derive(SimpleObject)]
+struct Capacity {
+    /// Ports the media worker allocates to transports, if known.
+    rtc_ports: Option<usize>,
+    /// Ports not yet allocated to transports, if known.
+    rtc_ports_remaining: Option<usize>,
+    /// CPU usage of the media worker, as a fraction of one core, if sampled.
+    worker_cpu_usage: Option<f64>,
+    /// CPU usage the media worker may grow by before it is considered saturated,
+    /// if sampled.
+    worker_cpu_headroom: Option<f64>,
+    /// Cap on the incoming bitrate (bps) of each transport, if any.
+    max_incoming_bitrate: Option<u32>,
+    /// Consumers a web client may create, unless overridden in its room.
+    max_consumers_per_web_client: usize,
+    sessions: usize,
+    transports: usize,
+    producers: usize,
+    consumers: usize,
+    /// Estimate of the additional consumers this relay supports, limited by whichever
+    /// of CPU and ports runs out first, if either is known.
+    additional_consumers: Option<usize>,
+}
+impl From<capacity::Capacity> for Capacity {
+    fn from(capacity: capacity::Capacity) -> Self {
+        Self {
+            rtc_ports: capacity.rtc_ports,
+            rtc_ports_remaining: capacity.rtc_ports_remaining,
+            worker_cpu_usage: capacity.worker_cpu_usage,
+            worker_cpu_headroom: capacity.worker_cpu_headroom,
+            max_incoming_bitrate: capacity.max_incoming_bitrate,
+            max_consumers_per_web_client: capacity.max_consumers_per_web_client,
+            sessions: capacity.sessions,
+            transports: capacity.transports,
+            producers: capacity.producers,
+            consumers: capacity.consumers,
+            additional_consumers: capacity.additional_consumers,
+        }
+    }
+}
+
+/// Summary of the load on a relay instance.
+#[derive(SimpleObject)]
+struct ServerStats {
+    rooms: usize,
+    /// Sessions connected to the rooms.
+    sessions: usize,
+    producers: usize,
+    consumers: usize,
+    data_producers: usize,
+    data_consumers: usize,
+    /// Routers alive in the media worker.
+    worker_routers: usize,
+    /// CPU time spent by the media worker in user mode, in milliseconds.
+    worker_user_time_ms: u64,
+    /// CPU time spent by the media worker in kernel mode, in milliseconds.
+    worker_system_time_ms: u64,
+    /// Peak resident memory of the media worker in KiB.
+    worker_max_rss_kib: u64,
+    /// RTP bytes received by open transports since they were created.
+    rtp_bytes_received: u64,
+    /// RTP bytes sent by open transports since they were created.
+    rtp_bytes_sent: u64,
+}
+impl From<server_stats::ServerStats> for ServerStats {
+    fn from(stats: server_stats::ServerStats) -> Self {
+        Self {
+            rooms: stats.rooms,
+            sessions: stats.sessions,
+            producers: stats.producers,
+            consumers: stats.consumers,
+            data_producers: stats.data_producers,
+            data_consumers: stats.data_consumers,
+            worker_routers: stats.worker_routers,
+            worker_user_time_ms: stats.worker_user_time_ms,
+            worker_system_time_ms: stats.worker_system_time_ms,
+            worker_max_rss_kib: stats.worker_max_rss_kib,
+            rtp_bytes_received: stats.rtp_bytes_received,
+            rtp_bytes_sent: stats.rtp_bytes_sent,
+        }
+    }
+}
+
+/// Stats aggregated across the sessions of a room.
+#[derive(SimpleObject)]
+struct RoomStats {
+    vulcasts: usize,
+    web_clients: usize,
+    hosts: usize,
+    /// Bitrate received from the sessions, in bits per second.
+    recv_bitrate: u64,
+    /// Bitrate sent to the sessions, in bits per second.
+    send_bitrate: u64,
+    /// RTP packets received by producers.
+    packets_received: u64,
+    /// RTP packets lost before reaching producers.
+    packets_lost: u64,
+    /// Median round trip time of consumers in milliseconds, if known.
+    round_trip_time_p50: Option<f64>,
+    round_trip_time_p90: Option<f64>,
+    round_trip_time_p99: Option<f64>,
+    /// Subscriptions to the messages of the room, e.g. producer announcements.
+    subscribers: usize,
+    messages_sent: u64,
+    /// Messages received by subscribers, counting each subscriber separately.
+    messages_delivered: u64,
+    /// Mean time from sending a message until a subscriber received it in
+    /// milliseconds, if any were delivered.
+    fan_out_latency_mean: Option<f64>,
+    fan_out_latency_max: Option<f64>,
+}
+impl From<room_stats::RoomStats> for RoomStats {
+    fn from(stats: room_stats::RoomStats) -> Self {
+        Self {
+            vulcasts: stats.vulcasts,
+            web_clients: stats.web_clients,
+            hosts: stats.hosts,
+            recv_bitrate: stats.recv_bitrate,
+            send_bitrate: stats.send_bitrate,
+            packets_received: stats.packets_received,
+            packets_lost: stats.packets_lost,
+            round_trip_time_p50: stats.round_trip_time_p50,
+            round_trip_time_p90: stats.round_trip_time_p90,
+            round_trip_time_p99: stats.round_trip_time_p99,
+            subscribers: stats.subscribers,
+            messages_sent: stats.messages_sent,
+            messages_delivered: stats.messages_delivered,
+            fan_out_latency_mean: stats.fan_out_latency_mean,
+            fan_out_latency_max: stats.fan_out_latency_max,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+struct ConsumerStats {
+    consumer_id: ID,
+    /// RTP stream sent to the client.
+    stream: RtpStreamStats,
+}
+
+#[derive(SimpleObject)]
+struct ProducerStats {
+    producer_id: ID,
+    /// RTP streams received from the client, one per simulcast layer.
+    streams: Vec<RtpStreamStats>,
+}
+
+/// Statistics of an RTP stream of a producer or consumer.
+#[derive(SimpleObject)]
+struct RtpStreamStats {
+    ssrc: u32,
+    /// RID of the stream, if it is a simulcast layer of a producer.
+    rid: Option<String>,
+    mime_type: Option<String>,
+    packet_count: u64,
+    byte_count: u64,
+    /// Bitrate in bits per second.
+    bitrate: u64,
+    packets_lost: u64,
+    /// Fraction of packets lost, from 0 to 255.
+    fraction_lost: u32,
+    nack_count: u64,
+    pli_count: u64,
+    fir_count: u64,
+    /// Score of the stream, from 0 (bad) to 10 (perfect).
+    score: u32,
+    /// Round trip time in milliseconds, if known.
+    round_trip_time: Option<f64>,
+    /// Jitter of the stream, if it is received by a producer.
+    jitter: Option<u64>,
+}
+#[allow(clippy::unnecessary_cast)]
+impl From<mediasoup::consumer::ConsumerStat> for RtpStreamStats {
+    fn from(stat: mediasoup::consumer::ConsumerStat) -> Self {
+        Self {
+            ssrc: stat.ssrc,
+            rid: None,
+            mime_type: serde_name(&stat.mime_type),
+            packet_count: stat.packet_count as u64,
+            byte_count: stat.byte_count as u64,
+            bitrate: stat.bitrate as u64,
+            packets_lost: stat.packets_lost as u64,
+            fraction_lost: stat.fraction_lost as u32,
+            nack_count: stat.nack_count as u64,
+            pli_count: stat.pli_count as u64,
+            fir_count: stat.fir_count as u64,
+            score: stat.score as u32,
+            round_trip_time: stat.round_trip_time.map(|rtt| rtt as f64),
+            jitter: None,
+        }
+    }
+}
+#[allow(clippy::unnecessary_cast)]
+impl From<mediasoup::producer::ProducerStat> for RtpStreamStats {
+    fn from(stat: mediasoup::producer::ProducerStat) -> Self {
+        Self {
+            ssrc: stat.ssrc,
+            rid: stat.rid,
+            mime_type: serde_name(&stat.mime_type),
+            packet_count: stat.packet_count as u64,
+            byte_count: stat.byte_count as u64,
+            bitrate: stat.bitrate as u64,
+            packets_lost: stat.packets_lost as u64,
+            fraction_lost: stat.fraction_lost as u32,
+            nack_count: stat.nack_count as u64,
+            pli_count: stat.pli_count as u64,
+            fir_count: stat.fir_count as u64,
+            score: stat.score as u32,
+            round_trip_time: stat.round_trip_time.map(|rtt| rtt as f64),
+            jitter: Some(stat.jitter as u64),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+struct DataConsumerStats {
+    data_consumer_id: ID,
+    label: String,
+    messages_sent: u64,
+    bytes_sent: u64,
+    /// Bytes queued to be sent to the client.
+    buffered_amount: u64,
+}
+
+#[derive(SimpleObject)]
+struct DataProducerStats {
+    data_producer_id: ID,
+    label: String,
+    messages_received: u64,
+    bytes_received: u64,
+    /// Messages which passed the data filter, if data is relayed through the server.
+    messages_relayed: Option<u64>,
+    /// Messages dropped by the data filter, if data is relayed through the server.
+    messages_dropped: Option<u64>,
+}
+
+#[derive(SimpleObject)]
+struct TransportStats {
+    transport_id: ID,
+    bytes_received: u64,
+    /// Receive bitrate in bits per second.
+    recv_bitrate: u64,
+    bytes_sent: u64,
+    /// Send bitrate in bits per second.
+    send_bitrate: u64,
+    /// Outgoing bitrate estimated by congestion control, if known.
+    available_outgoing_bitrate: Option<u64>,
+    /// State of ICE, if this is a WebRTC transport.
+    ice_state: Option<String>,
+    /// State of the DTLS handshake, if this is a WebRTC transport.
+    dtls_state: Option<String>,
+}
+
+fn set_subsystem_enabled(
+    ctx: &Context<'_>,
+    subsystem: Subsystem,
+    enabled: bool,
+) -> Vec<SubsystemStatus> {
+    let relay_server = ctx.data_unchecked::<RelayServer>();
+    relay_server
+        .subsystems()
+        .set_enabled(subsystem.into(), enabled);
+    subsystem_statuses(ctx)
+}
+
+fn subsystem_statuses(ctx: &Context<'_>) -> Vec<SubsystemStatus> {
+    let subsystems = ctx.data_unchecked::<RelayServer>().subsystems();
+    subsystems::Subsystem::ALL
+        .into_iter()
+        .map(|subsystem| SubsystemStatus {
+            subsystem: subsystem.into(),
+            enabled: subsystems.enabled(subsystem),
+        })
+        .collect()
+}
+
+fn set_room_locked(ctx: &Context<'_>, room_id: ID, locked: bool) -> SetRoomLockedResult {
+    let relay_server = ctx.data_unchecked::<RelayServer>();
+    match relay_server.set_room_locked(ForeignRoomId::from(room_id.clone()), locked) {
+        Ok(_) => SetRoomLockedResult::Ok(Room { id: room_id }),
+        Err(err) => err.into(),
+    }
+}
+
+fn set_room_input_locked(ctx: &Context<'_>, room_id: ID, locked: bool) -> RoomInputResult {
+    let relay_server = ctx.data_unchecked::<RelayServer>();
+    match relay_server.get_room(&ForeignRoomId::from(room_id.clone())) {
+        Some(room) => {
+            room.set_input_locked(locked);
+            let kind = if locked {
+                room::ModerationKind::LockInput
+            } else {
+                room::ModerationKind::UnlockInput
+            };
+            room.record_moderation(kind, None, None, None);
+            RoomInputResult::Ok(Room { id: room_id })
+        }
+        None => RoomInputResult::UnknownRoom(UnknownRoomError {
+            room: Room { id: room_id },
+        }),
+    }
+}
+
+pub(crate) fn version() -> String {
+    format!(
+        "{}_{}_{}_{}",
+        built_info::PKG_NAME,
+        built_info::PKG_VERSION,
+        built_info::TARGET,
+        built_info::PROFILE
+    )
+}
+
+/// Get a time in milliseconds since the Unix epoch.
+fn unix_ms(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+        * 1000.0
+}
+
+/// Get the name a unit enum variant is serialized as.
+fn serde_name<T: serde::Serialize>(value: &T) -> Option<String> {
+    serde_json::to_value(value).ok()?.as_str().map(String::from)
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+enum ResourceKind {
+    Consumer,
+    Producer,
+    DataConsumer,
+    DataProducer,
+    WebrtcTransport,
+    PlainTransport,
+}
+impl From<ResourceKind> for ResourceType {
+    fn from(resource: ResourceKind) -> Self {
+        match resource {
+            ResourceKind::Consumer => ResourceType::Consumer,
+            ResourceKind::Producer => ResourceType::Producer,
+            ResourceKind::DataConsumer => ResourceType::DataConsumer,
+            ResourceKind::DataProducer => ResourceType::DataProducer,
+            ResourceKind::WebrtcTransport => ResourceType::WebrtcTransport,
+            ResourceKind::PlainTransport => ResourceType::PlainTransport,
+        }
+    }
+}
+impl From<ResourceType> for ResourceKind {
+    fn from(resource: ResourceType) -> Self {
+        match resource {
+            ResourceType::Consumer => ResourceKind::Consumer,
+            ResourceType::Producer => ResourceKind::Producer,
+            ResourceType::DataConsumer => ResourceKind::DataConsumer,
+            ResourceType::DataProducer => ResourceKind::DataProducer,
+            ResourceType::WebrtcTransport => ResourceKind::WebrtcTransport,
+            ResourceType::PlainTransport => ResourceKind::PlainTransport,
+        }
+    }
+}
+
+/// Media carried by a room.
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+enum RoomPreset {
+    /// Audio, video and data.
+    Standard,
+    /// Audio and data only, e.g. voice chat.
+    AudioOnly,
+}
+impl From<RoomPreset> for room::RoomPreset {
+    fn from(preset: RoomPreset) -> Self {
+        match preset {
+            RoomPreset::Standard => room::RoomPreset::Standard,
+            RoomPreset::AudioOnly => room::RoomPreset::AudioOnly,
+        }
+    }
+}
+
+/// Optional subsystem of the relay.
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+enum Subsystem {
+    /// Sampling of the latency of requests to the media worker.
+    WorkerSampling,
+    /// Audits of media objects for leaks.
+    LeakAudit,
+    /// Soak reports.
+    SoakReport,
+    /// New SRT ingest listeners.
+    SrtIngest,
+    /// New WHEP sessions.
+    Whep,
+}
+impl From<Subsystem> for subsystems::Subsystem {
+    fn from(subsystem: Subsystem) -> Self {
+        match subsystem {
+            Subsystem::WorkerSampling => subsystems::Subsystem::WorkerSampling,
+            Subsystem::LeakAudit => subsystems::Subsystem::LeakAudit,
+            Subsystem::SoakReport => subsystems::Subsystem::SoakReport,
+            Subsystem::SrtIngest => subsystems::Subsystem::SrtIngest,
+            Subsystem::Whep => subsystems::Subsystem::Whep,
+        }
+    }
+}
+impl From<subsystems::Subsystem> for Subsystem {
+    fn from(subsystem: subsystems::Subsystem) -> Self {
+        match subsystem {
+            subsystems::Subsystem::WorkerSampling => Subsystem::WorkerSampling,
+            subsystems::Subsystem::LeakAudit => Subsystem::LeakAudit,
+            subsystems::Subsystem::SoakReport => Subsystem::SoakReport,
+            subsystems::Subsystem::SrtIngest => Subsystem::SrtIngest,
+            subsystems::Subsystem::Whep => Subsystem::Whep,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+struct SubsystemStatus {
+    subsystem: Subsystem,
+    enabled: bool,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+enum StreamRole {
+    Game,
+    Camera,
+    Voice,
+    Screen,
+}
+impl From<StreamRole> for stream_policy::StreamRole {
+    fn from(stream_role: StreamRole) -> Self {
+        match stream_role {
+            StreamRole::Game => stream_policy::StreamRole::Game,
+            StreamRole::Camera => stream_policy::StreamRole::Camera,
+            StreamRole::Voice => stream_policy::StreamRole::Voice,
+            StreamRole::Screen => stream_policy::StreamRole::Screen,
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+enum SessionRole {
+    Vulcast,
+    WebClient,
+    Host,
+}
+impl From<SessionRole> for Role {
+    fn from(role: SessionRole) -> Self {
+        match role {
+            SessionRole::Vulcast => Role::Vulcast,
+            SessionRole::WebClient => Role::WebClient,
+            SessionRole::Host => Role::Host,
+        }
+    }
+}
+impl From<Role> for SessionRole {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::Vulcast => SessionRole::Vulcast,
+            Role::WebClient => SessionRole::WebClient,
+            Role::Host => SessionRole::Host,
+        }
+    }
+}
+
 #[derive(SimpleObject)]
 struct SessionWithToken {
     id: ID,
@@ -163,6 +1624,18 @@ struct SessionWithToken {
 struct VulcastInRoomError {
     vulcast: Session,
 }
+/// The Vulcast already negotiated media of another preset than the specified room.
+#[derive(SimpleObject)]
+struct PresetUnavailableError {
+    room: Room,
+}
+/// The Vulcast registered as many rooms as allowed recently.
+#[derive(SimpleObject)]
+struct ChurnLimitedError {
+    vulcast: Session,
+    /// Time until the Vulcast may register another room, in milliseconds.
+    retry_after_ms: u64,
+}
 /// The specified room does not exist.
 #[derive(SimpleObject)]
 struct UnknownRoomError {
@@ -179,6 +1652,8 @@ enum RegisterRoomResult {
     Ok(Room),
     VulcastInRoom(VulcastInRoomError),
     UnknownSession(UnknownSessionError),
+    PresetUnavailable(PresetUnavailableError),
+    ChurnLimited(ChurnLimitedError),
 }
 impl From<RegisterRoomError> for RegisterRoomResult {
     fn from(err: RegisterRoomError) -> Self {
@@ -200,6 +1675,22 @@ impl From<RegisterRoomError> for RegisterRoomResult {
                     },
                 })
             }
+            RegisterRoomError::PresetUnavailable(foreign_room_id) => {
+                RegisterRoomResult::PresetUnavailable(PresetUnavailableError {
+                    room: Room {
+                        id: foreign_room_id.into(),
+                    },
+                })
+            }
+            RegisterRoomError::ChurnLimited {
+                vulcast_fsid,
+                retry_after,
+            } => RegisterRoomResult::ChurnLimited(ChurnLimitedError {
+                vulcast: Session {
+                    id: vulcast_fsid.into(),
+                },
+                retry_after_ms: retry_after.as_millis() as u64,
+            }),
         }
     }
 }
@@ -223,6 +1714,113 @@ impl From<UnregisterRoomError> for UnregisterRoomResult {
     }
 }
 
+#[derive(Union)]
+enum SendRoomMessageResult {
+    Ok(Room),
+    UnknownRoom(UnknownRoomError),
+}
+
+#[derive(Union)]
+enum SetRoomDataLimitsResult {
+    Ok(Room),
+    UnknownRoom(UnknownRoomError),
+}
+impl From<SetRoomDataFilterError> for SetRoomDataLimitsResult {
+    fn from(err: SetRoomDataFilterError) -> Self {
+        match err {
+            SetRoomDataFilterError::UnknownRoom(foreign_room_id) => {
+                SetRoomDataLimitsResult::UnknownRoom(UnknownRoomError {
+                    room: Room {
+                        id: foreign_room_id.into(),
+                    },
+                })
+            }
+        }
+    }
+}
+
+#[derive(Union)]
+enum SetRoomTurnServerResult {
+    Ok(Room),
+    UnknownRoom(UnknownRoomError),
+}
+impl From<SetRoomTurnConfigError> for SetRoomTurnServerResult {
+    fn from(err: SetRoomTurnConfigError) -> Self {
+        match err {
+            SetRoomTurnConfigError::UnknownRoom(foreign_room_id) => {
+                SetRoomTurnServerResult::UnknownRoom(UnknownRoomError {
+                    room: Room {
+                        id: foreign_room_id.into(),
+                    },
+                })
+            }
+        }
+    }
+}
+
+#[derive(Union)]
+enum SetRoomPublicResult {
+    Ok(Room),
+    UnknownRoom(UnknownRoomError),
+}
+impl From<SetRoomPublicError> for SetRoomPublicResult {
+    fn from(err: SetRoomPublicError) -> Self {
+        match err {
+            SetRoomPublicError::UnknownRoom(foreign_room_id) => {
+                SetRoomPublicResult::UnknownRoom(UnknownRoomError {
+                    room: Room {
+                        id: foreign_room_id.into(),
+                    },
+                })
+            }
+        }
+    }
+}
+
+#[derive(Union)]
+enum SetRoomStreamPolicyResult {
+    Ok(Room),
+    UnknownRoom(UnknownRoomError),
+}
+impl From<SetRoomStreamPolicyError> for SetRoomStreamPolicyResult {
+    fn from(err: SetRoomStreamPolicyError) -> Self {
+        match err {
+            SetRoomStreamPolicyError::UnknownRoom(foreign_room_id) => {
+                SetRoomStreamPolicyResult::UnknownRoom(UnknownRoomError {
+                    room: Room {
+                        id: foreign_room_id.into(),
+                    },
+                })
+            }
+        }
+    }
+}
+
+#[derive(Union)]
+enum SetRoomLockedResult {
+    Ok(Room),
+    UnknownRoom(UnknownRoomError),
+}
+impl From<SetRoomLockedError> for SetRoomLockedResult {
+    fn from(err: SetRoomLockedError) -> Self {
+        match err {
+            SetRoomLockedError::UnknownRoom(foreign_room_id) => {
+                SetRoomLockedResult::UnknownRoom(UnknownRoomError {
+                    room: Room {
+                        id: foreign_room_id.into(),
+                    },
+                })
+            }
+        }
+    }
+}
+
+#[derive(Union)]
+enum RoomInputResult {
+    Ok(Room),
+    UnknownRoom(UnknownRoomError),
+}
+
 #[derive(Union)]
 enum RegisterSessionResult {
     Ok(SessionWithToken),
@@ -280,10 +1878,24 @@ impl From<ID> for ForeignRoomId {
     }
 }
 
-pub type ControlSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+pub type ControlSchema = Schema<QueryRoot, MutationRoot, SubscriptionRoot>;
+
+/// Version of the control schema, incremented on breaking changes.
+/// See [`crate::schema_compat`] for the compatibility rules.
+pub const SCHEMA_VERSION: u32 = 1;
 
-pub fn schema(relay_server: RelayServer) -> ControlSchema {
-    ControlSchema::build(QueryRoot, MutationRoot, EmptySubscription)
+pub fn schema(
+    relay_server: RelayServer,
+    resource_policy: ResourcePolicy,
+    crypto_policy: CryptoPolicy,
+    deprecation_policy: DeprecationPolicy,
+) -> ControlSchema {
+    ControlSchema::build(QueryRoot, MutationRoot, SubscriptionRoot)
+        .extension(ControlPriority(relay_server.control_queue()))
+        .extension(PayloadLimitCheck(relay_server.payload_limit()))
         .data(relay_server)
+        .data(resource_policy)
+        .data(crypto_policy)
+        .data(deprecation_policy)
         .finish()
 }
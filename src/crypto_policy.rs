@@ -0,0 +1,91 @@
+use mediasoup::srtp_parameters::SrtpCryptoSuite;
+use thiserror::Error;
+
+use crate::session::PlainTransportConfig;
+
+/// Restrictions on the cryptography offered by the relay. In constrained mode,
+/// only algorithms approved for deployments with compliance requirements
+/// (e.g. FIPS 140) are offered, and configurations which would send media or
+/// signalling in the clear are rejected.
+///
+/// The DTLS cipher suites of WebRTC transports are chosen by the media worker, and
+/// the TLS versions and cipher suites of the endpoints by their TLS listeners, none
+/// of which can be restricted. Constrained mode only requires the endpoints to be
+/// served over TLS, and restricts SRTP on plain transports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CryptoPolicy {
+    constrained: bool,
+    tls: bool,
+}
+
+impl CryptoPolicy {
+    /// Validate the crypto configuration of the relay.
+    pub fn new(constrained: bool, tls: bool) -> Result<Self, CryptoPolicyError> {
+        if constrained && !tls {
+            return Err(CryptoPolicyError::TlsDisabled);
+        }
+        Ok(Self { constrained, tls })
+    }
+
+    pub fn constrained(&self) -> bool {
+        self.constrained
+    }
+
+    pub fn tls(&self) -> bool {
+        self.tls
+    }
+
+    /// Get the SRTP crypto suites permitted on plain transports, most preferred first.
+    pub fn srtp_crypto_suites(&self) -> Vec<SrtpCryptoSuite> {
+        if self.constrained {
+            vec![SrtpCryptoSuite::AesCm128HmacSha1_80]
+        } else {
+            vec![
+                SrtpCryptoSuite::AesCm128HmacSha1_80,
+                SrtpCryptoSuite::AesCm128HmacSha1_32,
+            ]
+        }
+    }
+
+    /// Check that a plain transport configuration is permitted.
+    pub fn check_plain_transport_config(
+        &self,
+        config: &PlainTransportConfig,
+    ) -> Result<(), CryptoPolicyError> {
+        if self.constrained && !config.enable_srtp {
+            return Err(CryptoPolicyError::SrtpDisabled);
+        }
+        self.check_srtp_crypto_suite(config.srtp_crypto_suite)
+    }
+
+    /// Check that an SRTP crypto suite is permitted.
+    pub fn check_srtp_crypto_suite(
+        &self,
+        crypto_suite: SrtpCryptoSuite,
+    ) -> Result<(), CryptoPolicyError> {
+        if self.srtp_crypto_suites().contains(&crypto_suite) {
+            Ok(())
+        } else {
+            Err(CryptoPolicyError::SrtpCryptoSuite(crypto_suite))
+        }
+    }
+}
+
+impl Default for CryptoPolicy {
+    fn default() -> Self {
+        Self {
+            constrained: false,
+            tls: true,
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum CryptoPolicyError {
+    #[error("TLS must be enabled in constrained crypto mode")]
+    TlsDisabled,
+    #[error("SRTP must be enabled on plain transports in constrained crypto mode")]
+    SrtpDisabled,
+    #[error("the SRTP crypto suite `{0:?}` is not permitted")]
+    SrtpCryptoSuite(SrtpCryptoSuite),
+}
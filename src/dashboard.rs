@@ -0,0 +1,42 @@
+use rust_embed::RustEmbed;
+use warp::http::{Response, StatusCode};
+use warp::{Filter, Rejection, Reply};
+
+/// Static assets of the operator dashboard, embedded in the binary.
+#[derive(RustEmbed)]
+#[folder = "dashboard/"]
+struct Assets;
+
+/// Operator dashboard routes, serving a small UI at `/dashboard` which shows rooms,
+/// sessions and live stats, backed by the control schema. The dashboard is served on
+/// the control endpoint, so it is reachable by whoever can reach the control endpoint.
+pub fn routes() -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let index = warp::path!("dashboard")
+        .and(warp::get())
+        .map(|| asset("index.html"));
+    let assets = warp::path!("dashboard" / String)
+        .and(warp::get())
+        .map(|path: String| asset(&path));
+    index.or(assets)
+}
+
+fn asset(path: &str) -> Response<Vec<u8>> {
+    let response = match Assets::get(path) {
+        Some(file) => Response::builder()
+            .header("content-type", content_type(path))
+            .body(file.data.into_owned()),
+        None => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Vec::new()),
+    };
+    response.unwrap()
+}
+
+fn content_type(path: &str) -> &'static str {
+    match path.rsplit_once('.').map(|(_, extension)| extension) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js") => "text/javascript; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use mediasoup::data_producer::DataProducerId;
+use mediasoup::data_structures::WebRtcMessage;
+use serde_json::Value;
+
+use crate::relay_server::Role;
+
+/// Hook which inspects data relayed through the server, from a data producer
+/// owned by a session to the sessions consuming it. Filters may rewrite a
+/// message to sanitize it, or drop it altogether by returning `None`.
+pub trait DataFilter: Debug + Send + Sync {
+    /// Filter a message from a data producer owned by a session with the given role.
+    fn filter<'a>(
+        &self,
+        data_producer_id: DataProducerId,
+        role: Role,
+        message: WebRtcMessage<'a>,
+    ) -> Option<WebRtcMessage<'a>>;
+
+    /// Called when a data producer is closed, to release any state held for it.
+    fn remove(&self, _data_producer_id: DataProducerId) {}
+}
+
+/// Filter which limits the rate of messages from each data producer,
+/// dropping messages in excess of the limit, and optionally drops oversized messages.
+#[derive(Debug)]
+pub struct RateLimitFilter {
+    max_messages: u32,
+    period: Duration,
+    max_message_size: Option<usize>,
+    windows: Mutex<HashMap<DataProducerId, Window>>,
+}
+
+#[derive(Debug)]
+struct Window {
+    start: Instant,
+    count: u32,
+}
+
+impl RateLimitFilter {
+    /// Allow at most `max_messages` messages per `period` from each data producer.
+    pub fn new(max_messages: u32, period: Duration) -> Self {
+        Self {
+            max_messages,
+            period,
+            max_message_size: None,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Also drop messages larger than `max_message_size` bytes.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = Some(max_message_size);
+        self
+    }
+}
+
+/// Get the size of the payload of a message in bytes.
+fn message_size(message: &WebRtcMessage<'_>) -> usize {
+    match message {
+        WebRtcMessage::String(string) => string.len(),
+        WebRtcMessage::Binary(binary) => binary.len(),
+        WebRtcMessage::EmptyString | WebRtcMessage::EmptyBinary => 0,
+    }
+}
+
+impl DataFilter for RateLimitFilter {
+    fn filter<'a>(
+        &self,
+        data_producer_id: DataProducerId,
+        _role: Role,
+        message: WebRtcMessage<'a>,
+    ) -> Option<WebRtcMessage<'a>> {
+        if let Some(max_message_size) = self.max_message_size {
+            if message_size(&message) > max_message_size {
+                return None;
+            }
+        }
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap();
+        let window = windows.entry(data_producer_id).or_insert(Window {
+            start: now,
+            count: 0,
+        });
+        if now.duration_since(window.start) >= self.period {
+            window.start = now;
+            window.count = 0;
+        }
+        if window.count < self.max_messages {
+            window.count += 1;
+            Some(message)
+        } else {
+            None
+        }
+    }
+
+    fn remove(&self, data_producer_id: DataProducerId) {
+        self.windows.lock().unwrap().remove(&data_producer_id);
+    }
+}
+
+/// Filter which protects input from WebClients against duplicated and replayed
+/// messages. Each message must be a JSON object with a sequence number `seq`
+/// greater than that of the previous message from the same data producer, and at
+/// most `window` ahead of it. Accepted messages are stamped with a sequence number
+/// assigned by the relay (`relaySeq`) and the time they were relayed (`relayTs`, in
+/// milliseconds since the UNIX epoch). Data from other roles passes through unchanged.
+#[derive(Debug)]
+pub struct SequenceFilter {
+    window: u64,
+    sequences: Mutex<HashMap<DataProducerId, Sequence>>,
+}
+
+#[derive(Debug, Default)]
+struct Sequence {
+    last_seq: Option<u64>,
+    relay_seq: u64,
+}
+
+impl SequenceFilter {
+    pub fn new(window: u64) -> Self {
+        Self {
+            window,
+            sequences: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl DataFilter for SequenceFilter {
+    fn filter<'a>(
+        &self,
+        data_producer_id: DataProducerId,
+        role: Role,
+        message: WebRtcMessage<'a>,
+    ) -> Option<WebRtcMessage<'a>> {
+        if role != Role::WebClient {
+            return Some(message);
+        }
+        let mut object = match message {
+            WebRtcMessage::String(string) => match serde_json::from_str(&string).ok()? {
+                Value::Object(object) => object,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let seq = object.get("seq")?.as_u64()?;
+
+        let mut sequences = self.sequences.lock().unwrap();
+        let sequence = sequences.entry(data_producer_id).or_default();
+        if let Some(last_seq) = sequence.last_seq {
+            if seq <= last_seq || seq - last_seq > self.window {
+                return None;
+            }
+        }
+        sequence.last_seq = Some(seq);
+        sequence.relay_seq += 1;
+
+        let relay_ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |x| x.as_millis() as u64);
+        object.insert("relaySeq".to_owned(), sequence.relay_seq.into());
+        object.insert("relayTs".to_owned(), relay_ts.into());
+        Some(WebRtcMessage::String(Value::Object(object).to_string()))
+    }
+
+    fn remove(&self, data_producer_id: DataProducerId) {
+        self.sequences.lock().unwrap().remove(&data_producer_id);
+    }
+}
+
+/// Filter which passes each message through several filters in order, dropping it
+/// if any filter drops it.
+#[derive(Debug)]
+pub struct FilterChain {
+    filters: Vec<Arc<dyn DataFilter>>,
+}
+
+impl FilterChain {
+    pub fn new(filters: Vec<Arc<dyn DataFilter>>) -> Self {
+        Self { filters }
+    }
+}
+
+impl DataFilter for FilterChain {
+    fn filter<'a>(
+        &self,
+        data_producer_id: DataProducerId,
+        role: Role,
+        message: WebRtcMessage<'a>,
+    ) -> Option<WebRtcMessage<'a>> {
+        self.filters.iter().try_fold(message, |message, filter| {
+            filter.filter(data_producer_id, role, message)
+        })
+    }
+
+    fn remove(&self, data_producer_id: DataProducerId) {
+        for filter in &self.filters {
+            filter.remove(data_producer_id);
+        }
+    }
+}
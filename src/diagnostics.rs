@@ -0,0 +1,139 @@
+//! Async diagnostics for stalls in the heavily concurrent session/room code, enabled by
+//! the `console` feature. With the feature, the relay serves tokio-console (on its
+//! default port 6669), and periodically logs the time spent polling instrumented paths
+//! along with metrics of the Tokio runtime. Task instrumentation and runtime metrics
+//! additionally require building with `RUSTFLAGS="--cfg tokio_unstable"`, which raises
+//! the tokio requirement to 1.39 for the runtime metrics API.
+//!
+//! Without the feature, instrumentation compiles down to the instrumented futures.
+
+use std::future::Future;
+use std::time::Duration;
+
+/// Async path instrumented with poll times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsyncPath {
+    /// Collection of session stats.
+    Stats,
+    /// Signal connections, from connection init until the connection closes.
+    Signal,
+}
+
+/// Record the time spent polling a future on an instrumented path.
+#[cfg(feature = "console")]
+pub async fn instrument<F: Future>(path: AsyncPath, future: F) -> F::Output {
+    use std::time::Instant;
+
+    let mut future = Box::pin(future);
+    futures::future::poll_fn(move |cx| {
+        let start = Instant::now();
+        let poll = future.as_mut().poll(cx);
+        console::poll_stats(path).record(start.elapsed());
+        poll
+    })
+    .await
+}
+#[cfg(not(feature = "console"))]
+pub async fn instrument<F: Future>(_path: AsyncPath, future: F) -> F::Output {
+    future.await
+}
+
+/// Serve tokio-console and log diagnostics at a fixed interval.
+#[cfg(feature = "console")]
+pub fn init(period: Duration) {
+    console_subscriber::init();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            console::report();
+        }
+    });
+}
+#[cfg(not(feature = "console"))]
+pub fn init(_period: Duration) {}
+
+#[cfg(feature = "console")]
+mod console {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+
+    use super::AsyncPath;
+
+    /// Polls of an instrumented path since the last report.
+    pub struct PollStats {
+        polls: AtomicU64,
+        busy_ns: AtomicU64,
+        slowest_ns: AtomicU64,
+    }
+
+    impl PollStats {
+        const fn new() -> Self {
+            Self {
+                polls: AtomicU64::new(0),
+                busy_ns: AtomicU64::new(0),
+                slowest_ns: AtomicU64::new(0),
+            }
+        }
+
+        pub fn record(&self, elapsed: Duration) {
+            let elapsed_ns = elapsed.as_nanos() as u64;
+            self.polls.fetch_add(1, Ordering::Relaxed);
+            self.busy_ns.fetch_add(elapsed_ns, Ordering::Relaxed);
+            self.slowest_ns.fetch_max(elapsed_ns, Ordering::Relaxed);
+        }
+
+        fn take(&self) -> (u64, Duration, Duration) {
+            (
+                self.polls.swap(0, Ordering::Relaxed),
+                Duration::from_nanos(self.busy_ns.swap(0, Ordering::Relaxed)),
+                Duration::from_nanos(self.slowest_ns.swap(0, Ordering::Relaxed)),
+            )
+        }
+    }
+
+    static STATS_POLLS: PollStats = PollStats::new();
+    static SIGNAL_POLLS: PollStats = PollStats::new();
+
+    pub fn poll_stats(path: AsyncPath) -> &'static PollStats {
+        match path {
+            AsyncPath::Stats => &STATS_POLLS,
+            AsyncPath::Signal => &SIGNAL_POLLS,
+        }
+    }
+
+    pub fn report() {
+        for path in [AsyncPath::Stats, AsyncPath::Signal] {
+            let (polls, busy, slowest) = poll_stats(path).take();
+            if polls > 0 {
+                log::info!(
+                    "{:?} path: {} polls, {:?} busy, slowest poll {:?}",
+                    path,
+                    polls,
+                    busy,
+                    slowest
+                );
+            }
+        }
+
+        #[cfg(tokio_unstable)]
+        {
+            let metrics = tokio::runtime::Handle::current().metrics();
+            let workers = metrics.num_workers();
+            let polls = (0..workers)
+                .map(|worker| metrics.worker_poll_count(worker))
+                .sum::<u64>();
+            let busy = (0..workers)
+                .map(|worker| metrics.worker_total_busy_duration(worker))
+                .sum::<Duration>();
+            log::info!(
+                "runtime: {} workers, {} tasks alive, {} queued, {} polls and {:?} busy in total",
+                workers,
+                metrics.num_alive_tasks(),
+                metrics.global_queue_depth(),
+                polls,
+                busy
+            );
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use futures::{future, stream, Stream, StreamExt};
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+
+/// Item with its position in an [`EventLog`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sequenced<T> {
+    /// Position of the item, numbered from 1 in the order items were sent.
+    pub sequence: u64,
+    pub item: T,
+    /// Time at which the item was sent.
+    pub sent_at: Instant,
+}
+
+/// Broadcast channel which numbers each item it sends, and retains the most recent
+/// items, so that subscribers which reconnect can resume after the last item they saw
+/// rather than resyncing from scratch. Items which are superseded by later items (e.g.
+/// periodic reports) may be numbered without being retained, and are not replayed.
+///
+/// Items are shared between subscribers rather than cloned for each, so that the cost
+/// of sending an item does not grow with the size of its payload.
+pub struct EventLog<T> {
+    shared: Arc<Shared<T>>,
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    tx: broadcast::Sender<Arc<Sequenced<T>>>,
+    /// whether an item is retained for replay
+    retain: fn(&T) -> bool,
+    /// deliveries to subscribers, held by subscribers apart from the channel so that
+    /// their streams still end once the log is dropped
+    fan_out: Arc<FanOut>,
+}
+
+struct State<T> {
+    /// maximum number of items retained
+    capacity: usize,
+    last_sequence: u64,
+    retained: VecDeque<Arc<Sequenced<T>>>,
+    /// sequence number of the last evicted item, up to which items may be missing
+    evicted_through: u64,
+}
+
+/// Deliveries of items to subscribers, counting each subscriber separately.
+#[derive(Debug, Default)]
+struct FanOut {
+    delivered: AtomicU64,
+    latency_ns: AtomicU64,
+    max_latency_ns: AtomicU64,
+}
+impl FanOut {
+    fn record(&self, sent_at: Instant) {
+        let latency_ns = sent_at.elapsed().as_nanos() as u64;
+        self.delivered.fetch_add(1, Ordering::Relaxed);
+        self.latency_ns.fetch_add(latency_ns, Ordering::Relaxed);
+        self.max_latency_ns.fetch_max(latency_ns, Ordering::Relaxed);
+    }
+}
+
+/// Cost of fanning the items of an [`EventLog`] out to its subscribers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FanOutStats {
+    /// Subscribers currently receiving items.
+    pub subscribers: usize,
+    /// Items sent.
+    pub sent: u64,
+    /// Items received by subscribers, counting each subscriber separately. Replayed
+    /// items are not counted.
+    pub delivered: u64,
+    /// Mean time from sending an item until a subscriber receives it, if any were
+    /// delivered. Includes the time items wait for slow subscribers to catch up.
+    pub mean_latency: Option<Duration>,
+    /// Longest time from sending an item until a subscriber received it, if any were
+    /// delivered.
+    pub max_latency: Option<Duration>,
+}
+
+impl<T> Clone for EventLog<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for EventLog<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.shared.state.lock().unwrap();
+        f.debug_struct("EventLog")
+            .field("capacity", &state.capacity)
+            .field("last_sequence", &state.last_sequence)
+            .field("retained", &state.retained.len())
+            .finish()
+    }
+}
+
+impl<T: Send + Sync + 'static> EventLog<T> {
+    /// Create a log which retains up to `capacity` items for which `retain` holds, and
+    /// broadcasts to subscribers lagging by up to `channel_capacity` items.
+    pub fn new(capacity: usize, channel_capacity: usize, retain: fn(&T) -> bool) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                state: Mutex::new(State {
+                    capacity,
+                    last_sequence: 0,
+                    retained: VecDeque::new(),
+                    evicted_through: 0,
+                }),
+                tx: broadcast::channel(channel_capacity).0,
+                retain,
+                fan_out: Arc::default(),
+            }),
+        }
+    }
+
+    /// Send an item to subscribers, returning its sequence number.
+    pub fn send(&self, item: T) -> u64 {
+        let mut state = self.shared.state.lock().unwrap();
+        state.last_sequence += 1;
+        let sequenced = Arc::new(Sequenced {
+            sequence: state.last_sequence,
+            item,
+            sent_at: Instant::now(),
+        });
+        if (self.shared.retain)(&sequenced.item) {
+            state.retained.push_back(sequenced.clone());
+            while state.retained.len() > state.capacity {
+                if let Some(evicted) = state.retained.pop_front() {
+                    state.evicted_through = evicted.sequence;
+                }
+            }
+        }
+        // send while locked, so that subscribers receive items in sequence
+        let _ = self.shared.tx.send(sequenced);
+        state.last_sequence
+    }
+
+    /// Get the sequence number of the last item sent, or 0 if none were.
+    pub fn last_sequence(&self) -> u64 {
+        self.shared.state.lock().unwrap().last_sequence
+    }
+
+    /// Get the cost of fanning items out to subscribers so far.
+    pub fn fan_out_stats(&self) -> FanOutStats {
+        let fan_out = &self.shared.fan_out;
+        let delivered = fan_out.delivered.load(Ordering::Relaxed);
+        let latency = |latency_ns: u64| (delivered > 0).then(|| Duration::from_nanos(latency_ns));
+        FanOutStats {
+            subscribers: self.shared.tx.receiver_count(),
+            sent: self.last_sequence(),
+            delivered,
+            mean_latency: latency(fan_out.latency_ns.load(Ordering::Relaxed) / delivered.max(1)),
+            max_latency: latency(fan_out.max_latency_ns.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Get a stream of items sent from now on. The stream ends if the subscriber lags
+    /// behind the channel.
+    pub fn stream(&self) -> impl Stream<Item = Arc<Sequenced<T>>> {
+        self.live(self.shared.tx.subscribe())
+    }
+
+    /// Get a stream of the retained items sent after the given sequence number, followed
+    /// by items sent from now on. Fails if items after the sequence number were already
+    /// evicted, or if the sequence number was never reached (e.g. it was seen in a
+    /// previous log).
+    pub fn resume(
+        &self,
+        resume_from: u64,
+    ) -> Result<impl Stream<Item = Arc<Sequenced<T>>>, ResumeError> {
+        let state = self.shared.state.lock().unwrap();
+        if resume_from < state.evicted_through || resume_from > state.last_sequence {
+            return Err(ResumeError {
+                resume_from,
+                evicted_through: state.evicted_through,
+                last_sequence: state.last_sequence,
+            });
+        }
+        let replay = state
+            .retained
+            .iter()
+            .filter(|sequenced| sequenced.sequence > resume_from)
+            .cloned()
+            .collect::<Vec<_>>();
+        // subscribe while locked, so that no items are missed or replayed twice
+        let rx = self.shared.tx.subscribe();
+        Ok(stream::iter(replay).chain(self.live(rx)))
+    }
+
+    fn live(
+        &self,
+        rx: broadcast::Receiver<Arc<Sequenced<T>>>,
+    ) -> impl Stream<Item = Arc<Sequenced<T>>> {
+        let fan_out = self.shared.fan_out.clone();
+        BroadcastStream::new(rx)
+            .take_while(|x| future::ready(x.is_ok()))
+            .map(move |x| {
+                let sequenced = x.unwrap();
+                fan_out.record(sequenced.sent_at);
+                sequenced
+            })
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("cannot resume after event {resume_from}, only after events {evicted_through} to {last_sequence}; resync required")]
+pub struct ResumeError {
+    pub resume_from: u64,
+    /// Sequence number of the last evicted event, after which events are retained.
+    pub evicted_through: u64,
+    /// Sequence number of the last event sent.
+    pub last_sequence: u64,
+}
@@ -0,0 +1,179 @@
+//! Outbound events emitted by the relay, e.g. to webhooks, an event bus or subscriptions.
+//!
+//! Events are serialized as JSON envelopes:
+//! ```json
+//! { "version": 1, "timestamp": 1650000000000, "type": "producerAvailable", "data": { ... } }
+//! ```
+//! Events of a room also carry the `sequence` number of the event within the room.
+//!
+//! The schema evolves under the following rules, so that consumers written against
+//! a version keep working with every later release of the same version:
+//! - Fields may be added to the data of an event. Added fields are optional, and
+//!   consumers must ignore fields they do not recognize.
+//! - Event types may be added. Consumers must ignore types they do not recognize.
+//! - Fields and event types are never renamed, removed, or changed in type or
+//!   meaning. Doing so requires incrementing [`SCHEMA_VERSION`].
+//!
+//! The serialized form of every event is pinned by the golden files in `tests/golden/events`.
+
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use mediasoup::data_producer::DataProducerId;
+use mediasoup::producer::ProducerId;
+use mediasoup::rtp_parameters::MediaKind;
+
+use crate::room::{AudioLevel, ClientState, Message};
+
+/// Version of the event schema, incremented on breaking changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Versioned, timestamped event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Envelope {
+    pub version: u32,
+    /// Milliseconds since the Unix epoch at which the event occurred.
+    pub timestamp: u64,
+    /// Position of the event among the events of its room, if sequenced. Numbered from
+    /// 1 in the order events occurred.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<u64>,
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+impl Envelope {
+    /// Wrap an event which occurred now.
+    pub fn new(event: Event) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        Self {
+            version: SCHEMA_VERSION,
+            timestamp,
+            sequence: None,
+            event,
+        }
+    }
+
+    /// Set the position of the event among the events of its room.
+    pub fn with_sequence(mut self, sequence: u64) -> Self {
+        self.sequence = Some(sequence);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "camelCase")]
+pub enum Event {
+    #[serde(rename_all = "camelCase")]
+    ProducerAvailable {
+        producer_id: ProducerId,
+        /// FSID of the session owning the producer.
+        session_id: Option<String>,
+        kind: Option<MediaKind>,
+        /// Label the producer was created with, if any.
+        label: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    ProducerClosed { producer_id: ProducerId },
+    #[serde(rename_all = "camelCase")]
+    DataProducerAvailable { data_producer_id: DataProducerId },
+    #[serde(rename_all = "camelCase")]
+    DataProducerClosed { data_producer_id: DataProducerId },
+    /// Dominant audio producer changed, or none if the room is silent.
+    #[serde(rename_all = "camelCase")]
+    ActiveSpeaker { speaker: Option<AudioLevelEvent> },
+    /// Audio levels of the loudest audio producers, empty if the room is silent.
+    #[serde(rename_all = "camelCase")]
+    AudioLevels { levels: Vec<AudioLevelEvent> },
+    #[serde(rename_all = "camelCase")]
+    VulcastCommand {
+        id: String,
+        /// FSID of the session which sent the command.
+        sender: String,
+        command: serde_json::Value,
+    },
+    /// WebClient holding control of input changed, or none if input is unarbitrated.
+    #[serde(rename_all = "camelCase")]
+    ControllerChanged { session_id: Option<String> },
+    /// Key of the shared room state was written, or removed if the value is null.
+    #[serde(rename_all = "camelCase")]
+    StateChanged {
+        key: String,
+        value: Option<serde_json::Value>,
+        /// FSID of the session which wrote the key.
+        session_id: String,
+    },
+    /// Client joined the room.
+    #[serde(rename_all = "camelCase")]
+    ClientJoined { session_id: String },
+    /// Client left the room.
+    #[serde(rename_all = "camelCase")]
+    ClientLeft { session_id: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioLevelEvent {
+    pub producer_id: ProducerId,
+    /// Average audio level in dBov, from -127 (silence) to 0 (loudest).
+    pub volume: i8,
+}
+
+impl From<AudioLevel> for AudioLevelEvent {
+    fn from(audio_level: AudioLevel) -> Self {
+        Self {
+            producer_id: audio_level.producer_id,
+            volume: audio_level.volume,
+        }
+    }
+}
+
+impl From<Message> for Event {
+    fn from(message: Message) -> Self {
+        match message {
+            Message::ProducerAvailable(announcement) => Event::ProducerAvailable {
+                producer_id: announcement.producer_id,
+                session_id: Some(announcement.foreign_session_id.0),
+                kind: Some(announcement.kind),
+                label: announcement.label,
+            },
+            Message::ProducerClosed(producer_id) => Event::ProducerClosed { producer_id },
+            Message::DataProducerAvailable(data_producer_id) => {
+                Event::DataProducerAvailable { data_producer_id }
+            }
+            Message::DataProducerClosed(data_producer_id) => {
+                Event::DataProducerClosed { data_producer_id }
+            }
+            Message::ActiveSpeaker(speaker) => Event::ActiveSpeaker {
+                speaker: speaker.map(Into::into),
+            },
+            Message::AudioLevels(levels) => Event::AudioLevels {
+                levels: levels.into_iter().map(Into::into).collect(),
+            },
+            Message::VulcastCommand(vulcast_command) => Event::VulcastCommand {
+                id: vulcast_command.id.to_string(),
+                sender: vulcast_command.sender.0,
+                command: vulcast_command.command,
+            },
+            Message::ControllerChanged(controller) => Event::ControllerChanged {
+                session_id: controller.map(|x| x.0),
+            },
+            Message::StateChanged(entry) => Event::StateChanged {
+                key: entry.key,
+                value: entry.value,
+                session_id: entry.writer.0,
+            },
+            Message::ClientState(update) => match update.state {
+                ClientState::Join => Event::ClientJoined {
+                    session_id: update.foreign_session_id.0,
+                },
+                ClientState::Leave => Event::ClientLeft {
+                    session_id: update.foreign_session_id.0,
+                },
+            },
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+use crate::relay_server::ForeignRoomId;
+
+/// Limits the rate at which sessions join each room, so that a thundering herd
+/// joining a popular room does not overwhelm transport creation. Joins beyond the
+/// rate are queued and admitted in turn, unless they would wait longer than the
+/// maximum queue delay, in which case they are rejected with a time to retry after.
+#[derive(Debug, Clone)]
+pub struct JoinThrottle {
+    shared: Arc<Mutex<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    /// joins admitted per second in each room, if limited
+    default_rate: Option<u32>,
+    /// rates overriding the default rate in specific rooms
+    room_rates: HashMap<ForeignRoomId, u32>,
+    /// longest time a join may be queued for
+    max_queue_delay: Duration,
+    /// earliest time the next join to each throttled room may be admitted
+    next_admissions: HashMap<ForeignRoomId, Instant>,
+}
+
+impl JoinThrottle {
+    pub fn new(max_queue_delay: Duration) -> Self {
+        Self {
+            shared: Arc::new(Mutex::new(State {
+                default_rate: None,
+                room_rates: HashMap::new(),
+                max_queue_delay,
+                next_admissions: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Limit the joins per second to each room. Joins are unlimited if unset.
+    pub fn set_default_rate(&self, joins_per_second: Option<u32>) {
+        let mut state = self.shared.lock().unwrap();
+        state.default_rate = joins_per_second;
+    }
+
+    /// Limit the joins per second to a specific room instead of the default rate.
+    /// The default rate is restored if unset.
+    pub fn set_room_rate(&self, frid: ForeignRoomId, joins_per_second: Option<u32>) {
+        let mut state = self.shared.lock().unwrap();
+        log::debug!("join rate of room {} = {:?}", &frid, &joins_per_second);
+        match joins_per_second {
+            Some(joins_per_second) => state.room_rates.insert(frid, joins_per_second),
+            None => state.room_rates.remove(&frid),
+        };
+    }
+
+    /// Set the longest time a join may be queued for.
+    pub fn set_max_queue_delay(&self, max_queue_delay: Duration) {
+        let mut state = self.shared.lock().unwrap();
+        state.max_queue_delay = max_queue_delay;
+    }
+
+    /// Resolve the joins per second to a room, if limited.
+    pub fn rate(&self, frid: &ForeignRoomId) -> Option<u32> {
+        let state = self.shared.lock().unwrap();
+        state.room_rates.get(frid).copied().or(state.default_rate)
+    }
+
+    /// Reserve a turn to join a room at the given time, returning how long the join
+    /// must be queued for until its turn.
+    pub fn reserve(
+        &self,
+        frid: &ForeignRoomId,
+        now: Instant,
+    ) -> Result<Duration, JoinThrottledError> {
+        let mut state = self.shared.lock().unwrap();
+        let rate = match state.room_rates.get(frid).copied().or(state.default_rate) {
+            Some(rate) => rate,
+            None => return Ok(Duration::ZERO),
+        };
+        if rate == 0 {
+            return Err(JoinThrottledError {
+                frid: frid.clone(),
+                retry_after: state.max_queue_delay.max(Duration::from_secs(1)),
+            });
+        }
+        let max_queue_delay = state.max_queue_delay;
+        let next_admission = state.next_admissions.entry(frid.clone()).or_insert(now);
+        let admission = (*next_admission).max(now);
+        let delay = admission - now;
+        if delay > max_queue_delay {
+            return Err(JoinThrottledError {
+                frid: frid.clone(),
+                retry_after: delay - max_queue_delay,
+            });
+        }
+        *next_admission = admission + Duration::from_secs(1) / rate;
+        // forget rooms whose queues have drained
+        state
+            .next_admissions
+            .retain(|_, admission| *admission > now);
+        Ok(delay)
+    }
+
+    /// Wait for a turn to join a room.
+    pub async fn admit(&self, frid: &ForeignRoomId) -> Result<(), JoinThrottledError> {
+        let delay = self.reserve(frid, Instant::now())?;
+        if !delay.is_zero() {
+            log::debug!("queued join to room {} for {:?}", frid, delay);
+            tokio::time::sleep(delay).await;
+        }
+        Ok(())
+    }
+}
+
+impl Default for JoinThrottle {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(2))
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("too many sessions are joining room {frid}, retry after {retry_after:?}")]
+pub struct JoinThrottledError {
+    pub frid: ForeignRoomId,
+    pub retry_after: Duration,
+}
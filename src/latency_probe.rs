@@ -0,0 +1,214 @@
+//! Measurement of the latency of media through the relay, from a packet arriving
+//! at a producer (ingest) to the same packet leaving a consumer (egress).
+//!
+//! Mediasoup does not let the relay insert header extensions, so packets are
+//! correlated by their RTP timestamp using RTP trace events of producers and
+//! consumers. Tracing every packet is costly, so the probe is meant for
+//! performance regression runs rather than production.
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use mediasoup::consumer::{Consumer, ConsumerId, ConsumerTraceEventData, ConsumerTraceEventType};
+use mediasoup::producer::{Producer, ProducerId, ProducerTraceEventData, ProducerTraceEventType};
+
+/// Maximum number of RTP timestamps remembered per producer. Packets which leave
+/// a consumer after this many later frames arrived are not measured.
+const MAX_PENDING_TIMESTAMPS: usize = 256;
+
+#[derive(Debug, Clone, Default)]
+pub struct LatencyProbe {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// arrival time (ms) of the first packet of recent RTP timestamps of each producer
+    ingress: HashMap<ProducerId, VecDeque<(u32, u64)>>,
+    /// latency measured through each consumer
+    consumers: HashMap<ConsumerId, ConsumerLatency>,
+}
+
+#[derive(Debug)]
+struct ConsumerLatency {
+    producer_id: ProducerId,
+    /// RTP timestamp last measured, so that each frame is measured once
+    last_rtp_timestamp: Option<u32>,
+    samples: u64,
+    total_ms: u64,
+    min_ms: u64,
+    max_ms: u64,
+    last_ms: u64,
+}
+
+/// Latency measured from a producer to one of its consumers, in milliseconds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairLatency {
+    pub producer_id: ProducerId,
+    pub consumer_id: ConsumerId,
+    /// Frames measured.
+    pub samples: u64,
+    pub min_ms: u64,
+    pub mean_ms: f64,
+    pub max_ms: u64,
+    pub last_ms: u64,
+}
+
+impl LatencyProbe {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the arrival of RTP packets at a producer until it closes.
+    pub async fn watch_producer(&self, producer: &Producer) -> Result<()> {
+        let producer_id = producer.id();
+        producer
+            .on_trace({
+                let probe = self.clone();
+                move |data| {
+                    if let ProducerTraceEventData::Rtp {
+                        timestamp, info, ..
+                    } = data
+                    {
+                        if let Some(rtp_timestamp) = rtp_timestamp(info) {
+                            probe.record_ingress(producer_id, rtp_timestamp, *timestamp);
+                        }
+                    }
+                }
+            })
+            .detach();
+        producer
+            .on_close({
+                let probe = self.clone();
+                move || {
+                    probe.state.lock().unwrap().ingress.remove(&producer_id);
+                }
+            })
+            .detach();
+        producer
+            .enable_trace_event(vec![ProducerTraceEventType::Rtp])
+            .await?;
+        Ok(())
+    }
+
+    /// Measure the departure of RTP packets from a consumer until it closes.
+    pub async fn watch_consumer(&self, consumer: &Consumer) -> Result<()> {
+        let consumer_id = consumer.id();
+        let producer_id = consumer.producer_id();
+        consumer
+            .on_trace({
+                let probe = self.clone();
+                move |data| {
+                    if let ConsumerTraceEventData::Rtp {
+                        timestamp, info, ..
+                    } = data
+                    {
+                        if let Some(rtp_timestamp) = rtp_timestamp(info) {
+                            probe.record_egress(
+                                consumer_id,
+                                producer_id,
+                                rtp_timestamp,
+                                *timestamp,
+                            );
+                        }
+                    }
+                }
+            })
+            .detach();
+        consumer
+            .on_close({
+                let probe = self.clone();
+                move || {
+                    probe.state.lock().unwrap().consumers.remove(&consumer_id);
+                }
+            })
+            .detach();
+        consumer
+            .enable_trace_event(vec![ConsumerTraceEventType::Rtp])
+            .await?;
+        Ok(())
+    }
+
+    /// Record a packet arriving at a producer at the given time (ms).
+    pub fn record_ingress(&self, producer_id: ProducerId, rtp_timestamp: u32, time_ms: u64) {
+        let mut state = self.state.lock().unwrap();
+        let pending = state.ingress.entry(producer_id).or_default();
+        // packets of a frame share a timestamp, remember the first to arrive
+        if pending.iter().any(|(x, _)| *x == rtp_timestamp) {
+            return;
+        }
+        pending.push_back((rtp_timestamp, time_ms));
+        if pending.len() > MAX_PENDING_TIMESTAMPS {
+            pending.pop_front();
+        }
+    }
+
+    /// Record a packet leaving a consumer at the given time (ms), measuring its
+    /// latency if its arrival was recorded.
+    pub fn record_egress(
+        &self,
+        consumer_id: ConsumerId,
+        producer_id: ProducerId,
+        rtp_timestamp: u32,
+        time_ms: u64,
+    ) {
+        let mut state = self.state.lock().unwrap();
+        let arrival_ms = match state.ingress.get(&producer_id).and_then(|pending| {
+            pending
+                .iter()
+                .find(|(x, _)| *x == rtp_timestamp)
+                .map(|(_, arrival_ms)| *arrival_ms)
+        }) {
+            Some(arrival_ms) => arrival_ms,
+            None => return,
+        };
+        let latency = state
+            .consumers
+            .entry(consumer_id)
+            .or_insert_with(|| ConsumerLatency {
+                producer_id,
+                last_rtp_timestamp: None,
+                samples: 0,
+                total_ms: 0,
+                min_ms: u64::MAX,
+                max_ms: 0,
+                last_ms: 0,
+            });
+        if latency.last_rtp_timestamp == Some(rtp_timestamp) {
+            return;
+        }
+        let latency_ms = time_ms.saturating_sub(arrival_ms);
+        latency.last_rtp_timestamp = Some(rtp_timestamp);
+        latency.samples += 1;
+        latency.total_ms += latency_ms;
+        latency.min_ms = latency.min_ms.min(latency_ms);
+        latency.max_ms = latency.max_ms.max(latency_ms);
+        latency.last_ms = latency_ms;
+    }
+
+    /// Get the latency measured through each open consumer.
+    pub fn report(&self) -> Vec<PairLatency> {
+        let state = self.state.lock().unwrap();
+        state
+            .consumers
+            .iter()
+            .map(|(consumer_id, latency)| PairLatency {
+                producer_id: latency.producer_id,
+                consumer_id: *consumer_id,
+                samples: latency.samples,
+                min_ms: latency.min_ms,
+                mean_ms: latency.total_ms as f64 / latency.samples as f64,
+                max_ms: latency.max_ms,
+                last_ms: latency.last_ms,
+            })
+            .collect()
+    }
+}
+
+/// Read the RTP timestamp from the packet info of an RTP trace event.
+fn rtp_timestamp(info: &serde_json::Value) -> Option<u32> {
+    info.get("timestamp")?.as_u64().map(|x| x as u32)
+}
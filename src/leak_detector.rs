@@ -0,0 +1,203 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use anyhow::Result;
+use mediasoup::{
+    consumer::ConsumerId, data_consumer::DataConsumerId, data_producer::DataProducerId,
+    producer::ProducerId, router::RouterId, transport::Transport, transport::TransportId,
+};
+
+use crate::relay_server::RelayServer;
+use crate::session::Resource;
+use crate::subsystems::Subsystem;
+
+/// Discrepancies between the objects alive in the Mediasoup worker and the
+/// objects tracked by rooms and sessions.
+#[derive(Debug, Default)]
+pub struct LeakReport {
+    /// Routers alive in the worker which do not belong to any room.
+    pub orphan_routers: Vec<RouterId>,
+    /// Transports alive in a router which are not tracked by any session.
+    pub leaked_transports: Vec<TransportId>,
+    /// Producers alive in a router which are not tracked by any session.
+    pub leaked_producers: Vec<ProducerId>,
+    /// Consumers alive in a router which are not tracked by any session.
+    pub leaked_consumers: Vec<ConsumerId>,
+    /// Data producers alive in a router which are not tracked by any session.
+    pub leaked_data_producers: Vec<DataProducerId>,
+    /// Data consumers alive in a router which are not tracked by any session.
+    pub leaked_data_consumers: Vec<DataConsumerId>,
+    /// Resources tracked by a session which have already been closed.
+    pub stale_resources: Vec<Resource>,
+}
+
+impl LeakReport {
+    pub fn is_clean(&self) -> bool {
+        self.orphan_routers.is_empty()
+            && self.leaked_transports.is_empty()
+            && self.leaked_producers.is_empty()
+            && self.leaked_consumers.is_empty()
+            && self.leaked_data_producers.is_empty()
+            && self.leaked_data_consumers.is_empty()
+            && self.stale_resources.is_empty()
+    }
+
+    /// Count the discrepancies found.
+    pub fn discrepancies(&self) -> usize {
+        self.orphan_routers.len()
+            + self.leaked_transports.len()
+            + self.leaked_producers.len()
+            + self.leaked_consumers.len()
+            + self.leaked_data_producers.len()
+            + self.leaked_data_consumers.len()
+            + self.stale_resources.len()
+    }
+}
+
+/// Compare Mediasoup object counts (via worker and router dumps) against the
+/// bookkeeping of every room and session in the relay.
+pub async fn audit(relay_server: &RelayServer) -> Result<LeakReport> {
+    let mut report = LeakReport::default();
+    let rooms = relay_server.get_rooms();
+
+    let worker_dump = relay_server.worker().dump().await?;
+    let room_router_ids = rooms
+        .iter()
+        .filter_map(|room| room.router())
+        .map(|router| router.id())
+        .collect::<HashSet<_>>();
+    report.orphan_routers = worker_dump
+        .router_ids
+        .into_iter()
+        .filter(|router_id| !room_router_ids.contains(router_id))
+        .collect();
+
+    for room in rooms {
+        let router = match room.router() {
+            Some(router) => router,
+            None => continue,
+        };
+        let router_dump = router.dump().await?;
+
+        let mut transports = HashSet::new();
+        let mut producers = HashSet::new();
+        let mut consumers = HashSet::new();
+        let mut data_producers = HashSet::new();
+        let mut data_consumers = HashSet::new();
+        // resources owned by the relay itself
+        if let Some(direct_transport) = room.direct_transport() {
+            transports.insert(direct_transport.id());
+        }
+        for data_producer in room.get_server_data_producers() {
+            data_producers.insert(data_producer.id());
+        }
+        for (data_consumer, data_producer) in room.get_relayed_data() {
+            data_consumers.insert(data_consumer.id());
+            data_producers.insert(data_producer.id());
+        }
+        for session in room.active_sessions() {
+            for transport in session.get_webrtc_transports() {
+                if transport.closed() {
+                    report
+                        .stale_resources
+                        .push(Resource::WebrtcTransport(transport.id()));
+                }
+                transports.insert(transport.id());
+            }
+            for transport in session.get_plain_transports() {
+                if transport.closed() {
+                    report
+                        .stale_resources
+                        .push(Resource::PlainTransport(transport.id()));
+                }
+                transports.insert(transport.id());
+            }
+            for producer in session.get_producers() {
+                if producer.closed() {
+                    report
+                        .stale_resources
+                        .push(Resource::Producer(producer.id()));
+                }
+                producers.insert(producer.id());
+            }
+            for consumer in session.get_consumers() {
+                if consumer.closed() {
+                    report
+                        .stale_resources
+                        .push(Resource::Consumer(consumer.id()));
+                }
+                consumers.insert(consumer.id());
+            }
+            for data_producer in session.get_data_producers() {
+                if data_producer.closed() {
+                    report
+                        .stale_resources
+                        .push(Resource::DataProducer(data_producer.id()));
+                }
+                data_producers.insert(data_producer.id());
+            }
+            for data_consumer in session.get_data_consumers() {
+                if data_consumer.closed() {
+                    report
+                        .stale_resources
+                        .push(Resource::DataConsumer(data_consumer.id()));
+                }
+                data_consumers.insert(data_consumer.id());
+            }
+        }
+
+        report.leaked_transports.extend(
+            router_dump
+                .transport_ids
+                .into_iter()
+                .filter(|id| !transports.contains(id)),
+        );
+        report.leaked_producers.extend(
+            router_dump
+                .map_producer_id_consumer_ids
+                .into_keys()
+                .filter(|id| !producers.contains(id)),
+        );
+        report.leaked_consumers.extend(
+            router_dump
+                .map_consumer_id_producer_id
+                .into_keys()
+                .filter(|id| !consumers.contains(id)),
+        );
+        report.leaked_data_producers.extend(
+            router_dump
+                .map_data_producer_id_data_consumer_ids
+                .into_keys()
+                .filter(|id| !data_producers.contains(id)),
+        );
+        report.leaked_data_consumers.extend(
+            router_dump
+                .map_data_consumer_id_data_producer_id
+                .into_keys()
+                .filter(|id| !data_consumers.contains(id)),
+        );
+    }
+    Ok(report)
+}
+
+/// Spawn a background task which audits the relay for leaks at a fixed interval.
+pub fn spawn(relay_server: RelayServer, period: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if !relay_server.subsystems().enabled(Subsystem::LeakAudit) {
+                continue;
+            }
+            if relay_server.worker_monitor().overloaded() {
+                log::warn!("leak audit skipped, worker is overloaded");
+                continue;
+            }
+            match audit(&relay_server).await {
+                Ok(report) if report.is_clean() => log::trace!("leak audit clean"),
+                Ok(report) => log::warn!("leak audit found discrepancies: {:#?}", report),
+                Err(err) => log::error!("leak audit failed: {}", err),
+            }
+        }
+    });
+}
@@ -1,12 +1,46 @@
 #[macro_use]
 pub mod util;
 
+pub mod capacity;
+pub mod chaos;
 pub mod cmdline;
+pub mod codecs;
+pub mod control_queue;
 pub mod control_schema;
+pub mod crypto_policy;
+pub mod dashboard;
+pub mod data_filter;
+pub mod diagnostics;
+pub mod event_log;
+pub mod events;
+pub mod join_throttle;
+pub mod latency_probe;
+pub mod leak_detector;
+pub mod message_store;
+pub mod negotiation;
+pub mod payload_limit;
+pub mod permissions;
+pub mod public_schema;
+pub mod reachability;
 pub mod relay_server;
+pub mod resource_policy;
 pub mod room;
+pub mod room_churn;
+pub mod room_gc;
+pub mod room_stats;
+pub mod schema_compat;
+pub mod server_stats;
 pub mod session;
 pub mod signal_schema;
+pub mod signal_server;
+pub mod soak_report;
+pub mod srt_ingest;
+pub mod stream_policy;
+pub mod subsystems;
+pub mod turn;
+pub mod version_gate;
+pub mod whep;
+pub mod worker_monitor;
 pub mod built_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
 }
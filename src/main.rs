@@ -1,32 +1,47 @@
-use async_graphql_warp::GraphQLWebSocket;
 use clap::Parser;
 use futures::future;
 use std::convert::Infallible;
 use std::net::{IpAddr, SocketAddr};
-use std::num::{NonZeroU32, NonZeroU8};
-use uuid::Uuid;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
 
 use async_graphql::http::{playground_source, GraphQLPlaygroundConfig};
 use mediasoup::worker::WorkerLogLevel;
 use mediasoup::{
-    data_structures::TransportListenIp,
-    rtp_parameters::{
-        MimeTypeAudio, MimeTypeVideo, RtcpFeedback, RtpCodecCapability,
-        RtpCodecParametersParameters,
-    },
-    worker::WorkerSettings,
-    worker_manager::WorkerManager,
+    data_structures::TransportListenIp, worker::WorkerSettings, worker_manager::WorkerManager,
 };
-use tokio::sync::oneshot;
-use warp::{http::Response as HttpResponse, Filter};
+use warp::{http::Response as HttpResponse, Filter, Rejection};
 
 use vulcan_relay::{
-    cmdline::Opts,
+    cmdline::{Opts, StreamRolesArg},
     control_schema::ControlSchema,
-    relay_server::{RelayServer, SessionToken},
+    crypto_policy::CryptoPolicy,
+    data_filter::{DataFilter, FilterChain, RateLimitFilter, SequenceFilter},
+    latency_probe::LatencyProbe,
+    message_store::MemoryMessageStore,
+    public_schema::PublicSchema,
+    relay_server::{RelayServer, Role},
+    resource_policy::{LimitScope, ResourcePolicy},
+    schema_compat::DeprecationPolicy,
+    session::{ConsumerConfig, PlainTransportConfig},
+    signal_server::TokenAuthenticator,
+    stream_policy::{StreamPolicy, StreamRules},
+    turn::TurnConfig,
     *,
 };
 
+/// Maximum number of commands, and of room events, buffered per disconnected session.
+const MAX_BUFFERED_MESSAGES: usize = 64;
+/// Interval in seconds between samples of request latency to the worker.
+const WORKER_SAMPLE_INTERVAL: u64 = 1;
+/// Interval in seconds between soak reports.
+const SOAK_REPORT_INTERVAL: u64 = 3600;
+/// Interval in seconds between logs of async diagnostics, if enabled.
+const DIAGNOSTICS_INTERVAL: u64 = 10;
+/// Interval in seconds between collections of idle rooms.
+const ROOM_GC_INTERVAL: u64 = 60;
+
 #[tokio::main]
 async fn main() {
     env_logger::init_from_env(
@@ -34,6 +49,7 @@ async fn main() {
     );
 
     let opts: Opts = Opts::parse();
+    diagnostics::init(Duration::from_secs(DIAGNOSTICS_INTERVAL));
 
     log::info!(
         "{} {}-{:?} {} {}",
@@ -44,8 +60,22 @@ async fn main() {
         built_info::PROFILE
     );
 
+    let crypto_policy = match CryptoPolicy::new(opts.constrained_crypto, !opts.no_tls) {
+        Ok(crypto_policy) => crypto_policy,
+        Err(err) => {
+            log::error!("invalid crypto configuration: {}", err);
+            std::process::exit(1);
+        }
+    };
+    if crypto_policy.constrained() {
+        log::info!(
+            "constrained crypto: srtp {:?}",
+            crypto_policy.srtp_crypto_suites()
+        );
+    }
+
     let rtc_ip: IpAddr = opts.rtc_ip.parse().unwrap();
-    let announced_ip = opts.rtc_announce_ip.map(|x| x.parse().unwrap());
+    let announced_ip = opts.rtc_announce_ip.as_ref().map(|x| x.parse().unwrap());
     log::info!("rtc ip: {}, rtc announce ip: {:?}", &rtc_ip, &announced_ip);
     log::info!(
         "rtc port range: {}-{}",
@@ -57,7 +87,32 @@ async fn main() {
         ip: rtc_ip,
         announced_ip,
     };
-    let media_codecs = media_codecs();
+    let media_codecs = match &opts.codecs_file {
+        Some(codecs_file) => match codecs::load(Path::new(codecs_file)) {
+            Ok(media_codecs) => {
+                log::info!("loaded {} codecs from {}", media_codecs.len(), codecs_file);
+                media_codecs
+            }
+            Err(err) => {
+                log::error!("invalid codecs file {}: {}", codecs_file, err);
+                std::process::exit(1);
+            }
+        },
+        None => codecs::default_codecs(),
+    };
+    let mut media_codecs = match opts.codec_filter().apply(media_codecs) {
+        Ok(media_codecs) => media_codecs,
+        Err(err) => {
+            log::error!("invalid codec flags: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let opus_config = opts.opus_config();
+    if let Err(err) = opus_config.apply(&mut media_codecs) {
+        log::error!("invalid opus flags: {}", err);
+        std::process::exit(1);
+    }
+    log::info!("opus: {:?}", opus_config);
 
     let worker_manager = WorkerManager::new();
     let mut worker_settings = WorkerSettings::default();
@@ -66,57 +121,304 @@ async fn main() {
     worker_settings.rtc_ports_range = opts.rtc_ports_range_min..=opts.rtc_ports_range_max;
     let worker = worker_manager.create_worker(worker_settings).await.unwrap();
     let relay_server = RelayServer::new(worker, transport_listen_ip, media_codecs);
+    relay_server.set_rtc_ports_range(Some(opts.rtc_ports_range_min..=opts.rtc_ports_range_max));
+    if !opts.skip_announce_check {
+        match reachability::check(&relay_server).await {
+            Ok(Some(rtt)) => log::info!("rtc announce ip reachable (rtt {:?})", rtt),
+            Ok(None) => {}
+            Err(err) => {
+                log::error!("rtc announce ip unreachable: {}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Some(message_ttl) = opts.message_ttl {
+        log::info!(
+            "buffering vulcast commands and room events for {}s",
+            message_ttl
+        );
+        relay_server.set_message_store(Some(Arc::new(MemoryMessageStore::new(
+            Duration::from_secs(message_ttl),
+            MAX_BUFFERED_MESSAGES,
+        ))));
+    }
+    if let Some(turn_secret) = &opts.turn_secret {
+        log::info!("turn servers: {:?}", opts.turn_url);
+        relay_server.set_turn_config(Some(TurnConfig {
+            urls: opts.turn_url.clone(),
+            shared_secret: turn_secret.clone(),
+            ttl: Duration::from_secs(opts.turn_ttl),
+        }));
+    }
+    if opts.latency_probe {
+        log::warn!("latency probe enabled, tracing all rtp packets");
+        relay_server.set_latency_probe(Some(LatencyProbe::new()));
+    }
+    if let Some(max_incoming_bitrate) = opts.max_incoming_bitrate {
+        log::info!(
+            "max incoming bitrate per transport: {} bps",
+            max_incoming_bitrate
+        );
+        relay_server.set_max_incoming_bitrate(Some(max_incoming_bitrate));
+    }
+    if let Some(stats_cache_ms) = opts.stats_cache_ms {
+        log::info!("caching session stats for {} ms", stats_cache_ms);
+        relay_server.set_stats_cache_ttl(Some(Duration::from_millis(stats_cache_ms)));
+    }
+    log::info!(
+        "duplicate connection policy: {:?}",
+        opts.duplicate_connections.0
+    );
+    relay_server.set_duplicate_connection_policy(opts.duplicate_connections.0);
+    if let Some(reconnect_grace_ms) = opts.reconnect_grace_ms {
+        log::info!("reconnect grace period: {} ms", reconnect_grace_ms);
+        relay_server.set_reconnect_grace(Some(Duration::from_millis(reconnect_grace_ms)));
+    }
+    if let Some(idle_transport_timeout) = opts.idle_transport_timeout {
+        log::info!(
+            "closing transports idle for {} seconds",
+            idle_transport_timeout
+        );
+        relay_server.set_idle_transport_timeout(Some(Duration::from_secs(idle_transport_timeout)));
+    }
+    let chaos = relay_server.chaos();
+    if let Some(percent) = opts.chaos_transport_failure_percent {
+        if let Err(err) = chaos.set_transport_failure_percent(percent) {
+            log::error!("ignoring --chaos-transport-failure-percent: {}", err);
+        }
+    }
+    if let Some(delay_ms) = opts.chaos_resolver_delay_ms {
+        if let Err(err) = chaos.set_resolver_delay(Some(Duration::from_millis(delay_ms))) {
+            log::error!("ignoring --chaos-resolver-delay-ms: {}", err);
+        }
+    }
+    let join_throttle = relay_server.join_throttle();
+    join_throttle.set_max_queue_delay(Duration::from_millis(opts.max_join_queue_ms));
+    if let Some(max_joins_per_second) = opts.max_joins_per_second {
+        log::info!("max joins per room: {}/s", max_joins_per_second);
+        join_throttle.set_default_rate(Some(max_joins_per_second));
+    }
+    let room_churn = relay_server.room_churn();
+    room_churn.set_window(Duration::from_secs(opts.room_churn_window_minutes * 60));
+    if let Some(max_rooms_per_vulcast) = opts.max_rooms_per_vulcast {
+        log::info!(
+            "max rooms per vulcast: {} per {} minutes",
+            max_rooms_per_vulcast,
+            opts.room_churn_window_minutes
+        );
+        room_churn.set_max_registrations(Some(max_rooms_per_vulcast));
+    }
+    if let Some(max_concurrent_control_ops) = opts.max_concurrent_control_ops {
+        log::info!("max concurrent control ops: {}", max_concurrent_control_ops);
+        relay_server
+            .control_queue()
+            .set_max_concurrent(Some(max_concurrent_control_ops));
+    }
+    log::info!(
+        "max request size: {} bytes, max variable size: {} bytes",
+        opts.max_request_bytes,
+        opts.max_variable_bytes
+    );
+    let payload_limit = relay_server.payload_limit();
+    payload_limit.set_max_request_bytes(Some(opts.max_request_bytes));
+    payload_limit.set_max_variable_bytes(Some(opts.max_variable_bytes));
+    let mut data_filters: Vec<Arc<dyn DataFilter>> = vec![];
+    if let Some(data_rate_limit) = opts.data_rate_limit {
+        log::info!("relaying data through server: {} msg/s", data_rate_limit);
+        data_filters.push(Arc::new(RateLimitFilter::new(
+            data_rate_limit,
+            Duration::from_secs(1),
+        )));
+    }
+    if let Some(input_sequence_window) = opts.input_sequence_window {
+        log::info!(
+            "sequencing input through server: window {}",
+            input_sequence_window
+        );
+        data_filters.push(Arc::new(SequenceFilter::new(input_sequence_window)));
+    }
+    if !data_filters.is_empty() {
+        relay_server.set_data_filter(Some(Arc::new(FilterChain::new(data_filters))));
+    }
 
-    let signal_schema = signal_schema::schema();
-    let control_schema = control_schema::schema(relay_server.clone());
+    let worker_monitor = relay_server.worker_monitor();
+    if let Some(worker_latency_threshold) = opts.worker_latency_threshold {
+        log::info!("worker latency threshold: {}ms", worker_latency_threshold);
+        worker_monitor.set_threshold(Some(Duration::from_millis(worker_latency_threshold)));
+    }
+    worker_monitor.spawn(
+        Duration::from_secs(WORKER_SAMPLE_INTERVAL),
+        relay_server.subsystems(),
+    );
 
-    let graphql_signal_ws = warp::ws()
-        .and(warp::filters::cookie::optional("token"))
-        .and(async_graphql_warp::graphql_protocol())
-        .map(
-            move |ws: warp::ws::Ws, cookie_token: Option<String>, protocol| {
-                let reply = ws.on_upgrade(
-                    enclose! { (relay_server, signal_schema) move |websocket| async move {
-                        // get token from cookie if it exists
-                        let cookie_token = cookie_token.and_then(|cookie_token| {
-                            Uuid::parse_str(&cookie_token).ok().map(SessionToken)
-                        });
+    if let Some(leak_audit_interval) = opts.leak_audit_interval {
+        log::info!("leak audit interval: {}s", leak_audit_interval);
+        leak_detector::spawn(
+            relay_server.clone(),
+            Duration::from_secs(leak_audit_interval.get()),
+        );
+    }
 
-                        let (tx, rx) = oneshot::channel();
-                        GraphQLWebSocket::new(websocket, signal_schema, protocol).on_connection_init(
-                            enclose! { (relay_server) move |value| async move {
-                                let mut data = async_graphql::Data::default();
-                                // get token from connection params if it exists
-                                let param_token = value.get("token").and_then(|param_token| {
-                                    serde_json::from_value::<SessionToken>(param_token.to_owned()).ok()
-                                });
-                                let token = param_token.or(cookie_token);
-                                if let Some(token) = token {
-                                    // create session from the selected token
-                                    if let Some(session) =
-                                        relay_server.session_from_token(token)
-                                    {
-                                        tx.send(token).unwrap();
-                                        data.insert(session.downgrade());
-                                    }
-                                }
-                                Ok(data)
-                            }
-                        }).serve().await;
+    for min_client_version in &opts.min_client_version {
+        log::info!(
+            "minimum {} client version: {}",
+            min_client_version.role,
+            min_client_version.min_version
+        );
+        relay_server.version_gate().set_min_version(
+            min_client_version.role,
+            Some(min_client_version.min_version.clone()),
+        );
+    }
+    let permissions = relay_server.permissions();
+    for grant in &opts.grant {
+        for permission in &grant.permissions {
+            log::info!(
+                "granted {} permission to {} clients",
+                permission,
+                grant.role
+            );
+            permissions.grant(grant.role, *permission);
+        }
+    }
+    for revoke in &opts.revoke {
+        for permission in &revoke.permissions {
+            log::info!(
+                "revoked {} permission of {} clients",
+                permission,
+                revoke.role
+            );
+            permissions.revoke(revoke.role, *permission);
+        }
+    }
+    if !opts.produce_streams.is_empty() || !opts.auto_consume_streams.is_empty() {
+        let mut stream_policy = StreamPolicy::new();
+        for role in [Role::Vulcast, Role::WebClient, Role::Host] {
+            let stream_roles = |args: &[StreamRolesArg]| {
+                args.iter()
+                    .filter(|arg| arg.role == role)
+                    .map(|arg| arg.stream_roles.clone())
+                    .reduce(|mut stream_roles, more| {
+                        stream_roles.extend(more);
+                        stream_roles
+                    })
+            };
+            let rules = StreamRules {
+                produce: stream_roles(&opts.produce_streams),
+                auto_consume: stream_roles(&opts.auto_consume_streams),
+            };
+            if rules != StreamRules::default() {
+                log::info!("{} stream rules: {:?}", role, rules);
+                stream_policy.set_rules(role, rules);
+            }
+        }
+        relay_server.set_stream_policy(stream_policy);
+    }
+    for max_session_duration in &opts.max_session_duration {
+        log::info!(
+            "max session duration in {:?} rooms: {} minutes",
+            max_session_duration.preset,
+            max_session_duration.minutes
+        );
+        relay_server.set_max_session_duration(
+            max_session_duration.preset,
+            Some(Duration::from_secs(max_session_duration.minutes * 60)),
+        );
+    }
 
+    if let Some(idle_room_timeout) = opts.idle_room_timeout {
+        log::info!("collecting rooms idle for {} minutes", idle_room_timeout);
+        relay_server
+            .room_gc()
+            .set_idle_timeout(Some(Duration::from_secs(idle_room_timeout * 60)));
+        room_gc::spawn(relay_server.clone(), Duration::from_secs(ROOM_GC_INTERVAL));
+    }
 
-                        if let Ok(token) = rx.await {
-                            drop(relay_server.take_session_by_token(&token))
-                        }
-                    }},
-                );
-                warp::reply::with_header(
-                    reply,
-                    "Sec-WebSocket-Protocol",
-                    protocol.sec_websocket_protocol(),
-                )
-            },
+    if opts.soak_report {
+        log::info!("soak report interval: {}s", SOAK_REPORT_INTERVAL);
+        soak_report::spawn(
+            relay_server.clone(),
+            Duration::from_secs(SOAK_REPORT_INTERVAL),
         );
+    }
+
+    let srt_ingest = if opts.srt_ingest {
+        log::info!(
+            "srt ingest port range: {}-{}",
+            &opts.srt_ports_range_min,
+            &opts.srt_ports_range_max
+        );
+        Some(srt_ingest::SrtIngest::new(
+            rtc_ip,
+            opts.srt_ports_range_min..=opts.srt_ports_range_max,
+            opts.ffmpeg_path.clone(),
+        ))
+    } else {
+        None
+    };
+
+    let resource_policy = ResourcePolicy::new();
+    for resource_limit in &opts.resource_limit {
+        resource_policy.set_limit(
+            resource_limit
+                .role
+                .map_or(LimitScope::Default, LimitScope::Role),
+            resource_limit.resource,
+            Some(resource_limit.limit),
+        );
+    }
+
+    let plain_transport_config = PlainTransportConfig {
+        enable_srtp: opts.plain_transport_srtp || crypto_policy.constrained(),
+        srtp_crypto_suite: crypto_policy.srtp_crypto_suites()[0],
+        ..PlainTransportConfig::default()
+    };
+    if let Err(err) = crypto_policy.check_plain_transport_config(&plain_transport_config) {
+        log::error!("invalid crypto configuration: {}", err);
+        std::process::exit(1);
+    }
+    let consumer_config = ConsumerConfig {
+        mid_strategy: opts.consumer_mid_strategy.0,
+        enable_rtx: !opts.consumer_disable_rtx,
+        pipe: opts.consumer_pipe,
+        ignore_dtx: opts.consumer_ignore_dtx,
+    };
+    let deprecation_policy = DeprecationPolicy {
+        serve_deprecated: !opts.no_deprecated_fields,
+    };
+    if !deprecation_policy.serve_deprecated {
+        log::info!("rejecting requests to deprecated fields");
+    }
+
+    let signal_schema = signal_schema::schema(
+        relay_server.clone(),
+        resource_policy.clone(),
+        srt_ingest,
+        plain_transport_config,
+        consumer_config,
+        crypto_policy,
+        deprecation_policy,
+    );
+    let control_schema = control_schema::schema(
+        relay_server.clone(),
+        resource_policy,
+        crypto_policy,
+        deprecation_policy,
+    );
+    let public_schema = public_schema::schema(relay_server.clone());
+
+    let graphql_signal_ws = signal_server::routes(
+        relay_server.clone(),
+        signal_schema.clone(),
+        Arc::new(TokenAuthenticator),
+    );
+    if opts.enable_playground {
+        log::warn!("serving graphql playgrounds, which are intended for development");
+    }
+    let signal_playground = enabled(opts.enable_playground).and(signal_server::playground_routes(
+        relay_server.clone(),
+        signal_schema,
+    ));
 
     let mut cors = warp::cors();
     // TODO force adoption after updating documentation
@@ -128,7 +430,8 @@ async fn main() {
         .allow_methods(vec!["POST"]);
     // }
 
-    let graphql_control_post = async_graphql_warp::graphql(control_schema.clone())
+    let graphql_control_post = payload_limit::limit_request(relay_server.payload_limit())
+        .and(async_graphql_warp::graphql(control_schema.clone()))
         .and_then(
             |(schema, request): (ControlSchema, async_graphql::Request)| async move {
                 Ok::<_, Infallible>(async_graphql_warp::GraphQLResponse::from(
@@ -138,26 +441,78 @@ async fn main() {
         )
         .with(cors);
 
-    let graphql_playground = warp::path::end().and(warp::get()).map(|| {
-        HttpResponse::builder()
-            .header("content-type", "text/html")
-            .body(playground_source(GraphQLPlaygroundConfig::new("/")))
-    });
+    // live stats are pushed over websocket subscriptions
+    let graphql_control_ws = async_graphql_warp::graphql_subscription(control_schema);
 
-    let signal_routes = graphql_signal_ws;
-    let control_routes = graphql_playground.or(graphql_control_post);
+    let graphql_playground = enabled(opts.enable_playground)
+        .and(warp::path::end())
+        .and(warp::get())
+        .map(|| {
+            HttpResponse::builder()
+                .header("content-type", "text/html")
+                .body(playground_source(
+                    GraphQLPlaygroundConfig::new("/").subscription_endpoint("/"),
+                ))
+        });
+
+    // the public endpoint is read-only, so it may be queried from any page
+    let public_cors = warp::cors()
+        .allow_any_origin()
+        .allow_headers(vec!["content-type"])
+        .allow_methods(vec!["POST"]);
+    let graphql_public_post = payload_limit::limit_request(relay_server.payload_limit())
+        .and(async_graphql_warp::graphql(public_schema))
+        .and_then(
+            |(schema, request): (PublicSchema, async_graphql::Request)| async move {
+                Ok::<_, Infallible>(async_graphql_warp::GraphQLResponse::from(
+                    schema.execute(request).await,
+                ))
+            },
+        )
+        .with(public_cors);
+
+    let whep_cors = warp::cors()
+        .allow_any_origin()
+        .allow_headers(vec!["authorization", "content-type"])
+        .allow_methods(vec!["POST", "DELETE"])
+        .expose_headers(vec!["location"]);
+    let whep = payload_limit::limit_request(relay_server.payload_limit())
+        .and(whep::routes(relay_server.clone(), resource_policy.clone()))
+        .with(whep_cors);
+
+    let signal_routes = graphql_signal_ws
+        .or(whep)
+        .or(signal_playground)
+        .recover(payload_limit::recover);
+    let control_routes = graphql_control_ws
+        .or(graphql_playground)
+        .or(dashboard::routes())
+        .or(graphql_control_post)
+        .recover(payload_limit::recover);
+    let public_routes = graphql_public_post.recover(payload_limit::recover);
 
     let signal_addr = opts.signal_addr.parse::<SocketAddr>().unwrap();
     let control_addr = opts.control_addr.parse::<SocketAddr>().unwrap();
+    let public_control_addr = opts
+        .public_control_addr
+        .as_ref()
+        .map(|public_control_addr| public_control_addr.parse::<SocketAddr>().unwrap());
 
     if opts.no_tls {
         log::info!("signal graphql endpoint: ws://{}", signal_addr);
         log::info!("control endpoint: http://{}", control_addr);
         let signal_server = warp::serve(signal_routes.with(warp::log("signal-server")));
         let control_server = warp::serve(control_routes.with(warp::log("control-server")));
-        future::join(
+        let public_server = warp::serve(public_routes.with(warp::log("public-server")));
+        future::join3(
             signal_server.run(signal_addr),
             control_server.run(control_addr),
+            async move {
+                if let Some(public_control_addr) = public_control_addr {
+                    log::info!("public control endpoint: http://{}", public_control_addr);
+                    public_server.run(public_control_addr).await;
+                }
+            },
         )
         .await;
     } else {
@@ -168,90 +523,36 @@ async fn main() {
             .cert_path(opts.cert_path.clone().unwrap())
             .key_path(opts.key_path.clone().unwrap());
         let control_server = warp::serve(control_routes.with(warp::log("control-server")))
+            .tls()
+            .cert_path(opts.cert_path.clone().unwrap())
+            .key_path(opts.key_path.clone().unwrap());
+        let public_server = warp::serve(public_routes.with(warp::log("public-server")))
             .tls()
             .cert_path(opts.cert_path.unwrap())
             .key_path(opts.key_path.unwrap());
-        future::join(
+        future::join3(
             signal_server.run(signal_addr),
             control_server.run(control_addr),
+            async move {
+                if let Some(public_control_addr) = public_control_addr {
+                    log::info!("public control endpoint: https://{}", public_control_addr);
+                    public_server.run(public_control_addr).await;
+                }
+            },
         )
         .await;
     };
 }
 
-fn media_codecs() -> Vec<RtpCodecCapability> {
-    vec![
-        RtpCodecCapability::Audio {
-            mime_type: MimeTypeAudio::Opus,
-            preferred_payload_type: None,
-            clock_rate: NonZeroU32::new(48000).unwrap(),
-            channels: NonZeroU8::new(2).unwrap(),
-            parameters: RtpCodecParametersParameters::from([("useinbandfec", 1u32.into())]),
-            rtcp_feedback: vec![RtcpFeedback::TransportCc],
-        },
-        RtpCodecCapability::Video {
-            mime_type: MimeTypeVideo::H264,
-            preferred_payload_type: None,
-            clock_rate: NonZeroU32::new(90000).unwrap(),
-            parameters: RtpCodecParametersParameters::from([
-                ("packetization-mode", 1u32.into()),
-                ("level-asymmetry-allowed", 1u32.into()),
-                ("profile-level-id", "42e01f".into()),
-            ]),
-            rtcp_feedback: vec![
-                RtcpFeedback::Nack,
-                RtcpFeedback::NackPli,
-                RtcpFeedback::CcmFir,
-                RtcpFeedback::GoogRemb,
-                RtcpFeedback::TransportCc,
-            ],
-        },
-        RtpCodecCapability::Video {
-            mime_type: MimeTypeVideo::H264,
-            preferred_payload_type: None,
-            clock_rate: NonZeroU32::new(90000).unwrap(),
-            parameters: RtpCodecParametersParameters::from([
-                ("packetization-mode", 1u32.into()),
-                ("level-asymmetry-allowed", 1u32.into()),
-                ("profile-level-id", "4d0032".into()),
-            ]),
-            rtcp_feedback: vec![
-                RtcpFeedback::Nack,
-                RtcpFeedback::NackPli,
-                RtcpFeedback::CcmFir,
-                RtcpFeedback::GoogRemb,
-                RtcpFeedback::TransportCc,
-            ],
-        },
-        RtpCodecCapability::Video {
-            mime_type: MimeTypeVideo::H264,
-            preferred_payload_type: None,
-            clock_rate: NonZeroU32::new(90000).unwrap(),
-            parameters: RtpCodecParametersParameters::from([
-                ("packetization-mode", 1u32.into()),
-                ("level-asymmetry-allowed", 1u32.into()),
-                ("profile-level-id", "64002a".into()),
-            ]),
-            rtcp_feedback: vec![
-                RtcpFeedback::Nack,
-                RtcpFeedback::NackPli,
-                RtcpFeedback::CcmFir,
-                RtcpFeedback::GoogRemb,
-                RtcpFeedback::TransportCc,
-            ],
-        },
-        RtpCodecCapability::Video {
-            mime_type: MimeTypeVideo::Vp8,
-            preferred_payload_type: None,
-            clock_rate: NonZeroU32::new(90000).unwrap(),
-            parameters: RtpCodecParametersParameters::default(),
-            rtcp_feedback: vec![
-                RtcpFeedback::Nack,
-                RtcpFeedback::NackPli,
-                RtcpFeedback::CcmFir,
-                RtcpFeedback::GoogRemb,
-                RtcpFeedback::TransportCc,
-            ],
-        },
-    ]
+/// Pass requests on to the filters that follow only if enabled.
+fn enabled(enabled: bool) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::any()
+        .and_then(move || async move {
+            if enabled {
+                Ok(())
+            } else {
+                Err(warp::reject::not_found())
+            }
+        })
+        .untuple_one()
 }
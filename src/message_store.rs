@@ -0,0 +1,114 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::events::Envelope;
+use crate::relay_server::ForeignSessionId;
+use crate::room::{MessageId, VulcastCommand};
+
+/// Storage for messages which have not yet been delivered to a session, so they
+/// can be replayed once the session reconnects. Messages are commands sent to a
+/// Vulcast, and events of the room of a session whose client is disconnected.
+pub trait MessageStore: Debug + Send + Sync {
+    /// Buffer a command for a session until it is delivered or expires.
+    fn push(&self, fsid: &ForeignSessionId, command: VulcastCommand);
+    /// Remove a command which has been delivered.
+    fn remove(&self, fsid: &ForeignSessionId, id: MessageId);
+    /// Get all buffered commands for a session which have not expired, oldest first.
+    fn pending(&self, fsid: &ForeignSessionId) -> Vec<VulcastCommand>;
+    /// Buffer a room event for a session until it is replayed or expires.
+    fn push_event(&self, fsid: &ForeignSessionId, envelope: Envelope);
+    /// Take all buffered room events for a session which have not expired, oldest first.
+    fn take_events(&self, fsid: &ForeignSessionId) -> Vec<Envelope>;
+    /// Discard all buffered commands and room events for a session.
+    fn clear(&self, fsid: &ForeignSessionId);
+}
+
+/// In-memory message store, which discards messages after a time-to-live and
+/// bounds the number of commands and of room events buffered per session.
+#[derive(Debug)]
+pub struct MemoryMessageStore {
+    ttl: Duration,
+    max_messages: usize,
+    queues: Mutex<HashMap<ForeignSessionId, VecDeque<(Instant, VulcastCommand)>>>,
+    events: Mutex<HashMap<ForeignSessionId, VecDeque<(Instant, Envelope)>>>,
+}
+
+impl MemoryMessageStore {
+    pub fn new(ttl: Duration, max_messages: usize) -> Self {
+        Self {
+            ttl,
+            max_messages,
+            queues: Mutex::new(HashMap::new()),
+            events: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Append a message to a queue, discarding expired messages and the oldest
+    /// messages beyond the bound.
+    fn push_bounded<T>(&self, queue: &mut VecDeque<(Instant, T)>, message: T) {
+        let now = Instant::now();
+        while queue
+            .front()
+            .map_or(false, |(pushed, _)| now.duration_since(*pushed) >= self.ttl)
+        {
+            queue.pop_front();
+        }
+        queue.push_back((now, message));
+        while queue.len() > self.max_messages {
+            queue.pop_front();
+        }
+    }
+}
+
+impl MessageStore for MemoryMessageStore {
+    fn push(&self, fsid: &ForeignSessionId, command: VulcastCommand) {
+        let mut queues = self.queues.lock().unwrap();
+        self.push_bounded(queues.entry(fsid.clone()).or_default(), command);
+    }
+
+    fn remove(&self, fsid: &ForeignSessionId, id: MessageId) {
+        let mut queues = self.queues.lock().unwrap();
+        if let Some(queue) = queues.get_mut(fsid) {
+            queue.retain(|(_, command)| command.id != id);
+            if queue.is_empty() {
+                queues.remove(fsid);
+            }
+        }
+    }
+
+    fn pending(&self, fsid: &ForeignSessionId) -> Vec<VulcastCommand> {
+        let mut queues = self.queues.lock().unwrap();
+        let queue = match queues.get_mut(fsid) {
+            Some(queue) => queue,
+            None => return vec![],
+        };
+        let now = Instant::now();
+        queue.retain(|(pushed, _)| now.duration_since(*pushed) < self.ttl);
+        queue.iter().map(|(_, command)| command.clone()).collect()
+    }
+
+    fn push_event(&self, fsid: &ForeignSessionId, envelope: Envelope) {
+        let mut events = self.events.lock().unwrap();
+        self.push_bounded(events.entry(fsid.clone()).or_default(), envelope);
+    }
+
+    fn take_events(&self, fsid: &ForeignSessionId) -> Vec<Envelope> {
+        let now = Instant::now();
+        self.events
+            .lock()
+            .unwrap()
+            .remove(fsid)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(pushed, _)| now.duration_since(*pushed) < self.ttl)
+            .map(|(_, envelope)| envelope)
+            .collect()
+    }
+
+    fn clear(&self, fsid: &ForeignSessionId) {
+        self.queues.lock().unwrap().remove(fsid);
+        self.events.lock().unwrap().remove(fsid);
+    }
+}
@@ -0,0 +1,443 @@
+use std::collections::HashSet;
+use std::num::{NonZeroU32, NonZeroU8};
+
+use serde::{Deserialize, Serialize};
+
+use mediasoup::rtp_parameters::{
+    MediaKind, MimeTypeAudio, MimeTypeVideo, RtpCapabilities, RtpCapabilitiesFinalized,
+    RtpCodecCapability, RtpCodecCapabilityFinalized, RtpCodecParameters,
+    RtpCodecParametersParameters, RtpCodecParametersValue, RtpParameters,
+};
+
+/// Outcome of matching the RTP parameters of a producer against the router capabilities.
+/// Rejected codecs cause producer creation to fail, whereas rejected header extensions
+/// are silently ignored by the router.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NegotiationReport {
+    pub codecs: Vec<Negotiation>,
+    pub header_extensions: Vec<Negotiation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Negotiation {
+    /// Mime type of the codec, or URI of the header extension.
+    pub name: String,
+    /// Payload type of the codec, or ID of the header extension.
+    pub id: u16,
+    pub accepted: bool,
+    /// Reason the codec or header extension was rejected.
+    pub reason: Option<String>,
+}
+
+impl NegotiationReport {
+    /// Get human-readable reasons for each rejected codec.
+    pub fn codec_rejections(&self) -> Vec<String> {
+        self.codecs
+            .iter()
+            .filter_map(|codec| {
+                codec
+                    .reason
+                    .as_ref()
+                    .map(|reason| format!("{} ({}): {}", codec.name, codec.id, reason))
+            })
+            .collect()
+    }
+}
+
+/// Whether a session is able to consume a producer, with reasons if it cannot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsumeCheck {
+    pub can_consume: bool,
+    pub reasons: Vec<String>,
+}
+
+impl ConsumeCheck {
+    pub fn accepted() -> Self {
+        Self {
+            can_consume: true,
+            reasons: vec![],
+        }
+    }
+    pub fn rejected(reasons: Vec<String>) -> Self {
+        Self {
+            can_consume: false,
+            reasons,
+        }
+    }
+}
+
+/// Match producer RTP parameters against router capabilities, approximating the
+/// (strict) codec matching rules mediasoup applies when creating a producer.
+pub fn negotiate(
+    kind: MediaKind,
+    rtp_parameters: &RtpParameters,
+    capabilities: &RtpCapabilitiesFinalized,
+) -> NegotiationReport {
+    let mut report = NegotiationReport::default();
+
+    // media codecs first, so rtx codecs can refer to their associated codec
+    let (rtx_codecs, media_codecs): (Vec<_>, Vec<_>) = rtp_parameters
+        .codecs
+        .iter()
+        .partition(|codec| is_rtx(codec));
+    let router_codecs = capabilities
+        .codecs
+        .iter()
+        .map(Codec::from)
+        .collect::<Vec<_>>();
+    for codec in media_codecs {
+        let reason = find_codec(&codec.into(), &router_codecs, "router").err();
+        report.codecs.push(Negotiation {
+            name: mime_type(codec),
+            id: payload_type(codec).into(),
+            accepted: reason.is_none(),
+            reason,
+        });
+    }
+    for codec in rtx_codecs {
+        let apt = parameters(codec).get("apt").and_then(|apt| match apt {
+            RtpCodecParametersValue::Number(apt) => Some(*apt),
+            RtpCodecParametersValue::String(apt) => apt.parse().ok(),
+        });
+        let associated = report
+            .codecs
+            .iter()
+            .find(|negotiation| Some(u32::from(negotiation.id)) == apt);
+        let reason = match associated {
+            None => Some("no associated media codec (apt)".to_owned()),
+            Some(associated) if !associated.accepted => {
+                Some("associated media codec was rejected".to_owned())
+            }
+            Some(_) => None,
+        };
+        report.codecs.push(Negotiation {
+            name: mime_type(codec),
+            id: payload_type(codec).into(),
+            accepted: reason.is_none(),
+            reason,
+        });
+    }
+
+    for header_extension in &rtp_parameters.header_extensions {
+        let supported = capabilities
+            .header_extensions
+            .iter()
+            .any(|capability| capability.kind == kind && capability.uri == header_extension.uri);
+        report.header_extensions.push(Negotiation {
+            name: serde_json::to_value(&header_extension.uri)
+                .ok()
+                .and_then(|uri| uri.as_str().map(str::to_owned))
+                .unwrap_or_else(|| format!("{:?}", header_extension.uri)),
+            id: header_extension.id.into(),
+            accepted: supported,
+            reason: (!supported)
+                .then(|| format!("router does not support this {} extension", kind_name(kind))),
+        });
+    }
+    report
+}
+
+/// Explain why the encodings of a producer are invalid. Audio producers have a single
+/// encoding, whereas each encoding (layer) of a simulcast video producer must be
+/// uniquely identified by RID or SSRC.
+pub fn encoding_errors(kind: MediaKind, rtp_parameters: &RtpParameters) -> Vec<String> {
+    let encodings = &rtp_parameters.encodings;
+    let mut errors = vec![];
+    if encodings.is_empty() {
+        errors.push("no encodings".to_owned());
+    }
+    if kind == MediaKind::Audio && encodings.len() > 1 {
+        errors.push(format!(
+            "audio must have a single encoding, not {}",
+            encodings.len()
+        ));
+    }
+    if encodings.len() > 1 {
+        let mut rids = HashSet::new();
+        let mut ssrcs = HashSet::new();
+        for (i, encoding) in encodings.iter().enumerate() {
+            if encoding.rid.is_none() && encoding.ssrc.is_none() {
+                errors.push(format!("simulcast encoding {} has no rid or ssrc", i));
+            }
+            if let Some(rid) = &encoding.rid {
+                if !rids.insert(rid) {
+                    errors.push(format!(
+                        "simulcast encoding {} has duplicate rid {}",
+                        i, rid
+                    ));
+                }
+            }
+            let rtx_ssrc = encoding.rtx.as_ref().map(|rtx| rtx.ssrc);
+            for ssrc in encoding.ssrc.iter().chain(rtx_ssrc.iter()) {
+                if !ssrcs.insert(*ssrc) {
+                    errors.push(format!(
+                        "simulcast encoding {} has duplicate ssrc {}",
+                        i, ssrc
+                    ));
+                }
+            }
+        }
+    }
+    errors
+}
+
+/// Explain why each consumable media codec of a producer does not match the
+/// RTP capabilities of a consuming client.
+pub fn consume_mismatches(
+    consumable_rtp_parameters: &RtpParameters,
+    capabilities: &RtpCapabilities,
+) -> Vec<String> {
+    let client_codecs = capabilities
+        .codecs
+        .iter()
+        .map(Codec::from)
+        .collect::<Vec<_>>();
+    consumable_rtp_parameters
+        .codecs
+        .iter()
+        .filter(|codec| !is_rtx(codec))
+        .filter_map(|codec| {
+            find_codec(&codec.into(), &client_codecs, "client")
+                .err()
+                .map(|reason| format!("{}: {}", mime_type(codec), reason))
+        })
+        .collect()
+}
+
+/// Codec fields relevant to matching, common to RTP parameters and capabilities.
+struct Codec<'a> {
+    mime_type: String,
+    clock_rate: NonZeroU32,
+    channels: Option<NonZeroU8>,
+    parameters: &'a RtpCodecParametersParameters,
+}
+
+impl<'a> From<&'a RtpCodecParameters> for Codec<'a> {
+    fn from(codec: &'a RtpCodecParameters) -> Self {
+        match codec {
+            RtpCodecParameters::Audio {
+                mime_type,
+                clock_rate,
+                channels,
+                parameters,
+                ..
+            } => Self {
+                mime_type: audio_mime_type(mime_type),
+                clock_rate: *clock_rate,
+                channels: Some(*channels),
+                parameters,
+            },
+            RtpCodecParameters::Video {
+                mime_type,
+                clock_rate,
+                parameters,
+                ..
+            } => Self {
+                mime_type: video_mime_type(mime_type),
+                clock_rate: *clock_rate,
+                channels: None,
+                parameters,
+            },
+        }
+    }
+}
+
+impl<'a> From<&'a RtpCodecCapabilityFinalized> for Codec<'a> {
+    fn from(capability: &'a RtpCodecCapabilityFinalized) -> Self {
+        match capability {
+            RtpCodecCapabilityFinalized::Audio {
+                mime_type,
+                clock_rate,
+                channels,
+                parameters,
+                ..
+            } => Self {
+                mime_type: audio_mime_type(mime_type),
+                clock_rate: *clock_rate,
+                channels: Some(*channels),
+                parameters,
+            },
+            RtpCodecCapabilityFinalized::Video {
+                mime_type,
+                clock_rate,
+                parameters,
+                ..
+            } => Self {
+                mime_type: video_mime_type(mime_type),
+                clock_rate: *clock_rate,
+                channels: None,
+                parameters,
+            },
+        }
+    }
+}
+
+impl<'a> From<&'a RtpCodecCapability> for Codec<'a> {
+    fn from(capability: &'a RtpCodecCapability) -> Self {
+        match capability {
+            RtpCodecCapability::Audio {
+                mime_type,
+                clock_rate,
+                channels,
+                parameters,
+                ..
+            } => Self {
+                mime_type: audio_mime_type(mime_type),
+                clock_rate: *clock_rate,
+                channels: Some(*channels),
+                parameters,
+            },
+            RtpCodecCapability::Video {
+                mime_type,
+                clock_rate,
+                parameters,
+                ..
+            } => Self {
+                mime_type: video_mime_type(mime_type),
+                clock_rate: *clock_rate,
+                channels: None,
+                parameters,
+            },
+        }
+    }
+}
+
+/// Find a matching codec among the capabilities of `peer`, or the reason none matched.
+fn find_codec(codec: &Codec, capabilities: &[Codec], peer: &str) -> Result<(), String> {
+    let mut same_mime_type = capabilities
+        .iter()
+        .filter(|capability| capability.mime_type.eq_ignore_ascii_case(&codec.mime_type))
+        .peekable();
+    if same_mime_type.peek().is_none() {
+        return Err(format!("{} does not support this codec", peer));
+    }
+    // report the mismatch from the first codec of the same mime type
+    let mut first_err = None;
+    for capability in same_mime_type {
+        match match_codec(codec, capability, peer) {
+            Ok(()) => return Ok(()),
+            Err(err) => {
+                first_err.get_or_insert(err);
+            }
+        }
+    }
+    Err(first_err.unwrap())
+}
+
+fn match_codec(codec: &Codec, capability: &Codec, peer: &str) -> Result<(), String> {
+    if codec.clock_rate != capability.clock_rate {
+        Err(format!(
+            "clock rate {} does not match {} clock rate {}",
+            codec.clock_rate, peer, capability.clock_rate
+        ))
+    } else if codec.channels != capability.channels {
+        Err(format!(
+            "channels {} does not match {} channels {}",
+            codec.channels.map_or(0, NonZeroU8::get),
+            peer,
+            capability.channels.map_or(0, NonZeroU8::get)
+        ))
+    } else if codec.mime_type.eq_ignore_ascii_case("video/H264") {
+        match_h264(codec.parameters, capability.parameters, peer)
+    } else if codec.mime_type.eq_ignore_ascii_case("video/VP9")
+        && parameter(codec.parameters, "profile-id", "0")
+            != parameter(capability.parameters, "profile-id", "0")
+    {
+        Err(format!("profile-id does not match {}", peer))
+    } else {
+        Ok(())
+    }
+}
+
+fn match_h264(
+    parameters: &RtpCodecParametersParameters,
+    capability_parameters: &RtpCodecParametersParameters,
+    peer: &str,
+) -> Result<(), String> {
+    let packetization_mode = parameter(parameters, "packetization-mode", "0");
+    let capability_packetization_mode = parameter(capability_parameters, "packetization-mode", "0");
+    if packetization_mode != capability_packetization_mode {
+        return Err(format!(
+            "packetization-mode {} does not match {} packetization-mode {}",
+            packetization_mode, peer, capability_packetization_mode
+        ));
+    }
+    let profile_level_id = parameter(parameters, "profile-level-id", "42e01f");
+    let capability_profile_level_id =
+        parameter(capability_parameters, "profile-level-id", "42e01f");
+    if h264_profile(&profile_level_id) != h264_profile(&capability_profile_level_id) {
+        return Err(format!(
+            "profile of profile-level-id {} does not match {} profile-level-id {}",
+            profile_level_id, peer, capability_profile_level_id
+        ));
+    }
+    Ok(())
+}
+
+/// Approximate H264 profile from profile-level-id, ignoring the level.
+/// Baseline is distinguished from constrained baseline by constraint_set1_flag.
+fn h264_profile(profile_level_id: &str) -> Option<(u8, bool)> {
+    let profile_idc = u8::from_str_radix(profile_level_id.get(0..2)?, 16).ok()?;
+    let profile_iop = u8::from_str_radix(profile_level_id.get(2..4)?, 16).ok()?;
+    Some((profile_idc, profile_idc == 0x42 && profile_iop & 0x40 != 0))
+}
+
+fn parameter(parameters: &RtpCodecParametersParameters, key: &str, default: &str) -> String {
+    match parameters.get(key) {
+        Some(RtpCodecParametersValue::Number(value)) => value.to_string(),
+        Some(RtpCodecParametersValue::String(value)) => value.to_lowercase(),
+        None => default.to_owned(),
+    }
+}
+
+fn is_rtx(codec: &RtpCodecParameters) -> bool {
+    match codec {
+        RtpCodecParameters::Audio { .. } => false,
+        RtpCodecParameters::Video { mime_type, .. } => *mime_type == MimeTypeVideo::Rtx,
+    }
+}
+
+fn payload_type(codec: &RtpCodecParameters) -> u8 {
+    match codec {
+        RtpCodecParameters::Audio { payload_type, .. }
+        | RtpCodecParameters::Video { payload_type, .. } => *payload_type,
+    }
+}
+
+fn parameters(codec: &RtpCodecParameters) -> &RtpCodecParametersParameters {
+    match codec {
+        RtpCodecParameters::Audio { parameters, .. }
+        | RtpCodecParameters::Video { parameters, .. } => parameters,
+    }
+}
+
+fn mime_type(codec: &RtpCodecParameters) -> String {
+    match codec {
+        RtpCodecParameters::Audio { mime_type, .. } => audio_mime_type(mime_type),
+        RtpCodecParameters::Video { mime_type, .. } => video_mime_type(mime_type),
+    }
+}
+
+fn audio_mime_type(mime_type: &MimeTypeAudio) -> String {
+    serde_json::to_value(mime_type)
+        .ok()
+        .and_then(|x| x.as_str().map(str::to_owned))
+        .unwrap_or_else(|| format!("{:?}", mime_type))
+}
+
+fn video_mime_type(mime_type: &MimeTypeVideo) -> String {
+    serde_json::to_value(mime_type)
+        .ok()
+        .and_then(|x| x.as_str().map(str::to_owned))
+        .unwrap_or_else(|| format!("{:?}", mime_type))
+}
+
+fn kind_name(kind: MediaKind) -> &'static str {
+    match kind {
+        MediaKind::Audio => "audio",
+        MediaKind::Video => "video",
+    }
+}
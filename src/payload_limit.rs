@@ -0,0 +1,214 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextPrepareRequest,
+};
+use async_graphql::{ErrorExtensions, Request, ServerError, ServerResult};
+use thiserror::Error;
+use warp::http::StatusCode;
+use warp::{Filter, Rejection, Reply};
+
+/// Limits on the size of GraphQL requests, so that clients cannot tie up the relay with
+/// oversized payloads (e.g. multi-megabyte `RtpParameters`). The size of a request is
+/// limited as it arrives, and the size of each variable as the request is prepared,
+/// before any scalar is deserialized. Rejected requests are counted per limit.
+#[derive(Debug, Clone, Default)]
+pub struct PayloadLimit {
+    shared: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    max_request_bytes: Option<usize>,
+    max_variable_bytes: Option<usize>,
+    rejections: PayloadRejections,
+}
+
+/// Requests rejected for exceeding a limit since the relay started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PayloadRejections {
+    pub requests: u64,
+    pub variables: u64,
+}
+
+impl PayloadLimit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the size of request bodies and websocket messages. Unlimited if unset.
+    pub fn set_max_request_bytes(&self, max_request_bytes: Option<usize>) {
+        self.shared.lock().unwrap().max_request_bytes = max_request_bytes;
+    }
+    pub fn max_request_bytes(&self) -> Option<usize> {
+        self.shared.lock().unwrap().max_request_bytes
+    }
+
+    /// Limit the size of each variable of a request, as serialized JSON. Unlimited if
+    /// unset.
+    pub fn set_max_variable_bytes(&self, max_variable_bytes: Option<usize>) {
+        self.shared.lock().unwrap().max_variable_bytes = max_variable_bytes;
+    }
+    pub fn max_variable_bytes(&self) -> Option<usize> {
+        self.shared.lock().unwrap().max_variable_bytes
+    }
+
+    /// Get the number of requests rejected by each limit.
+    pub fn rejections(&self) -> PayloadRejections {
+        self.shared.lock().unwrap().rejections
+    }
+
+    /// Check the size of a request, e.g. from its content length.
+    pub fn check_request(&self, size: usize) -> Result<(), PayloadTooLargeError> {
+        let mut state = self.shared.lock().unwrap();
+        match state.max_request_bytes {
+            Some(max_bytes) if size > max_bytes => {
+                state.rejections.requests += 1;
+                Err(PayloadTooLargeError {
+                    variable: None,
+                    size,
+                    max_bytes,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check the size of the query and of each variable of a GraphQL request. Variables
+    /// are measured as they are serialized, stopping at the limit, so that oversized
+    /// variables are never copied.
+    pub fn check_graphql_request(&self, request: &Request) -> Result<(), PayloadTooLargeError> {
+        self.check_request(request.query.len())?;
+        let max_bytes = match self.max_variable_bytes() {
+            Some(max_bytes) => max_bytes,
+            None => return Ok(()),
+        };
+        for (name, value) in request.variables.iter() {
+            let mut counter = ByteCounter { size: 0, max_bytes };
+            if serde_json::to_writer(&mut counter, value).is_err() {
+                self.shared.lock().unwrap().rejections.variables += 1;
+                return Err(PayloadTooLargeError {
+                    variable: Some(name.to_string()),
+                    size: counter.size,
+                    max_bytes,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sink counting the bytes written to it, which fails once they exceed a limit.
+struct ByteCounter {
+    size: usize,
+    max_bytes: usize,
+}
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.size += buf.len();
+        if self.size > self.max_bytes {
+            return Err(io::Error::new(io::ErrorKind::Other, "payload too large"));
+        }
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("{} of {size} bytes exceeds the maximum of {max_bytes} bytes", subject(.variable))]
+pub struct PayloadTooLargeError {
+    /// Name of the oversized variable, or none if the request as a whole is oversized.
+    pub variable: Option<String>,
+    /// Size of the payload, or of the part measured before exceeding the limit.
+    pub size: usize,
+    pub max_bytes: usize,
+}
+fn subject(variable: &Option<String>) -> String {
+    match variable {
+        Some(variable) => format!("variable ${}", variable),
+        None => "request".to_owned(),
+    }
+}
+impl ErrorExtensions for PayloadTooLargeError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, extensions| {
+            extensions.set("code", "PAYLOAD_TOO_LARGE");
+            extensions.set("maxBytes", self.max_bytes as u64);
+            if let Some(variable) = &self.variable {
+                extensions.set("variable", variable.as_str());
+            }
+        })
+    }
+}
+impl warp::reject::Reject for PayloadTooLargeError {}
+
+/// Reject requests whose content length exceeds the request limit, before their body
+/// is read. Requests without a content length (e.g. chunked) are limited as
+/// GraphQL requests once read.
+pub fn limit_request(
+    payload_limit: PayloadLimit,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::header::optional::<u64>("content-length")
+        .and_then(move |content_length: Option<u64>| {
+            let payload_limit = payload_limit.clone();
+            async move {
+                match content_length {
+                    Some(content_length) => payload_limit
+                        .check_request(content_length as usize)
+                        .map_err(warp::reject::custom),
+                    None => Ok(()),
+                }
+            }
+        })
+        .untuple_one()
+}
+
+/// Reply to requests rejected by [`limit_request`] with `413 Payload Too Large`, along
+/// with a GraphQL error carrying the `code` `PAYLOAD_TOO_LARGE`.
+pub async fn recover(rejection: Rejection) -> Result<impl Reply, Rejection> {
+    match rejection.find::<PayloadTooLargeError>() {
+        Some(err) => {
+            let response = async_graphql::Response::from_errors(vec![server_error(err)]);
+            Ok(warp::reply::with_status(
+                warp::reply::json(&response),
+                StatusCode::PAYLOAD_TOO_LARGE,
+            ))
+        }
+        None => Err(rejection),
+    }
+}
+
+fn server_error(err: &PayloadTooLargeError) -> ServerError {
+    let err = err.extend();
+    let mut server_error = ServerError::new(err.message, None);
+    server_error.extensions = err.extensions;
+    server_error
+}
+
+/// GraphQL extension rejecting requests with oversized queries or variables.
+pub struct PayloadLimitCheck(pub PayloadLimit);
+impl ExtensionFactory for PayloadLimitCheck {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(PayloadLimitCheckExtension(self.0.clone()))
+    }
+}
+
+struct PayloadLimitCheckExtension(PayloadLimit);
+#[async_trait::async_trait]
+impl Extension for PayloadLimitCheckExtension {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        if let Err(err) = self.0.check_graphql_request(&request) {
+            log::warn!("rejected graphql request: {}", err);
+            return Err(server_error(&err));
+        }
+        next.run(ctx, request).await
+    }
+}
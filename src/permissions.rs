@@ -0,0 +1,158 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use async_graphql::ErrorExtensions;
+use thiserror::Error;
+
+use crate::relay_server::Role;
+
+/// Class of signal operation a session may be permitted to perform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Permission {
+    /// Produce media into the room.
+    ProduceMedia,
+    /// Produce data (e.g. input) into the room.
+    ProduceData,
+    /// Consume media from the room.
+    ConsumeMedia,
+    /// Consume data from the room.
+    ConsumeData,
+    /// Moderate the room, e.g. mute producers, kick sessions and lock the room.
+    Moderate,
+    /// Hold exclusive control of input to the room.
+    Control,
+    /// Send commands to the Vulcast of the room.
+    SendCommands,
+    /// Receive and acknowledge the commands sent to the Vulcast of the room.
+    ReceiveCommands,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 8] = [
+        Permission::ProduceMedia,
+        Permission::ProduceData,
+        Permission::ConsumeMedia,
+        Permission::ConsumeData,
+        Permission::Moderate,
+        Permission::Control,
+        Permission::SendCommands,
+        Permission::ReceiveCommands,
+    ];
+}
+
+impl FromStr for Permission {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Permission::ALL
+            .iter()
+            .copied()
+            .find(|permission| permission.to_string() == s)
+            .ok_or_else(|| s.to_owned())
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Permission::ProduceMedia => "produce-media",
+            Permission::ProduceData => "produce-data",
+            Permission::ConsumeMedia => "consume-media",
+            Permission::ConsumeData => "consume-data",
+            Permission::Moderate => "moderate",
+            Permission::Control => "control",
+            Permission::SendCommands => "send-commands",
+            Permission::ReceiveCommands => "receive-commands",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Operations permitted to each role of session. By default, Vulcasts produce and
+/// consume media and data and receive commands, WebClients consume media, produce and
+/// consume data and may hold control of input, and Hosts consume media, produce and
+/// consume data, moderate the room and send commands.
+#[derive(Debug, Clone)]
+pub struct Permissions {
+    shared: Arc<Mutex<HashMap<Role, HashSet<Permission>>>>,
+}
+
+impl Permissions {
+    pub fn new() -> Self {
+        let mut web_client = HashSet::from([
+            Permission::ConsumeMedia,
+            Permission::ProduceData,
+            Permission::ConsumeData,
+        ]);
+        let mut host = web_client.clone();
+        host.insert(Permission::Moderate);
+        host.insert(Permission::SendCommands);
+        web_client.insert(Permission::Control);
+        let vulcast = HashSet::from([
+            Permission::ProduceMedia,
+            Permission::ConsumeMedia,
+            Permission::ProduceData,
+            Permission::ConsumeData,
+            Permission::ReceiveCommands,
+        ]);
+        Self {
+            shared: Arc::new(Mutex::new(HashMap::from([
+                (Role::Vulcast, vulcast),
+                (Role::WebClient, web_client),
+                (Role::Host, host),
+            ]))),
+        }
+    }
+
+    /// Permit sessions of a role to perform a class of operation.
+    pub fn grant(&self, role: Role, permission: Permission) {
+        let mut permissions = self.shared.lock().unwrap();
+        permissions.entry(role).or_default().insert(permission);
+    }
+
+    /// Forbid sessions of a role from performing a class of operation.
+    pub fn revoke(&self, role: Role, permission: Permission) {
+        let mut permissions = self.shared.lock().unwrap();
+        permissions.entry(role).or_default().remove(&permission);
+    }
+
+    /// Whether sessions of a role may perform a class of operation.
+    pub fn allows(&self, role: Role, permission: Permission) -> bool {
+        let permissions = self.shared.lock().unwrap();
+        permissions
+            .get(&role)
+            .map_or(false, |permissions| permissions.contains(&permission))
+    }
+
+    /// Check that sessions of a role may perform a class of operation.
+    pub fn check(&self, role: Role, permission: Permission) -> Result<(), PermissionDeniedError> {
+        if self.allows(role, permission) {
+            Ok(())
+        } else {
+            Err(PermissionDeniedError { role, permission })
+        }
+    }
+}
+
+impl Default for Permissions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("{role} sessions lack the `{permission}` permission")]
+pub struct PermissionDeniedError {
+    pub role: Role,
+    pub permission: Permission,
+}
+impl ErrorExtensions for PermissionDeniedError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, extensions| {
+            extensions.set("code", "FORBIDDEN");
+            extensions.set("permission", self.permission.to_string());
+        })
+    }
+}
@@ -0,0 +1,58 @@
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema, SimpleObject, ID};
+
+use crate::control_schema;
+use crate::payload_limit::PayloadLimitCheck;
+use crate::relay_server::{RelayServer, Role};
+
+/// Read-only subset of the control schema, which is safe to serve without
+/// authentication (e.g. to a public lobby page), on a separate address from the
+/// control endpoint. Only rooms listed with the `setRoomPublic` control mutation are
+/// exposed, and never session IDs or tokens.
+#[derive(Default)]
+pub struct QueryRoot;
+#[Object]
+impl QueryRoot {
+    /// Get the version and build info of this relay instance.
+    async fn version(&self, _ctx: &Context<'_>) -> String {
+        control_schema::version()
+    }
+
+    /// Get the rooms listed as public, with the number of viewers connected to each.
+    async fn public_rooms(&self, ctx: &Context<'_>) -> Vec<PublicRoom> {
+        let relay_server = ctx.data_unchecked::<RelayServer>();
+        relay_server
+            .public_rooms()
+            .into_iter()
+            .map(|frid| PublicRoom {
+                viewers: relay_server.get_room(&frid).map_or(0, |room| {
+                    room.active_sessions()
+                        .iter()
+                        .filter(|session| session.get_session_options().role() == Role::WebClient)
+                        .count()
+                }),
+                id: frid.0.into(),
+            })
+            .collect()
+    }
+}
+
+/// Room listed as public.
+#[derive(SimpleObject)]
+struct PublicRoom {
+    id: ID,
+    /// Web clients currently connected to the room.
+    viewers: usize,
+}
+
+pub type PublicSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Version of the public schema, incremented on breaking changes.
+/// See [`crate::schema_compat`] for the compatibility rules.
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub fn schema(relay_server: RelayServer) -> PublicSchema {
+    PublicSchema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .extension(PayloadLimitCheck(relay_server.payload_limit()))
+        .data(relay_server)
+        .finish()
+}
@@ -0,0 +1,108 @@
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+use ring::rand::{SecureRandom, SystemRandom};
+use thiserror::Error;
+use tokio::net::UdpSocket;
+
+use crate::relay_server::RelayServer;
+
+/// Time to wait for a probe to return before the announce IP is deemed unreachable.
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Probes sent within the timeout, as UDP may drop any single probe.
+const PROBE_ATTEMPTS: u32 = 3;
+
+const STUN_BINDING_REQUEST: u16 = 0x0001;
+const STUN_MAGIC_COOKIE: u32 = 0x2112_a442;
+
+/// Check that the announce IP of a relay routes back to it, so that clients are able
+/// to reach its transports. Returns the round trip time of the probe, or `None` if the
+/// relay announces its listen IP.
+pub async fn check(relay_server: &RelayServer) -> Result<Option<Duration>, ReachabilityError> {
+    let transport_listen_ip = relay_server.transport_listen_ip();
+    match transport_listen_ip.announced_ip {
+        Some(announced_ip) => probe(
+            transport_listen_ip.ip,
+            announced_ip,
+            relay_server.rtc_ports_range().unwrap_or(0..=0),
+            PROBE_TIMEOUT,
+        )
+        .await
+        .map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Send a STUN binding request from a port in the RTC port range to the same port on
+/// the announce IP, and wait for the request to arrive back at the port. Media only
+/// flows if the announce IP routes to the listen IP, including through NAT hairpinning
+/// when probing from behind the NAT, so an unreachable announce IP is otherwise only
+/// noticed as silent media failure.
+pub async fn probe(
+    listen_ip: IpAddr,
+    announced_ip: IpAddr,
+    rtc_ports: RangeInclusive<u16>,
+    timeout: Duration,
+) -> Result<Duration, ReachabilityError> {
+    let socket = bind(listen_ip, rtc_ports).await?;
+    let target = SocketAddr::new(announced_ip, socket.local_addr()?.port());
+
+    let mut transaction_id = [0u8; 12];
+    SystemRandom::new()
+        .fill(&mut transaction_id)
+        .expect("system randomness is unavailable");
+    let mut request = Vec::with_capacity(20);
+    request.extend_from_slice(&STUN_BINDING_REQUEST.to_be_bytes());
+    request.extend_from_slice(&0u16.to_be_bytes());
+    request.extend_from_slice(&STUN_MAGIC_COOKIE.to_be_bytes());
+    request.extend_from_slice(&transaction_id);
+
+    let start = Instant::now();
+    let deadline = start + timeout;
+    let mut buf = [0u8; 1500];
+    for attempt in 0..PROBE_ATTEMPTS {
+        log::debug!("probing {} from {}", target, socket.local_addr()?);
+        socket
+            .send_to(&request, target)
+            .await
+            .map_err(ReachabilityError::Send)?;
+        let retry = (start + timeout * (attempt + 1) / PROBE_ATTEMPTS).min(deadline);
+        while let Ok(received) =
+            tokio::time::timeout_at(retry.into(), socket.recv_from(&mut buf)).await
+        {
+            // skip unrelated packets, and errors reported for previous probes
+            if matches!(received, Ok((len, _)) if buf[..len] == request[..]) {
+                return Ok(start.elapsed());
+            }
+        }
+    }
+    Err(ReachabilityError::Unreachable(target, timeout))
+}
+
+/// Bind a UDP socket to the first free port in the range, or any port if the range is
+/// `0..=0`.
+async fn bind(
+    listen_ip: IpAddr,
+    ports: RangeInclusive<u16>,
+) -> Result<UdpSocket, ReachabilityError> {
+    let mut last_err = io::Error::new(io::ErrorKind::AddrNotAvailable, "empty port range");
+    for port in ports {
+        match UdpSocket::bind(SocketAddr::new(listen_ip, port)).await {
+            Ok(socket) => return Ok(socket),
+            Err(err) => last_err = err,
+        }
+    }
+    Err(ReachabilityError::Bind(last_err))
+}
+
+#[derive(Debug, Error)]
+pub enum ReachabilityError {
+    #[error("failed to bind a probe socket in the rtc port range: {0}")]
+    Bind(#[from] io::Error),
+    #[error("failed to send probe: {0}")]
+    Send(io::Error),
+    #[error("probe to {0} did not return within {1:?}; check that the rtc announce ip routes to this relay (including nat hairpinning)")]
+    Unreachable(SocketAddr, Duration),
+}
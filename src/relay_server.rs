@@ -1,16 +1,41 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::RangeInclusive;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 use uuid::Uuid;
 
 use bimap::BiMap;
 use derive_more::Display;
+use futures::StreamExt;
 use mediasoup::data_structures::TransportListenIp;
 use mediasoup::{rtp_parameters::RtpCodecCapability, worker::Worker};
 use thiserror::Error;
+use tokio::task::JoinHandle;
 
-use crate::room::{Room, WeakRoom};
-use crate::session::Session;
+use crate::chaos::Chaos;
+use crate::control_queue::ControlQueue;
+use crate::data_filter::DataFilter;
+use crate::events::{Envelope, Event};
+use crate::join_throttle::{JoinThrottle, JoinThrottledError};
+use crate::latency_probe::LatencyProbe;
+use crate::message_store::MessageStore;
+use crate::payload_limit::PayloadLimit;
+use crate::permissions::Permissions;
+use crate::room::{Room, RoomOptions, RoomPreset, WeakRoom};
+use crate::room_churn::RoomChurnLimit;
+use crate::room_gc::RoomGc;
+use crate::session::{Session, SessionId, WeakSession};
+use crate::stream_policy::{StreamPolicy, StreamRules};
+use crate::subsystems::Subsystems;
+use crate::turn::{TurnConfig, TurnCredentials};
+use crate::version_gate::VersionGate;
+use crate::worker_monitor::WorkerMonitor;
+
+/// Maximum number of sessions whose last disconnect is remembered.
+const MAX_DISCONNECTS: usize = 4096;
+/// Time before a session reaches its maximum duration at which it is warned.
+const SESSION_EXPIRY_WARNING: Duration = Duration::from_secs(60);
 
 #[derive(Clone)]
 pub struct RelayServer {
@@ -23,6 +48,16 @@ struct Shared {
     transport_listen_ip: TransportListenIp,
     media_codecs: Vec<RtpCodecCapability>,
     worker: Worker,
+    worker_monitor: WorkerMonitor,
+    join_throttle: JoinThrottle,
+    room_churn: RoomChurnLimit,
+    room_gc: RoomGc,
+    subsystems: Subsystems,
+    chaos: Chaos,
+    version_gate: VersionGate,
+    control_queue: ControlQueue,
+    payload_limit: PayloadLimit,
+    permissions: Permissions,
 }
 
 struct State {
@@ -32,10 +67,114 @@ struct State {
     registered_rooms: BiMap<ForeignRoomId, ForeignSessionId>,
     /// mapping of foreign session id to session options
     session_options: HashMap<ForeignSessionId, SessionOptions>,
+    /// human-readable names of sessions registered with one
+    display_names: HashMap<ForeignSessionId, String>,
     /// mapping of foreign session id of vulcast to corresponding room
     rooms: HashMap<ForeignSessionId, WeakRoom>,
     /// mapping of foreign session id to owning session
     sessions: HashMap<ForeignSessionId, Session>,
+    /// sessions of further connections with the same token as an owning session, oldest
+    /// first, if allowed by the duplicate connection policy
+    parallel_sessions: HashMap<ForeignSessionId, Vec<Session>>,
+    /// handling of connections with the token of an already connected session
+    duplicate_connection_policy: DuplicateConnectionPolicy,
+    /// filter applied to data relayed through the server in new rooms, if any
+    data_filter: Option<Arc<dyn DataFilter>>,
+    /// filters overriding the default data filter in specific rooms
+    room_data_filters: HashMap<ForeignRoomId, Arc<dyn DataFilter>>,
+    /// streams each role of session may produce and auto-consume in new rooms
+    stream_policy: StreamPolicy,
+    /// stream policies overriding the default stream policy in specific rooms
+    room_stream_policies: HashMap<ForeignRoomId, StreamPolicy>,
+    /// media carried by each registered room
+    room_presets: HashMap<ForeignRoomId, RoomPreset>,
+    /// registered rooms listed on the public control endpoint
+    public_rooms: HashSet<ForeignRoomId>,
+    /// registered rooms which new WebClient sessions may not connect to
+    locked_rooms: HashSet<ForeignRoomId>,
+    /// TURN server offered to sessions, if any
+    turn_config: Option<Arc<TurnConfig>>,
+    /// TURN servers overriding the default TURN server in specific rooms
+    room_turn_configs: HashMap<ForeignRoomId, Arc<TurnConfig>>,
+    /// store buffering undelivered commands and room events for sessions, if any
+    message_store: Option<Arc<dyn MessageStore>>,
+    /// buffers of the room events of sessions whose client is disconnected
+    event_buffers: HashMap<ForeignSessionId, EventBuffer>,
+    /// ports the worker allocates to transports, if known
+    rtc_ports_range: Option<RangeInclusive<u16>>,
+    /// cap on the incoming bitrate of each transport of new sessions, if any
+    max_incoming_bitrate: Option<u32>,
+    /// time for which stats snapshots of new sessions are reused, if cached
+    stats_cache_ttl: Option<Duration>,
+    /// time after which idle transports of new sessions are closed, if any
+    idle_transport_timeout: Option<Duration>,
+    /// maximum connected duration of web client sessions in rooms of each preset
+    max_session_durations: HashMap<RoomPreset, Duration>,
+    /// time at which each web client session with a maximum duration expires
+    session_deadlines: HashMap<ForeignSessionId, Instant>,
+    /// probe measuring the latency of media through new rooms, if any
+    latency_probe: Option<LatencyProbe>,
+    /// time for which sessions outlive the connection of their client, if any
+    reconnect_grace: Option<Duration>,
+    /// deadline of each session outliving the connection of its client, until which
+    /// the client may reconnect to it
+    lingering_sessions: HashMap<ForeignSessionId, Instant>,
+    /// last disconnect of each recently disconnected session
+    disconnects: HashMap<ForeignSessionId, Disconnect>,
+    /// recently disconnected sessions, least recent first
+    disconnect_order: VecDeque<ForeignSessionId>,
+}
+impl State {
+    /// Take ownership of the owning session of an FSID, promoting its oldest parallel
+    /// session to the owning session, if any.
+    fn remove_session(&mut self, fsid: &ForeignSessionId) -> Option<Session> {
+        let session = self.sessions.remove(fsid)?;
+        if let Some(parallel_sessions) = self.parallel_sessions.get_mut(fsid) {
+            let promoted = parallel_sessions.remove(0);
+            if parallel_sessions.is_empty() {
+                self.parallel_sessions.remove(fsid);
+            }
+            self.sessions.insert(fsid.clone(), promoted);
+        }
+        Some(session)
+    }
+
+    /// Whether a session is kept from connecting by the lock of its room.
+    fn locked_out(&self, session_options: &SessionOptions) -> bool {
+        match session_options {
+            SessionOptions::WebClient(frid) => self.locked_rooms.contains(frid),
+            SessionOptions::Vulcast | SessionOptions::Host(_) => false,
+        }
+    }
+
+    fn record_disconnect(&mut self, fsid: &ForeignSessionId, reason: DisconnectReason) {
+        log::debug!("session {} disconnected: {:?}", fsid, reason);
+        let disconnect = Disconnect {
+            reason,
+            time: SystemTime::now(),
+        };
+        if self.disconnects.insert(fsid.clone(), disconnect).is_none() {
+            self.disconnect_order.push_back(fsid.clone());
+        }
+        while self.disconnect_order.len() > MAX_DISCONNECTS {
+            if let Some(fsid) = self.disconnect_order.pop_front() {
+                self.disconnects.remove(&fsid);
+            }
+        }
+    }
+}
+
+/// Buffers the room events of a session into the message store while its client is
+/// disconnected. Buffering stops once dropped.
+struct EventBuffer {
+    /// sequence of the last room event before the client disconnected
+    last_sequence: u64,
+    task: JoinHandle<()>,
+}
+impl Drop for EventBuffer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
 }
 
 impl RelayServer {
@@ -50,11 +189,46 @@ impl RelayServer {
                     registered_sessions: BiMap::new(),
                     registered_rooms: BiMap::new(),
                     session_options: HashMap::new(),
+                    display_names: HashMap::new(),
                     rooms: HashMap::new(),
                     sessions: HashMap::new(),
+                    parallel_sessions: HashMap::new(),
+                    duplicate_connection_policy: DuplicateConnectionPolicy::default(),
+                    data_filter: None,
+                    room_data_filters: HashMap::new(),
+                    stream_policy: StreamPolicy::new(),
+                    room_stream_policies: HashMap::new(),
+                    room_presets: HashMap::new(),
+                    public_rooms: HashSet::new(),
+                    locked_rooms: HashSet::new(),
+                    turn_config: None,
+                    room_turn_configs: HashMap::new(),
+                    message_store: None,
+                    event_buffers: HashMap::new(),
+                    rtc_ports_range: None,
+                    max_incoming_bitrate: None,
+                    stats_cache_ttl: None,
+                    idle_transport_timeout: None,
+                    max_session_durations: HashMap::new(),
+                    session_deadlines: HashMap::new(),
+                    latency_probe: None,
+                    reconnect_grace: None,
+                    lingering_sessions: HashMap::new(),
+                    disconnects: HashMap::new(),
+                    disconnect_order: VecDeque::new(),
                 }),
                 media_codecs,
                 transport_listen_ip,
+                worker_monitor: WorkerMonitor::new(worker.clone()),
+                join_throttle: JoinThrottle::default(),
+                room_churn: RoomChurnLimit::default(),
+                room_gc: RoomGc::new(),
+                subsystems: Subsystems::new(),
+                chaos: Chaos::new(),
+                version_gate: VersionGate::new(),
+                control_queue: ControlQueue::new(),
+                payload_limit: PayloadLimit::new(),
+                permissions: Permissions::new(),
                 worker,
             }),
         }
@@ -65,6 +239,17 @@ impl RelayServer {
         &self,
         frid: ForeignRoomId,
         vulcast_fsid: ForeignSessionId,
+    ) -> Result<(), RegisterRoomError> {
+        self.register_room_with_preset(frid, vulcast_fsid, RoomPreset::default())
+    }
+
+    /// Register a room with specified FRID, associated to a Vulcast by FSID,
+    /// carrying the media of the given preset.
+    pub fn register_room_with_preset(
+        &self,
+        frid: ForeignRoomId,
+        vulcast_fsid: ForeignSessionId,
+        preset: RoomPreset,
     ) -> Result<(), RegisterRoomError> {
         let mut state = self.shared.state.lock().unwrap();
         match state.session_options.get(&vulcast_fsid) {
@@ -73,8 +258,31 @@ impl RelayServer {
                     Err(RegisterRoomError::NonUniqueId(frid))
                 } else if state.registered_rooms.contains_right(&vulcast_fsid) {
                     Err(RegisterRoomError::VulcastInRoom(vulcast_fsid))
+                } else if let Err(err) = self
+                    .shared
+                    .room_churn
+                    .register(&vulcast_fsid, Instant::now())
+                {
+                    Err(RegisterRoomError::ChurnLimited {
+                        vulcast_fsid: err.vulcast_fsid,
+                        retry_after: err.retry_after,
+                    })
+                } else if state
+                    .rooms
+                    .get(&vulcast_fsid)
+                    .and_then(|weak_room| weak_room.upgrade())
+                    .map_or(false, |room| room.set_preset(preset).is_err())
+                {
+                    // the vulcast already negotiated media in a room of another preset
+                    Err(RegisterRoomError::PresetUnavailable(frid))
                 } else {
-                    log::trace!("+foreign room {} (vulcast fsid {})", &frid, &vulcast_fsid);
+                    log::trace!(
+                        "+foreign room {} (vulcast fsid {}) [{:?}]",
+                        &frid,
+                        &vulcast_fsid,
+                        preset
+                    );
+                    state.room_presets.insert(frid.clone(), preset);
                     state
                         .registered_rooms
                         .insert_no_overwrite(frid, vulcast_fsid)
@@ -91,11 +299,23 @@ impl RelayServer {
         let mut state = self.shared.state.lock().unwrap();
         match state.registered_rooms.remove_by_left(&frid) {
             Some(_) => {
+                state.room_data_filters.remove(&frid);
+                state.room_stream_policies.remove(&frid);
+                state.room_presets.remove(&frid);
+                state.room_turn_configs.remove(&frid);
+                state.public_rooms.remove(&frid);
+                state.locked_rooms.remove(&frid);
                 drop(state);
                 // nuke all client sessions in this room
                 self.get_client_sessions_in_room(&frid)
                     .into_iter()
-                    .for_each(|fsid| self.unregister_session(fsid).unwrap());
+                    .for_each(|fsid| {
+                        self.unregister_session_with_reason(
+                            fsid,
+                            DisconnectReason::RoomUnregistered,
+                        )
+                        .unwrap()
+                    });
                 log::trace!("-foreign room {}", frid);
                 Ok(())
             }
@@ -109,6 +329,17 @@ impl RelayServer {
         &self,
         fsid: ForeignSessionId,
         session_options: SessionOptions,
+    ) -> Result<SessionToken, RegisterSessionError> {
+        self.register_session_with_display_name(fsid, session_options, None)
+    }
+
+    /// Register a session with specified FSID, with a human-readable name shown to the
+    /// other participants of its room.
+    pub fn register_session_with_display_name(
+        &self,
+        fsid: ForeignSessionId,
+        session_options: SessionOptions,
+        display_name: Option<String>,
     ) -> Result<SessionToken, RegisterSessionError> {
         let mut state = self.shared.state.lock().unwrap();
         let session_token = SessionToken::new();
@@ -124,6 +355,9 @@ impl RelayServer {
             {
                 Ok(_) => {
                     log::trace!("+foreign session {} [{:?}]", &fsid, session_options);
+                    if let Some(display_name) = display_name {
+                        state.display_names.insert(fsid.clone(), display_name);
+                    }
                     state.session_options.insert(fsid, session_options.clone());
                     Ok(session_token)
                 }
@@ -141,11 +375,26 @@ impl RelayServer {
     /// Unregister a session by FSID. This will drop the PHY session.
     /// If the session belongs to a Vulcast, this will unregister the PHY room.
     pub fn unregister_session(&self, fsid: ForeignSessionId) -> Result<(), UnregisterSessionError> {
+        self.unregister_session_with_reason(fsid, DisconnectReason::Unregistered)
+    }
+    fn unregister_session_with_reason(
+        &self,
+        fsid: ForeignSessionId,
+        reason: DisconnectReason,
+    ) -> Result<(), UnregisterSessionError> {
         let mut state = self.shared.state.lock().unwrap();
         // remove registration info
         match state.registered_sessions.remove_by_left(&fsid) {
             Some(_) => {
                 let session_options = state.session_options.remove(&fsid).unwrap();
+                state.display_names.remove(&fsid);
+                state.session_deadlines.remove(&fsid);
+                state.lingering_sessions.remove(&fsid);
+                state.event_buffers.remove(&fsid);
+                let parallel_sessions = state.parallel_sessions.remove(&fsid);
+                if let Some(message_store) = &state.message_store {
+                    message_store.clear(&fsid);
+                }
                 // this code is a deadlock nightmare so don't touch it
                 match session_options {
                     SessionOptions::Vulcast => {
@@ -153,17 +402,18 @@ impl RelayServer {
                         if let Some(frid) = state.registered_rooms.get_by_right(&fsid).cloned() {
                             drop(state);
                             self.unregister_room(frid).unwrap();
-                            drop(self.take_session(&fsid));
+                            drop(self.end_session(&fsid, reason));
                         } else {
                             drop(state);
-                            drop(self.take_session(&fsid));
+                            drop(self.end_session(&fsid, reason));
                         }
                     }
                     SessionOptions::WebClient(_) | SessionOptions::Host(_) => {
                         drop(state);
-                        drop(self.take_session(&fsid));
+                        drop(self.end_session(&fsid, reason));
                     }
                 }
+                drop(parallel_sessions);
                 log::trace!("-foreign session {} [{:?}]", &fsid, session_options);
                 Ok(())
             }
@@ -171,6 +421,36 @@ impl RelayServer {
         }
     }
 
+    /// Drop the PHY sessions of an FSID, including parallel sessions, e.g. when a host
+    /// kicks a disruptive client from its room. Other participants are notified that
+    /// the client left. The client may connect again with its token unless the session
+    /// is also unregistered.
+    pub fn kick_session(
+        &self,
+        fsid: ForeignSessionId,
+        unregister: bool,
+    ) -> Result<(), UnregisterSessionError> {
+        if unregister {
+            return self.unregister_session_with_reason(fsid, DisconnectReason::Kicked);
+        }
+        let mut state = self.shared.state.lock().unwrap();
+        if !state.registered_sessions.contains_left(&fsid) {
+            return Err(UnregisterSessionError::UnknownSession(fsid));
+        }
+        state.lingering_sessions.remove(&fsid);
+        let parallel_sessions = state.parallel_sessions.remove(&fsid);
+        let session = state.sessions.remove(&fsid);
+        if session.is_some() {
+            state.record_disconnect(&fsid, DisconnectReason::Kicked);
+        }
+        drop(state);
+        // drop sessions once unlocked, as they leave their rooms
+        drop(session);
+        drop(parallel_sessions);
+        log::debug!("kicked session {}", fsid);
+        Ok(())
+    }
+
     /// Get a reference to a PHY session by FSID. You MUST drop this reference
     /// after you are done with it.
     pub fn get_session(&self, fsid: &ForeignSessionId) -> Option<Session> {
@@ -178,20 +458,207 @@ impl RelayServer {
         state.sessions.get(fsid).cloned()
     }
 
+    /// Get PHY session by session token, if connected.
+    pub fn get_session_by_token(&self, token: &SessionToken) -> Option<Session> {
+        let state = self.shared.state.lock().unwrap();
+        let fsid = state.registered_sessions.get_by_right(token)?;
+        state.sessions.get(fsid).cloned()
+    }
+
     /// Take ownership of PHY session by FSID.
     pub fn take_session(&self, fsid: &ForeignSessionId) -> Option<Session> {
         let mut state = self.shared.state.lock().unwrap();
         state.sessions.remove(fsid)
     }
 
-    /// Take ownership of PHY session by session token.
-    pub fn take_session_by_token(&self, token: &SessionToken) -> Option<Session> {
+    /// Take ownership of PHY session by FSID as it ends, recording why.
+    fn end_session(&self, fsid: &ForeignSessionId, reason: DisconnectReason) -> Option<Session> {
         let mut state = self.shared.state.lock().unwrap();
-        state
-            .registered_sessions
-            .get_by_right(token)
-            .cloned()
-            .and_then(|fsid| state.sessions.remove(&fsid))
+        let session = state.remove_session(fsid)?;
+        state.record_disconnect(fsid, reason);
+        Some(session)
+    }
+
+    /// Take ownership of PHY session by session token once its client disconnected.
+    pub fn disconnect_session_by_token(&self, token: &SessionToken) -> Option<Session> {
+        let mut state = self.shared.state.lock().unwrap();
+        let fsid = state.registered_sessions.get_by_right(token).cloned()?;
+        let session = state.remove_session(&fsid)?;
+        state.record_disconnect(&fsid, DisconnectReason::ClientClosed);
+        Some(session)
+    }
+
+    /// Release a PHY session, specified by ID, of a client whose connection closed.
+    /// Parallel sessions are dropped immediately, and owning sessions released as by
+    /// [`RelayServer::release_session_by_token`]. Sessions which were already replaced
+    /// are ignored, so that a closing connection never releases the session of a newer
+    /// connection with the same token.
+    pub fn release_session(&self, token: &SessionToken, session_id: SessionId) {
+        let mut state = self.shared.state.lock().unwrap();
+        let fsid = match state.registered_sessions.get_by_right(token) {
+            Some(fsid) => fsid.clone(),
+            None => return,
+        };
+        if let Some(parallel_sessions) = state.parallel_sessions.get_mut(&fsid) {
+            if let Some(index) = parallel_sessions
+                .iter()
+                .position(|session| session.id() == session_id)
+            {
+                let session = parallel_sessions.remove(index);
+                if parallel_sessions.is_empty() {
+                    state.parallel_sessions.remove(&fsid);
+                }
+                drop(state);
+                log::debug!("parallel session {} of {} closed", session_id, fsid);
+                drop(session);
+                return;
+            }
+        }
+        let owning = state
+            .sessions
+            .get(&fsid)
+            .map_or(false, |session| session.id() == session_id);
+        drop(state);
+        if owning {
+            self.release_session_by_token(token);
+        }
+    }
+
+    /// Release the PHY session of a client whose connection closed. Within the reconnect
+    /// grace period, the session outlives the connection, so that a client reconnecting
+    /// with the same token resumes the session along with its transports and producers
+    /// (e.g. after a brief network blip of a Vulcast, so its viewers do not renegotiate).
+    /// The session is dropped once the grace period passes without a reconnect.
+    pub fn release_session_by_token(&self, token: &SessionToken) {
+        self.buffer_events(token);
+        let mut state = self.shared.state.lock().unwrap();
+        let reconnect_grace = match state.reconnect_grace {
+            Some(reconnect_grace) => reconnect_grace,
+            None => {
+                drop(state);
+                drop(self.disconnect_session_by_token(token));
+                return;
+            }
+        };
+        let fsid = match state.registered_sessions.get_by_right(token) {
+            Some(fsid) if state.sessions.contains_key(fsid) => fsid.clone(),
+            _ => return,
+        };
+        log::debug!("session {} lingering for {:?}", fsid, reconnect_grace);
+        let deadline = Instant::now() + reconnect_grace;
+        state.lingering_sessions.insert(fsid.clone(), deadline);
+        drop(state);
+
+        let relay_server = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep_until(deadline.into()).await;
+            let mut state = relay_server.shared.state.lock().unwrap();
+            // the client may have resumed the session, and possibly disconnected again
+            if state.lingering_sessions.get(&fsid) == Some(&deadline) {
+                state.lingering_sessions.remove(&fsid);
+                drop(state);
+                drop(relay_server.end_session(&fsid, DisconnectReason::ClientClosed));
+            }
+        });
+    }
+
+    /// Buffer the events of the room of a session while its client is disconnected, if
+    /// a message store is set, until the client replays them with
+    /// [`RelayServer::take_buffered_events`] or the session is unregistered. Audio
+    /// levels are not buffered, as they are superseded by the next report.
+    fn buffer_events(&self, token: &SessionToken) {
+        let mut state = self.shared.state.lock().unwrap();
+        let message_store = match &state.message_store {
+            Some(message_store) => message_store.clone(),
+            None => return,
+        };
+        let fsid = match state.registered_sessions.get_by_right(token) {
+            // keep buffering if the client disconnected again before replaying
+            Some(fsid) if !state.event_buffers.contains_key(fsid) => fsid.clone(),
+            _ => return,
+        };
+        let room = match state.sessions.get(&fsid) {
+            Some(session) => session.get_room(),
+            None => return,
+        };
+        // discard events pushed after the last replay stopped buffering
+        message_store.take_events(&fsid);
+
+        let last_sequence = room.last_sequence();
+        let events = room.events();
+        let task = tokio::spawn({
+            let fsid = fsid.clone();
+            async move {
+                tokio::pin!(events);
+                while let Some(envelope) = events.next().await {
+                    if !matches!(
+                        envelope.event,
+                        Event::ActiveSpeaker { .. } | Event::AudioLevels { .. }
+                    ) {
+                        message_store.push_event(&fsid, envelope);
+                    }
+                }
+            }
+        });
+        log::debug!("buffering room events of session {}", fsid);
+        state.event_buffers.insert(
+            fsid,
+            EventBuffer {
+                last_sequence,
+                task,
+            },
+        );
+    }
+
+    /// Stop buffering the room events of a session whose client reconnected, taking
+    /// the events buffered while it was disconnected, oldest first. Also returns the
+    /// sequence of the last room event the buffer covers, from which the client may
+    /// resume the events of its room. None if the room events of the session were not
+    /// buffered.
+    pub fn take_buffered_events(&self, fsid: &ForeignSessionId) -> Option<(Vec<Envelope>, u64)> {
+        let mut state = self.shared.state.lock().unwrap();
+        let event_buffer = state.event_buffers.remove(fsid)?;
+        let last_sequence = event_buffer.last_sequence;
+        drop(event_buffer);
+        let events = state
+            .message_store
+            .as_ref()
+            .map(|message_store| message_store.take_events(fsid))
+            .unwrap_or_default();
+        // events after the last buffered event are resumed from the room instead
+        let last_sequence = events
+            .last()
+            .and_then(|envelope| envelope.sequence)
+            .map_or(last_sequence, |sequence| sequence.max(last_sequence));
+        Some((events, last_sequence))
+    }
+
+    /// Get the last disconnect of a recently disconnected session, specified by FSID.
+    pub fn last_disconnect(&self, fsid: &ForeignSessionId) -> Option<Disconnect> {
+        let state = self.shared.state.lock().unwrap();
+        state.disconnects.get(fsid).copied()
+    }
+
+    /// Get the role of the session with the given token, if registered.
+    pub fn get_role_by_token(&self, token: &SessionToken) -> Option<Role> {
+        let state = self.shared.state.lock().unwrap();
+        let fsid = state.registered_sessions.get_by_right(token)?;
+        state.session_options.get(fsid).map(SessionOptions::role)
+    }
+
+    /// Wait for a turn for the session with the given token to join its room, so
+    /// that sessions join each room no faster than the join throttle allows.
+    /// Sessions which do not belong to a room yet are admitted immediately.
+    pub async fn admit_session(&self, token: &SessionToken) -> Result<(), JoinThrottledError> {
+        let frid = {
+            let state = self.shared.state.lock().unwrap();
+            state.registered_sessions.get_by_right(token).cloned()
+        }
+        .and_then(|fsid| self.get_foreign_room_id(&fsid));
+        match frid {
+            Some(frid) => self.shared.join_throttle.admit(&frid).await,
+            None => Ok(()),
+        }
     }
 
     /// Create PHY session from session token, obtained via registration.
@@ -206,8 +673,59 @@ impl RelayServer {
             .cloned()
             .unwrap();
 
-        // drop existing session if exists
-        state.sessions.remove(&foreign_session_id);
+        // resume the session if it outlived the connection of its client
+        if state
+            .lingering_sessions
+            .remove(&foreign_session_id)
+            .is_some()
+        {
+            if let Some(session) = state.sessions.get(&foreign_session_id) {
+                log::debug!("session {} resumed", &foreign_session_id);
+                return Some(session.clone());
+            }
+        }
+
+        // keep new web clients out of locked rooms
+        if state.locked_out(&session_options) {
+            log::debug!(
+                "rejected connection of session {} to locked room",
+                &foreign_session_id
+            );
+            return None;
+        }
+
+        // handle existing session if exists
+        let mut parallel = false;
+        if state.sessions.contains_key(&foreign_session_id) {
+            match state.duplicate_connection_policy {
+                DuplicateConnectionPolicy::RejectNew => {
+                    log::debug!(
+                        "rejected duplicate connection of session {}",
+                        &foreign_session_id
+                    );
+                    return None;
+                }
+                DuplicateConnectionPolicy::EvictOld => {
+                    state.sessions.remove(&foreign_session_id);
+                    state.record_disconnect(&foreign_session_id, DisconnectReason::Replaced);
+                }
+                DuplicateConnectionPolicy::AllowParallel(max_connections) => {
+                    let connections = 1 + state
+                        .parallel_sessions
+                        .get(&foreign_session_id)
+                        .map_or(0, Vec::len);
+                    if connections >= max_connections {
+                        log::debug!(
+                            "rejected connection of session {} beyond {} parallel connections",
+                            &foreign_session_id,
+                            max_connections
+                        );
+                        return None;
+                    }
+                    parallel = true;
+                }
+            }
+        }
 
         // find vulcast fsid of the room this session should connect to
         let vulcast_fsid = match &session_options {
@@ -218,23 +736,533 @@ impl RelayServer {
         };
 
         // find/create the phy room corresponding to the vulcast fsid
+        let registered_room = state.registered_rooms.get_by_right(&vulcast_fsid);
         let room = state
             .rooms
             .get(&vulcast_fsid)
             .and_then(|weak_room| weak_room.upgrade())
             .unwrap_or_else(|| {
-                Room::new(self.shared.worker.clone(), self.shared.media_codecs.clone())
+                Room::new(
+                    self.shared.worker.clone(),
+                    self.shared.media_codecs.clone(),
+                    RoomOptions {
+                        vulcast_session_id: vulcast_fsid.clone(),
+                        data_filter: registered_room
+                            .and_then(|frid| state.room_data_filters.get(frid).cloned())
+                            .or_else(|| state.data_filter.clone()),
+                        message_store: state.message_store.clone(),
+                        latency_probe: state.latency_probe.clone(),
+                        preset: registered_room
+                            .and_then(|frid| state.room_presets.get(frid).copied())
+                            .unwrap_or_default(),
+                        stream_policy: registered_room
+                            .and_then(|frid| state.room_stream_policies.get(frid).cloned())
+                            .unwrap_or_else(|| state.stream_policy.clone()),
+                    },
+                )
             });
         state.rooms.insert(vulcast_fsid, room.downgrade()); // may re-insert
 
         // create and bind session to room
-        let session = Session::new(room, session_options, self.shared.transport_listen_ip);
+        let session = Session::new(
+            room,
+            foreign_session_id.clone(),
+            session_options.clone(),
+            state.display_names.get(&foreign_session_id).cloned(),
+            self.shared.transport_listen_ip,
+            state.max_incoming_bitrate,
+            state.stats_cache_ttl,
+            state.idle_transport_timeout,
+        );
 
-        // store owning session
-        state.sessions.insert(foreign_session_id, session.clone());
+        // web clients expire once connected for the maximum duration of the room, however
+        // often they reconnect
+        let max_session_duration = match &session_options {
+            SessionOptions::WebClient(frid) => state
+                .max_session_durations
+                .get(&state.room_presets.get(frid).copied().unwrap_or_default())
+                .copied(),
+            SessionOptions::Vulcast | SessionOptions::Host(_) => None,
+        };
+        if let Some(max_session_duration) = max_session_duration {
+            let deadline = *state
+                .session_deadlines
+                .entry(foreign_session_id.clone())
+                .or_insert_with(|| Instant::now() + max_session_duration);
+            self.spawn_session_expiry(foreign_session_id.clone(), session.downgrade(), deadline);
+        }
+
+        // store owning session, or parallel session alongside it
+        if parallel {
+            state
+                .parallel_sessions
+                .entry(foreign_session_id)
+                .or_default()
+                .push(session.clone());
+        } else {
+            state.sessions.insert(foreign_session_id, session.clone());
+        }
         Some(session)
     }
 
+    /// Warn a session shortly before its deadline, then unregister it at the deadline,
+    /// unless the session ended before then.
+    fn spawn_session_expiry(
+        &self,
+        fsid: ForeignSessionId,
+        session: WeakSession,
+        deadline: Instant,
+    ) {
+        let relay_server = self.clone();
+        tokio::spawn(async move {
+            let warning = deadline
+                .checked_sub(SESSION_EXPIRY_WARNING)
+                .unwrap_or(deadline);
+            tokio::time::sleep_until(warning.into()).await;
+            match session.upgrade() {
+                Some(session) => {
+                    session.warn_expiring(deadline.saturating_duration_since(Instant::now()))
+                }
+                None => return,
+            }
+            tokio::time::sleep_until(deadline.into()).await;
+            if session.upgrade().is_some() {
+                log::debug!("session {} reached its maximum duration", fsid);
+                drop(relay_server.unregister_session_with_reason(fsid, DisconnectReason::Expired));
+            }
+        });
+    }
+
+    /// Get the FRID of the room a session belongs to, specified by FSID.
+    /// A Vulcast only belongs to a room once a room is registered to it.
+    pub fn get_foreign_room_id(&self, fsid: &ForeignSessionId) -> Option<ForeignRoomId> {
+        let state = self.shared.state.lock().unwrap();
+        match state.session_options.get(fsid)? {
+            SessionOptions::Vulcast => state.registered_rooms.get_by_right(fsid).cloned(),
+            SessionOptions::WebClient(frid) | SessionOptions::Host(frid) => Some(frid.clone()),
+        }
+    }
+
+    /// Relay data from sessions through the server in rooms created from now on,
+    /// passing each message through the given filter. Data flows directly between
+    /// sessions if unset.
+    pub fn set_data_filter(&self, data_filter: Option<Arc<dyn DataFilter>>) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.data_filter = data_filter;
+    }
+
+    /// Relay data from sessions through the server in a specific room, passing each
+    /// message through the given filter instead of the default filter. Applies to data
+    /// producers created from now on. The default filter is restored if unset.
+    pub fn set_room_data_filter(
+        &self,
+        frid: ForeignRoomId,
+        data_filter: Option<Arc<dyn DataFilter>>,
+    ) -> Result<(), SetRoomDataFilterError> {
+        let mut state = self.shared.state.lock().unwrap();
+        let vulcast_fsid = state
+            .registered_rooms
+            .get_by_left(&frid)
+            .cloned()
+            .ok_or_else(|| SetRoomDataFilterError::UnknownRoom(frid.clone()))?;
+        match &data_filter {
+            Some(data_filter) => state.room_data_filters.insert(frid, data_filter.clone()),
+            None => state.room_data_filters.remove(&frid),
+        };
+        if let Some(room) = state.rooms.get(&vulcast_fsid).and_then(|x| x.upgrade()) {
+            room.set_data_filter(data_filter.or_else(|| state.data_filter.clone()));
+        }
+        Ok(())
+    }
+
+    /// Restrict the streams each role of session may produce and auto-consume in rooms
+    /// created from now on.
+    pub fn set_stream_policy(&self, stream_policy: StreamPolicy) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.stream_policy = stream_policy;
+    }
+    /// Get the stream policy of rooms without a room-specific policy.
+    pub fn stream_policy(&self) -> StreamPolicy {
+        self.shared.state.lock().unwrap().stream_policy.clone()
+    }
+
+    /// Restrict the streams each role of session may produce and auto-consume in a
+    /// specific room instead of the default stream policy. Applies to producers created
+    /// and announced from now on. The default stream policy is restored if unset.
+    pub fn set_room_stream_policy(
+        &self,
+        frid: ForeignRoomId,
+        stream_policy: Option<StreamPolicy>,
+    ) -> Result<(), SetRoomStreamPolicyError> {
+        let mut state = self.shared.state.lock().unwrap();
+        let vulcast_fsid = state
+            .registered_rooms
+            .get_by_left(&frid)
+            .cloned()
+            .ok_or_else(|| SetRoomStreamPolicyError::UnknownRoom(frid.clone()))?;
+        match &stream_policy {
+            Some(stream_policy) => state
+                .room_stream_policies
+                .insert(frid, stream_policy.clone()),
+            None => state.room_stream_policies.remove(&frid),
+        };
+        if let Some(room) = state.rooms.get(&vulcast_fsid).and_then(|x| x.upgrade()) {
+            room.set_stream_policy(stream_policy.unwrap_or_else(|| state.stream_policy.clone()));
+        }
+        Ok(())
+    }
+
+    /// Replace the stream rules of a role of session in a specific room, keeping the
+    /// rules of other roles from the current policy of the room.
+    pub fn set_room_stream_rules(
+        &self,
+        frid: ForeignRoomId,
+        role: Role,
+        rules: StreamRules,
+    ) -> Result<(), SetRoomStreamPolicyError> {
+        let mut state = self.shared.state.lock().unwrap();
+        let vulcast_fsid = state
+            .registered_rooms
+            .get_by_left(&frid)
+            .cloned()
+            .ok_or_else(|| SetRoomStreamPolicyError::UnknownRoom(frid.clone()))?;
+        let mut stream_policy = state
+            .room_stream_policies
+            .get(&frid)
+            .cloned()
+            .unwrap_or_else(|| state.stream_policy.clone());
+        stream_policy.set_rules(role, rules);
+        state
+            .room_stream_policies
+            .insert(frid, stream_policy.clone());
+        if let Some(room) = state.rooms.get(&vulcast_fsid).and_then(|x| x.upgrade()) {
+            room.set_stream_policy(stream_policy);
+        }
+        Ok(())
+    }
+
+    /// Offer credentials for a TURN server to sessions. No TURN server is offered if unset.
+    pub fn set_turn_config(&self, turn_config: Option<TurnConfig>) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.turn_config = turn_config.map(Arc::new);
+    }
+
+    /// Offer credentials for a TURN server to sessions in a specific room instead of
+    /// the default TURN server. The default TURN server is restored if unset.
+    pub fn set_room_turn_config(
+        &self,
+        frid: ForeignRoomId,
+        turn_config: Option<TurnConfig>,
+    ) -> Result<(), SetRoomTurnConfigError> {
+        let mut state = self.shared.state.lock().unwrap();
+        if !state.registered_rooms.contains_left(&frid) {
+            return Err(SetRoomTurnConfigError::UnknownRoom(frid));
+        }
+        match turn_config {
+            Some(turn_config) => state.room_turn_configs.insert(frid, Arc::new(turn_config)),
+            None => state.room_turn_configs.remove(&frid),
+        };
+        Ok(())
+    }
+
+    /// List a room on the public control endpoint, or unlist it. Rooms are unlisted
+    /// when registered.
+    pub fn set_room_public(
+        &self,
+        frid: ForeignRoomId,
+        public: bool,
+    ) -> Result<(), SetRoomPublicError> {
+        let mut state = self.shared.state.lock().unwrap();
+        if !state.registered_rooms.contains_left(&frid) {
+            return Err(SetRoomPublicError::UnknownRoom(frid));
+        }
+        if public {
+            state.public_rooms.insert(frid);
+        } else {
+            state.public_rooms.remove(&frid);
+        }
+        Ok(())
+    }
+
+    /// Lock a room, so that new WebClient sessions cannot connect to it, or unlock it.
+    /// Connected sessions stay connected, and may resume after brief disconnects.
+    /// Rooms are unlocked when registered.
+    pub fn set_room_locked(
+        &self,
+        frid: ForeignRoomId,
+        locked: bool,
+    ) -> Result<(), SetRoomLockedError> {
+        let mut state = self.shared.state.lock().unwrap();
+        if !state.registered_rooms.contains_left(&frid) {
+            return Err(SetRoomLockedError::UnknownRoom(frid));
+        }
+        if locked {
+            state.locked_rooms.insert(frid);
+        } else {
+            state.locked_rooms.remove(&frid);
+        }
+        Ok(())
+    }
+
+    /// Whether a room is locked against new WebClient sessions.
+    pub fn room_locked(&self, frid: &ForeignRoomId) -> bool {
+        let state = self.shared.state.lock().unwrap();
+        state.locked_rooms.contains(frid)
+    }
+
+    /// Whether the session with the given token is kept from connecting by the lock of
+    /// its room.
+    pub fn is_locked_out(&self, token: &SessionToken) -> bool {
+        let state = self.shared.state.lock().unwrap();
+        state
+            .registered_sessions
+            .get_by_right(token)
+            .and_then(|fsid| state.session_options.get(fsid))
+            .map_or(false, |session_options| state.locked_out(session_options))
+    }
+
+    /// Get the FRIDs of rooms listed on the public control endpoint.
+    pub fn public_rooms(&self) -> Vec<ForeignRoomId> {
+        let state = self.shared.state.lock().unwrap();
+        let mut rooms = state.public_rooms.iter().cloned().collect::<Vec<_>>();
+        rooms.sort();
+        rooms
+    }
+
+    /// Mint credentials for the TURN server offered to a session, if any.
+    pub fn turn_credentials(&self, fsid: &ForeignSessionId) -> Option<TurnCredentials> {
+        let frid = self.get_foreign_room_id(fsid);
+        let state = self.shared.state.lock().unwrap();
+        let turn_config = frid
+            .and_then(|frid| state.room_turn_configs.get(&frid))
+            .or(state.turn_config.as_ref())?;
+        Some(turn_config.mint(&fsid.0, SystemTime::now()))
+    }
+
+    /// Buffer commands to Vulcasts in rooms created from now on while they are
+    /// disconnected, replaying them on reconnect. Commands are lost if unset.
+    pub fn set_message_store(&self, message_store: Option<Arc<dyn MessageStore>>) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.message_store = message_store;
+    }
+
+    /// Record the ports the worker was configured to allocate to transports, so that
+    /// the remaining capacity of the relay can be reported.
+    pub fn set_rtc_ports_range(&self, rtc_ports_range: Option<RangeInclusive<u16>>) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.rtc_ports_range = rtc_ports_range;
+    }
+    pub fn rtc_ports_range(&self) -> Option<RangeInclusive<u16>> {
+        self.shared.state.lock().unwrap().rtc_ports_range.clone()
+    }
+
+    /// Get the IP transports listen on, and the IP announced to clients, if any.
+    pub fn transport_listen_ip(&self) -> TransportListenIp {
+        self.shared.transport_listen_ip
+    }
+
+    /// Handle connections with the token of an already connected session by the given
+    /// policy, from now on.
+    pub fn set_duplicate_connection_policy(&self, policy: DuplicateConnectionPolicy) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.duplicate_connection_policy = policy;
+    }
+
+    /// Keep sessions alive for the given time once the connection of their client closes,
+    /// so that clients may reconnect to them. Sessions are dropped immediately if unset.
+    pub fn set_reconnect_grace(&self, reconnect_grace: Option<Duration>) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.reconnect_grace = reconnect_grace;
+    }
+
+    /// Cap the incoming bitrate (bps) of each transport of sessions created from now on,
+    /// e.g. so that a single Vulcast cannot saturate the uplink of the relay.
+    pub fn set_max_incoming_bitrate(&self, max_incoming_bitrate: Option<u32>) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.max_incoming_bitrate = max_incoming_bitrate;
+    }
+    pub fn max_incoming_bitrate(&self) -> Option<u32> {
+        self.shared.state.lock().unwrap().max_incoming_bitrate
+    }
+
+    /// Reuse stats snapshots of sessions created from now on for the given time, so that
+    /// back-to-back stats queries do not each fan out requests to the worker.
+    pub fn set_stats_cache_ttl(&self, stats_cache_ttl: Option<Duration>) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.stats_cache_ttl = stats_cache_ttl;
+    }
+
+    /// Close transports of sessions created from now on once they have had no producers,
+    /// consumers or traffic for the given time, e.g. after abandoned negotiation attempts.
+    pub fn set_idle_transport_timeout(&self, idle_transport_timeout: Option<Duration>) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.idle_transport_timeout = idle_transport_timeout;
+    }
+
+    /// Limit the connected duration of web client sessions in rooms of the given preset,
+    /// e.g. against squatting on free tiers. Sessions are warned shortly before their
+    /// deadline, then unregistered. The duration is unlimited if unset. Applies to
+    /// sessions connecting from now on.
+    pub fn set_max_session_duration(&self, preset: RoomPreset, duration: Option<Duration>) {
+        let mut state = self.shared.state.lock().unwrap();
+        match duration {
+            Some(duration) => state.max_session_durations.insert(preset, duration),
+            None => state.max_session_durations.remove(&preset),
+        };
+    }
+
+    /// Measure the latency of media through rooms created from now on.
+    pub fn set_latency_probe(&self, latency_probe: Option<LatencyProbe>) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.latency_probe = latency_probe;
+    }
+    pub fn latency_probe(&self) -> Option<LatencyProbe> {
+        self.shared.state.lock().unwrap().latency_probe.clone()
+    }
+
+    /// Get the FRIDs of registered rooms, with the FSIDs of their Vulcasts.
+    pub fn registered_rooms(&self) -> Vec<(ForeignRoomId, ForeignSessionId)> {
+        let state = self.shared.state.lock().unwrap();
+        let mut rooms = state
+            .registered_rooms
+            .iter()
+            .map(|(frid, fsid)| (frid.clone(), fsid.clone()))
+            .collect::<Vec<_>>();
+        rooms.sort();
+        rooms
+    }
+
+    /// Get the FSIDs of registered sessions, with their options and whether their
+    /// PHY sessions are connected.
+    pub fn registered_sessions(&self) -> Vec<(ForeignSessionId, SessionOptions, bool)> {
+        let state = self.shared.state.lock().unwrap();
+        let mut sessions = state
+            .session_options
+            .iter()
+            .map(|(fsid, session_options)| {
+                (
+                    fsid.clone(),
+                    session_options.clone(),
+                    state.sessions.contains_key(fsid),
+                )
+            })
+            .collect::<Vec<_>>();
+        sessions.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+        sessions
+    }
+
+    /// Get the FRIDs of registered rooms, with whether any session of the room (the
+    /// Vulcast, web clients or hosts) has a connected PHY session.
+    pub fn room_activity(&self) -> Vec<(ForeignRoomId, bool)> {
+        let state = self.shared.state.lock().unwrap();
+        state
+            .registered_rooms
+            .iter()
+            .map(|(frid, vulcast_fsid)| {
+                let active = state.sessions.contains_key(vulcast_fsid)
+                    || state.session_options.iter().any(|(fsid, session_options)| {
+                        match session_options {
+                            SessionOptions::WebClient(client_frid)
+                            | SessionOptions::Host(client_frid) => {
+                                client_frid == frid && state.sessions.contains_key(fsid)
+                            }
+                            SessionOptions::Vulcast => false,
+                        }
+                    });
+                (frid.clone(), active)
+            })
+            .collect()
+    }
+
+    /// Forget PHY rooms which are no longer alive, returning how many were forgotten.
+    pub fn prune_rooms(&self) -> usize {
+        let mut state = self.shared.state.lock().unwrap();
+        let count = state.rooms.len();
+        state
+            .rooms
+            .retain(|_, weak_room| weak_room.upgrade().is_some());
+        count - state.rooms.len()
+    }
+
+    pub fn registered_session_count(&self) -> usize {
+        self.shared.state.lock().unwrap().registered_sessions.len()
+    }
+    pub fn registered_room_count(&self) -> usize {
+        self.shared.state.lock().unwrap().registered_rooms.len()
+    }
+
+    /// Get a room by FRID, if its Vulcast has connected.
+    pub fn get_room(&self, frid: &ForeignRoomId) -> Option<Room> {
+        let state = self.shared.state.lock().unwrap();
+        let vulcast_fsid = state.registered_rooms.get_by_left(frid)?;
+        state.rooms.get(vulcast_fsid)?.upgrade()
+    }
+
+    /// Get all PHY rooms which are still alive.
+    pub fn get_rooms(&self) -> Vec<Room> {
+        let state = self.shared.state.lock().unwrap();
+        state
+            .rooms
+            .values()
+            .filter_map(|weak_room| weak_room.upgrade())
+            .collect()
+    }
+
+    /// Get the Mediasoup worker backing this relay.
+    pub fn worker(&self) -> Worker {
+        self.shared.worker.clone()
+    }
+
+    /// Get the monitor of request latency to the Mediasoup worker.
+    pub fn worker_monitor(&self) -> WorkerMonitor {
+        self.shared.worker_monitor.clone()
+    }
+
+    /// Get the throttle on the rate at which sessions join rooms.
+    pub fn join_throttle(&self) -> JoinThrottle {
+        self.shared.join_throttle.clone()
+    }
+
+    /// Get the limit on the rooms registered per Vulcast over time.
+    pub fn room_churn(&self) -> RoomChurnLimit {
+        self.shared.room_churn.clone()
+    }
+
+    /// Get the switches of the optional subsystems of this relay.
+    pub fn subsystems(&self) -> Subsystems {
+        self.shared.subsystems.clone()
+    }
+
+    /// Get the garbage collection of idle rooms.
+    pub fn room_gc(&self) -> RoomGc {
+        self.shared.room_gc.clone()
+    }
+
+    /// Get the failures injected into this relay for chaos testing.
+    pub fn chaos(&self) -> Chaos {
+        self.shared.chaos.clone()
+    }
+
+    /// Get the minimum client versions admitted by this relay.
+    pub fn version_gate(&self) -> VersionGate {
+        self.shared.version_gate.clone()
+    }
+
+    /// Get the queue prioritizing control operations while the relay is saturated.
+    pub fn control_queue(&self) -> ControlQueue {
+        self.shared.control_queue.clone()
+    }
+
+    /// Get the limits on the size of GraphQL requests to this relay.
+    pub fn payload_limit(&self) -> PayloadLimit {
+        self.shared.payload_limit.clone()
+    }
+
+    /// Get the operations permitted to each role of session.
+    pub fn permissions(&self) -> Permissions {
+        self.shared.permissions.clone()
+    }
+
     /// Get all client sessions in the given room, specified by FRID.
     fn get_client_sessions_in_room(&self, frid: &ForeignRoomId) -> Vec<ForeignSessionId> {
         let state = self.shared.state.lock().unwrap();
@@ -289,6 +1317,63 @@ pub enum SessionOptions {
     Host(ForeignRoomId),
 }
 
+impl SessionOptions {
+    pub fn role(&self) -> Role {
+        match self {
+            SessionOptions::Vulcast => Role::Vulcast,
+            SessionOptions::WebClient(_) => Role::WebClient,
+            SessionOptions::Host(_) => Role::Host,
+        }
+    }
+}
+
+/// Handling of a connection presenting the token of an already connected session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateConnectionPolicy {
+    /// Reject the new connection, keeping the existing session.
+    RejectNew,
+    /// Replace the existing session with a session for the new connection.
+    EvictOld,
+    /// Create a parallel session for the new connection, up to the given number of
+    /// connections in total, rejecting further connections.
+    AllowParallel(usize),
+}
+impl Default for DuplicateConnectionPolicy {
+    fn default() -> Self {
+        Self::EvictOld
+    }
+}
+
+/// Why a PHY session ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DisconnectReason {
+    /// The client closed its connection.
+    ClientClosed,
+    /// The client connected again with the same token, replacing the session.
+    Replaced,
+    /// The session was unregistered, invalidating its token (e.g. kicked).
+    Unregistered,
+    /// The room of the session was unregistered.
+    RoomUnregistered,
+    /// The session reached its maximum duration, and was unregistered.
+    Expired,
+    /// A host of the room of the session kicked it.
+    Kicked,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Disconnect {
+    pub reason: DisconnectReason,
+    pub time: SystemTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Display)]
+pub enum Role {
+    Vulcast,
+    WebClient,
+    Host,
+}
+
 #[derive(Debug, Error, PartialEq, Eq, PartialOrd, Ord)]
 pub enum RegisterSessionError {
     #[error("the room `{0}` is not registered")]
@@ -314,6 +1399,13 @@ pub enum RegisterRoomError {
     VulcastInRoom(ForeignSessionId),
     #[error("the room id `{0}` is already taken")]
     NonUniqueId(ForeignRoomId),
+    #[error("the vulcast already negotiated media of another preset than room `{0}`")]
+    PresetUnavailable(ForeignRoomId),
+    #[error("the vulcast `{vulcast_fsid}` registered too many rooms recently, retry after {retry_after:?}")]
+    ChurnLimited {
+        vulcast_fsid: ForeignSessionId,
+        retry_after: Duration,
+    },
 }
 
 #[derive(Debug, Error, PartialEq, Eq, PartialOrd, Ord)]
@@ -321,3 +1413,33 @@ pub enum UnregisterRoomError {
     #[error("the room `{0}` is not registered")]
     UnknownRoom(ForeignRoomId),
 }
+
+#[derive(Debug, Error, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SetRoomDataFilterError {
+    #[error("the room `{0}` is not registered")]
+    UnknownRoom(ForeignRoomId),
+}
+
+#[derive(Debug, Error, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SetRoomStreamPolicyError {
+    #[error("the room `{0}` is not registered")]
+    UnknownRoom(ForeignRoomId),
+}
+
+#[derive(Debug, Error, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SetRoomTurnConfigError {
+    #[error("the room `{0}` is not registered")]
+    UnknownRoom(ForeignRoomId),
+}
+
+#[derive(Debug, Error, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SetRoomPublicError {
+    #[error("the room `{0}` is not registered")]
+    UnknownRoom(ForeignRoomId),
+}
+
+#[derive(Debug, Error, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SetRoomLockedError {
+    #[error("the room `{0}` is not registered")]
+    UnknownRoom(ForeignRoomId),
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::relay_server::{ForeignRoomId, Role};
+use crate::session::ResourceType;
+
+/// Runtime limits on the resources a session may allocate, resolved at request time.
+/// The most specific limit applies: per-room limits take precedence over per-role
+/// limits, which take precedence over default limits.
+#[derive(Clone)]
+pub struct ResourcePolicy {
+    shared: Arc<Mutex<State>>,
+}
+
+struct State {
+    default_limits: HashMap<ResourceType, usize>,
+    role_limits: HashMap<(Role, ResourceType), usize>,
+    room_limits: HashMap<(ForeignRoomId, ResourceType), usize>,
+}
+
+/// Scope that a resource limit applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LimitScope {
+    Default,
+    Role(Role),
+    Room(ForeignRoomId),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceLimit {
+    pub scope: LimitScope,
+    pub resource: ResourceType,
+    pub limit: usize,
+}
+
+impl ResourcePolicy {
+    pub fn new() -> Self {
+        Self {
+            shared: Arc::new(Mutex::new(State {
+                default_limits: HashMap::from([
+                    (ResourceType::Consumer, 2),
+                    (ResourceType::Producer, 2),
+                    (ResourceType::DataConsumer, 128),
+                    (ResourceType::DataProducer, 2),
+                    (ResourceType::WebrtcTransport, 2),
+                    (ResourceType::PlainTransport, 2),
+                ]),
+                role_limits: HashMap::new(),
+                room_limits: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Resolve the limit of a resource for a session with the given role, in the given room.
+    pub fn limit(
+        &self,
+        role: Role,
+        foreign_room_id: Option<&ForeignRoomId>,
+        resource: ResourceType,
+    ) -> usize {
+        let state = self.shared.lock().unwrap();
+        foreign_room_id
+            .and_then(|frid| state.room_limits.get(&(frid.clone(), resource)))
+            .or_else(|| state.role_limits.get(&(role, resource)))
+            .or_else(|| state.default_limits.get(&resource))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Set the limit of a resource for the given scope. Clears the limit if unspecified,
+    /// deferring to a less specific scope (default limits cannot be cleared).
+    pub fn set_limit(&self, scope: LimitScope, resource: ResourceType, limit: Option<usize>) {
+        let mut state = self.shared.lock().unwrap();
+        log::debug!("resource limit {:?} {} = {:?}", &scope, &resource, &limit);
+        match (scope, limit) {
+            (LimitScope::Default, Some(limit)) => {
+                state.default_limits.insert(resource, limit);
+            }
+            (LimitScope::Default, None) => {}
+            (LimitScope::Role(role), Some(limit)) => {
+                state.role_limits.insert((role, resource), limit);
+            }
+            (LimitScope::Role(role), None) => {
+                state.role_limits.remove(&(role, resource));
+            }
+            (LimitScope::Room(frid), Some(limit)) => {
+                state.room_limits.insert((frid, resource), limit);
+            }
+            (LimitScope::Room(frid), None) => {
+                state.room_limits.remove(&(frid, resource));
+            }
+        }
+    }
+
+    /// Get all configured limits.
+    pub fn limits(&self) -> Vec<ResourceLimit> {
+        let state = self.shared.lock().unwrap();
+        let default_limits = state
+            .default_limits
+            .iter()
+            .map(|(resource, limit)| ResourceLimit {
+                scope: LimitScope::Default,
+                resource: *resource,
+                limit: *limit,
+            });
+        let role_limits = state
+            .role_limits
+            .iter()
+            .map(|((role, resource), limit)| ResourceLimit {
+                scope: LimitScope::Role(*role),
+                resource: *resource,
+                limit: *limit,
+            });
+        let room_limits = state
+            .room_limits
+            .iter()
+            .map(|((frid, resource), limit)| ResourceLimit {
+                scope: LimitScope::Room(frid.clone()),
+                resource: *resource,
+                limit: *limit,
+            });
+        default_limits
+            .chain(role_limits)
+            .chain(room_limits)
+            .collect()
+    }
+}
+
+impl Default for ResourcePolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,20 +2,37 @@ use futures::{
     future,
     stream::{self, Stream, StreamExt},
 };
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::num::NonZeroU16;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant, SystemTime};
 use uuid::Uuid;
 
+use anyhow::{anyhow, Result};
 use derive_more::Display;
-use mediasoup::data_producer::DataProducerId;
-use mediasoup::producer::ProducerId;
+use mediasoup::audio_level_observer::{AudioLevelObserver, AudioLevelObserverOptions};
+use mediasoup::data_consumer::{DataConsumer, DataConsumerOptions};
+use mediasoup::data_producer::{DataProducer, DataProducerId, DataProducerOptions};
+use mediasoup::data_structures::WebRtcMessage;
+use mediasoup::direct_transport::{DirectTransport, DirectTransportOptions};
+use mediasoup::producer::{Producer, ProducerId};
 use mediasoup::router::{Router, RouterOptions};
-use mediasoup::rtp_parameters::RtpCodecCapability;
+use mediasoup::rtp_observer::{RtpObserver, RtpObserverAddProducerOptions};
+use mediasoup::rtp_parameters::{MediaKind, RtpCodecCapability, RtpCodecParameters};
 use mediasoup::worker::Worker;
-use tokio::sync::{broadcast, OnceCell};
-use tokio_stream::wrappers::BroadcastStream;
+use tokio::sync::OnceCell;
 
-use crate::session::{Session, SessionId, WeakSession};
+use crate::data_filter::{DataFilter, FilterChain};
+use crate::event_log::{EventLog, FanOutStats, ResumeError, Sequenced};
+use crate::events::{Envelope, Event};
+use crate::latency_probe::LatencyProbe;
+use crate::message_store::MessageStore;
+use crate::relay_server::{ForeignSessionId, Role};
+use crate::session::{ProducerAppData, Session, SessionId, WeakSession};
+use crate::soak_report::Tracked;
+use crate::stream_policy::{StreamPolicy, StreamRole};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Display, Hash, Default)]
 pub struct RoomId(Uuid);
@@ -44,34 +61,421 @@ struct Shared {
     codecs: Vec<RtpCodecCapability>,
 
     router: OnceCell<Router>,
-    channel_tx: broadcast::Sender<Message>,
+    /// transport for data produced by the relay itself
+    direct_transport: OnceCell<DirectTransport>,
+    /// observer of audio levels of audio producers, for active speaker detection
+    audio_level_observer: OnceCell<AudioLevelObserver>,
+    /// FSID of the Vulcast this room belongs to
+    vulcast_session_id: ForeignSessionId,
+    /// if set, commands are buffered here until delivered to the Vulcast
+    message_store: Option<Arc<dyn MessageStore>>,
+    /// if set, the latency of media through the room is measured
+    latency_probe: Option<LatencyProbe>,
+    /// which WebClients may send input (data) to the room
+    input: Arc<Mutex<InputState>>,
+    /// which streams each role of session may produce and auto-consume
+    stream_policy: Arc<Mutex<StreamPolicy>>,
+    /// numbers messages and retains recent ones, so that subscribers can resume
+    channel_tx: EventLog<Message>,
 }
 
 #[derive(Debug)]
 struct State {
     sessions: HashMap<SessionId, WeakSession>,
+    /// client of each session, retained so that leaving sessions can be announced once
+    /// they can no longer be upgraded
+    clients: HashMap<SessionId, Participant>,
+    /// data producers owned by the relay rather than any session
+    server_data_producers: HashMap<DataProducerId, DataProducer>,
+    /// if set, new data producers of sessions are relayed through the server via this filter
+    data_filter: Option<Arc<dyn DataFilter>>,
+    /// mapping of session data producers to the server-side relay of their data
+    relayed_data: HashMap<DataProducerId, RelayedData>,
+    /// delivery status of recent messages sent through the relay
+    message_statuses: HashMap<MessageId, MessageStatus>,
+    /// order in which messages were sent, to evict the oldest statuses
+    message_order: VecDeque<MessageId>,
+    /// media carried by the room, fixed once the router is created
+    preset: RoomPreset,
+    /// lightweight key/value state shared by participants, e.g. the scoreboard
+    shared_state: BTreeMap<String, StateEntry>,
+    /// recent moderation actions, oldest first
+    moderation_log: VecDeque<ModerationAction>,
+    /// ID of the last moderation action
+    last_moderation_id: u64,
+    /// loudest audio producer last announced as the active speaker, if any
+    active_speaker: Option<ProducerId>,
+}
+
+/// Which WebClients may send input (data) to the room.
+#[derive(Debug, Default)]
+struct InputState {
+    /// input from all WebClients is blocked, e.g. during cutscenes
+    locked: bool,
+    /// if set, only this WebClient may send input
+    controller: Option<SessionId>,
+}
+impl InputState {
+    fn accepts(&self, session_id: SessionId, role: Role) -> bool {
+        role != Role::WebClient
+            || (!self.locked && self.controller.map_or(true, |x| x == session_id))
+    }
+}
+
+/// Maximum number of audio producers reported by the audio level observer.
+const MAX_AUDIO_LEVELS: u16 = 16;
+/// Interval at which the audio level observer reports, in milliseconds.
+pub const AUDIO_LEVEL_INTERVAL_MS: u16 = 500;
+/// Longest interval at which audio levels are notified to subscribers, in milliseconds.
+pub const MAX_AUDIO_LEVELS_INTERVAL_MS: u64 = 60_000;
+
+/// Shortest interval between producer bitrate samples, in milliseconds.
+pub const MIN_BITRATE_INTERVAL_MS: u64 = 100;
+/// Longest interval between producer bitrate samples, in milliseconds.
+pub const MAX_BITRATE_INTERVAL_MS: u64 = 60_000;
+
+/// Maximum number of messages retained per room for subscribers resuming events.
+const MAX_RETAINED_MESSAGES: usize = 256;
+
+/// Maximum number of message statuses retained per room.
+const MAX_MESSAGE_STATUSES: usize = 256;
+
+/// Maximum number of keys in the shared state of a room.
+const MAX_STATE_KEYS: usize = 64;
+/// Maximum length of a shared state key, in bytes.
+const MAX_STATE_KEY_LEN: usize = 128;
+/// Maximum length of a serialized shared state value, in bytes.
+const MAX_STATE_VALUE_LEN: usize = 4096;
+
+/// Maximum number of moderation actions retained per room.
+const MAX_MODERATION_ACTIONS: usize = 256;
+
+#[derive(Debug)]
+struct RelayedData {
+    /// consumes data from the session data producer
+    data_consumer: DataConsumer,
+    /// produces filtered data to the room
+    data_producer: DataProducer,
+    /// filter the data is relayed through
+    data_filter: Arc<dyn DataFilter>,
+    /// counts of messages relayed and dropped by the filter
+    counters: Arc<RelayCounters>,
+}
+
+#[derive(Debug, Default)]
+struct RelayCounters {
+    relayed: AtomicU64,
+    dropped: AtomicU64,
+}
+
+/// Counts of messages from a session data producer relayed through the server.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DataRelayStat {
+    /// messages which passed the filter
+    pub relayed: u64,
+    /// messages which were dropped by the filter, or while input was blocked
+    pub dropped: u64,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    ProducerAvailable(ProducerId),
+    ProducerAvailable(ProducerAnnouncement),
+    ProducerClosed(ProducerId),
     DataProducerAvailable(DataProducerId),
+    DataProducerClosed(DataProducerId),
+    /// dominant audio producer changed, or none if the room is silent
+    ActiveSpeaker(Option<AudioLevel>),
+    /// audio levels of the loudest audio producers, empty if the room is silent
+    AudioLevels(Vec<AudioLevel>),
+    VulcastCommand(VulcastCommand),
+    /// WebClient holding control of input changed, or none if input is unarbitrated
+    ControllerChanged(Option<ForeignSessionId>),
+    StateChanged(StateEntry),
+    /// client joined or left the room
+    ClientState(ClientStateUpdate),
+}
+impl Message {
+    /// Whether the message is retained for subscribers resuming events. Audio levels
+    /// are superseded by the next report, so are not replayed.
+    fn is_retained(&self) -> bool {
+        !matches!(self, Message::ActiveSpeaker(_) | Message::AudioLevels(_))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Display, Hash, Default)]
+pub struct MessageId(pub Uuid);
+impl MessageId {
+    pub fn new() -> Self {
+        MessageId(Uuid::new_v4())
+    }
+}
+
+/// Delivery status of a message sent through the relay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MessageStatus {
+    /// Sent, but no connected session has received it yet.
+    Pending,
+    /// Received by a connected session.
+    Delivered,
+    /// Acknowledged by the receiving session.
+    Acknowledged,
+}
+
+/// Command sent by a host to the Vulcast of a room, e.g. reboot or game switch.
+/// The relay does not interpret the command.
+#[derive(Debug, Clone)]
+pub struct VulcastCommand {
+    pub id: MessageId,
+    /// FSID of the session which sent the command.
+    pub sender: ForeignSessionId,
+    pub command: serde_json::Value,
+}
+
+/// Entry of the key/value state shared by the participants of a room.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateEntry {
+    pub key: String,
+    /// Value of the key, or none if the key was removed.
+    pub value: Option<serde_json::Value>,
+    /// FSID of the session which last wrote the key.
+    pub writer: ForeignSessionId,
+}
+
+/// Kind of moderation action taken in a room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModerationKind {
+    /// A producer was paused.
+    Mute,
+    /// A client was dropped from the room.
+    Kick,
+    /// Input from WebClients was locked.
+    LockInput,
+    /// Input from WebClients was unlocked.
+    UnlockInput,
+}
+impl ModerationKind {
+    /// Whether actions of this kind may be undone. Kicked clients reconnect on their
+    /// own, unless they were unregistered.
+    pub fn reversible(self) -> bool {
+        self != ModerationKind::Kick
+    }
+}
+
+/// Moderation action taken in a room, retained so that it can be audited and undone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModerationAction {
+    /// ID of the action, numbered from 1 per room.
+    pub id: u64,
+    pub kind: ModerationKind,
+    /// FSID of the host which took the action, or none if taken by the backend.
+    pub actor: Option<ForeignSessionId>,
+    /// FSID of the client the action was taken against, if any.
+    pub target: Option<ForeignSessionId>,
+    /// Producer paused by the action, if any.
+    pub producer_id: Option<ProducerId>,
+    pub time: SystemTime,
+    /// Time at which the action was undone, and by whom, if it was.
+    pub undone: Option<(SystemTime, Option<ForeignSessionId>)>,
+}
+impl ModerationAction {
+    /// Whether this action overrides the effect of an earlier action, such that
+    /// undoing the earlier action would also revert this one.
+    fn supersedes(&self, earlier: &ModerationAction) -> bool {
+        let locks_input = |kind: ModerationKind| {
+            matches!(
+                kind,
+                ModerationKind::LockInput | ModerationKind::UnlockInput
+            )
+        };
+        match (self.kind, earlier.kind) {
+            (ModerationKind::Mute, ModerationKind::Mute) => self.producer_id == earlier.producer_id,
+            (kind, earlier_kind) => locks_input(kind) && locks_input(earlier_kind),
+        }
+    }
+}
+
+/// Client joining or leaving a room.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientStateUpdate {
+    pub state: ClientState,
+    /// PHY session of the client.
+    pub session_id: SessionId,
+    /// FSID of the client, by which other participants know it.
+    pub foreign_session_id: ForeignSessionId,
+    pub role: Role,
+    /// Human-readable name of the client, if registered with one.
+    pub display_name: Option<String>,
+}
+
+/// Client in a room.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Participant {
+    /// PHY session of the client.
+    pub session_id: SessionId,
+    /// FSID of the client, by which other participants know it.
+    pub foreign_session_id: ForeignSessionId,
+    pub role: Role,
+    /// Human-readable name of the client, if registered with one.
+    pub display_name: Option<String>,
+}
+impl Participant {
+    fn update(self, state: ClientState) -> ClientStateUpdate {
+        ClientStateUpdate {
+            state,
+            session_id: self.session_id,
+            foreign_session_id: self.foreign_session_id,
+            role: self.role,
+            display_name: self.display_name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ClientState {
+    Join,
+    Leave,
+}
+
+/// Producer announced as available in a room.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProducerAnnouncement {
+    pub producer_id: ProducerId,
+    /// PHY session owning the producer.
+    pub session_id: SessionId,
+    /// FSID of the session owning the producer, by which other participants know it.
+    pub foreign_session_id: ForeignSessionId,
+    pub kind: MediaKind,
+    /// Label the producer was created with, if any.
+    pub label: Option<String>,
+    /// Role of the stream declared by the producer, if any.
+    pub role: Option<StreamRole>,
+}
+impl ProducerAnnouncement {
+    pub fn new(session: &Session, producer: &Producer) -> Self {
+        Self {
+            producer_id: producer.id(),
+            session_id: session.id(),
+            foreign_session_id: session.get_foreign_session_id(),
+            kind: producer.kind(),
+            label: ProducerAppData::of(producer).and_then(|app_data| app_data.label.clone()),
+            role: ProducerAppData::of(producer).and_then(|app_data| app_data.role),
+        }
+    }
+}
+
+/// Audio level of an audio producer in a room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioLevel {
+    pub producer_id: ProducerId,
+    /// Average audio level in dBov, from -127 (silence) to 0 (loudest).
+    pub volume: i8,
+}
+
+/// Sync metadata of a producer in a sync group.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncInfo {
+    pub producer_id: ProducerId,
+    pub kind: MediaKind,
+    /// RTP clock rate of the producer, in Hz.
+    pub clock_rate: Option<u32>,
+    /// Time at which the statistics were sampled, in milliseconds, if RTP was received.
+    pub timestamp: Option<u64>,
+    /// Interarrival jitter of received RTP, in RTP timestamp units.
+    pub jitter: Option<u32>,
+    /// Round trip time to the producer, in milliseconds, if known.
+    pub round_trip_time: Option<f32>,
+}
+
+/// Options shared by all rooms of a relay.
+#[derive(Debug, Clone)]
+pub struct RoomOptions {
+    /// FSID of the Vulcast the room belongs to.
+    pub vulcast_session_id: ForeignSessionId,
+    /// Relay data from sessions through the server via this filter, if set.
+    pub data_filter: Option<Arc<dyn DataFilter>>,
+    /// Buffer commands to the Vulcast while it is disconnected, if set.
+    pub message_store: Option<Arc<dyn MessageStore>>,
+    pub preset: RoomPreset,
+    /// Which streams each role of session may produce and auto-consume.
+    pub stream_policy: StreamPolicy,
+    /// Measure the latency of media through the room, if set.
+    pub latency_probe: Option<LatencyProbe>,
+}
+
+/// Media carried by a room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoomPreset {
+    /// Audio, video and data.
+    Standard,
+    /// Audio and data only (e.g. voice chat). The router carries no video codecs,
+    /// reducing worker overhead and negotiation payloads.
+    AudioOnly,
+}
+impl Default for RoomPreset {
+    fn default() -> Self {
+        RoomPreset::Standard
+    }
+}
+impl RoomPreset {
+    /// Whether rooms with this preset carry media of the given kind.
+    pub fn allows(&self, kind: MediaKind) -> bool {
+        match self {
+            RoomPreset::Standard => true,
+            RoomPreset::AudioOnly => kind == MediaKind::Audio,
+        }
+    }
+    /// Filter media codecs to those carried by rooms with this preset.
+    pub fn codecs(&self, codecs: &[RtpCodecCapability]) -> Vec<RtpCodecCapability> {
+        codecs
+            .iter()
+            .filter(|codec| {
+                self.allows(match codec {
+                    RtpCodecCapability::Audio { .. } => MediaKind::Audio,
+                    RtpCodecCapability::Video { .. } => MediaKind::Video,
+                })
+            })
+            .cloned()
+            .collect()
+    }
 }
 
 impl Room {
-    pub fn new(worker: Worker, codecs: Vec<RtpCodecCapability>) -> Self {
+    pub fn new(worker: Worker, codecs: Vec<RtpCodecCapability>, options: RoomOptions) -> Self {
         let id = RoomId::new();
         log::trace!("+room {}", id);
+        Tracked::Room.created();
         Self {
             shared: Arc::new(Shared {
                 state: Mutex::new(State {
                     sessions: HashMap::new(),
+                    clients: HashMap::new(),
+                    server_data_producers: HashMap::new(),
+                    data_filter: options.data_filter,
+                    relayed_data: HashMap::new(),
+                    message_statuses: HashMap::new(),
+                    message_order: VecDeque::new(),
+                    preset: options.preset,
+                    shared_state: BTreeMap::new(),
+                    moderation_log: VecDeque::new(),
+                    last_moderation_id: 0,
+                    active_speaker: None,
                 }),
                 id,
                 worker,
                 codecs,
                 router: OnceCell::new(),
-                channel_tx: broadcast::channel(16).0,
+                direct_transport: OnceCell::new(),
+                audio_level_observer: OnceCell::new(),
+                vulcast_session_id: options.vulcast_session_id,
+                message_store: options.message_store,
+                latency_probe: options.latency_probe,
+                input: Arc::new(Mutex::new(InputState::default())),
+                stream_policy: Arc::new(Mutex::new(options.stream_policy)),
+                // audio levels are broadcast periodically, so leave room for slow subscribers
+                channel_tx: EventLog::new(MAX_RETAINED_MESSAGES, 64, Message::is_retained),
             }),
         }
     }
@@ -81,37 +485,118 @@ impl Room {
         self.shared
             .router
             .get_or_init(|| async {
+                let codecs = self.preset().codecs(&self.shared.codecs);
                 self.shared
                     .worker
-                    .create_router(RouterOptions::new(self.shared.codecs.clone()))
+                    .create_router(RouterOptions::new(codecs))
                     .await
                     .unwrap()
             })
             .await
             .clone()
     }
+    /// Get the probe measuring the latency of media through this room, if any.
+    pub fn latency_probe(&self) -> Option<LatencyProbe> {
+        self.shared.latency_probe.clone()
+    }
 
-    /// Add a session to this room.
+    /// Get the media carried by this room.
+    pub fn preset(&self) -> RoomPreset {
+        self.shared.state.lock().unwrap().preset
+    }
+    /// Change the media carried by this room. Fails once the router is created.
+    pub fn set_preset(&self, preset: RoomPreset) -> Result<()> {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.preset != preset && self.shared.router.initialized() {
+            return Err(anyhow!("room {} already has a router", self.id()));
+        }
+        state.preset = preset;
+        Ok(())
+    }
+    /// Get the streams each role of session may produce and auto-consume in this room.
+    pub fn stream_policy(&self) -> StreamPolicy {
+        self.shared.stream_policy.lock().unwrap().clone()
+    }
+    /// Change the streams each role of session may produce and auto-consume. Existing
+    /// producers are kept.
+    pub fn set_stream_policy(&self, stream_policy: StreamPolicy) {
+        *self.shared.stream_policy.lock().unwrap() = stream_policy;
+    }
+    /// Get the Mediasoup Router associated with this room, if it was created.
+    pub fn router(&self) -> Option<Router> {
+        self.shared.router.get().cloned()
+    }
+
+    /// Add a session to this room, announcing that its client joined.
     pub fn add_session(&self, session: Session) {
         let mut state = self.shared.state.lock().unwrap();
         let session_id = session.id();
+        let participant = Participant {
+            session_id,
+            foreign_session_id: session.get_foreign_session_id(),
+            role: session.get_session_options().role(),
+            display_name: session.get_display_name(),
+        };
         state.sessions.insert(session_id, session.downgrade());
+        state.clients.insert(session_id, participant.clone());
         log::trace!("<-> session {} (room {})", session.id(), self.id());
+        // send while locked, so that subscribers observe joins and leaves in order
+        let _ = self
+            .shared
+            .channel_tx
+            .send(Message::ClientState(participant.update(ClientState::Join)));
     }
 
-    /// Remove a session from this room.
+    /// Remove a session from this room, announcing that its client left.
     pub fn remove_session(&self, session_id: SessionId) {
         let mut state = self.shared.state.lock().unwrap();
         state.sessions.remove(&session_id).unwrap();
+        if let Some(participant) = state.clients.remove(&session_id) {
+            let _ = self
+                .shared
+                .channel_tx
+                .send(Message::ClientState(participant.update(ClientState::Leave)));
+        }
         log::trace!("</> session {} (room {})", session_id, self.id());
+        drop(state);
+        if self.controller() == Some(session_id) {
+            self.set_controller(None);
+        }
+    }
+
+    /// Get the clients in this room.
+    pub fn roster(&self) -> Vec<Participant> {
+        let state = self.shared.state.lock().unwrap();
+        state.clients.values().cloned().collect()
+    }
+    /// Get a stream which yields a join for each client in this room, followed by
+    /// subsequent joins and leaves, e.g. to render a live roster.
+    pub fn client_state_updates(&self) -> impl Stream<Item = ClientStateUpdate> {
+        // subscribe before taking the snapshot, so no joins or leaves are missed
+        let live = self.channel_stream(|message| match message {
+            Message::ClientState(update) => Some(update.clone()),
+            _ => None,
+        });
+        let snapshot = self
+            .roster()
+            .into_iter()
+            .map(|participant| participant.update(ClientState::Join));
+        stream::iter(snapshot).chain(live)
     }
 
     /// Announce a new producer to all sessions in this room.
-    pub fn announce_producer(&self, producer_id: ProducerId) {
+    pub fn announce_producer(&self, announcement: ProducerAnnouncement) {
         let _ = self
             .shared
             .channel_tx
-            .send(Message::ProducerAvailable(producer_id));
+            .send(Message::ProducerAvailable(announcement));
+    }
+    /// Announce a closed producer to all sessions in this room.
+    pub fn announce_producer_closed(&self, producer_id: ProducerId) {
+        let _ = self
+            .shared
+            .channel_tx
+            .send(Message::ProducerClosed(producer_id));
     }
     /// Announce a new data producer to all sessions in this room.
     pub fn announce_data_producer(&self, data_producer_id: DataProducerId) {
@@ -120,47 +605,981 @@ impl Room {
             .channel_tx
             .send(Message::DataProducerAvailable(data_producer_id));
     }
+    /// Announce a closed data producer to all sessions in this room. If the data of
+    /// a session data producer is relayed, the relay is torn down and the closure of
+    /// the relayed data producer announced instead, as sessions consume the relayed
+    /// data producer. Sessions announce each of their data producers closed once.
+    pub fn announce_data_producer_closed(&self, data_producer_id: DataProducerId) {
+        let data_producer_id = match self.remove_relayed_data(data_producer_id) {
+            Some(relayed_data) => {
+                let relayed_id = relayed_data.data_producer.id();
+                drop(relayed_data);
+                relayed_id
+            }
+            None => data_producer_id,
+        };
+        let _ = self
+            .shared
+            .channel_tx
+            .send(Message::DataProducerClosed(data_producer_id));
+    }
+
+    /// Get the AudioLevelObserver of this room, creating it if it does not exist.
+    async fn get_audio_level_observer(&self) -> Result<AudioLevelObserver> {
+        let router = self.get_router().await;
+        Ok(self
+            .shared
+            .audio_level_observer
+            .get_or_try_init(|| async {
+                let mut options = AudioLevelObserverOptions::default();
+                options.max_entries = NonZeroU16::new(MAX_AUDIO_LEVELS).unwrap();
+                options.interval = AUDIO_LEVEL_INTERVAL_MS;
+                let audio_level_observer = router.create_audio_level_observer(options).await?;
+                audio_level_observer
+                    .on_volumes({
+                        let room = self.downgrade();
+                        move |volumes| {
+                            if let Some(room) = room.upgrade() {
+                                room.report_audio_levels(
+                                    volumes
+                                        .iter()
+                                        .map(|volume| AudioLevel {
+                                            producer_id: volume.producer.id(),
+                                            volume: volume.volume,
+                                        })
+                                        .collect(),
+                                );
+                            }
+                        }
+                    })
+                    .detach();
+                audio_level_observer
+                    .on_silence({
+                        let room = self.downgrade();
+                        move || {
+                            if let Some(room) = room.upgrade() {
+                                room.report_audio_levels(vec![]);
+                            }
+                        }
+                    })
+                    .detach();
+                Ok::<_, anyhow::Error>(audio_level_observer)
+            })
+            .await?
+            .clone())
+    }
+
+    /// Report the audio levels of the loudest audio producers in this room, ordered from
+    /// loudest to quietest, or none if the room is silent. The active speaker is only
+    /// announced when the loudest producer changes.
+    pub fn report_audio_levels(&self, audio_levels: Vec<AudioLevel>) {
+        let active_speaker = audio_levels.first().copied();
+        let changed = {
+            let mut state = self.shared.state.lock().unwrap();
+            let producer_id = active_speaker.map(|active_speaker| active_speaker.producer_id);
+            std::mem::replace(&mut state.active_speaker, producer_id) != producer_id
+        };
+        if changed {
+            let _ = self
+                .shared
+                .channel_tx
+                .send(Message::ActiveSpeaker(active_speaker));
+        }
+        let _ = self
+            .shared
+            .channel_tx
+            .send(Message::AudioLevels(audio_levels));
+    }
+
+    /// Observe the audio level of a producer for active speaker detection.
+    /// Non-audio producers are ignored. Closed producers are removed automatically.
+    pub async fn observe_producer(&self, producer: &Producer) {
+        if producer.kind() != MediaKind::Audio {
+            return;
+        }
+        let result = match self.get_audio_level_observer().await {
+            Ok(audio_level_observer) => audio_level_observer
+                .add_producer(RtpObserverAddProducerOptions::new(producer.id()))
+                .await
+                .map_err(anyhow::Error::from),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            log::warn!(
+                "failed to observe producer {} (room {}): {}",
+                producer.id(),
+                self.id(),
+                err
+            );
+        }
+    }
+
+    /// Get a stream which yields changes of the active speaker in this room.
+    pub fn active_speaker(&self) -> impl Stream<Item = Option<AudioLevel>> {
+        self.channel_stream(|message| match message {
+            Message::ActiveSpeaker(active_speaker) => Some(*active_speaker),
+            _ => None,
+        })
+    }
+    /// Get a stream which yields audio levels of the loudest audio producers in this
+    /// room, at most once per `interval`.
+    pub fn audio_levels(&self, interval: Duration) -> impl Stream<Item = Vec<AudioLevel>> {
+        let mut last_yielded: Option<Instant> = None;
+        self.channel_stream(move |message| match message {
+            Message::AudioLevels(audio_levels) => {
+                let now = Instant::now();
+                match last_yielded {
+                    Some(last) if now.duration_since(last) < interval => None,
+                    _ => {
+                        last_yielded = Some(now);
+                        Some(audio_levels.clone())
+                    }
+                }
+            }
+            _ => None,
+        })
+    }
+
+    /// Get a stream which periodically yields the bitrate of a producer in this room in
+    /// bits per second, summed over its RTP streams. Ends once the producer is closed.
+    pub fn producer_bitrate(
+        &self,
+        producer_id: ProducerId,
+        interval: Duration,
+    ) -> Result<impl Stream<Item = u32>> {
+        let producer = self
+            .get_producers()
+            .into_iter()
+            .find(|producer| producer.id() == producer_id)
+            .ok_or_else(|| anyhow!("producer {} does not exist", producer_id))?;
+        // hold the producer weakly, so the stream does not keep it open
+        let weak_producer = producer.downgrade();
+        Ok(stream::unfold(
+            weak_producer,
+            move |weak_producer| async move {
+                tokio::time::sleep(interval).await;
+                let producer = weak_producer.upgrade()?;
+                let stats = producer.get_stats().await.ok()?;
+                drop(producer);
+                let bitrate = stats.iter().map(|stat| stat.bitrate).sum();
+                Some((bitrate, weak_producer))
+            },
+        ))
+    }
+
+    /// Get the sync metadata of each producer in a sync group, so clients can play
+    /// them back in sync. Mediasoup does not expose the NTP timestamps of RTCP sender
+    /// reports, so metadata is limited to what the receive statistics provide.
+    pub async fn sync_group(&self, sync_group: &str) -> Vec<SyncInfo> {
+        let producers = self.get_producers().into_iter().filter(|producer| {
+            ProducerAppData::of(producer).and_then(|app_data| app_data.sync_group.as_deref())
+                == Some(sync_group)
+        });
+        stream::iter(producers)
+            .then(|producer| async move {
+                let clock_rate =
+                    producer
+                        .rtp_parameters()
+                        .codecs
+                        .first()
+                        .map(|codec| match codec {
+                            RtpCodecParameters::Audio { clock_rate, .. }
+                            | RtpCodecParameters::Video { clock_rate, .. } => clock_rate.get(),
+                        });
+                // the first stream is the base simulcast layer
+                let stats = producer.get_stats().await.unwrap_or_default();
+                let stat = stats.first();
+                SyncInfo {
+                    producer_id: producer.id(),
+                    kind: producer.kind(),
+                    clock_rate,
+                    timestamp: stat.map(|stat| stat.timestamp),
+                    jitter: stat.map(|stat| stat.jitter),
+                    round_trip_time: stat.and_then(|stat| stat.round_trip_time),
+                }
+            })
+            .collect()
+            .await
+    }
+
+    /// Get the DirectTransport used for data produced by the relay itself.
+    async fn get_direct_transport(&self) -> Result<DirectTransport> {
+        let router = self.get_router().await;
+        Ok(self
+            .shared
+            .direct_transport
+            .get_or_try_init(|| async {
+                router
+                    .create_direct_transport(DirectTransportOptions::default())
+                    .await
+            })
+            .await?
+            .clone())
+    }
+    /// Get the DirectTransport used for data produced by the relay itself, if it was created.
+    pub fn direct_transport(&self) -> Option<DirectTransport> {
+        self.shared.direct_transport.get().cloned()
+    }
+
+    /// Create a data producer owned by the relay, and announce it to all sessions
+    /// in this room so they may consume it.
+    pub async fn produce_server_data(&self, label: String) -> Result<DataProducer> {
+        let direct_transport = self.get_direct_transport().await?;
+        let mut options = DataProducerOptions::new_direct();
+        options.label = label;
+        let data_producer = direct_transport.produce_data(options).await?;
+        {
+            let mut state = self.shared.state.lock().unwrap();
+            state
+                .server_data_producers
+                .insert(data_producer.id(), data_producer.clone());
+        }
+        log::trace!(
+            "+data producer {} [server] (room {})",
+            data_producer.id(),
+            self.id()
+        );
+        self.announce_data_producer(data_producer.id());
+        Ok(data_producer)
+    }
+    /// Get all data producers owned by the relay in this room.
+    pub fn get_server_data_producers(&self) -> Vec<DataProducer> {
+        let state = self.shared.state.lock().unwrap();
+        state.server_data_producers.values().cloned().collect()
+    }
+
+    /// Publish a data producer owned by a session to this room. If a data filter
+    /// is configured, the data is relayed through the server and filtered, and
+    /// sessions consume the relayed data producer instead. Data from WebClients is
+    /// also relayed while input is locked or arbitrated, so that their input can be
+    /// dropped while it is blocked.
+    pub async fn publish_data_producer(
+        &self,
+        data_producer: &DataProducer,
+        session_id: SessionId,
+        role: Role,
+    ) -> Result<()> {
+        let data_filter = self.shared.state.lock().unwrap().data_filter.clone();
+        let data_filter = match (data_filter, role) {
+            (Some(data_filter), _) => data_filter,
+            // an empty chain passes all messages, leaving only input arbitration
+            (None, Role::WebClient) if self.input_restricted() => {
+                Arc::new(FilterChain::new(Vec::new()))
+            }
+            (None, _) => {
+                self.announce_data_producer(data_producer.id());
+                return Ok(());
+            }
+        };
+        self.relay_data_producer(data_producer, session_id, role, data_filter)
+            .await?;
+        Ok(())
+    }
+    /// Relay a session data producer through the server, announcing the relayed data
+    /// producer. Returns whether a relay was set up, as the data producer may have
+    /// been relayed or closed meanwhile.
+    async fn relay_data_producer(
+        &self,
+        data_producer: &DataProducer,
+        session_id: SessionId,
+        role: Role,
+        data_filter: Arc<dyn DataFilter>,
+    ) -> Result<bool> {
+        let direct_transport = self.get_direct_transport().await?;
+        let source_id = data_producer.id();
+        let data_consumer = direct_transport
+            .consume_data(DataConsumerOptions::new_direct(source_id))
+            .await?;
+        let mut options = DataProducerOptions::new_direct();
+        options.label = data_producer.label().clone();
+        options.protocol = data_producer.protocol().clone();
+        let relayed_data_producer = direct_transport.produce_data(options).await?;
+        let direct_data_producer = match &relayed_data_producer {
+            DataProducer::Direct(direct_data_producer) => direct_data_producer.clone(),
+            DataProducer::Regular(_) => unreachable!("server data producers are direct"),
+        };
+
+        let counters = Arc::new(RelayCounters::default());
+        data_consumer
+            .on_message({
+                let data_filter = data_filter.clone();
+                let input = self.shared.input.clone();
+                let counters = counters.clone();
+                move |message| {
+                    let message = if input.lock().unwrap().accepts(session_id, role) {
+                        data_filter.filter(source_id, role, message.clone())
+                    } else {
+                        None
+                    };
+                    match message {
+                        Some(message) => {
+                            counters.relayed.fetch_add(1, Ordering::Relaxed);
+                            let _ = direct_data_producer.send(message);
+                        }
+                        None => {
+                            counters.dropped.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                }
+            })
+            .detach();
+        // the relay is torn down once the session announces the data producer closed
+
+        let relayed_id = relayed_data_producer.id();
+        let relayed_data = RelayedData {
+            data_consumer,
+            data_producer: relayed_data_producer,
+            data_filter,
+            counters,
+        };
+        {
+            let mut state = self.shared.state.lock().unwrap();
+            if state.relayed_data.contains_key(&source_id) {
+                // closing the relay invokes close handlers which lock state
+                drop(state);
+                drop(relayed_data);
+                return Ok(false);
+            }
+            state.relayed_data.insert(source_id, relayed_data);
+        }
+        // the session data producer may have closed before the relay was in place,
+        // and been announced closed with no relay to tear down
+        if data_producer.closed() {
+            drop(self.remove_relayed_data(source_id));
+            return Ok(false);
+        }
+        log::trace!(
+            "+data producer {} [relay of {}] (room {})",
+            relayed_id,
+            source_id,
+            self.id()
+        );
+        self.announce_data_producer(relayed_id);
+        Ok(true)
+    }
+    /// Lock or unlock input from WebClients in this room, e.g. during cutscenes.
+    /// Input is dropped by the relay while locked, leaving data producers open.
+    pub fn set_input_locked(&self, locked: bool) {
+        self.shared.input.lock().unwrap().locked = locked;
+        log::trace!(
+            "input {} (room {})",
+            if locked { "locked" } else { "unlocked" },
+            self.id()
+        );
+        if locked {
+            self.spawn_input_relays();
+        }
+    }
+    /// Whether input from WebClients in this room is locked or arbitrated.
+    fn input_restricted(&self) -> bool {
+        let input = self.shared.input.lock().unwrap();
+        input.locked || input.controller.is_some()
+    }
+    /// Relay the data producers of WebClients which were published directly, once
+    /// their input is restricted. Sessions consuming them directly have their data
+    /// consumers closed, and are announced the relayed data producers instead.
+    /// Relays stay in place once input is no longer restricted.
+    fn spawn_input_relays(&self) {
+        let room = self.clone();
+        tokio::spawn(async move {
+            for session in room.active_sessions() {
+                let role = session.get_session_options().role();
+                if role != Role::WebClient {
+                    continue;
+                }
+                for data_producer in session.get_data_producers() {
+                    let source_id = data_producer.id();
+                    if room.is_relayed(source_id) {
+                        continue;
+                    }
+                    let data_filter = Arc::new(FilterChain::new(Vec::new()));
+                    match room
+                        .relay_data_producer(&data_producer, session.id(), role, data_filter)
+                        .await
+                    {
+                        Ok(true) => {
+                            for consumer_session in room.active_sessions() {
+                                consumer_session.close_data_consumers_of(source_id);
+                            }
+                            let _ = room
+                                .shared
+                                .channel_tx
+                                .send(Message::DataProducerClosed(source_id));
+                        }
+                        Ok(false) => {}
+                        Err(err) => log::warn!(
+                            "failed to relay data producer {} (room {}): {}",
+                            source_id,
+                            room.id(),
+                            err
+                        ),
+                    }
+                }
+            }
+        });
+    }
+    /// Whether input from WebClients in this room is locked.
+    pub fn input_locked(&self) -> bool {
+        self.shared.input.lock().unwrap().locked
+    }
+    /// Whether a session may currently send input to this room.
+    pub fn accepts_input(&self, session_id: SessionId, role: Role) -> bool {
+        self.shared.input.lock().unwrap().accepts(session_id, role)
+    }
+
+    /// Give a WebClient exclusive control of input, or make input unarbitrated.
+    /// Input from other WebClients is dropped by the relay, leaving data producers open.
+    pub fn set_controller(&self, session: Option<&Session>) {
+        let controller = session.map(|session| session.id());
+        let changed = {
+            let mut input = self.shared.input.lock().unwrap();
+            std::mem::replace(&mut input.controller, controller) != controller
+        };
+        if changed && controller.is_some() {
+            self.spawn_input_relays();
+        }
+        if changed {
+            let foreign_session_id = session.map(|session| session.get_foreign_session_id());
+            log::trace!("controller {:?} (room {})", foreign_session_id, self.id());
+            let _ = self
+                .shared
+                .channel_tx
+                .send(Message::ControllerChanged(foreign_session_id));
+        }
+    }
+    /// Get the session holding control of input, if input is arbitrated.
+    pub fn controller(&self) -> Option<SessionId> {
+        self.shared.input.lock().unwrap().controller
+    }
+    /// Get a stream which yields the FSID of the current and subsequent controllers.
+    pub fn controllers(&self) -> impl Stream<Item = Option<ForeignSessionId>> {
+        let controller_changes = self.channel_stream(|message| match message {
+            Message::ControllerChanged(controller) => Some(controller.clone()),
+            _ => None,
+        });
+        let controller = self.controller().and_then(|controller| {
+            self.active_sessions()
+                .into_iter()
+                .find(|session| session.id() == controller)
+                .map(|session| session.get_foreign_session_id())
+        });
+        stream::once(future::ready(controller)).chain(controller_changes)
+    }
+    /// Get a stream of versioned events for subsequent messages sent in this room.
+    pub fn events(&self) -> impl Stream<Item = Envelope> {
+        self.shared.channel_tx.stream().map(Self::envelope)
+    }
+    /// Get a stream of versioned events for the messages sent in this room after the
+    /// event with the given sequence number, followed by subsequent messages, so that
+    /// a reconnecting subscriber can catch up on the events it missed. Fails if the
+    /// missed events are no longer retained, in which case the subscriber must resync.
+    pub fn resume_events(
+        &self,
+        resume_from: u64,
+    ) -> Result<impl Stream<Item = Envelope>, ResumeError> {
+        Ok(self
+            .shared
+            .channel_tx
+            .resume(resume_from)?
+            .map(Self::envelope))
+    }
+    /// Get the sequence number of the last event in this room, or 0 if none occurred.
+    pub fn last_sequence(&self) -> u64 {
+        self.shared.channel_tx.last_sequence()
+    }
+    /// Get the cost of fanning messages out to the subscribers of this room.
+    pub fn fan_out_stats(&self) -> FanOutStats {
+        self.shared.channel_tx.fan_out_stats()
+    }
+    fn envelope(sequenced: Arc<Sequenced<Message>>) -> Envelope {
+        Envelope::new(Event::from(sequenced.item.clone())).with_sequence(sequenced.sequence)
+    }
+
+    /// Relay data producers of sessions created from now on through the server,
+    /// passing each message through the given filter. Existing relays keep the
+    /// filter they were created with.
+    pub fn set_data_filter(&self, data_filter: Option<Arc<dyn DataFilter>>) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.data_filter = data_filter;
+    }
+    /// Whether data from a session data producer is relayed through the server.
+    fn is_relayed(&self, data_producer_id: DataProducerId) -> bool {
+        let state = self.shared.state.lock().unwrap();
+        state.relayed_data.contains_key(&data_producer_id)
+    }
+    /// Get the counts of messages relayed from a session data producer, if relayed.
+    pub fn get_data_relay_stat(&self, data_producer_id: DataProducerId) -> Option<DataRelayStat> {
+        let state = self.shared.state.lock().unwrap();
+        let counters = &state.relayed_data.get(&data_producer_id)?.counters;
+        Some(DataRelayStat {
+            relayed: counters.relayed.load(Ordering::Relaxed),
+            dropped: counters.dropped.load(Ordering::Relaxed),
+        })
+    }
+
+    /// Remove the relay of a session data producer. The relay is closed once the
+    /// returned value is dropped, so it MUST NOT be dropped while holding state.
+    fn remove_relayed_data(&self, source_id: DataProducerId) -> Option<RelayedData> {
+        let mut state = self.shared.state.lock().unwrap();
+        let relayed_data = state.relayed_data.remove(&source_id)?;
+        relayed_data.data_filter.remove(source_id);
+        log::trace!(
+            "-data producer {} [relay of {}] (room {})",
+            relayed_data.data_producer.id(),
+            source_id,
+            self.id()
+        );
+        Some(relayed_data)
+    }
+    /// Get all data consumers and data producers owned by the relay which relay
+    /// data from sessions in this room.
+    pub fn get_relayed_data(&self) -> Vec<(DataConsumer, DataProducer)> {
+        let state = self.shared.state.lock().unwrap();
+        state
+            .relayed_data
+            .values()
+            .map(|relayed_data| {
+                (
+                    relayed_data.data_consumer.clone(),
+                    relayed_data.data_producer.clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Send a message to all sessions in this room which consume the server
+    /// data producer with the given label, creating it if it does not exist.
+    pub async fn send_server_data(&self, label: &str, message: String) -> Result<()> {
+        let data_producer = self
+            .get_server_data_producers()
+            .into_iter()
+            .find(|data_producer| data_producer.label() == label);
+        let data_producer = match data_producer {
+            Some(data_producer) => data_producer,
+            None => self.produce_server_data(label.to_owned()).await?,
+        };
+        match data_producer {
+            DataProducer::Direct(data_producer) => {
+                data_producer.send(WebRtcMessage::String(message))?
+            }
+            DataProducer::Regular(_) => unreachable!("server data producers are direct"),
+        }
+        Ok(())
+    }
+
+    /// Send a command to the Vulcast of this room. Delivery of the command can
+    /// be tracked with `message_status`. If a message store is configured, the
+    /// command is buffered until delivered, so it survives the Vulcast reconnecting.
+    pub fn send_vulcast_command(
+        &self,
+        sender: ForeignSessionId,
+        command: serde_json::Value,
+    ) -> MessageId {
+        let id = MessageId::new();
+        log::trace!(
+            "vulcast command {} from {} (room {})",
+            id,
+            sender,
+            self.id()
+        );
+        self.track_message(id);
+        let vulcast_command = VulcastCommand {
+            id,
+            sender,
+            command,
+        };
+        if let Some(message_store) = &self.shared.message_store {
+            message_store.push(&self.shared.vulcast_session_id, vulcast_command.clone());
+        }
+        let _ = self
+            .shared
+            .channel_tx
+            .send(Message::VulcastCommand(vulcast_command));
+        id
+    }
+    /// Get a stream which yields commands sent to the Vulcast of this room,
+    /// starting with buffered commands which were not yet delivered.
+    /// Commands are considered delivered once yielded.
+    pub fn vulcast_commands(&self) -> impl Stream<Item = VulcastCommand> {
+        // subscribe before reading buffered commands, so none are missed
+        let live = self.channel_stream(|message| match message {
+            Message::VulcastCommand(vulcast_command) => Some(vulcast_command.clone()),
+            _ => None,
+        });
+        let buffered = self
+            .shared
+            .message_store
+            .as_ref()
+            .map(|message_store| message_store.pending(&self.shared.vulcast_session_id))
+            .unwrap_or_default();
+
+        // buffered commands may also be received live, so skip them once. The commands
+        // to skip are bounded by the store, rather than growing with every command.
+        let mut buffered_ids = buffered
+            .iter()
+            .map(|vulcast_command| vulcast_command.id)
+            .collect::<HashSet<_>>();
+        let live = live.filter(move |vulcast_command| {
+            future::ready(!buffered_ids.remove(&vulcast_command.id))
+        });
+
+        let room = self.downgrade();
+        stream::iter(buffered)
+            .chain(live)
+            .map(move |vulcast_command| {
+                if let Some(room) = room.upgrade() {
+                    room.mark_delivered(vulcast_command.id);
+                }
+                vulcast_command
+            })
+    }
+
+    /// Get the delivery status of a recent message sent in this room.
+    pub fn message_status(&self, id: MessageId) -> Option<MessageStatus> {
+        let state = self.shared.state.lock().unwrap();
+        state.message_statuses.get(&id).copied()
+    }
+    /// Acknowledge receipt of a message sent in this room.
+    pub fn acknowledge_message(&self, id: MessageId) -> Result<()> {
+        let mut state = self.shared.state.lock().unwrap();
+        let status = state
+            .message_statuses
+            .get_mut(&id)
+            .ok_or_else(|| anyhow!("message {} does not exist", id))?;
+        *status = MessageStatus::Acknowledged;
+        Ok(())
+    }
+    fn mark_delivered(&self, id: MessageId) {
+        if let Some(message_store) = &self.shared.message_store {
+            message_store.remove(&self.shared.vulcast_session_id, id);
+        }
+        let mut state = self.shared.state.lock().unwrap();
+        if let Some(status @ MessageStatus::Pending) = state.message_statuses.get_mut(&id) {
+            *status = MessageStatus::Delivered;
+        }
+    }
+    fn track_message(&self, id: MessageId) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.message_statuses.insert(id, MessageStatus::Pending);
+        state.message_order.push_back(id);
+        while state.message_order.len() > MAX_MESSAGE_STATUSES {
+            if let Some(oldest) = state.message_order.pop_front() {
+                state.message_statuses.remove(&oldest);
+            }
+        }
+    }
+
+    /// Write a key of the shared state of this room, or remove it if the value is none.
+    /// Concurrent writes to a key are resolved by the last write received.
+    pub fn set_state(
+        &self,
+        writer: ForeignSessionId,
+        key: String,
+        value: Option<serde_json::Value>,
+    ) -> Result<()> {
+        if key.len() > MAX_STATE_KEY_LEN {
+            return Err(anyhow!("state key exceeds {} bytes", MAX_STATE_KEY_LEN));
+        }
+        if let Some(value) = &value {
+            if serde_json::to_string(value)?.len() > MAX_STATE_VALUE_LEN {
+                return Err(anyhow!("state value exceeds {} bytes", MAX_STATE_VALUE_LEN));
+            }
+        }
+
+        let mut state = self.shared.state.lock().unwrap();
+        if value.is_some()
+            && !state.shared_state.contains_key(&key)
+            && state.shared_state.len() >= MAX_STATE_KEYS
+        {
+            return Err(anyhow!("room state exceeds {} keys", MAX_STATE_KEYS));
+        }
+        let entry = StateEntry { key, value, writer };
+        log::trace!(
+            "state {} set by {} (room {})",
+            entry.key,
+            entry.writer,
+            self.id()
+        );
+        if entry.value.is_some() {
+            state.shared_state.insert(entry.key.clone(), entry.clone());
+        } else {
+            state.shared_state.remove(&entry.key);
+        }
+        // send while locked, so that subscribers observe writes in the order applied
+        let _ = self.shared.channel_tx.send(Message::StateChanged(entry));
+        Ok(())
+    }
+    /// Record a moderation action taken in this room.
+    pub fn record_moderation(
+        &self,
+        kind: ModerationKind,
+        actor: Option<ForeignSessionId>,
+        target: Option<ForeignSessionId>,
+        producer_id: Option<ProducerId>,
+    ) -> ModerationAction {
+        let mut state = self.shared.state.lock().unwrap();
+        state.last_moderation_id += 1;
+        let action = ModerationAction {
+            id: state.last_moderation_id,
+            kind,
+            actor,
+            target,
+            producer_id,
+            time: SystemTime::now(),
+            undone: None,
+        };
+        log::info!("moderation {:?} (room {})", action, self.id());
+        state.moderation_log.push_back(action.clone());
+        while state.moderation_log.len() > MAX_MODERATION_ACTIONS {
+            state.moderation_log.pop_front();
+        }
+        action
+    }
+    /// Get the recent moderation actions taken in this room, oldest first.
+    pub fn moderation_log(&self) -> Vec<ModerationAction> {
+        let state = self.shared.state.lock().unwrap();
+        state.moderation_log.iter().cloned().collect()
+    }
+    /// Undo a reversible moderation action, e.g. resume a muted producer, recording
+    /// who undid it. Actions superseded by a later action which was not undone, e.g. a
+    /// lock of input followed by an unlock, cannot be undone.
+    pub async fn undo_moderation(
+        &self,
+        id: u64,
+        actor: Option<ForeignSessionId>,
+    ) -> Result<ModerationAction> {
+        let action = {
+            let mut state = self.shared.state.lock().unwrap();
+            let index = state
+                .moderation_log
+                .iter()
+                .position(|action| action.id == id)
+                .ok_or_else(|| anyhow!("moderation action {} does not exist", id))?;
+            let action = &state.moderation_log[index];
+            if !action.kind.reversible() {
+                return Err(anyhow!("moderation action {} cannot be undone", id));
+            }
+            if action.undone.is_some() {
+                return Err(anyhow!("moderation action {} was already undone", id));
+            }
+            if let Some(later) = state
+                .moderation_log
+                .iter()
+                .skip(index + 1)
+                .find(|later| later.undone.is_none() && later.supersedes(action))
+            {
+                return Err(anyhow!(
+                    "moderation action {} was superseded by {}",
+                    id,
+                    later.id
+                ));
+            }
+            // mark the action undone while locked, so that concurrent undos of it fail
+            let action = &mut state.moderation_log[index];
+            action.undone = Some((SystemTime::now(), actor));
+            action.clone()
+        };
+        if let Err(err) = self.revert_moderation(&action).await {
+            let mut state = self.shared.state.lock().unwrap();
+            if let Some(action) = state
+                .moderation_log
+                .iter_mut()
+                .find(|action| action.id == id)
+            {
+                action.undone = None;
+            }
+            return Err(err);
+        }
+        log::info!("undid moderation {:?} (room {})", action, self.id());
+        Ok(action)
+    }
+    /// Revert the effect of a moderation action.
+    async fn revert_moderation(&self, action: &ModerationAction) -> Result<()> {
+        match (action.kind, action.producer_id) {
+            (ModerationKind::Mute, Some(producer_id)) => {
+                let owner = self
+                    .active_sessions()
+                    .into_iter()
+                    .find(|owner| owner.get_producer(producer_id).is_some())
+                    .ok_or_else(|| anyhow!("producer {} does not exist", producer_id))?;
+                owner.producer_resume(producer_id).await?;
+            }
+            (ModerationKind::LockInput, _) => self.set_input_locked(false),
+            (ModerationKind::UnlockInput, _) => self.set_input_locked(true),
+            _ => return Err(anyhow!("moderation action {} cannot be undone", action.id)),
+        }
+        Ok(())
+    }
+
+    /// Get a key of the shared state of this room, if set.
+    pub fn get_state(&self, key: &str) -> Option<StateEntry> {
+        let state = self.shared.state.lock().unwrap();
+        state.shared_state.get(key).cloned()
+    }
+    /// Get all keys of the shared state of this room.
+    pub fn state_snapshot(&self) -> Vec<StateEntry> {
+        let state = self.shared.state.lock().unwrap();
+        state.shared_state.values().cloned().collect()
+    }
+    /// Get a stream which yields the current entries of the shared state of this room,
+    /// followed by subsequent writes. Writes made while the stream is created may be
+    /// yielded twice, but the last entry yielded for a key is always its latest value.
+    pub fn state_changes(&self) -> impl Stream<Item = StateEntry> {
+        // subscribe before taking the snapshot, so no writes are missed
+        let live = self.channel_stream(|message| match message {
+            Message::StateChanged(entry) => Some(entry.clone()),
+            _ => None,
+        });
+        stream::iter(self.state_snapshot()).chain(live)
+    }
+
+    /// Get all producers in this room which have not been closed.
+    pub fn get_producers(&self) -> Vec<Producer> {
+        self.active_sessions() // ignore dropped sessions
+            .into_iter()
+            .flat_map(|session| session.get_producers())
+            .filter(|producer| !producer.closed()) // ignore closed producers
+            .collect()
+    }
 
     /// Get a stream which yields existing and new producers.
     pub fn available_producers(&self) -> impl Stream<Item = ProducerId> {
-        let producers = self
+        self.producer_announcements()
+            .map(|announcement| announcement.producer_id)
+    }
+    /// Get a stream which yields existing and new producers, along with the session
+    /// owning each, so that sessions can decide which producers to consume.
+    pub fn producer_announcements(&self) -> impl Stream<Item = ProducerAnnouncement> {
+        let announcements = self
             .active_sessions() // ignore dropped sessions
             .into_iter()
-            .flat_map(|session| session.get_producers())
-            .filter(|producer| !producer.closed()) // ignore closed producers
-            .map(|producer| producer.id())
-            .collect::<Vec<ProducerId>>();
+            .flat_map(|session| {
+                session
+                    .get_producers()
+                    .into_iter()
+                    .filter(|producer| !producer.closed()) // ignore closed producers
+                    .map(move |producer| ProducerAnnouncement::new(&session, &producer))
+            })
+            .collect::<Vec<_>>();
         stream::select(
-            stream::iter(producers),
-            self.channel_stream().filter_map(|x| async move {
-                match x {
-                    Message::ProducerAvailable(producer_id) => Some(producer_id),
-                    _ => None,
-                }
+            stream::iter(announcements),
+            self.channel_stream(|message| match message {
+                Message::ProducerAvailable(announcement) => Some(announcement.clone()),
+                _ => None,
             }),
         )
     }
-    /// Get a stream which yields existing and new data producers.
-    pub fn available_data_producers(&self) -> impl Stream<Item = DataProducerId> {
-        let data_producers = self
+    /// Get a stream which yields existing and new producers which sessions of a role
+    /// auto-consume under the stream policy of this room. The policy is applied as each
+    /// producer is yielded, so that changes take effect for existing subscribers.
+    pub fn auto_consumed_producers(&self, role: Role) -> impl Stream<Item = ProducerAnnouncement> {
+        let stream_policy = self.shared.stream_policy.clone();
+        self.producer_announcements().filter(move |announcement| {
+            future::ready(
+                stream_policy
+                    .lock()
+                    .unwrap()
+                    .auto_consumes(role, announcement.role),
+            )
+        })
+    }
+    /// Get a stream which yields producers closed by their sessions, or closed along
+    /// with their transports or sessions.
+    pub fn closed_producers(&self) -> impl Stream<Item = ProducerId> {
+        self.channel_stream(|message| match message {
+            Message::ProducerClosed(producer_id) => Some(*producer_id),
+            _ => None,
+        })
+    }
+    /// Get all data producers in this room which have not been closed,
+    /// including those owned by the relay.
+    /// Session data producers are replaced by their relays if data is relayed.
+    pub fn get_data_producers(&self) -> Vec<DataProducer> {
+        let session_data_producers = self
             .active_sessions() // ignore dropped sessions
             .into_iter()
             .flat_map(|session| session.get_data_producers())
+            .filter(|data_producer| !self.is_relayed(data_producer.id()))
+            .collect::<Vec<_>>();
+        let relayed_data_producers = self
+            .get_relayed_data()
+            .into_iter()
+            .map(|(_, data_producer)| data_producer);
+        session_data_producers
+            .into_iter()
+            .chain(relayed_data_producers)
+            .chain(self.get_server_data_producers())
             .filter(|data_producer| !data_producer.closed()) // ignore closed data producers
+            .collect()
+    }
+
+    /// Get the data producers available in this room, along with the FSID of the
+    /// session producing their data, or none for data produced by the relay itself.
+    /// Data relayed through the server is attributed to the session producing it.
+    pub fn get_data_producer_sources(&self) -> Vec<(DataProducer, Option<ForeignSessionId>)> {
+        let relays = {
+            let state = self.shared.state.lock().unwrap();
+            state
+                .relayed_data
+                .iter()
+                .map(|(id, relayed_data)| (*id, relayed_data.data_producer.clone()))
+                .collect::<HashMap<_, _>>()
+        };
+        let relays = &relays;
+        let session_data_producers = self
+            .active_sessions() // ignore dropped sessions
+            .into_iter()
+            .flat_map(|session| {
+                let foreign_session_id = session.get_foreign_session_id();
+                session
+                    .get_data_producers()
+                    .into_iter()
+                    .map(move |data_producer| {
+                        let data_producer = relays
+                            .get(&data_producer.id())
+                            .cloned()
+                            .unwrap_or(data_producer);
+                        (data_producer, Some(foreign_session_id.clone()))
+                    })
+            })
+            .collect::<Vec<_>>();
+        let server_data_producers = self
+            .get_server_data_producers()
+            .into_iter()
+            .map(|data_producer| (data_producer, None));
+        session_data_producers
+            .into_iter()
+            .chain(server_data_producers)
+            .filter(|(data_producer, _)| !data_producer.closed()) // ignore closed data producers
+            .collect()
+    }
+
+    /// Get a stream which yields existing and new data producers.
+    pub fn available_data_producers(&self) -> impl Stream<Item = DataProducerId> {
+        let data_producers = self
+            .get_data_producers()
+            .into_iter()
             .map(|data_producer| data_producer.id())
             .collect::<Vec<DataProducerId>>();
         stream::select(
             stream::iter(data_producers),
-            self.channel_stream().filter_map(|x| async move {
-                match x {
-                    Message::DataProducerAvailable(data_producer_id) => Some(data_producer_id),
-                    _ => None,
-                }
+            self.channel_stream(|message| match message {
+                Message::DataProducerAvailable(data_producer_id) => Some(*data_producer_id),
+                _ => None,
             }),
         )
     }
+    /// Get a stream which yields data producers closed by their sessions, or closed
+    /// along with their transports or sessions.
+    pub fn closed_data_producers(&self) -> impl Stream<Item = DataProducerId> {
+        self.channel_stream(|message| match message {
+            Message::DataProducerClosed(data_producer_id) => Some(*data_producer_id),
+            _ => None,
+        })
+    }
 
-    fn active_sessions(&self) -> Vec<Session> {
+    /// Get all sessions in this room which have not been dropped.
+    pub fn active_sessions(&self) -> Vec<Session> {
         let state = self.shared.state.lock().unwrap();
         state
             .sessions
@@ -168,10 +1587,17 @@ impl Room {
             .filter_map(|weak_session| weak_session.upgrade())
             .collect()
     }
-    fn channel_stream(&self) -> impl Stream<Item = Message> {
-        BroadcastStream::new(self.shared.channel_tx.subscribe())
-            .take_while(|x| future::ready(x.is_ok()))
-            .map(|x| x.unwrap())
+    /// Get a stream of the messages sent in this room from now on, passed through a
+    /// filter. Messages are shared between subscribers, so each subscriber evaluates
+    /// its filter as it receives messages, and clones only the parts it keeps.
+    fn channel_stream<T>(
+        &self,
+        mut filter: impl FnMut(&Message) -> Option<T>,
+    ) -> impl Stream<Item = T> {
+        self.shared
+            .channel_tx
+            .stream()
+            .filter_map(move |sequenced| future::ready(filter(&sequenced.item)))
     }
 
     pub fn id(&self) -> RoomId {
@@ -193,6 +1619,7 @@ impl WeakRoom {
 
 impl Drop for Shared {
     fn drop(&mut self) {
-        log::trace!("-room {}", self.id)
+        log::trace!("-room {}", self.id);
+        Tracked::Room.dropped();
     }
 }
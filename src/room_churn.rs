@@ -0,0 +1,148 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+use crate::relay_server::ForeignSessionId;
+
+/// Limits the number of rooms registered for each Vulcast within a sliding window, so
+/// that buggy firmware rapidly registering and unregistering rooms cannot thrash the
+/// routers of the relay. Registrations are tracked per Vulcast FSID, so they outlive
+/// the rooms and sessions of the Vulcast until they leave the window.
+#[derive(Debug, Clone)]
+pub struct RoomChurnLimit {
+    shared: Arc<Mutex<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    /// rooms which may be registered per Vulcast within the window, if limited
+    max_registrations: Option<u32>,
+    /// length of the sliding window
+    window: Duration,
+    /// times of the registrations of each Vulcast within the window, oldest first
+    registrations: HashMap<ForeignSessionId, VecDeque<Instant>>,
+    /// registrations rejected since the relay started
+    rejections: u64,
+}
+
+impl State {
+    /// Forget registrations which left the window.
+    fn expire(&mut self, now: Instant) {
+        let window = self.window;
+        self.registrations.retain(|_, registrations| {
+            while registrations
+                .front()
+                .map_or(false, |registration| now - *registration >= window)
+            {
+                registrations.pop_front();
+            }
+            !registrations.is_empty()
+        });
+    }
+}
+
+impl RoomChurnLimit {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            shared: Arc::new(Mutex::new(State {
+                max_registrations: None,
+                window,
+                registrations: HashMap::new(),
+                rejections: 0,
+            })),
+        }
+    }
+
+    /// Limit the rooms registered per Vulcast within the window. Registrations are
+    /// unlimited if unset, though still tracked.
+    pub fn set_max_registrations(&self, max_registrations: Option<u32>) {
+        let mut state = self.shared.lock().unwrap();
+        state.max_registrations = max_registrations;
+    }
+    pub fn max_registrations(&self) -> Option<u32> {
+        self.shared.lock().unwrap().max_registrations
+    }
+
+    /// Set the length of the sliding window registrations are limited within.
+    pub fn set_window(&self, window: Duration) {
+        let mut state = self.shared.lock().unwrap();
+        state.window = window;
+    }
+    pub fn window(&self) -> Duration {
+        self.shared.lock().unwrap().window
+    }
+
+    /// Get the number of rooms registered for a Vulcast within the window.
+    pub fn registrations(&self, vulcast_fsid: &ForeignSessionId, now: Instant) -> usize {
+        let mut state = self.shared.lock().unwrap();
+        state.expire(now);
+        state
+            .registrations
+            .get(vulcast_fsid)
+            .map_or(0, VecDeque::len)
+    }
+
+    /// Get the number of registrations rejected since the relay started.
+    pub fn rejections(&self) -> u64 {
+        self.shared.lock().unwrap().rejections
+    }
+
+    /// Record the registration of a room for a Vulcast at the given time, unless the
+    /// Vulcast already registered as many rooms as allowed within the window.
+    pub fn register(
+        &self,
+        vulcast_fsid: &ForeignSessionId,
+        now: Instant,
+    ) -> Result<(), RoomChurnLimitedError> {
+        let mut state = self.shared.lock().unwrap();
+        state.expire(now);
+        if let Some(max_registrations) = state.max_registrations {
+            let registrations = state.registrations.get(vulcast_fsid);
+            if registrations.map_or(0, VecDeque::len) >= max_registrations as usize {
+                // retry once enough registrations leave the window
+                let window = state.window;
+                let retry_after = registrations
+                    .and_then(|registrations| {
+                        registrations
+                            .iter()
+                            .rev()
+                            .nth(max_registrations.saturating_sub(1) as usize)
+                    })
+                    .map_or(window, |registration| window - (now - *registration));
+                state.rejections += 1;
+                log::debug!(
+                    "rejected room registration of vulcast {}, retry after {:?}",
+                    vulcast_fsid,
+                    retry_after
+                );
+                return Err(RoomChurnLimitedError {
+                    vulcast_fsid: vulcast_fsid.clone(),
+                    retry_after,
+                });
+            }
+        }
+        state
+            .registrations
+            .entry(vulcast_fsid.clone())
+            .or_default()
+            .push_back(now);
+        Ok(())
+    }
+}
+
+impl Default for RoomChurnLimit {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(3600))
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error(
+    "the vulcast {vulcast_fsid} registered too many rooms recently, retry after {retry_after:?}"
+)]
+pub struct RoomChurnLimitedError {
+    pub vulcast_fsid: ForeignSessionId,
+    pub retry_after: Duration,
+}
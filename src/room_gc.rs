@@ -0,0 +1,140 @@
+use futures::{future, Stream, StreamExt};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+
+use crate::relay_server::{ForeignRoomId, RelayServer};
+
+/// Garbage collection of registered rooms. Rooms are only dropped once their last
+/// session is, but their registrations are retained until unregistered, so rooms
+/// whose sessions have all disconnected (e.g. as their Vulcast crashed) would be
+/// retained indefinitely. Rooms without connected sessions for the idle timeout are
+/// unregistered, and subscribers are notified of each collected room.
+#[derive(Debug, Clone)]
+pub struct RoomGc {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<State>,
+    collected_tx: broadcast::Sender<CollectedRoom>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// time after which idle rooms are collected, if collected at all
+    idle_timeout: Option<Duration>,
+    /// time since which each idle room has had no connected sessions
+    idle_since: HashMap<ForeignRoomId, Instant>,
+}
+
+/// Room unregistered as it had no connected sessions for the idle timeout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectedRoom {
+    pub frid: ForeignRoomId,
+    /// time for which the room had no connected sessions
+    pub idle: Duration,
+}
+
+impl Default for RoomGc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RoomGc {
+    pub fn new() -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                state: Mutex::new(State::default()),
+                collected_tx: broadcast::channel(16).0,
+            }),
+        }
+    }
+
+    /// Collect rooms without connected sessions for the given time. Rooms are never
+    /// collected if unset.
+    pub fn set_idle_timeout(&self, idle_timeout: Option<Duration>) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.idle_timeout = idle_timeout;
+    }
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.shared.state.lock().unwrap().idle_timeout
+    }
+
+    /// Get a stream of rooms collected from now on.
+    pub fn collected(&self) -> impl Stream<Item = CollectedRoom> {
+        BroadcastStream::new(self.shared.collected_tx.subscribe())
+            .filter_map(|collected| future::ready(collected.ok()))
+    }
+}
+
+/// Unregister the rooms of a relay which have had no connected sessions for the idle
+/// timeout, returning the collected rooms.
+pub fn sweep(relay_server: &RelayServer) -> Vec<CollectedRoom> {
+    let room_gc = relay_server.room_gc();
+    let now = Instant::now();
+    let idle_rooms = relay_server
+        .room_activity()
+        .into_iter()
+        .filter(|(_, active)| !active)
+        .map(|(frid, _)| frid)
+        .collect::<Vec<_>>();
+
+    let idle = {
+        let mut state = room_gc.shared.state.lock().unwrap();
+        let idle_timeout = match state.idle_timeout {
+            Some(idle_timeout) => idle_timeout,
+            None => {
+                state.idle_since.clear();
+                return Vec::new();
+            }
+        };
+        // forget rooms which were unregistered or became active
+        state.idle_since.retain(|frid, _| idle_rooms.contains(frid));
+        idle_rooms
+            .into_iter()
+            .filter_map(|frid| {
+                let idle_since = *state.idle_since.entry(frid.clone()).or_insert(now);
+                let idle = now.duration_since(idle_since);
+                if idle >= idle_timeout {
+                    Some(CollectedRoom { frid, idle })
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let collected = idle
+        .into_iter()
+        .filter(|collected| relay_server.unregister_room(collected.frid.clone()).is_ok())
+        .collect::<Vec<_>>();
+    relay_server.prune_rooms();
+
+    let mut state = room_gc.shared.state.lock().unwrap();
+    for collected in &collected {
+        log::info!(
+            "collected room {} (idle for {:?})",
+            collected.frid,
+            collected.idle
+        );
+        state.idle_since.remove(&collected.frid);
+        let _ = room_gc.shared.collected_tx.send(collected.clone());
+    }
+    collected
+}
+
+/// Spawn a background task which collects idle rooms at a fixed interval.
+pub fn spawn(relay_server: RelayServer, period: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            sweep(&relay_server);
+        }
+    });
+}
@@ -0,0 +1,106 @@
+use serde::Serialize;
+use std::time::Duration;
+
+use mediasoup::worker::RequestError;
+
+use crate::relay_server::Role;
+use crate::room::Room;
+use crate::session::Stats;
+
+/// Stats aggregated across the sessions of a room, for spotting unhealthy rooms
+/// without inspecting each session.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoomStats {
+    pub vulcasts: usize,
+    pub web_clients: usize,
+    pub hosts: usize,
+    /// Bitrate received by the relay from the sessions, in bits per second.
+    pub recv_bitrate: u64,
+    /// Bitrate sent by the relay to the sessions, in bits per second.
+    pub send_bitrate: u64,
+    /// RTP packets received by producers.
+    pub packets_received: u64,
+    /// RTP packets lost before reaching producers.
+    pub packets_lost: u64,
+    /// Percentiles of the round trip times of consumers in milliseconds, if known.
+    pub round_trip_time_p50: Option<f64>,
+    pub round_trip_time_p90: Option<f64>,
+    pub round_trip_time_p99: Option<f64>,
+    /// Subscriptions to the messages of the room, e.g. producer announcements.
+    pub subscribers: usize,
+    /// Messages sent in the room.
+    pub messages_sent: u64,
+    /// Messages received by subscribers, counting each subscriber separately.
+    pub messages_delivered: u64,
+    /// Mean and longest time from sending a message until a subscriber received it in
+    /// milliseconds, if any were delivered.
+    pub fan_out_latency_mean: Option<f64>,
+    pub fan_out_latency_max: Option<f64>,
+}
+
+/// Aggregate the stats of the connected sessions of a room.
+pub async fn report(room: &Room) -> Result<RoomStats, RequestError> {
+    let mut sessions = Vec::new();
+    for session in room.active_sessions() {
+        let role = session.get_session_options().role();
+        sessions.push((role, session.get_stats().await?));
+    }
+    let fan_out_stats = room.fan_out_stats();
+    let as_ms = |latency: Duration| latency.as_secs_f64() * 1000.0;
+    Ok(RoomStats {
+        subscribers: fan_out_stats.subscribers,
+        messages_sent: fan_out_stats.sent,
+        messages_delivered: fan_out_stats.delivered,
+        fan_out_latency_mean: fan_out_stats.mean_latency.map(as_ms),
+        fan_out_latency_max: fan_out_stats.max_latency.map(as_ms),
+        ..aggregate(&sessions)
+    })
+}
+
+// integer widths of stats differ between fields and mediasoup versions, so all
+// counts are widened to u64
+#[allow(clippy::unnecessary_cast)]
+fn aggregate(sessions: &[(Role, Stats)]) -> RoomStats {
+    let mut room_stats = RoomStats::default();
+    let mut round_trip_times = Vec::new();
+    for (role, stats) in sessions {
+        match role {
+            Role::Vulcast => room_stats.vulcasts += 1,
+            Role::WebClient => room_stats.web_clients += 1,
+            Role::Host => room_stats.hosts += 1,
+        }
+        for stat in stats.webrtc_transport_stats.values().flatten() {
+            room_stats.recv_bitrate += stat.recv_bitrate as u64;
+            room_stats.send_bitrate += stat.send_bitrate as u64;
+        }
+        for stat in stats.plain_transport_stats.values().flatten() {
+            room_stats.recv_bitrate += stat.recv_bitrate as u64;
+            room_stats.send_bitrate += stat.send_bitrate as u64;
+        }
+        for stat in stats.producer_stats.values().flatten() {
+            room_stats.packets_received += stat.packet_count as u64;
+            room_stats.packets_lost += stat.packets_lost as u64;
+        }
+        round_trip_times.extend(
+            stats
+                .consumer_stats
+                .values()
+                .filter_map(|stat| stat.round_trip_time.map(|rtt| rtt as f64)),
+        );
+    }
+    round_trip_times.sort_by(|a, b| a.total_cmp(b));
+    room_stats.round_trip_time_p50 = percentile(&round_trip_times, 50);
+    room_stats.round_trip_time_p90 = percentile(&round_trip_times, 90);
+    room_stats.round_trip_time_p99 = percentile(&round_trip_times, 99);
+    room_stats
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[f64], percent: usize) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (percent * sorted.len()).div_ceil(100);
+    Some(sorted[rank.saturating_sub(1)])
+}
@@ -0,0 +1,350 @@
+//! Compatibility of the GraphQL schemas across releases.
+//!
+//! The SDL of each schema is pinned by a snapshot in `tests/golden/schema`, along
+//! with the schema version it was taken at. Changes are checked against the
+//! snapshot under the following rules, so that clients written against a version
+//! keep working with every later release of the same version:
+//! - Types, fields, arguments and enum values may be added, as long as added
+//!   arguments and input fields are optional.
+//! - Output fields may become non-null, and arguments may become nullable.
+//! - Anything else breaks clients, and requires incrementing the schema version.
+//!   Elements may only be removed if they were deprecated (`@deprecated` with a
+//!   reason) in the snapshot, so that clients had a migration window.
+//!
+//! During a migration window, deprecated fields are served unless disabled by the
+//! [`DeprecationPolicy`], e.g. to check that clients migrated before removal.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use async_graphql::parser::types::{
+    ConstDirective, InputValueDefinition, TypeKind, TypeSystemDefinition,
+};
+use async_graphql::parser::{self, Positioned};
+use async_graphql::{Context, Guard, Result};
+use thiserror::Error;
+
+/// Whether deprecated fields are served.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeprecationPolicy {
+    pub serve_deprecated: bool,
+}
+impl Default for DeprecationPolicy {
+    fn default() -> Self {
+        DeprecationPolicy {
+            serve_deprecated: true,
+        }
+    }
+}
+
+/// Guard of a deprecated field, which rejects requests once deprecated fields
+/// are no longer served, e.g.
+/// `#[graphql(deprecation = "use stats", guard = "DeprecatedGuard::new(\"QueryRoot.legacyStats\")")]`.
+pub struct DeprecatedGuard {
+    field: &'static str,
+}
+impl DeprecatedGuard {
+    pub fn new(field: &'static str) -> Self {
+        DeprecatedGuard { field }
+    }
+}
+#[async_trait::async_trait]
+impl Guard for DeprecatedGuard {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        let policy = ctx
+            .data_opt::<DeprecationPolicy>()
+            .copied()
+            .unwrap_or_default();
+        if policy.serve_deprecated {
+            log::debug!("served deprecated field {}", self.field);
+            Ok(())
+        } else {
+            log::warn!("rejected deprecated field {}", self.field);
+            Err(format!("field {} is deprecated and no longer served", self.field).into())
+        }
+    }
+}
+
+/// Change to a schema which breaks existing clients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BreakingChange {
+    /// Path of the changed element, e.g. `QueryRoot.stats(sessionId)`.
+    pub path: String,
+    pub kind: BreakingChangeKind,
+    /// Whether the element was deprecated before it changed.
+    pub deprecated: bool,
+}
+impl fmt::Display for BreakingChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            BreakingChangeKind::Removed => write!(f, "{} was removed", self.path),
+            BreakingChangeKind::TypeChanged { from, to } => {
+                write!(f, "{} changed from {} to {}", self.path, from, to)
+            }
+            BreakingChangeKind::RequiredInputAdded => {
+                write!(f, "{} was added as a required input", self.path)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BreakingChangeKind {
+    Removed,
+    TypeChanged {
+        from: String,
+        to: String,
+    },
+    /// A required argument or input field was added to an existing element.
+    RequiredInputAdded,
+}
+
+#[derive(Debug, Error)]
+pub enum CompatError {
+    #[error("invalid schema: {0}")]
+    Parse(#[from] parser::Error),
+    #[error("breaking changes require a schema version bump: {}", list(.0))]
+    VersionNotBumped(Vec<BreakingChange>),
+    #[error("only deprecated elements may be removed: {}", list(.0))]
+    NotDeprecated(Vec<BreakingChange>),
+}
+fn list(changes: &[BreakingChange]) -> String {
+    changes
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Check a schema against a snapshot of an earlier version, returning the
+/// breaking changes allowed by a version bump.
+pub fn check(
+    snapshot: &str,
+    snapshot_version: u32,
+    sdl: &str,
+    version: u32,
+) -> Result<Vec<BreakingChange>, CompatError> {
+    let changes = breaking_changes(snapshot, sdl)?;
+    if changes.is_empty() {
+        return Ok(changes);
+    }
+    if version <= snapshot_version {
+        return Err(CompatError::VersionNotBumped(changes));
+    }
+    let not_deprecated = changes
+        .iter()
+        .filter(|change| change.kind == BreakingChangeKind::Removed && !change.deprecated)
+        .cloned()
+        .collect::<Vec<_>>();
+    if !not_deprecated.is_empty() {
+        return Err(CompatError::NotDeprecated(not_deprecated));
+    }
+    Ok(changes)
+}
+
+/// Find the changes from one SDL to another which break existing clients.
+pub fn breaking_changes(old: &str, new: &str) -> Result<Vec<BreakingChange>, parser::Error> {
+    let old = elements(old)?;
+    let new = elements(new)?;
+    let mut changes = Vec::new();
+    for (path, old_element) in &old {
+        let kind = match new.get(path) {
+            None => Some(BreakingChangeKind::Removed),
+            Some(new_element) if !old_element.accepts(new_element) => {
+                Some(BreakingChangeKind::TypeChanged {
+                    from: old_element.ty.clone(),
+                    to: new_element.ty.clone(),
+                })
+            }
+            Some(_) => None,
+        };
+        if let Some(kind) = kind {
+            changes.push(BreakingChange {
+                path: path.clone(),
+                kind,
+                deprecated: old_element.deprecated,
+            });
+        }
+    }
+    for (path, new_element) in &new {
+        let parent_existed = new_element
+            .parent
+            .as_ref()
+            .is_some_and(|parent| old.contains_key(parent));
+        if new_element.required && parent_existed && !old.contains_key(path) {
+            changes.push(BreakingChange {
+                path: path.clone(),
+                kind: BreakingChangeKind::RequiredInputAdded,
+                deprecated: false,
+            });
+        }
+    }
+    Ok(changes)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    /// Types, enum values and union members, which must match exactly.
+    Exact,
+    /// Output fields, which may become stricter.
+    Output,
+    /// Arguments and input fields, which may become looser.
+    Input,
+}
+
+/// Element of a schema which clients may depend on.
+#[derive(Debug)]
+struct Element {
+    /// Kind of a type, or type of a field or argument.
+    ty: String,
+    direction: Direction,
+    /// Whether clients must provide the element, if it is an input.
+    required: bool,
+    deprecated: bool,
+    /// Path of the element this element belongs to, if any.
+    parent: Option<String>,
+}
+impl Element {
+    /// Whether clients of this element keep working with another element.
+    fn accepts(&self, other: &Element) -> bool {
+        let (old, new) = (self.ty.as_str(), other.ty.as_str());
+        old == new
+            || match self.direction {
+                Direction::Exact => false,
+                Direction::Output => new.strip_suffix('!') == Some(old),
+                Direction::Input => old.strip_suffix('!') == Some(new),
+            }
+    }
+}
+
+fn elements(sdl: &str) -> Result<BTreeMap<String, Element>, parser::Error> {
+    let document = parser::parse_schema(sdl)?;
+    let mut elements = BTreeMap::new();
+    for definition in document.definitions {
+        let definition = match definition {
+            TypeSystemDefinition::Type(definition) => definition.node,
+            _ => continue,
+        };
+        let type_name = definition.name.node.to_string();
+        let (kind, fields, inputs, values) = match &definition.kind {
+            TypeKind::Scalar => ("scalar", vec![], vec![], vec![]),
+            TypeKind::Object(object) => ("object", object.fields.iter().collect(), vec![], vec![]),
+            TypeKind::Interface(interface) => (
+                "interface",
+                interface.fields.iter().collect(),
+                vec![],
+                vec![],
+            ),
+            TypeKind::Union(union) => (
+                "union",
+                vec![],
+                vec![],
+                union
+                    .members
+                    .iter()
+                    .map(|member| (member.node.to_string(), false))
+                    .collect(),
+            ),
+            TypeKind::Enum(enum_type) => (
+                "enum",
+                vec![],
+                vec![],
+                enum_type
+                    .values
+                    .iter()
+                    .map(|value| {
+                        let value = &value.node;
+                        (
+                            value.value.node.to_string(),
+                            is_deprecated(&value.directives),
+                        )
+                    })
+                    .collect(),
+            ),
+            TypeKind::InputObject(input_object) => (
+                "input",
+                vec![],
+                input_object.fields.iter().collect(),
+                vec![],
+            ),
+        };
+        elements.insert(
+            type_name.clone(),
+            Element {
+                ty: kind.to_owned(),
+                direction: Direction::Exact,
+                required: false,
+                deprecated: is_deprecated(&definition.directives),
+                parent: None,
+            },
+        );
+        for field in fields {
+            let field = &field.node;
+            let field_path = format!("{}.{}", type_name, field.name.node);
+            for argument in &field.arguments {
+                insert_input(
+                    &mut elements,
+                    format!("{}({})", field_path, argument.node.name.node),
+                    argument,
+                    &field_path,
+                );
+            }
+            elements.insert(
+                field_path,
+                Element {
+                    ty: field.ty.node.to_string(),
+                    direction: Direction::Output,
+                    required: false,
+                    deprecated: is_deprecated(&field.directives),
+                    parent: Some(type_name.clone()),
+                },
+            );
+        }
+        for input in inputs {
+            insert_input(
+                &mut elements,
+                format!("{}.{}", type_name, input.node.name.node),
+                input,
+                &type_name,
+            );
+        }
+        for (value, deprecated) in values {
+            elements.insert(
+                format!("{}.{}", type_name, value),
+                Element {
+                    ty: kind.to_owned(),
+                    direction: Direction::Exact,
+                    required: false,
+                    deprecated,
+                    parent: Some(type_name.clone()),
+                },
+            );
+        }
+    }
+    Ok(elements)
+}
+
+fn insert_input(
+    elements: &mut BTreeMap<String, Element>,
+    path: String,
+    input: &Positioned<InputValueDefinition>,
+    parent: &str,
+) {
+    let input = &input.node;
+    elements.insert(
+        path,
+        Element {
+            ty: input.ty.node.to_string(),
+            direction: Direction::Input,
+            required: !input.ty.node.nullable && input.default_value.is_none(),
+            deprecated: is_deprecated(&input.directives),
+            parent: Some(parent.to_owned()),
+        },
+    );
+}
+
+fn is_deprecated(directives: &[Positioned<ConstDirective>]) -> bool {
+    directives
+        .iter()
+        .any(|directive| directive.node.name.node.as_str() == "deprecated")
+}
@@ -0,0 +1,74 @@
+use serde::Serialize;
+
+use mediasoup::transport::TransportGeneric;
+use mediasoup::worker::RequestError;
+
+use crate::relay_server::RelayServer;
+
+/// Summary of the load on a relay, for capacity planning.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStats {
+    /// Rooms reachable from the relay server.
+    pub rooms: usize,
+    /// Sessions connected to the rooms.
+    pub sessions: usize,
+    pub producers: usize,
+    pub consumers: usize,
+    pub data_producers: usize,
+    pub data_consumers: usize,
+    /// Routers alive in the Mediasoup worker.
+    pub worker_routers: usize,
+    /// CPU time spent by the Mediasoup worker in user mode, in milliseconds.
+    pub worker_user_time_ms: u64,
+    /// CPU time spent by the Mediasoup worker in kernel mode, in milliseconds.
+    pub worker_system_time_ms: u64,
+    /// Peak resident memory of the Mediasoup worker in KiB.
+    pub worker_max_rss_kib: u64,
+    /// RTP bytes received by open transports since they were created.
+    pub rtp_bytes_received: u64,
+    /// RTP bytes sent by open transports since they were created.
+    pub rtp_bytes_sent: u64,
+}
+
+/// Summarize the load on a relay. Requests stats of every transport from the worker,
+/// so this should not be called while the worker is overloaded.
+// integer widths of stats differ between fields and mediasoup versions, so all
+// counts are widened to u64
+#[allow(clippy::unnecessary_cast)]
+pub async fn report(relay_server: &RelayServer) -> Result<ServerStats, RequestError> {
+    let worker = relay_server.worker();
+    let usage = worker.get_resource_usage().await?;
+    let mut stats = ServerStats {
+        worker_routers: worker.dump().await?.router_ids.len(),
+        worker_user_time_ms: usage.ru_utime as u64,
+        worker_system_time_ms: usage.ru_stime as u64,
+        worker_max_rss_kib: usage.ru_maxrss as u64,
+        ..ServerStats::default()
+    };
+
+    let rooms = relay_server.get_rooms();
+    stats.rooms = rooms.len();
+    for room in &rooms {
+        for session in room.active_sessions() {
+            stats.sessions += 1;
+            stats.producers += session.get_producers().len();
+            stats.consumers += session.get_consumers().len();
+            stats.data_producers += session.get_data_producers().len();
+            stats.data_consumers += session.get_data_consumers().len();
+            for transport in session.get_webrtc_transports() {
+                for stat in transport.get_stats().await? {
+                    stats.rtp_bytes_received += stat.rtp_bytes_received as u64;
+                    stats.rtp_bytes_sent += stat.rtp_bytes_sent as u64;
+                }
+            }
+            for transport in session.get_plain_transports() {
+                for stat in transport.get_stats().await? {
+                    stats.rtp_bytes_received += stat.rtp_bytes_received as u64;
+                    stats.rtp_bytes_sent += stat.rtp_bytes_sent as u64;
+                }
+            }
+        }
+    }
+    Ok(stats)
+}
@@ -1,23 +1,32 @@
-use futures::{future, stream, Stream, StreamExt};
-use mediasoup::producer::ProducerTraceEventType;
+use futures::{future, stream, Future, Stream, StreamExt};
+use mediasoup::producer::{ProducerTraceEventData, ProducerTraceEventType};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::hash::Hash;
 use std::sync::{Arc, Mutex, Weak};
-use tokio::sync::broadcast;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, oneshot};
 use tokio_stream::wrappers::BroadcastStream;
 use uuid::Uuid;
 
 use anyhow::{anyhow, Result};
 use derive_more::Display;
 use mediasoup::{
-    consumer::{Consumer, ConsumerId, ConsumerOptions, ConsumerStat},
+    consumer::{
+        Consumer, ConsumerId, ConsumerLayers, ConsumerOptions, ConsumerScore, ConsumerStat,
+    },
     data_consumer::{DataConsumer, DataConsumerId, DataConsumerOptions, DataConsumerStat},
     data_producer::{DataProducer, DataProducerId, DataProducerOptions, DataProducerStat},
-    data_structures::{DtlsParameters, TransportListenIp},
-    plain_transport::{PlainTransport, PlainTransportOptions, PlainTransportStat},
-    producer::{Producer, ProducerId, ProducerOptions, ProducerStat},
-    rtp_parameters::{MediaKind, RtpCapabilities, RtpParameters},
+    data_structures::{AppData, DtlsParameters, DtlsState, TransportListenIp},
+    plain_transport::{
+        PlainTransport, PlainTransportOptions, PlainTransportRemoteParameters, PlainTransportStat,
+    },
+    producer::{Producer, ProducerId, ProducerOptions, ProducerScore, ProducerStat},
+    rtp_parameters::{
+        MediaKind, MimeTypeVideo, RtpCapabilities, RtpCodecCapability, RtpParameters,
+    },
     sctp_parameters::SctpStreamParameters,
+    srtp_parameters::SrtpCryptoSuite,
     transport::{Transport, TransportGeneric, TransportId},
     webrtc_transport::{
         TransportListenIps, WebRtcTransport, WebRtcTransportOptions,
@@ -25,8 +34,20 @@ use mediasoup::{
     },
 };
 
-use crate::relay_server::SessionOptions;
-use crate::room::Room;
+use crate::diagnostics::{self, AsyncPath};
+use crate::negotiation::{self, ConsumeCheck, NegotiationReport};
+use crate::relay_server::{ForeignSessionId, Role, SessionOptions};
+use crate::room::{DataRelayStat, ProducerAnnouncement, Room};
+use crate::soak_report::Tracked;
+use crate::stream_policy::StreamRole;
+
+/// Most stats requests of a session in flight to the worker at once.
+const STATS_CONCURRENCY: usize = 32;
+/// Time after which a stats request is abandoned.
+const STATS_TIMEOUT: Duration = Duration::from_secs(2);
+/// Bounds on the period at which transports are checked for idleness.
+const MIN_IDLE_CHECK_PERIOD: Duration = Duration::from_millis(50);
+const MAX_IDLE_CHECK_PERIOD: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Display, Hash, Default)]
 pub struct SessionId(Uuid);
@@ -53,8 +74,15 @@ struct Shared {
     id: SessionId,
     room: Room,
 
+    foreign_session_id: ForeignSessionId,
     session_options: SessionOptions,
+    /// human-readable name of the participant, if registered with one
+    display_name: Option<String>,
     transport_listen_ip: TransportListenIp,
+    /// cap on the incoming bitrate of each transport, if any
+    max_incoming_bitrate: Option<u32>,
+    /// time for which a stats snapshot is reused, if stats are cached
+    stats_cache_ttl: Option<Duration>,
     channel_tx: broadcast::Sender<Message>,
 }
 impl PartialEq for Shared {
@@ -67,6 +95,16 @@ impl Eq for Shared {}
 #[derive(Debug, Clone)]
 enum Message {
     ResourceClosed(Resource),
+    /// current layers of a simulcast/SVC consumer changed, or none if inactive
+    LayersChanged(ConsumerId, Option<ConsumerLayers>),
+    /// score of the RTP streams received by a producer changed
+    ProducerScore(ProducerId, Vec<ProducerScore>),
+    /// score of the RTP stream sent by a consumer changed
+    ConsumerScore(ConsumerId, ConsumerScore),
+    /// trace event of a producer, if enabled
+    ProducerTrace(ProducerId, ProducerTraceEventData),
+    /// session reaches its maximum duration after the given time
+    ExpiringSoon(Duration),
 }
 
 #[derive(Debug)]
@@ -78,16 +116,31 @@ struct State {
     data_producers: HashMap<DataProducerId, DataProducer>,
     webrtc_transports: HashMap<TransportId, WebRtcTransport>,
     plain_transports: HashMap<TransportId, PlainTransport>,
+    /// mapping of producers/consumers to the transport they were created on
+    resource_transports: HashMap<Resource, TransportId>,
+    /// codec/extension negotiation outcome of each producer
+    negotiation_reports: HashMap<ProducerId, NegotiationReport>,
+    /// count of trace subscriptions to each kind of trace event of each producer
+    trace_subscriptions: HashMap<ProducerId, HashMap<TraceKind, usize>>,
+    /// last stats snapshot and when it was taken, if stats are cached
+    stats_cache: Option<(Instant, Stats)>,
 }
 
 impl Session {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         room: Room,
+        foreign_session_id: ForeignSessionId,
         session_options: SessionOptions,
+        display_name: Option<String>,
         transport_listen_ip: TransportListenIp,
+        max_incoming_bitrate: Option<u32>,
+        stats_cache_ttl: Option<Duration>,
+        idle_transport_timeout: Option<Duration>,
     ) -> Self {
         let id = SessionId::new();
         log::trace!("+session {}", id);
+        Tracked::Session.created();
         let session = Self {
             shared: Arc::new(Shared {
                 state: Mutex::new(State {
@@ -98,15 +151,26 @@ impl Session {
                     data_producers: HashMap::new(),
                     webrtc_transports: HashMap::new(),
                     plain_transports: HashMap::new(),
+                    resource_transports: HashMap::new(),
+                    negotiation_reports: HashMap::new(),
+                    trace_subscriptions: HashMap::new(),
+                    stats_cache: None,
                 }),
                 id,
                 room: room.clone(),
+                foreign_session_id,
                 session_options,
+                display_name,
                 transport_listen_ip,
+                max_incoming_bitrate,
+                stats_cache_ttl,
                 channel_tx: broadcast::channel(16).0,
             }),
         };
         room.add_session(session.clone());
+        if let Some(timeout) = idle_transport_timeout {
+            spawn_idle_transport_reaper(session.downgrade(), timeout);
+        }
         session
     }
 
@@ -127,23 +191,98 @@ impl Session {
         Ok(transport.id())
     }
 
+    /// Connect a local plain transport with the remote endpoint. The remote tuple
+    /// may only be given for plain transports created without comedia, and SRTP
+    /// parameters are required for plain transports created with SRTP enabled.
+    pub async fn connect_plain_transport(
+        &self,
+        id: TransportId,
+        remote_parameters: PlainTransportRemoteParameters,
+    ) -> Result<TransportId> {
+        let transport = self
+            .get_plain_transport(id)
+            .ok_or_else(|| anyhow!("plain transport does not exist"))?;
+
+        transport.connect(remote_parameters).await?;
+        log::trace!(
+            "<-> transport {} [plain] (session {})",
+            transport.id(),
+            self.id()
+        );
+        Ok(transport.id())
+    }
+
     /// Create a local consumer on the receive WebRTC transport.
     pub async fn consume(
         &self,
         transport_id: TransportId,
         producer_id: ProducerId,
+    ) -> Result<Consumer> {
+        self.consume_with_config(transport_id, producer_id, ConsumerConfig::default())
+            .await
+    }
+
+    /// Create a local consumer on the receive WebRTC transport, with an explicit
+    /// MID rather than one assigned by the transport.
+    pub async fn consume_with_mid(
+        &self,
+        transport_id: TransportId,
+        producer_id: ProducerId,
+        mid: Option<String>,
+    ) -> Result<Consumer> {
+        self.create_consumer(transport_id, producer_id, mid, ConsumerConfig::default())
+            .await
+    }
+
+    /// Create a local consumer on the receive WebRTC transport, configured to fit
+    /// the client implementation.
+    pub async fn consume_with_config(
+        &self,
+        transport_id: TransportId,
+        producer_id: ProducerId,
+        config: ConsumerConfig,
+    ) -> Result<Consumer> {
+        let mid = match config.mid_strategy {
+            MidStrategy::Sequential => None,
+            MidStrategy::ProducerId => Some(producer_id.to_string()),
+        };
+        self.create_consumer(transport_id, producer_id, mid, config)
+            .await
+    }
+
+    async fn create_consumer(
+        &self,
+        transport_id: TransportId,
+        producer_id: ProducerId,
+        mid: Option<String>,
+        config: ConsumerConfig,
     ) -> Result<Consumer> {
         let transport = self
             .get_webrtc_transport(transport_id)
             .ok_or_else(|| anyhow!("transport does not exist"))?;
         // make sure client has provided rtp caps
-        let rtp_capabilities = self
+        let mut rtp_capabilities = self
             .get_rtp_capabilities()
             .ok_or_else(|| anyhow!("missing rtp capabilities"))?;
+        if !config.enable_rtx {
+            // consumers only retransmit over rtx if the client supports it
+            rtp_capabilities.codecs.retain(|codec| {
+                !matches!(
+                    codec,
+                    RtpCodecCapability::Video {
+                        mime_type: MimeTypeVideo::Rtx,
+                        ..
+                    }
+                )
+            });
+        }
 
         // initialize consumer as paused (recommended by mediasoup docs)
         let mut options = ConsumerOptions::new(producer_id, rtp_capabilities);
         options.paused = true;
+        options.mid = mid;
+        options.pipe = config.pipe;
+        options.ignore_dtx = config.ignore_dtx;
 
         let consumer = transport.consume(options).await?;
         consumer
@@ -166,9 +305,132 @@ impl Session {
                 })
             })
             .detach();
+        consumer
+            .on_score({
+                let channel_tx = self.shared.channel_tx.clone();
+                let consumer_id = consumer.id();
+                Box::new(move |score: &ConsumerScore| {
+                    let _ = channel_tx.send(Message::ConsumerScore(consumer_id, score.clone()));
+                })
+            })
+            .detach();
+        consumer
+            .on_layers_change({
+                let channel_tx = self.shared.channel_tx.clone();
+                let consumer_id = consumer.id();
+                Box::new(move |layers: &Option<ConsumerLayers>| {
+                    let _ = channel_tx.send(Message::LayersChanged(consumer_id, *layers));
+                })
+            })
+            .detach();
 
         log::trace!("+consumer {} (session {})", consumer.id(), self.id());
+        if let Some(latency_probe) = self.get_room().latency_probe() {
+            latency_probe.watch_consumer(&consumer).await?;
+        }
         self.add_consumer(consumer.clone());
+        self.bind_resource(Resource::Consumer(consumer.id()), transport_id);
+        Ok(consumer)
+    }
+
+    /// Check whether this session is able to consume a producer in the room, using
+    /// the client RTP capabilities.
+    pub async fn can_consume(&self, producer_id: ProducerId) -> ConsumeCheck {
+        let rtp_capabilities = match self.get_rtp_capabilities() {
+            Some(rtp_capabilities) => rtp_capabilities,
+            None => return ConsumeCheck::rejected(vec!["missing rtp capabilities".to_owned()]),
+        };
+        let producer = self
+            .get_room()
+            .get_producers()
+            .into_iter()
+            .find(|producer| producer.id() == producer_id);
+        let producer = match producer {
+            Some(producer) => producer,
+            None => {
+                return ConsumeCheck::rejected(vec![format!(
+                    "producer {} does not exist",
+                    producer_id
+                )])
+            }
+        };
+        let router = self.shared.room.get_router().await;
+        if router.can_consume(&producer_id, &rtp_capabilities) {
+            return ConsumeCheck::accepted();
+        }
+        let mut reasons = negotiation::consume_mismatches(
+            producer.consumable_rtp_parameters(),
+            &rtp_capabilities,
+        );
+        if reasons.is_empty() {
+            reasons.push("no codec of the producer matches the rtp capabilities".to_owned());
+        }
+        ConsumeCheck::rejected(reasons)
+    }
+
+    /// Create a local consumer on a plain transport, for external receivers.
+    /// Uses the client RTP capabilities if provided, otherwise the router capabilities.
+    pub async fn consume_plain(
+        &self,
+        transport_id: TransportId,
+        producer_id: ProducerId,
+    ) -> Result<Consumer> {
+        let transport = self
+            .get_plain_transport(transport_id)
+            .ok_or_else(|| anyhow!("plain transport does not exist"))?;
+        let rtp_capabilities = match self.get_rtp_capabilities() {
+            Some(rtp_capabilities) => rtp_capabilities,
+            None => {
+                let router = self.shared.room.get_router().await;
+                serde_json::from_value(serde_json::to_value(router.rtp_capabilities())?)?
+            }
+        };
+
+        // initialize consumer as paused, to be resumed once the receiver is ready
+        let mut options = ConsumerOptions::new(producer_id, rtp_capabilities);
+        options.paused = true;
+
+        let consumer = transport.consume(options).await?;
+        consumer
+            .on_transport_close({
+                let channel_tx = self.shared.channel_tx.clone();
+                let consumer_id = consumer.id();
+                Box::new(move || {
+                    let _ =
+                        channel_tx.send(Message::ResourceClosed(Resource::Consumer(consumer_id)));
+                })
+            })
+            .detach();
+        consumer
+            .on_producer_close({
+                let channel_tx = self.shared.channel_tx.clone();
+                let consumer_id = consumer.id();
+                Box::new(move || {
+                    let _ =
+                        channel_tx.send(Message::ResourceClosed(Resource::Consumer(consumer_id)));
+                })
+            })
+            .detach();
+        consumer
+            .on_score({
+                let channel_tx = self.shared.channel_tx.clone();
+                let consumer_id = consumer.id();
+                Box::new(move |score: &ConsumerScore| {
+                    let _ = channel_tx.send(Message::ConsumerScore(consumer_id, score.clone()));
+                })
+            })
+            .detach();
+
+        log::trace!(
+            "+consumer {} [plain] (session {})",
+            consumer.id(),
+            self.id()
+        );
+        if let Some(latency_probe) = self.get_room().latency_probe() {
+            latency_probe.watch_consumer(&consumer).await?;
+        }
+        self.add_consumer(consumer.clone());
+        self.bind_resource(Resource::Consumer(consumer.id()), transport_id);
         Ok(consumer)
     }
 
@@ -180,19 +442,219 @@ impl Session {
         }
     }
 
+    /// Set the priority of a local consumer, from 1 (lowest) to 255. Consumers with a
+    /// higher priority are allocated more bandwidth when the transport is constrained.
+    pub async fn consumer_set_priority(&self, consumer_id: ConsumerId, priority: u8) -> Result<()> {
+        if priority == 0 {
+            return Err(anyhow!("consumer priority must be at least 1"));
+        }
+        let consumer = self
+            .get_consumer(consumer_id)
+            .ok_or_else(|| anyhow!("consumer {} does not exist", consumer_id))?;
+        Ok(consumer.set_priority(priority).await?)
+    }
+
+    /// Set the maximum incoming bitrate (bps) of a local transport. The bitrate may not
+    /// exceed the cap of this session, and is unlimited if 0 and the session is uncapped.
+    pub async fn set_max_incoming_bitrate(
+        &self,
+        transport_id: TransportId,
+        bitrate: u32,
+    ) -> Result<()> {
+        if let Some(max_incoming_bitrate) = self.shared.max_incoming_bitrate {
+            if bitrate == 0 || bitrate > max_incoming_bitrate {
+                return Err(anyhow!(
+                    "incoming bitrate may not exceed {} bps",
+                    max_incoming_bitrate
+                ));
+            }
+        }
+        if let Some(transport) = self.get_webrtc_transport(transport_id) {
+            transport.set_max_incoming_bitrate(bitrate).await?;
+        } else if let Some(transport) = self.get_plain_transport(transport_id) {
+            transport.set_max_incoming_bitrate(bitrate).await?;
+        } else {
+            return Err(anyhow!("transport does not exist"));
+        }
+        log::trace!(
+            "transport {} max incoming bitrate {} (session {})",
+            transport_id,
+            bitrate,
+            self.id()
+        );
+        Ok(())
+    }
+    /// Get the cap on the incoming bitrate (bps) of each transport of this session, if any.
+    pub fn max_incoming_bitrate(&self) -> Option<u32> {
+        self.shared.max_incoming_bitrate
+    }
+    async fn apply_max_incoming_bitrate(&self, transport: &impl Transport) {
+        if let Some(max_incoming_bitrate) = self.shared.max_incoming_bitrate {
+            if let Err(err) = transport
+                .set_max_incoming_bitrate(max_incoming_bitrate)
+                .await
+            {
+                log::error!(
+                    "failed to cap incoming bitrate of transport {}: {}",
+                    transport.id(),
+                    err
+                );
+            }
+        }
+    }
+
+    /// Set the preferred simulcast/SVC layers of a local consumer.
+    /// Returns the preferred layers applied, or none if the consumer is not layered.
+    pub async fn consumer_set_preferred_layers(
+        &self,
+        consumer_id: ConsumerId,
+        layers: ConsumerLayers,
+    ) -> Result<Option<ConsumerLayers>> {
+        let consumer = self
+            .get_consumer(consumer_id)
+            .ok_or_else(|| anyhow!("consumer {} does not exist", consumer_id))?;
+        Ok(consumer.set_preferred_layers(layers).await?)
+    }
+
+    /// Close a local data producer, and announce it to the room so that data consumers
+    /// of it can be cleaned up.
+    pub fn data_producer_close(&self, data_producer_id: DataProducerId) -> Result<()> {
+        let data_producer = self
+            .remove_data_producer(data_producer_id)
+            .ok_or_else(|| anyhow!("data producer {} does not exist", data_producer_id))?;
+        // closing the data producer invokes close handlers which lock state
+        drop(data_producer);
+        self.get_room()
+            .announce_data_producer_closed(data_producer_id);
+        log::trace!(
+            "-data producer {} (session {})",
+            data_producer_id,
+            self.id()
+        );
+        Ok(())
+    }
+
+    /// Close a local data consumer, releasing it from the session resource limits.
+    pub fn data_consumer_close(&self, data_consumer_id: DataConsumerId) -> Result<()> {
+        let data_consumer = self
+            .remove_data_consumer(data_consumer_id)
+            .ok_or_else(|| anyhow!("data consumer {} does not exist", data_consumer_id))?;
+        // closing the data consumer invokes close handlers which lock state
+        drop(data_consumer);
+        log::trace!(
+            "-data consumer {} (session {})",
+            data_consumer_id,
+            self.id()
+        );
+        Ok(())
+    }
+
+    /// Close the local data consumers of a data producer, notifying the client-side
+    /// data consumers to close, e.g. once the room relays the data producer instead.
+    pub fn close_data_consumers_of(&self, data_producer_id: DataProducerId) {
+        let data_consumer_ids = self
+            .get_data_consumers()
+            .into_iter()
+            .filter(|data_consumer| data_consumer.data_producer_id() == data_producer_id)
+            .map(|data_consumer| data_consumer.id())
+            .collect::<Vec<DataConsumerId>>();
+        for data_consumer_id in data_consumer_ids {
+            if self.data_consumer_close(data_consumer_id).is_ok() {
+                let _ =
+                    self.shared
+                        .channel_tx
+                        .send(Message::ResourceClosed(Resource::DataConsumer(
+                            data_consumer_id,
+                        )));
+            }
+        }
+    }
+
+    /// Close a local consumer, releasing it from the session resource limits.
+    pub fn consumer_close(&self, consumer_id: ConsumerId) -> Result<()> {
+        let consumer = self
+            .remove_consumer(consumer_id)
+            .ok_or_else(|| anyhow!("consumer {} does not exist", consumer_id))?;
+        // closing the consumer invokes close handlers which lock state
+        drop(consumer);
+        log::trace!("-consumer {} (session {})", consumer_id, self.id());
+        Ok(())
+    }
+
+    /// Pause a local producer, pausing all consumers of it.
+    pub async fn producer_pause(&self, producer_id: ProducerId) -> Result<()> {
+        match self.get_producer(producer_id) {
+            Some(producer) => Ok(producer.pause().await?),
+            None => Err(anyhow!("producer {} does not exist", producer_id)),
+        }
+    }
+
+    /// Resume a local producer.
+    pub async fn producer_resume(&self, producer_id: ProducerId) -> Result<()> {
+        match self.get_producer(producer_id) {
+            Some(producer) => Ok(producer.resume().await?),
+            None => Err(anyhow!("producer {} does not exist", producer_id)),
+        }
+    }
+
+    /// Close a local producer, and announce it to the room so that consumers of it
+    /// can be cleaned up.
+    pub fn producer_close(&self, producer_id: ProducerId) -> Result<()> {
+        let producer = self
+            .remove_producer(producer_id)
+            .ok_or_else(|| anyhow!("producer {} does not exist", producer_id))?;
+        // closing the producer invokes close handlers which lock state
+        drop(producer);
+        self.get_room().announce_producer_closed(producer_id);
+        log::trace!("-producer {} (session {})", producer_id, self.id());
+        Ok(())
+    }
+
     /// Create a local producer on the send WebRTC transport.
     pub async fn produce(
         &self,
         transport_id: TransportId,
         kind: MediaKind,
         rtp_parameters: RtpParameters,
+    ) -> Result<Producer> {
+        self.produce_with_app_data(
+            transport_id,
+            kind,
+            rtp_parameters,
+            ProducerAppData::default(),
+        )
+        .await
+    }
+
+    /// Create a local producer on the send WebRTC transport, annotated with app data.
+    pub async fn produce_with_app_data(
+        &self,
+        transport_id: TransportId,
+        kind: MediaKind,
+        rtp_parameters: RtpParameters,
+        app_data: ProducerAppData,
     ) -> Result<Producer> {
         let transport = self
             .get_webrtc_transport(transport_id)
             .ok_or_else(|| anyhow!("transport does not exist"))?;
+        check_encodings(kind, &rtp_parameters)?;
+        if !self.get_room().preset().allows(kind) {
+            return Err(anyhow!("room does not carry {:?} media", kind));
+        }
+        self.get_room().stream_policy().check_produce(
+            self.get_session_options().role(),
+            app_data.role,
+            kind,
+        )?;
+        let report = self.negotiate(kind, &rtp_parameters).await;
         let producer = transport
-            .produce(ProducerOptions::new(kind, rtp_parameters))
-            .await?;
+            .produce({
+                let mut options = ProducerOptions::new(kind, rtp_parameters);
+                options.app_data = AppData::new(app_data);
+                options
+            })
+            .await
+            .map_err(|err| negotiation_error(err.into(), &report))?;
         producer
             .on_transport_close({
                 let channel_tx = self.shared.channel_tx.clone();
@@ -203,7 +665,34 @@ impl Session {
                 })
             })
             .detach();
+        producer
+            .on_score({
+                let channel_tx = self.shared.channel_tx.clone();
+                let producer_id = producer.id();
+                Box::new(move |scores: &Vec<ProducerScore>| {
+                    let _ = channel_tx.send(Message::ProducerScore(producer_id, scores.clone()));
+                })
+            })
+            .detach();
+        producer
+            .on_trace({
+                let channel_tx = self.shared.channel_tx.clone();
+                let producer_id = producer.id();
+                Box::new(move |data: &ProducerTraceEventData| {
+                    // rtp trace events are consumed by the latency probe directly
+                    if TraceKind::of(data).is_some() {
+                        let _ = channel_tx.send(Message::ProducerTrace(producer_id, data.clone()));
+                    }
+                })
+            })
+            .detach();
+        if let Some(latency_probe) = self.get_room().latency_probe() {
+            latency_probe.watch_producer(&producer).await?;
+        }
         self.add_producer(producer.clone());
+        self.bind_resource(Resource::Producer(producer.id()), transport_id);
+        self.set_negotiation_report(producer.id(), report);
+        self.get_room().observe_producer(&producer).await;
 
         log::trace!("+producer {} (session {})", producer.id(), self.id());
 
@@ -215,15 +704,74 @@ impl Session {
         transport_id: TransportId,
         kind: MediaKind,
         rtp_parameters: RtpParameters,
+    ) -> Result<Producer> {
+        self.produce_plain_with_app_data(
+            transport_id,
+            kind,
+            rtp_parameters,
+            ProducerAppData::default(),
+        )
+        .await
+    }
+
+    /// Create a local producer on the plain transport, annotated with app data.
+    pub async fn produce_plain_with_app_data(
+        &self,
+        transport_id: TransportId,
+        kind: MediaKind,
+        rtp_parameters: RtpParameters,
+        app_data: ProducerAppData,
     ) -> Result<Producer> {
         let transport = self
             .get_plain_transport(transport_id)
             .ok_or_else(|| anyhow!("plain transport does not exist"))?;
+        check_encodings(kind, &rtp_parameters)?;
+        if !self.get_room().preset().allows(kind) {
+            return Err(anyhow!("room does not carry {:?} media", kind));
+        }
+        self.get_room().stream_policy().check_produce(
+            self.get_session_options().role(),
+            app_data.role,
+            kind,
+        )?;
 
+        let report = self.negotiate(kind, &rtp_parameters).await;
         let producer = transport
-            .produce(ProducerOptions::new(kind, rtp_parameters))
-            .await?;
+            .produce({
+                let mut options = ProducerOptions::new(kind, rtp_parameters);
+                options.app_data = AppData::new(app_data);
+                options
+            })
+            .await
+            .map_err(|err| negotiation_error(err.into(), &report))?;
+        producer
+            .on_score({
+                let channel_tx = self.shared.channel_tx.clone();
+                let producer_id = producer.id();
+                Box::new(move |scores: &Vec<ProducerScore>| {
+                    let _ = channel_tx.send(Message::ProducerScore(producer_id, scores.clone()));
+                })
+            })
+            .detach();
+        producer
+            .on_trace({
+                let channel_tx = self.shared.channel_tx.clone();
+                let producer_id = producer.id();
+                Box::new(move |data: &ProducerTraceEventData| {
+                    // rtp trace events are consumed by the latency probe directly
+                    if TraceKind::of(data).is_some() {
+                        let _ = channel_tx.send(Message::ProducerTrace(producer_id, data.clone()));
+                    }
+                })
+            })
+            .detach();
+        if let Some(latency_probe) = self.get_room().latency_probe() {
+            latency_probe.watch_producer(&producer).await?;
+        }
         self.add_producer(producer.clone());
+        self.bind_resource(Resource::Producer(producer.id()), transport_id);
+        self.set_negotiation_report(producer.id(), report);
+        self.get_room().observe_producer(&producer).await;
 
         log::trace!(
             "+producer {} [plain] (session {})",
@@ -275,6 +823,7 @@ impl Session {
             self.id()
         );
         self.add_data_consumer(data_consumer.clone());
+        self.bind_resource(Resource::DataConsumer(data_consumer.id()), transport_id);
         Ok(data_consumer)
     }
 
@@ -284,6 +833,7 @@ impl Session {
         transport_id: TransportId,
         sctp_stream_parameters: SctpStreamParameters,
     ) -> Result<DataProducer> {
+        let role = self.get_session_options().role();
         let transport = self
             .get_webrtc_transport(transport_id)
             .ok_or_else(|| anyhow!("transport does not exist"))?;
@@ -303,9 +853,16 @@ impl Session {
             .detach();
 
         self.add_data_producer(data_producer.clone());
+        self.bind_resource(Resource::DataProducer(data_producer.id()), transport_id);
 
         let room = self.get_room();
-        room.announce_data_producer(data_producer.id());
+        if let Err(err) = room
+            .publish_data_producer(&data_producer, self.id(), role)
+            .await
+        {
+            drop(self.remove_data_producer(data_producer.id()));
+            return Err(err);
+        }
         log::trace!(
             "+data producer {} (session {})",
             data_producer.id(),
@@ -316,9 +873,28 @@ impl Session {
     }
 
     /// Get aggregation of all stats related to this session.
-    /// Is quite computationally expensive to produce.
-    #[allow(clippy::eval_order_dependence)]
+    /// Is quite computationally expensive to produce, so if stats are cached, a snapshot
+    /// taken within the cache TTL is returned instead.
     pub async fn get_stats(&self) -> Result<Stats, mediasoup::worker::RequestError> {
+        let ttl = match self.shared.stats_cache_ttl {
+            Some(ttl) => ttl,
+            None => return diagnostics::instrument(AsyncPath::Stats, self.collect_stats()).await,
+        };
+        {
+            let state = self.shared.state.lock().unwrap();
+            if let Some((taken, stats)) = &state.stats_cache {
+                if taken.elapsed() < ttl {
+                    return Ok(stats.clone());
+                }
+            }
+        }
+        let stats = diagnostics::instrument(AsyncPath::Stats, self.collect_stats()).await?;
+        let mut state = self.shared.state.lock().unwrap();
+        state.stats_cache = Some((Instant::now(), stats.clone()));
+        Ok(stats)
+    }
+
+    async fn collect_stats(&self) -> Result<Stats, mediasoup::worker::RequestError> {
         let consumers = self.get_consumers();
         let producers = self.get_producers();
         let data_consumers = self.get_data_consumers();
@@ -326,55 +902,44 @@ impl Session {
         let webrtc_transports = self.get_webrtc_transports();
         let plain_transports = self.get_plain_transports();
 
-        let consumer_stats = stream::iter(consumers)
-            .filter_map(|consumer| async move {
-                let id = consumer.id();
-                let stats = consumer.get_stats().await.ok()?.consumer_stats().clone();
-                Some((id, stats))
-            })
-            .collect::<HashMap<_, _>>()
-            .await;
-
-        let producer_stats = stream::iter(producers)
-            .filter_map(|producer| async move {
-                let id = producer.id();
-                let stats = producer.get_stats().await.ok()?;
-                Some((id, stats))
-            })
-            .collect::<HashMap<_, _>>()
-            .await;
-        let data_consumer_stats = stream::iter(data_consumers)
-            .filter_map(|data_consumer| async move {
-                let id = data_consumer.id();
-                let stats = data_consumer.get_stats().await.ok()?;
-                Some((id, stats))
-            })
-            .collect::<HashMap<_, _>>()
-            .await;
-        let data_producer_stats = stream::iter(data_producers)
-            .filter_map(|data_producer| async move {
+        let room = self.get_room();
+        let data_relay_stats = data_producers
+            .iter()
+            .filter_map(|data_producer| {
                 let id = data_producer.id();
-                let stats = data_producer.get_stats().await.ok()?;
-                Some((id, stats))
-            })
-            .collect::<HashMap<_, _>>()
-            .await;
-        let webrtc_transport_stats = stream::iter(webrtc_transports)
-            .filter_map(|transport| async move {
-                let id = transport.id();
-                let stats = transport.get_stats().await.ok()?;
-                Some((id, stats))
+                Some((id, room.get_data_relay_stat(id)?))
             })
-            .collect::<HashMap<_, _>>()
-            .await;
-        let plain_transport_stats = stream::iter(plain_transports)
-            .filter_map(|transport| async move {
-                let id = transport.id();
-                let stats = transport.get_stats().await.ok()?;
-                Some((id, stats))
-            })
-            .collect::<HashMap<_, _>>()
-            .await;
+            .collect::<HashMap<_, _>>();
+
+        // request stats of all resources at once, rather than one category at a time
+        let (
+            consumer_stats,
+            producer_stats,
+            data_consumer_stats,
+            data_producer_stats,
+            webrtc_transport_stats,
+            plain_transport_stats,
+        ) = futures::join!(
+            request_stats(consumers, |consumer| async move {
+                let stats = consumer.get_stats().await.ok()?;
+                Some((consumer.id(), stats.consumer_stats().clone()))
+            }),
+            request_stats(producers, |producer| async move {
+                Some((producer.id(), producer.get_stats().await.ok()?))
+            }),
+            request_stats(data_consumers, |data_consumer| async move {
+                Some((data_consumer.id(), data_consumer.get_stats().await.ok()?))
+            }),
+            request_stats(data_producers, |data_producer| async move {
+                Some((data_producer.id(), data_producer.get_stats().await.ok()?))
+            }),
+            request_stats(webrtc_transports, |transport| async move {
+                Some((transport.id(), transport.get_stats().await.ok()?))
+            }),
+            request_stats(plain_transports, |transport| async move {
+                Some((transport.id(), transport.get_stats().await.ok()?))
+            }),
+        );
 
         Ok::<Stats, mediasoup::worker::RequestError>(Stats {
             consumer_stats,
@@ -383,15 +948,47 @@ impl Session {
             data_producer_stats,
             webrtc_transport_stats,
             plain_transport_stats,
+            data_relay_stats,
         })
     }
 
+    /// Get the DTLS/SRTP parameters in use on each transport of this session.
+    pub fn get_transport_crypto(&self) -> Vec<TransportCrypto> {
+        let webrtc_transports =
+            self.get_webrtc_transports()
+                .into_iter()
+                .map(|transport| TransportCrypto {
+                    transport_id: transport.id(),
+                    dtls_state: Some(transport.dtls_state()),
+                    dtls_parameters: Some(transport.dtls_parameters()),
+                    srtp_crypto_suite: None,
+                });
+        let plain_transports =
+            self.get_plain_transports()
+                .into_iter()
+                .map(|transport| TransportCrypto {
+                    transport_id: transport.id(),
+                    dtls_state: None,
+                    dtls_parameters: None,
+                    srtp_crypto_suite: transport
+                        .srtp_parameters()
+                        .map(|srtp_parameters| srtp_parameters.crypto_suite),
+                });
+        webrtc_transports.chain(plain_transports).collect()
+    }
+
     pub fn id(&self) -> SessionId {
         self.shared.id
     }
+    pub fn get_foreign_session_id(&self) -> ForeignSessionId {
+        self.shared.foreign_session_id.clone()
+    }
     pub fn get_session_options(&self) -> SessionOptions {
         self.shared.session_options.clone()
     }
+    pub fn get_display_name(&self) -> Option<String> {
+        self.shared.display_name.clone()
+    }
     pub fn get_room(&self) -> Room {
         self.shared.room.clone()
     }
@@ -413,6 +1010,7 @@ impl Session {
             .create_webrtc_transport(transport_options)
             .await
             .unwrap();
+        self.apply_max_incoming_bitrate(&transport).await;
         transport
             .on_router_close({
                 let channel_tx = self.shared.channel_tx.clone();
@@ -424,6 +1022,7 @@ impl Session {
                 })
             })
             .detach();
+        self.prune_transport_on_close(&transport);
         let mut state = self.shared.state.lock().unwrap();
         state
             .webrtc_transports
@@ -444,9 +1043,19 @@ impl Session {
             .collect::<Vec<WebRtcTransport>>()
     }
     pub async fn create_plain_transport(&self) -> PlainTransport {
+        self.create_plain_transport_with_config(PlainTransportConfig::default())
+            .await
+    }
+    pub async fn create_plain_transport_with_config(
+        &self,
+        config: PlainTransportConfig,
+    ) -> PlainTransport {
         let mut plain_transport_options =
             PlainTransportOptions::new(self.shared.transport_listen_ip);
-        plain_transport_options.comedia = true;
+        plain_transport_options.comedia = config.comedia;
+        plain_transport_options.rtcp_mux = config.rtcp_mux;
+        plain_transport_options.enable_srtp = config.enable_srtp;
+        plain_transport_options.srtp_crypto_suite = config.srtp_crypto_suite;
         let plain_transport = self
             .shared
             .room
@@ -455,6 +1064,8 @@ impl Session {
             .create_plain_transport(plain_transport_options)
             .await
             .unwrap();
+        self.apply_max_incoming_bitrate(&plain_transport).await;
+        self.prune_transport_on_close(&plain_transport);
 
         let mut state = self.shared.state.lock().unwrap();
         state
@@ -480,6 +1091,161 @@ impl Session {
             .collect::<Vec<PlainTransport>>()
     }
 
+    /// Prune a transport from this session once it is closed for any reason (e.g. as its
+    /// router closed), so that long-lived sessions do not accumulate closed transports.
+    /// Resources created on the transport close with it, and prune themselves.
+    fn prune_transport_on_close(&self, transport: &impl Transport) {
+        transport
+            .on_close({
+                let session = self.downgrade();
+                let transport_id = transport.id();
+                Box::new(move || {
+                    if let Some(session) = session.upgrade() {
+                        let mut state = session.shared.state.lock().unwrap();
+                        let webrtc_transport = state.webrtc_transports.remove(&transport_id);
+                        let plain_transport = state.plain_transports.remove(&transport_id);
+                        drop(state);
+                        if webrtc_transport.is_some() || plain_transport.is_some() {
+                            log::trace!(
+                                "-transport {} (session {}) [closed]",
+                                transport_id,
+                                session.id()
+                            );
+                        }
+                    }
+                })
+            })
+            .detach();
+    }
+
+    /// Get the total bytes received and sent on each transport of this session.
+    // integer widths of stats differ between fields and mediasoup versions, so all
+    // counts are widened to u64
+    #[allow(clippy::unnecessary_cast)]
+    async fn get_transport_traffic(&self) -> HashMap<TransportId, u64> {
+        let (webrtc_transport_traffic, plain_transport_traffic) = futures::join!(
+            request_stats(self.get_webrtc_transports(), |transport| async move {
+                let stat = transport.get_stats().await.ok()?.into_iter().next()?;
+                Some((
+                    transport.id(),
+                    stat.bytes_received as u64 + stat.bytes_sent as u64,
+                ))
+            }),
+            request_stats(self.get_plain_transports(), |transport| async move {
+                let stat = transport.get_stats().await.ok()?.into_iter().next()?;
+                Some((
+                    transport.id(),
+                    stat.bytes_received as u64 + stat.bytes_sent as u64,
+                ))
+            }),
+        );
+        webrtc_transport_traffic
+            .into_iter()
+            .chain(plain_transport_traffic)
+            .collect()
+    }
+
+    /// Get the transport a producer/consumer was created on.
+    pub fn get_resource_transport(&self, resource: &Resource) -> Option<TransportId> {
+        let state = self.shared.state.lock().unwrap();
+        state.resource_transports.get(resource).copied()
+    }
+
+    /// Check whether any producers/consumers were created on a transport.
+    fn transport_in_use(&self, id: TransportId) -> bool {
+        let state = self.shared.state.lock().unwrap();
+        state
+            .resource_transports
+            .values()
+            .any(|transport_id| *transport_id == id)
+    }
+
+    /// Close a transport, along with all producers and consumers created on it.
+    pub fn close_transport(&self, id: TransportId) -> Result<()> {
+        let mut state = self.shared.state.lock().unwrap();
+        let webrtc_transport = state.webrtc_transports.remove(&id);
+        let plain_transport = state.plain_transports.remove(&id);
+        if webrtc_transport.is_none() && plain_transport.is_none() {
+            return Err(anyhow!("transport does not exist"));
+        }
+
+        let resources = state
+            .resource_transports
+            .iter()
+            .filter(|(_, transport_id)| **transport_id == id)
+            .map(|(resource, _)| resource.clone())
+            .collect::<Vec<Resource>>();
+        let mut consumers = Vec::new();
+        let mut producers = Vec::new();
+        let mut data_consumers = Vec::new();
+        let mut data_producers = Vec::new();
+        for resource in &resources {
+            state.resource_transports.remove(resource);
+            match resource {
+                Resource::Consumer(id) => consumers.extend(state.consumers.remove(id)),
+                Resource::Producer(id) => producers.extend(state.producers.remove(id)),
+                Resource::DataConsumer(id) => {
+                    data_consumers.extend(state.data_consumers.remove(id))
+                }
+                Resource::DataProducer(id) => {
+                    data_producers.extend(state.data_producers.remove(id))
+                }
+                Resource::WebrtcTransport(_) | Resource::PlainTransport(_) => {}
+            }
+        }
+        // closing resources invokes close handlers which lock state, so they
+        // must only be dropped once state is released
+        drop(state);
+        drop((consumers, producers, data_consumers, data_producers));
+        drop((webrtc_transport, plain_transport));
+
+        // notify client-side of dependent resources which were closed, and other
+        // sessions of closed producers so they can clean up their consumers
+        let room = self.get_room();
+        for resource in resources {
+            match resource {
+                Resource::Producer(producer_id) => room.announce_producer_closed(producer_id),
+                Resource::DataProducer(data_producer_id) => {
+                    room.announce_data_producer_closed(data_producer_id)
+                }
+                _ => {}
+            }
+            let _ = self
+                .shared
+                .channel_tx
+                .send(Message::ResourceClosed(resource));
+        }
+        log::trace!("-transport {} (session {})", id, self.id());
+        Ok(())
+    }
+
+    fn bind_resource(&self, resource: Resource, transport_id: TransportId) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.resource_transports.insert(resource, transport_id);
+    }
+
+    /// Match producer RTP parameters against the router capabilities.
+    async fn negotiate(
+        &self,
+        kind: MediaKind,
+        rtp_parameters: &RtpParameters,
+    ) -> NegotiationReport {
+        let router = self.shared.room.get_router().await;
+        negotiation::negotiate(kind, rtp_parameters, router.rtp_capabilities())
+    }
+    fn set_negotiation_report(&self, producer_id: ProducerId, report: NegotiationReport) {
+        let mut state = self.shared.state.lock().unwrap();
+        // the producer may have closed in the meantime
+        if state.producers.contains_key(&producer_id) {
+            state.negotiation_reports.insert(producer_id, report);
+        }
+    }
+    /// Get the codec/extension negotiation outcome of a producer owned by this session.
+    pub fn get_negotiation_report(&self, producer_id: ProducerId) -> Option<NegotiationReport> {
+        let state = self.shared.state.lock().unwrap();
+        state.negotiation_reports.get(&producer_id).cloned()
+    }
+
     pub fn set_rtp_capabilities(&self, rtp_capabilities: RtpCapabilities) {
         let mut state = self.shared.state.lock().unwrap();
         state.client_rtp_capabilities.replace(rtp_capabilities);
@@ -490,6 +1256,18 @@ impl Session {
     }
 
     pub fn add_consumer(&self, consumer: Consumer) {
+        // prune consumer from session once it is closed for any reason
+        consumer
+            .on_close({
+                let session = self.downgrade();
+                let consumer_id = consumer.id();
+                Box::new(move || {
+                    if let Some(session) = session.upgrade() {
+                        drop(session.remove_consumer(consumer_id));
+                    }
+                })
+            })
+            .detach();
         let mut state = self.shared.state.lock().unwrap();
         state.consumers.insert(consumer.id(), consumer);
     }
@@ -497,23 +1275,52 @@ impl Session {
         let state = self.shared.state.lock().unwrap();
         state.consumers.get(&id).cloned()
     }
+    /// Remove a consumer from this session. The consumer is closed once the
+    /// returned reference is dropped, so it MUST NOT be dropped while holding state.
+    pub fn remove_consumer(&self, id: ConsumerId) -> Option<Consumer> {
+        let mut state = self.shared.state.lock().unwrap();
+        state.resource_transports.remove(&Resource::Consumer(id));
+        state.consumers.remove(&id)
+    }
     pub fn get_consumers(&self) -> Vec<Consumer> {
         let state = self.shared.state.lock().unwrap();
         state.consumers.values().cloned().collect::<Vec<Consumer>>()
     }
 
     pub fn add_producer(&self, producer: Producer) {
+        // prune producer from session once it is closed for any reason. A close started
+        // by the session is announced by the close mutation, while a close caused by
+        // the transport or router closing is announced here
+        producer
+            .on_close({
+                let session = self.downgrade();
+                let producer_id = producer.id();
+                Box::new(move || {
+                    if let Some(session) = session.upgrade() {
+                        if let Some(producer) = session.remove_producer(producer_id) {
+                            drop(producer);
+                            session.get_room().announce_producer_closed(producer_id);
+                        }
+                    }
+                })
+            })
+            .detach();
+        let announcement = ProducerAnnouncement::new(self, &producer);
         let mut state = self.shared.state.lock().unwrap();
-        self.get_room().announce_producer(producer.id());
+        self.get_room().announce_producer(announcement);
         state.producers.insert(producer.id(), producer);
     }
     pub fn get_producer(&self, id: ProducerId) -> Option<Producer> {
         let state = self.shared.state.lock().unwrap();
         state.producers.get(&id).cloned()
     }
-    pub fn remove_producer(&self, producer: &Producer) {
+    /// Remove a producer from this session. The producer is closed once the
+    /// returned reference is dropped, so it MUST NOT be dropped while holding state.
+    pub fn remove_producer(&self, id: ProducerId) -> Option<Producer> {
         let mut state = self.shared.state.lock().unwrap();
-        let _ = state.producers.remove(&producer.id()).unwrap();
+        state.resource_transports.remove(&Resource::Producer(id));
+        state.negotiation_reports.remove(&id);
+        state.producers.remove(&id)
     }
     pub fn get_producers(&self) -> Vec<Producer> {
         let state = self.shared.state.lock().unwrap();
@@ -521,14 +1328,39 @@ impl Session {
     }
 
     pub fn add_data_producer(&self, data_producer: DataProducer) {
+        // prune data producer from session once it is closed for any reason. A close
+        // started by the session is announced by the close mutation, while a close
+        // caused by the transport or router closing is announced here
+        data_producer
+            .on_close({
+                let session = self.downgrade();
+                let data_producer_id = data_producer.id();
+                Box::new(move || {
+                    if let Some(session) = session.upgrade() {
+                        if let Some(data_producer) = session.remove_data_producer(data_producer_id)
+                        {
+                            drop(data_producer);
+                            session
+                                .get_room()
+                                .announce_data_producer_closed(data_producer_id);
+                        }
+                    }
+                })
+            })
+            .detach();
         let mut state = self.shared.state.lock().unwrap();
         state
             .data_producers
             .insert(data_producer.id(), data_producer);
     }
-    pub fn remove_data_producer(&self, data_producer: &DataProducer) {
+    /// Remove a data producer from this session. The data producer is closed once the
+    /// returned reference is dropped, so it MUST NOT be dropped while holding state.
+    pub fn remove_data_producer(&self, id: DataProducerId) -> Option<DataProducer> {
         let mut state = self.shared.state.lock().unwrap();
-        let _ = state.data_producers.remove(&data_producer.id()).unwrap();
+        state
+            .resource_transports
+            .remove(&Resource::DataProducer(id));
+        state.data_producers.remove(&id)
     }
     pub fn get_data_producers(&self) -> Vec<DataProducer> {
         let state = self.shared.state.lock().unwrap();
@@ -540,11 +1372,32 @@ impl Session {
     }
 
     pub fn add_data_consumer(&self, data_consumer: DataConsumer) {
+        // prune data consumer from session once it is closed for any reason
+        data_consumer
+            .on_close({
+                let session = self.downgrade();
+                let data_consumer_id = data_consumer.id();
+                Box::new(move || {
+                    if let Some(session) = session.upgrade() {
+                        drop(session.remove_data_consumer(data_consumer_id));
+                    }
+                })
+            })
+            .detach();
         let mut state = self.shared.state.lock().unwrap();
         state
             .data_consumers
             .insert(data_consumer.id(), data_consumer);
     }
+    /// Remove a data consumer from this session. The data consumer is closed once the
+    /// returned reference is dropped, so it MUST NOT be dropped while holding state.
+    pub fn remove_data_consumer(&self, id: DataConsumerId) -> Option<DataConsumer> {
+        let mut state = self.shared.state.lock().unwrap();
+        state
+            .resource_transports
+            .remove(&Resource::DataConsumer(id));
+        state.data_consumers.remove(&id)
+    }
     pub fn get_data_consumers(&self) -> Vec<DataConsumer> {
         let state = self.shared.state.lock().unwrap();
         state
@@ -603,10 +1456,181 @@ impl Session {
         self.channel_stream().filter_map(|x| async move {
             match x {
                 Message::ResourceClosed(resource) => Some(resource),
+                _ => None,
+            }
+        })
+    }
+
+    /// Warn that this session reaches its maximum duration after the given time, and
+    /// will then be torn down.
+    pub fn warn_expiring(&self, remaining: Duration) {
+        log::debug!("session {} expires in {:?}", self.id(), remaining);
+        let _ = self
+            .shared
+            .channel_tx
+            .send(Message::ExpiringSoon(remaining));
+    }
+
+    /// Get a stream of warnings that this session reaches its maximum duration, with
+    /// the time remaining until then.
+    pub fn expiry_warnings(&self) -> impl Stream<Item = Duration> {
+        self.channel_stream().filter_map(|x| async move {
+            match x {
+                Message::ExpiringSoon(remaining) => Some(remaining),
+                _ => None,
+            }
+        })
+    }
+
+    /// Get a stream of changes to the current layers of simulcast/SVC consumers.
+    pub fn consumer_layers_changes(
+        &self,
+    ) -> impl Stream<Item = (ConsumerId, Option<ConsumerLayers>)> {
+        self.channel_stream().filter_map(|x| async move {
+            match x {
+                Message::LayersChanged(consumer_id, layers) => Some((consumer_id, layers)),
+                _ => None,
             }
         })
     }
 
+    /// Get a stream of the score of a producer of this session, starting with the
+    /// current score and ending when the producer closes. Each score is from 0 (bad)
+    /// to 10 (perfect), per received RTP stream (e.g. simulcast layer).
+    pub fn producer_scores(
+        &self,
+        producer_id: ProducerId,
+    ) -> Result<impl Stream<Item = Vec<ProducerScore>>> {
+        let producer = self
+            .get_producer(producer_id)
+            .ok_or_else(|| anyhow!("producer does not exist"))?;
+        // subscribe before reading the current score, so no changes are missed
+        let changes = self.channel_stream();
+        let (closed_tx, closed) = oneshot::channel::<()>();
+        producer
+            .on_close(move || {
+                let _ = closed_tx.send(());
+            })
+            .detach();
+        let score = producer.score();
+        Ok(stream::once(future::ready(score))
+            .chain(changes.filter_map(move |x| async move {
+                match x {
+                    Message::ProducerScore(id, scores) if id == producer_id => Some(scores),
+                    _ => None,
+                }
+            }))
+            .take_until(closed))
+    }
+
+    /// Get a stream of trace events of the given kinds of a producer of this session,
+    /// ending when the producer closes, e.g. to debug frozen video. Trace events are
+    /// enabled while any stream of the producer is alive.
+    pub async fn producer_traces(
+        &self,
+        producer_id: ProducerId,
+        kinds: Vec<TraceKind>,
+    ) -> Result<impl Stream<Item = ProducerTraceEventData>> {
+        let producer = self
+            .get_producer(producer_id)
+            .ok_or_else(|| anyhow!("producer does not exist"))?;
+        // subscribe before enabling trace events, so no events are missed
+        let traces = self.channel_stream();
+        let (closed_tx, closed) = oneshot::channel::<()>();
+        producer
+            .on_close(move || {
+                let _ = closed_tx.send(());
+            })
+            .detach();
+
+        {
+            let mut state = self.shared.state.lock().unwrap();
+            let subscriptions = state.trace_subscriptions.entry(producer_id).or_default();
+            for kind in &kinds {
+                *subscriptions.entry(*kind).or_default() += 1;
+            }
+        }
+        // unsubscribes once the stream is dropped, including on failure
+        let subscription = TraceSubscription {
+            session: self.downgrade(),
+            producer_id,
+            kinds: kinds.clone(),
+        };
+        self.update_producer_traces(producer_id).await?;
+
+        Ok(traces
+            .filter_map(move |x| {
+                let _ = &subscription;
+                future::ready(match x {
+                    Message::ProducerTrace(id, data)
+                        if id == producer_id
+                            && TraceKind::of(&data).map_or(false, |kind| kinds.contains(&kind)) =>
+                    {
+                        Some(data)
+                    }
+                    _ => None,
+                })
+            })
+            .take_until(closed))
+    }
+    /// Enable the trace events of a producer which are subscribed to.
+    async fn update_producer_traces(&self, producer_id: ProducerId) -> Result<()> {
+        let producer = match self.get_producer(producer_id) {
+            Some(producer) => producer,
+            None => {
+                let mut state = self.shared.state.lock().unwrap();
+                state.trace_subscriptions.remove(&producer_id);
+                return Ok(());
+            }
+        };
+        let mut event_types = {
+            let state = self.shared.state.lock().unwrap();
+            state
+                .trace_subscriptions
+                .get(&producer_id)
+                .into_iter()
+                .flatten()
+                .filter(|(_, count)| **count > 0)
+                .map(|(kind, _)| kind.event_type())
+                .collect::<Vec<_>>()
+        };
+        // the latency probe relies on rtp trace events
+        if self.get_room().latency_probe().is_some() {
+            event_types.push(ProducerTraceEventType::Rtp);
+        }
+        producer.enable_trace_event(event_types).await?;
+        Ok(())
+    }
+
+    /// Get a stream of the score of a consumer of this session, starting with the
+    /// current score and ending when the consumer closes. The score of the consumed
+    /// producer is included, to tell upstream from downstream link quality apart.
+    pub fn consumer_scores(
+        &self,
+        consumer_id: ConsumerId,
+    ) -> Result<impl Stream<Item = ConsumerScore>> {
+        let consumer = self
+            .get_consumer(consumer_id)
+            .ok_or_else(|| anyhow!("consumer does not exist"))?;
+        // subscribe before reading the current score, so no changes are missed
+        let changes = self.channel_stream();
+        let (closed_tx, closed) = oneshot::channel::<()>();
+        consumer
+            .on_close(move || {
+                let _ = closed_tx.send(());
+            })
+            .detach();
+        let score = consumer.score();
+        Ok(stream::once(future::ready(score))
+            .chain(changes.filter_map(move |x| async move {
+                match x {
+                    Message::ConsumerScore(id, score) if id == consumer_id => Some(score),
+                    _ => None,
+                }
+            }))
+            .take_until(closed))
+    }
+
     fn channel_stream(&self) -> impl Stream<Item = Message> {
         BroadcastStream::new(self.shared.channel_tx.subscribe())
             .take_while(|x| future::ready(x.is_ok()))
@@ -619,24 +1643,197 @@ impl WeakSession {
         Some(Session { shared })
     }
 }
+/// Request stats of resources concurrently, leaving out resources whose requests
+/// fail or time out (e.g. as the resource closed).
+async fn request_stats<T, K, V, F, Fut>(resources: Vec<T>, request: F) -> HashMap<K, V>
+where
+    K: Eq + Hash,
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = Option<(K, V)>>,
+{
+    stream::iter(resources)
+        .map(|resource| tokio::time::timeout(STATS_TIMEOUT, request(resource)))
+        .buffer_unordered(STATS_CONCURRENCY)
+        .filter_map(|stat| future::ready(stat.ok().flatten()))
+        .collect()
+        .await
+}
+
+/// Periodically close transports of a session which have had no producers/consumers and
+/// no traffic for the given timeout (e.g. abandoned negotiation attempts), freeing their
+/// ports and worker memory, until the session is dropped.
+fn spawn_idle_transport_reaper(session: WeakSession, timeout: Duration) {
+    tokio::spawn(async move {
+        let period = (timeout / 4).clamp(MIN_IDLE_CHECK_PERIOD, MAX_IDLE_CHECK_PERIOD);
+        let mut interval = tokio::time::interval(period);
+        // last seen traffic of each transport, and when it was last active
+        let mut activity: HashMap<TransportId, (u64, Instant)> = HashMap::new();
+        loop {
+            interval.tick().await;
+            let session = match session.upgrade() {
+                Some(session) => session,
+                None => break,
+            };
+            let traffic = session.get_transport_traffic().await;
+            let now = Instant::now();
+            activity.retain(|id, _| traffic.contains_key(id));
+            let mut idle = Vec::new();
+            for (id, bytes) in traffic {
+                let (last_bytes, last_active) = activity.entry(id).or_insert((bytes, now));
+                if *last_bytes != bytes || session.transport_in_use(id) {
+                    *last_bytes = bytes;
+                    *last_active = now;
+                } else if now.duration_since(*last_active) >= timeout {
+                    idle.push(id);
+                }
+            }
+            for id in idle {
+                activity.remove(&id);
+                if session.close_transport(id).is_ok() {
+                    log::debug!(
+                        "-transport {} (session {}) [idle for {:?}]",
+                        id,
+                        session.id(),
+                        timeout
+                    );
+                }
+            }
+        }
+    });
+}
+
+/// Reject producer RTP parameters with invalid (e.g. simulcast) encodings.
+fn check_encodings(kind: MediaKind, rtp_parameters: &RtpParameters) -> Result<()> {
+    let errors = negotiation::encoding_errors(kind, rtp_parameters);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("invalid encodings: {}", errors.join("; ")))
+    }
+}
+/// Annotate a failed producer creation with the codecs rejected by the router.
+fn negotiation_error(err: anyhow::Error, report: &NegotiationReport) -> anyhow::Error {
+    let rejections = report.codec_rejections();
+    if rejections.is_empty() {
+        err
+    } else {
+        anyhow!("{} (rejected codecs: {})", err, rejections.join("; "))
+    }
+}
+
 impl Drop for Shared {
     fn drop(&mut self) {
         log::trace!("-session {}", self.id);
+        Tracked::Session.dropped();
+        // producers of the session close with it, e.g. when a Vulcast disconnects
+        let state = self.state.get_mut().unwrap();
+        for producer_id in state.producers.keys() {
+            self.room.announce_producer_closed(*producer_id);
+        }
+        for data_producer_id in state.data_producers.keys() {
+            self.room.announce_data_producer_closed(*data_producer_id);
+        }
         self.room.remove_session(self.id);
     }
 }
 
+/// Application data declared by the client when producing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProducerAppData {
+    /// Producers in the same sync group are played back in sync, e.g. audio and
+    /// video captured by separate pipelines on the Vulcast.
+    pub sync_group: Option<String>,
+    /// Human-readable label announced along with the producer, e.g. `game` or `mic`.
+    pub label: Option<String>,
+    /// Purpose of the stream, which the stream policy of the room is enforced on.
+    pub role: Option<StreamRole>,
+}
+impl ProducerAppData {
+    /// Get the app data a producer was created with, if created by a session.
+    pub fn of(producer: &Producer) -> Option<&ProducerAppData> {
+        producer.app_data().downcast_ref::<ProducerAppData>()
+    }
+}
+
+/// How the MID of a consumer is assigned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MidStrategy {
+    /// Sequential MIDs assigned by the transport.
+    Sequential,
+    /// The ID of the consumed producer, which is stable across renegotiation.
+    ProducerId,
+}
+
+/// Configuration of a consumer, to fit heterogeneous client implementations.
+#[derive(Debug, Clone, Copy)]
+pub struct ConsumerConfig {
+    pub mid_strategy: MidStrategy,
+    /// Retransmit lost video packets over RTX, if the client supports it.
+    pub enable_rtx: bool,
+    /// Forward all simulcast/SVC layers rather than selecting one, e.g. for clients
+    /// which select layers themselves.
+    pub pipe: bool,
+    /// Drop DTX packets of audio, e.g. for clients which play back DTX as glitches.
+    pub ignore_dtx: bool,
+}
+impl Default for ConsumerConfig {
+    fn default() -> Self {
+        Self {
+            mid_strategy: MidStrategy::Sequential,
+            enable_rtx: true,
+            pipe: false,
+            ignore_dtx: false,
+        }
+    }
+}
+
+/// Configuration of a plain transport.
+#[derive(Debug, Clone, Copy)]
+pub struct PlainTransportConfig {
+    /// Learn the remote tuple from the first packet received, rather than requiring
+    /// an explicit connect.
+    pub comedia: bool,
+    /// Use the same port for RTP and RTCP.
+    pub rtcp_mux: bool,
+    /// Encrypt RTP with SRTP, for RTP traversing untrusted networks.
+    pub enable_srtp: bool,
+    /// Crypto suite used when SRTP is enabled.
+    pub srtp_crypto_suite: SrtpCryptoSuite,
+}
+impl Default for PlainTransportConfig {
+    fn default() -> Self {
+        Self {
+            comedia: true,
+            rtcp_mux: true,
+            enable_srtp: false,
+            srtp_crypto_suite: SrtpCryptoSuite::AesCm128HmacSha1_80,
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub struct Stats {
-    consumer_stats: HashMap<ConsumerId, ConsumerStat>,
-    producer_stats: HashMap<ProducerId, Vec<ProducerStat>>,
-    data_consumer_stats: HashMap<DataConsumerId, Vec<DataConsumerStat>>,
-    data_producer_stats: HashMap<DataProducerId, Vec<DataProducerStat>>,
-    webrtc_transport_stats: HashMap<TransportId, Vec<WebRtcTransportStat>>,
-    plain_transport_stats: HashMap<TransportId, Vec<PlainTransportStat>>,
+    pub consumer_stats: HashMap<ConsumerId, ConsumerStat>,
+    pub producer_stats: HashMap<ProducerId, Vec<ProducerStat>>,
+    pub data_consumer_stats: HashMap<DataConsumerId, Vec<DataConsumerStat>>,
+    pub data_producer_stats: HashMap<DataProducerId, Vec<DataProducerStat>>,
+    pub webrtc_transport_stats: HashMap<TransportId, Vec<WebRtcTransportStat>>,
+    pub plain_transport_stats: HashMap<TransportId, Vec<PlainTransportStat>>,
+    /// counts of messages relayed from data producers, if data is relayed
+    pub data_relay_stats: HashMap<DataProducerId, DataRelayStat>,
 }
 
-#[derive(Debug, Clone, Display)]
+/// Crypto parameters of a transport. WebRTC transports are secured with DTLS-SRTP,
+/// while plain transports may use SRTP with keys exchanged out of band.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransportCrypto {
+    pub transport_id: TransportId,
+    pub dtls_state: Option<DtlsState>,
+    pub dtls_parameters: Option<DtlsParameters>,
+    pub srtp_crypto_suite: Option<SrtpCryptoSuite>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display)]
 pub enum ResourceType {
     Consumer,
     Producer,
@@ -646,7 +1843,73 @@ pub enum ResourceType {
     PlainTransport,
 }
 
-#[derive(Debug, Clone, Display)]
+/// Kind of producer trace event which may be subscribed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TraceKind {
+    KeyFrame,
+    Fir,
+    Pli,
+    Nack,
+}
+
+impl TraceKind {
+    fn of(data: &ProducerTraceEventData) -> Option<Self> {
+        match data {
+            ProducerTraceEventData::KeyFrame { .. } => Some(TraceKind::KeyFrame),
+            ProducerTraceEventData::Fir { .. } => Some(TraceKind::Fir),
+            ProducerTraceEventData::Pli { .. } => Some(TraceKind::Pli),
+            ProducerTraceEventData::Nack { .. } => Some(TraceKind::Nack),
+            _ => None,
+        }
+    }
+    fn event_type(self) -> ProducerTraceEventType {
+        match self {
+            TraceKind::KeyFrame => ProducerTraceEventType::KeyFrame,
+            TraceKind::Fir => ProducerTraceEventType::Fir,
+            TraceKind::Pli => ProducerTraceEventType::Pli,
+            TraceKind::Nack => ProducerTraceEventType::Nack,
+        }
+    }
+}
+
+/// Subscription to trace events of a producer, released when dropped.
+#[derive(Debug)]
+struct TraceSubscription {
+    session: WeakSession,
+    producer_id: ProducerId,
+    kinds: Vec<TraceKind>,
+}
+
+impl Drop for TraceSubscription {
+    fn drop(&mut self) {
+        let session = match self.session.upgrade() {
+            Some(session) => session,
+            None => return,
+        };
+        {
+            let mut state = session.shared.state.lock().unwrap();
+            if let Some(subscriptions) = state.trace_subscriptions.get_mut(&self.producer_id) {
+                for kind in &self.kinds {
+                    if let Some(count) = subscriptions.get_mut(kind) {
+                        *count = count.saturating_sub(1);
+                    }
+                }
+            }
+        }
+        let producer_id = self.producer_id;
+        tokio::spawn(async move {
+            if let Err(err) = session.update_producer_traces(producer_id).await {
+                log::error!(
+                    "failed to update traces of producer {}: {}",
+                    producer_id,
+                    err
+                );
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Display)]
 pub enum Resource {
     Consumer(ConsumerId),
     Producer(ProducerId),
@@ -1,11 +1,32 @@
-use futures::{Stream, StreamExt};
+use futures::{future, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
 
 use anyhow::anyhow;
-use async_graphql::{scalar, Context, Guard, Object, Result, Schema, Subscription};
+use async_graphql::{
+    scalar, Context, Enum, ErrorExtensions, Guard, GuardExt, Json, Object, Result, Schema,
+    SimpleObject, Subscription, ID,
+};
+use mediasoup::plain_transport::PlainTransportRemoteParameters;
 use mediasoup::transport::Transport;
 
-use crate::session::{Resource, ResourceType, Session, WeakSession};
+use crate::chaos::{InjectedFailure, ResolverDelay};
+use crate::crypto_policy::CryptoPolicy;
+use crate::event_log::ResumeError;
+use crate::events::Envelope;
+use crate::permissions::Permission;
+use crate::relay_server::{ForeignSessionId, RelayServer, Role, SessionOptions};
+use crate::resource_policy::ResourcePolicy;
+use crate::room::{self, MessageId};
+use crate::schema_compat::DeprecationPolicy;
+use crate::session::{
+    self, ConsumerConfig, PlainTransportConfig, ProducerAppData, Resource, ResourceType, Session,
+    WeakSession,
+};
+use crate::srt_ingest::SrtIngest;
+use crate::stream_policy;
+use crate::subsystems::Subsystem;
 
 fn session_from_ctx(ctx: &Context<'_>) -> Result<Session, anyhow::Error> {
     ctx.data_opt::<WeakSession>()
@@ -13,16 +34,356 @@ fn session_from_ctx(ctx: &Context<'_>) -> Result<Session, anyhow::Error> {
         .ok_or_else(|| anyhow!("session is invalid or dropped"))
 }
 
+/// Get the current time in milliseconds since the Unix epoch.
+fn unix_time_ms() -> f64 {
+    unix_ms(SystemTime::now())
+}
+
+/// Get a time in milliseconds since the Unix epoch.
+fn unix_ms(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+        * 1000.0
+}
+
+fn crypto_policy_from_ctx(ctx: &Context<'_>) -> CryptoPolicy {
+    ctx.data_opt::<CryptoPolicy>().copied().unwrap_or_default()
+}
+
+/// Fail a transport creation if failures are injected for chaos testing.
+fn check_transport_creation(ctx: &Context<'_>) -> Result<(), InjectedFailure> {
+    match ctx.data_opt::<RelayServer>() {
+        Some(relay_server) => relay_server.chaos().check_transport_creation(),
+        None => Ok(()),
+    }
+}
+
+impl ErrorExtensions for ResumeError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, extensions| {
+            extensions.set("code", "RESUME_UNAVAILABLE");
+            extensions.set("lastSequence", self.last_sequence);
+        })
+    }
+}
+
+fn consumer_config_from_ctx(ctx: &Context<'_>) -> ConsumerConfig {
+    ctx.data_opt::<ConsumerConfig>()
+        .copied()
+        .unwrap_or_default()
+}
+
+fn parse_message_id(id: &ID) -> Result<MessageId, anyhow::Error> {
+    Ok(MessageId(Uuid::parse_str(id).map_err(|_| {
+        anyhow!("{} is not a valid message id", id.0)
+    })?))
+}
+
 #[derive(Default)]
 pub struct QueryRoot;
 #[Object]
 impl QueryRoot {
+    /// Capabilities and policies of the relay, e.g. the minimum client version of each
+    /// role admitted on connect.
+    async fn server_capabilities(&self, ctx: &Context<'_>) -> Result<ServerCapabilities> {
+        let relay_server = ctx
+            .data_opt::<RelayServer>()
+            .ok_or_else(|| anyhow!("relay server is unknown"))?;
+        Ok(ServerCapabilities {
+            schema_version: SCHEMA_VERSION,
+            min_client_versions: relay_server
+                .version_gate()
+                .min_versions()
+                .into_iter()
+                .map(|(role, min_version)| MinClientVersion {
+                    role: role.to_string(),
+                    min_version: min_version.to_string(),
+                })
+                .collect(),
+        })
+    }
+
+    /// Identity of this session and the room it is bound to, so clients need not
+    /// thread their context out-of-band.
+    async fn who_am_i(&self, ctx: &Context<'_>) -> Result<WhoAmI> {
+        let session = session_from_ctx(ctx)?;
+        let foreign_session_id = session.get_foreign_session_id();
+        let foreign_room_id = ctx.data_opt::<RelayServer>().and_then(|relay_server| {
+            relay_server
+                .get_foreign_room_id(&foreign_session_id)
+                .map(|frid| frid.0)
+        });
+        Ok(WhoAmI {
+            session_id: session.id().to_string(),
+            role: session.get_session_options().role().into(),
+            foreign_session_id: foreign_session_id.0,
+            room_id: session.get_room().id().to_string(),
+            foreign_room_id,
+        })
+    }
+
+    /// Moderation actions recently taken in the room, oldest first, so that hosts can
+    /// audit them. Requires the `moderate` permission.
+    #[graphql(guard = "RoleGuard::new(Permission::Moderate)")]
+    async fn moderation_log(&self, ctx: &Context<'_>) -> Result<Vec<ModerationAction>> {
+        let session = session_from_ctx(ctx)?;
+        Ok(session
+            .get_room()
+            .moderation_log()
+            .into_iter()
+            .map(ModerationAction::from)
+            .collect())
+    }
+
     /// Server-side WebRTC RTP capabilities for WebRTC negotiation.
     async fn server_rtp_capabilities(&self, ctx: &Context<'_>) -> Result<RtpCapabilitiesFinalized> {
         let session = session_from_ctx(ctx)?;
         let router = session.get_room().get_router().await;
         Ok(RtpCapabilitiesFinalized(router.rtp_capabilities().clone()))
     }
+
+    /// Exchange timestamps with the relay to align the client clock with the relay clock
+    /// (NTP-like), e.g. for latency overlays and input timestamping. Given the client
+    /// transmit time t0, the relay receive time t1, the relay transmit time t2 and the
+    /// client receive time t3, the client clock offset is ((t1 - t0) + (t2 - t3)) / 2.
+    /// All timestamps are in milliseconds since the Unix epoch.
+    async fn time_sync(&self, ctx: &Context<'_>, client_timestamp: f64) -> Result<TimeSync> {
+        let server_receive_timestamp = unix_time_ms();
+        session_from_ctx(ctx)?;
+        Ok(TimeSync {
+            client_timestamp,
+            server_receive_timestamp,
+            server_transmit_timestamp: unix_time_ms(),
+        })
+    }
+
+    /// Time-limited credentials for the TURN server offered to the session, if any,
+    /// to be passed as an ICE server to the client transports.
+    async fn turn_credentials(&self, ctx: &Context<'_>) -> Result<Option<TurnCredentials>> {
+        let session = session_from_ctx(ctx)?;
+        let relay_server = ctx
+            .data_opt::<RelayServer>()
+            .ok_or_else(|| anyhow!("relay server is unknown"))?;
+        Ok(relay_server
+            .turn_credentials(&session.get_foreign_session_id())
+            .map(TurnCredentials))
+    }
+
+    /// Sync metadata of the producers in a sync group, declared with the syncGroup
+    /// argument of produce, for playing back streams captured by separate pipelines
+    /// in sync (e.g. lip sync).
+    async fn sync_group(&self, ctx: &Context<'_>, name: String) -> Result<Vec<SyncInfo>> {
+        let session = session_from_ctx(ctx)?;
+        Ok(session
+            .get_room()
+            .sync_group(&name)
+            .await
+            .into_iter()
+            .map(SyncInfo)
+            .collect())
+    }
+
+    /// Existing transports, producers and consumers of the session, e.g. so that a client
+    /// resuming its session within the reconnect grace period can resynchronize rather
+    /// than recreate them (and exceed its resource limits).
+    async fn my_session(&self, ctx: &Context<'_>) -> Result<MySession> {
+        let session = session_from_ctx(ctx)?;
+        Ok(MySession {
+            webrtc_transports: session
+                .get_webrtc_transports()
+                .iter()
+                .map(WebRtcTransportOptions::from)
+                .collect(),
+            plain_transports: session
+                .get_plain_transports()
+                .iter()
+                .map(PlainTransportOptions::from)
+                .collect(),
+            producers: session
+                .get_producers()
+                .iter()
+                .filter_map(|producer| {
+                    Some(SessionProducer {
+                        transport_id: TransportId(
+                            session.get_resource_transport(&Resource::Producer(producer.id()))?,
+                        ),
+                        producer: producer.into(),
+                    })
+                })
+                .collect(),
+            consumers: session
+                .get_consumers()
+                .iter()
+                .filter_map(|consumer| {
+                    Some(SessionConsumer {
+                        transport_id: TransportId(
+                            session.get_resource_transport(&Resource::Consumer(consumer.id()))?,
+                        ),
+                        consumer: consumer.into(),
+                    })
+                })
+                .collect(),
+        })
+    }
+
+    /// Snapshot of the room at the time of joining, so clients need not race
+    /// subscriptions against queries. Producers created afterwards are still
+    /// announced through the producerAvailable/dataProducerAvailable subscriptions.
+    async fn join_info(&self, ctx: &Context<'_>) -> Result<JoinInfo> {
+        let session = session_from_ctx(ctx)?;
+        let room = session.get_room();
+        let router = room.get_router().await;
+        let room_id = ctx.data_opt::<RelayServer>().and_then(|relay_server| {
+            relay_server
+                .get_foreign_room_id(&session.get_foreign_session_id())
+                .map(|frid| frid.0)
+        });
+        Ok(JoinInfo {
+            room_id,
+            server_rtp_capabilities: router.rtp_capabilities().clone(),
+            producer_ids: room
+                .get_producers()
+                .into_iter()
+                .map(|producer| producer.id())
+                .collect(),
+            data_producer_ids: room
+                .get_data_producers()
+                .into_iter()
+                .map(|data_producer| data_producer.id())
+                .collect(),
+            participants: room
+                .active_sessions()
+                .into_iter()
+                .map(|session| Participant {
+                    session_id: session.get_foreign_session_id().0,
+                    role: session.get_session_options().role().to_string(),
+                    display_name: session.get_display_name(),
+                })
+                .collect(),
+        })
+    }
+
+    /// Delivery status of a recent message (e.g. a vulcast command) sent in the room.
+    async fn message_status(&self, ctx: &Context<'_>, message_id: ID) -> Result<MessageStatus> {
+        let session = session_from_ctx(ctx)?;
+        let status = session
+            .get_room()
+            .message_status(parse_message_id(&message_id)?)
+            .ok_or_else(|| anyhow!("message {} does not exist", message_id.0))?;
+        Ok(MessageStatus(status))
+    }
+
+    /// Clients currently in the room, including this session.
+    async fn roster(&self, ctx: &Context<'_>) -> Result<Vec<Participant>> {
+        let session = session_from_ctx(ctx)?;
+        Ok(session
+            .get_room()
+            .roster()
+            .into_iter()
+            .map(Participant::from)
+            .collect())
+    }
+    /// Clients currently in the room, including this session, along with their
+    /// current producers, so that a joining client can populate its UI at once.
+    async fn room_participants(&self, ctx: &Context<'_>) -> Result<Vec<RoomParticipant>> {
+        let session = session_from_ctx(ctx)?;
+        let room = session.get_room();
+        let sessions = room.active_sessions();
+        Ok(room
+            .roster()
+            .into_iter()
+            .map(|participant| RoomParticipant {
+                session_id: participant.foreign_session_id.0,
+                role: participant.role.into(),
+                display_name: participant.display_name,
+                producer_ids: sessions
+                    .iter()
+                    .find(|session| session.id() == participant.session_id)
+                    .map(|session| {
+                        session
+                            .get_producers()
+                            .into_iter()
+                            .filter(|producer| !producer.closed())
+                            .map(|producer| ProducerId(producer.id()))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// Producers currently available in the room, e.g. for clients which want a
+    /// snapshot rather than the producerAvailable subscription.
+    async fn producers(&self, ctx: &Context<'_>) -> Result<Vec<AvailableProducer>> {
+        let session = session_from_ctx(ctx)?;
+        Ok(session
+            .get_room()
+            .active_sessions()
+            .into_iter()
+            .flat_map(|session| {
+                let session_id = session.get_foreign_session_id().0;
+                session
+                    .get_producers()
+                    .into_iter()
+                    .filter(|producer| !producer.closed())
+                    .map(move |producer| AvailableProducer {
+                        producer_id: producer.id(),
+                        kind: producer.kind(),
+                        session_id: session_id.clone(),
+                    })
+            })
+            .collect())
+    }
+    /// Data producers currently available in the room, e.g. for clients which want a
+    /// snapshot rather than the dataProducerAvailable subscription.
+    async fn data_producers(&self, ctx: &Context<'_>) -> Result<Vec<AvailableDataProducer>> {
+        let session = session_from_ctx(ctx)?;
+        Ok(session
+            .get_room()
+            .get_data_producer_sources()
+            .into_iter()
+            .map(|(data_producer, session_id)| AvailableDataProducer {
+                data_producer_id: data_producer.id(),
+                label: data_producer.label().clone(),
+                session_id: session_id.map(|session_id| session_id.0),
+            })
+            .collect())
+    }
+
+    /// Value of a key of the state shared by the participants of the room, if set.
+    async fn room_state(&self, ctx: &Context<'_>, key: String) -> Result<Option<StateEntry>> {
+        let session = session_from_ctx(ctx)?;
+        Ok(session.get_room().get_state(&key).map(StateEntry::from))
+    }
+
+    /// Check whether the session can consume a producer with its RTP capabilities,
+    /// before creating any transports or consumers.
+    async fn can_consume(
+        &self,
+        ctx: &Context<'_>,
+        producer_id: ProducerId,
+    ) -> Result<ConsumeCheck> {
+        let session = session_from_ctx(ctx)?;
+        Ok(ConsumeCheck(session.can_consume(producer_id.0).await))
+    }
+
+    /// Codecs and header extensions accepted or rejected by the router when a
+    /// producer in the room was created.
+    async fn negotiation_report(
+        &self,
+        ctx: &Context<'_>,
+        producer_id: ProducerId,
+    ) -> Result<NegotiationReport> {
+        let session = session_from_ctx(ctx)?;
+        let report = session
+            .get_room()
+            .active_sessions()
+            .into_iter()
+            .find_map(|session| session.get_negotiation_report(producer_id.0))
+            .ok_or_else(|| anyhow!("producer {} does not exist", producer_id.0))?;
+        Ok(NegotiationReport(report))
+    }
 }
 
 #[derive(Default)]
@@ -41,27 +402,74 @@ impl MutationRoot {
     }
 
     /// WebRTC transport parameters.
-    #[graphql(guard = "ResourceGuard::new(ResourceType::WebrtcTransport, 2, 1)")]
+    #[graphql(guard = "ResourceGuard::new(ResourceType::WebrtcTransport, 1)")]
     async fn create_webrtc_transport(&self, ctx: &Context<'_>) -> Result<WebRtcTransportOptions> {
         let session = session_from_ctx(ctx)?;
+        check_transport_creation(ctx)?;
         let transport = session.create_webrtc_transport().await;
-        Ok(WebRtcTransportOptions {
-            id: transport.id(),
-            dtls_parameters: transport.dtls_parameters(),
-            sctp_parameters: transport.sctp_parameters().unwrap(),
-            ice_candidates: transport.ice_candidates().clone(),
-            ice_parameters: transport.ice_parameters().clone(),
-        })
+        Ok(WebRtcTransportOptions::from(&transport))
     }
-    /// Plain receive transport connection parameters.
-    #[graphql(guard = "ResourceGuard::new(ResourceType::PlainTransport, 2, 1)")]
-    async fn create_plain_transport(&self, ctx: &Context<'_>) -> Result<PlainTransportOptions> {
+    /// Plain receive transport connection parameters. Without comedia, the remote
+    /// tuple must be provided with connectPlainTransport, as must the remote SRTP
+    /// parameters if SRTP is enabled. Unspecified options use the server defaults.
+    #[graphql(guard = "ResourceGuard::new(ResourceType::PlainTransport, 1)")]
+    async fn create_plain_transport(
+        &self,
+        ctx: &Context<'_>,
+        comedia: Option<bool>,
+        rtcp_mux: Option<bool>,
+        enable_srtp: Option<bool>,
+    ) -> Result<PlainTransportOptions> {
         let session = session_from_ctx(ctx)?;
-        let plain_transport = session.create_plain_transport().await;
-        Ok(PlainTransportOptions {
-            id: plain_transport.id(),
-            tuple: plain_transport.tuple(),
-        })
+        let defaults = ctx
+            .data_opt::<PlainTransportConfig>()
+            .copied()
+            .unwrap_or_default();
+        let config = PlainTransportConfig {
+            comedia: comedia.unwrap_or(defaults.comedia),
+            rtcp_mux: rtcp_mux.unwrap_or(defaults.rtcp_mux),
+            enable_srtp: enable_srtp.unwrap_or(defaults.enable_srtp),
+            ..defaults
+        };
+        crypto_policy_from_ctx(ctx).check_plain_transport_config(&config)?;
+        check_transport_creation(ctx)?;
+        let plain_transport = session.create_plain_transport_with_config(config).await;
+        Ok(PlainTransportOptions::from(&plain_transport))
+    }
+
+    /// Provide the remote endpoint of a plain transport.
+    async fn connect_plain_transport(
+        &self,
+        ctx: &Context<'_>,
+        transport_id: TransportId,
+        ip: Option<String>,
+        port: Option<u16>,
+        rtcp_port: Option<u16>,
+        srtp_parameters: Option<SrtpParameters>,
+    ) -> Result<TransportId> {
+        let session = session_from_ctx(ctx)?;
+        if let Some(srtp_parameters) = &srtp_parameters {
+            crypto_policy_from_ctx(ctx).check_srtp_crypto_suite(srtp_parameters.0.crypto_suite)?;
+        }
+        let ip = ip
+            .map(|ip| {
+                ip.parse()
+                    .map_err(|_| anyhow!("{} is not a valid ip address", ip))
+            })
+            .transpose()?;
+        Ok(TransportId(
+            session
+                .connect_plain_transport(
+                    transport_id.0,
+                    PlainTransportRemoteParameters {
+                        ip,
+                        port,
+                        rtcp_port,
+                        srtp_parameters: srtp_parameters.map(|x| x.0),
+                    },
+                )
+                .await?,
+        ))
     }
 
     /// Provide connection parameters for server-side WebRTC transport.
@@ -79,22 +487,113 @@ impl MutationRoot {
         ))
     }
 
-    /// Request consumption of media stream.
-    #[graphql(guard = "ResourceGuard::new(ResourceType::Consumer, 2, 1)")]
+    /// Close an existing transport, along with all producers and consumers created on it.
+    async fn close_transport(&self, ctx: &Context<'_>, transport_id: TransportId) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        session.close_transport(transport_id.0)?;
+        Ok(true)
+    }
+
+    /// Limit the incoming bitrate (bps) of a transport, e.g. to keep a Vulcast from
+    /// saturating the uplink of the relay. 0 removes the limit, unless the relay caps
+    /// the incoming bitrate of transports, in which case the limit may only be lowered.
+    async fn set_max_incoming_bitrate(
+        &self,
+        ctx: &Context<'_>,
+        transport_id: TransportId,
+        bitrate: u32,
+    ) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        session
+            .set_max_incoming_bitrate(transport_id.0, bitrate)
+            .await?;
+        Ok(true)
+    }
+
+    /// Request consumption of media stream. The MID assignment, RTX, piping of all
+    /// simulcast/SVC layers and ignoring of DTX default to the relay configuration
+    /// if unspecified.
+    #[graphql(
+        guard = "RoleGuard::new(Permission::ConsumeMedia).and(ResourceGuard::new(ResourceType::Consumer, 1))"
+    )]
     async fn consume(
         &self,
         ctx: &Context<'_>,
         transport_id: TransportId,
         producer_id: ProducerId,
+        mid_strategy: Option<MidStrategy>,
+        enable_rtx: Option<bool>,
+        pipe: Option<bool>,
+        ignore_dtx: Option<bool>,
     ) -> Result<ConsumerOptions> {
         let session = session_from_ctx(ctx)?;
-        let consumer = session.consume(transport_id.0, producer_id.0).await?;
-        Ok(ConsumerOptions {
-            id: consumer.id(),
-            kind: consumer.kind(),
-            rtp_parameters: consumer.rtp_parameters().clone(),
-            producer_id: producer_id.0,
-        })
+        let defaults = consumer_config_from_ctx(ctx);
+        let config = ConsumerConfig {
+            mid_strategy: mid_strategy.map_or(defaults.mid_strategy, Into::into),
+            enable_rtx: enable_rtx.unwrap_or(defaults.enable_rtx),
+            pipe: pipe.unwrap_or(defaults.pipe),
+            ignore_dtx: ignore_dtx.unwrap_or(defaults.ignore_dtx),
+        };
+        let consumer = session
+            .consume_with_config(transport_id.0, producer_id.0, config)
+            .await?;
+        Ok(ConsumerOptions::from(&consumer))
+    }
+
+    /// Request consumption of many media streams concurrently, reporting the
+    /// outcome of each individually.
+    async fn consume_many(
+        &self,
+        ctx: &Context<'_>,
+        transport_id: TransportId,
+        producer_ids: Vec<ProducerId>,
+    ) -> Result<Vec<ConsumeResult>> {
+        RoleGuard::new(Permission::ConsumeMedia)
+            .and(ResourceGuard::new(
+                ResourceType::Consumer,
+                producer_ids.len(),
+            ))
+            .check(ctx)
+            .await?;
+        let session = session_from_ctx(ctx)?;
+        let config = consumer_config_from_ctx(ctx);
+        let results = future::join_all(producer_ids.into_iter().map(|producer_id| {
+            let session = session.clone();
+            async move {
+                match session
+                    .consume_with_config(transport_id.0, producer_id.0, config)
+                    .await
+                {
+                    Ok(consumer) => ConsumeResult {
+                        producer_id: producer_id.0,
+                        consumer: Some(ConsumerOptions::from(&consumer)),
+                        error: None,
+                    },
+                    Err(err) => ConsumeResult {
+                        producer_id: producer_id.0,
+                        consumer: None,
+                        error: Some(err.to_string()),
+                    },
+                }
+            }
+        }))
+        .await;
+        Ok(results)
+    }
+
+    /// Request consumption of media stream on plain transport.
+    #[graphql(
+        guard = "RoleGuard::new(Permission::ConsumeMedia).and(ResourceGuard::new(ResourceType::Consumer, 1))"
+    )]
+    async fn consume_plain(
+        &self,
+        ctx: &Context<'_>,
+        transport_id: TransportId,
+        producer_id: ProducerId,
+    ) -> Result<ConsumerOptions> {
+        let session = session_from_ctx(ctx)?;
+        let consumer = session.consume_plain(transport_id.0, producer_id.0).await?;
+        Ok(ConsumerOptions::from(&consumer))
     }
 
     /// Resume existing consumer.
@@ -104,44 +603,445 @@ impl MutationRoot {
         Ok(true)
     }
 
+    /// Resume many existing consumers concurrently, reporting the outcome of
+    /// each individually.
+    async fn resume_consumers(
+        &self,
+        ctx: &Context<'_>,
+        consumer_ids: Vec<ConsumerId>,
+    ) -> Result<Vec<ResumeResult>> {
+        let session = session_from_ctx(ctx)?;
+        let results = future::join_all(consumer_ids.into_iter().map(|consumer_id| {
+            let session = session.clone();
+            async move {
+                ResumeResult {
+                    consumer_id: consumer_id.0,
+                    error: session
+                        .consumer_resume(consumer_id.0)
+                        .await
+                        .err()
+                        .map(|err| err.to_string()),
+                }
+            }
+        }))
+        .await;
+        Ok(results)
+    }
+
+    /// Set the priority of a consumer, from 1 (lowest, default) to 255, e.g. to favour
+    /// the game video over thumbnails when bandwidth is constrained.
+    async fn consumer_set_priority(
+        &self,
+        ctx: &Context<'_>,
+        consumer_id: ConsumerId,
+        priority: u8,
+    ) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        session
+            .consumer_set_priority(consumer_id.0, priority)
+            .await?;
+        Ok(true)
+    }
+
+    /// Set the preferred spatial and temporal layers of a simulcast/SVC consumer,
+    /// e.g. to reduce bandwidth on constrained links. Returns the applied layers,
+    /// or null if the consumer is not layered.
+    async fn consumer_set_preferred_layers(
+        &self,
+        ctx: &Context<'_>,
+        consumer_id: ConsumerId,
+        spatial_layer: u8,
+        temporal_layer: Option<u8>,
+    ) -> Result<Option<ConsumerLayers>> {
+        let session = session_from_ctx(ctx)?;
+        Ok(session
+            .consumer_set_preferred_layers(
+                consumer_id.0,
+                mediasoup::consumer::ConsumerLayers {
+                    spatial_layer,
+                    temporal_layer,
+                },
+            )
+            .await?
+            .map(ConsumerLayers))
+    }
+
+    /// Close existing consumer, e.g. to consume a different producer.
+    async fn consumer_close(&self, ctx: &Context<'_>, consumer_id: ConsumerId) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        session.consumer_close(consumer_id.0)?;
+        Ok(true)
+    }
+
+    /// Close existing data producer.
+    async fn data_producer_close(
+        &self,
+        ctx: &Context<'_>,
+        data_producer_id: DataProducerId,
+    ) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        session.data_producer_close(data_producer_id.0)?;
+        Ok(true)
+    }
+
+    /// Close existing data consumer.
+    async fn data_consumer_close(
+        &self,
+        ctx: &Context<'_>,
+        data_consumer_id: DataConsumerId,
+    ) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        session.data_consumer_close(data_consumer_id.0)?;
+        Ok(true)
+    }
+
+    /// Pause existing producer, e.g. to mute it without renegotiating.
+    async fn producer_pause(&self, ctx: &Context<'_>, producer_id: ProducerId) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        session.producer_pause(producer_id.0).await?;
+        Ok(true)
+    }
+
+    /// Resume existing producer.
+    async fn producer_resume(&self, ctx: &Context<'_>, producer_id: ProducerId) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        session.producer_resume(producer_id.0).await?;
+        Ok(true)
+    }
+
+    /// Close existing producer, notifying other sessions in the room.
+    async fn producer_close(&self, ctx: &Context<'_>, producer_id: ProducerId) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        session.producer_close(producer_id.0)?;
+        Ok(true)
+    }
+
     /// Request production of media stream.
-    #[graphql(guard = "ResourceGuard::new(ResourceType::Producer, 2, 1)")]
+    #[graphql(
+        guard = "RoleGuard::new(Permission::ProduceMedia).and(ResourceGuard::new(ResourceType::Producer, 1))"
+    )]
     async fn produce(
         &self,
         ctx: &Context<'_>,
         transport_id: TransportId,
         kind: MediaKind,
         rtp_parameters: RtpParameters,
+        sync_group: Option<String>,
+        label: Option<String>,
+        role: Option<StreamRole>,
     ) -> Result<ProducerId> {
         let session = session_from_ctx(ctx)?;
         Ok(ProducerId(
             session
-                .produce(transport_id.0, kind.0, rtp_parameters.0)
+                .produce_with_app_data(
+                    transport_id.0,
+                    kind.0,
+                    rtp_parameters.0,
+                    ProducerAppData {
+                        sync_group,
+                        label,
+                        role: role.map(Into::into),
+                    },
+                )
                 .await?
                 .id(),
         ))
     }
 
     /// Request production of a media stream on plain transport.
-    #[graphql(guard = "ResourceGuard::new(ResourceType::Producer, 2, 1)")]
+    #[graphql(
+        guard = "RoleGuard::new(Permission::ProduceMedia).and(ResourceGuard::new(ResourceType::Producer, 1))"
+    )]
     async fn produce_plain(
         &self,
         ctx: &Context<'_>,
         transport_id: TransportId,
         kind: MediaKind,
         rtp_parameters: RtpParameters,
+        sync_group: Option<String>,
+        label: Option<String>,
+        role: Option<StreamRole>,
     ) -> Result<ProducerId> {
         let session = session_from_ctx(ctx)?;
         Ok(ProducerId(
             session
-                .produce_plain(transport_id.0, kind.0, rtp_parameters.0)
+                .produce_plain_with_app_data(
+                    transport_id.0,
+                    kind.0,
+                    rtp_parameters.0,
+                    ProducerAppData {
+                        sync_group,
+                        label,
+                        role: role.map(Into::into),
+                    },
+                )
                 .await?
                 .id(),
         ))
     }
 
+    /// Start an SRT listener which publishes an MPEG-TS stream into the room.
+    /// The stream must be encrypted using the returned passphrase.
+    #[graphql(
+        guard = "RoleGuard::new(Permission::ProduceMedia).and(ResourceGuard::new(ResourceType::PlainTransport, 2)).and(ResourceGuard::new(ResourceType::Producer, 2))"
+    )]
+    async fn create_srt_ingest(&self, ctx: &Context<'_>) -> Result<SrtIngestOptions> {
+        let session = session_from_ctx(ctx)?;
+        if session.get_session_options() != SessionOptions::Vulcast {
+            return Err("only vulcasts may ingest srt".into());
+        }
+        if let Some(relay_server) = ctx.data_opt::<RelayServer>() {
+            if !relay_server.subsystems().enabled(Subsystem::SrtIngest) {
+                return Err("srt ingest is disabled".into());
+            }
+        }
+        let srt_ingest = ctx
+            .data_opt::<SrtIngest>()
+            .ok_or_else(|| anyhow!("srt ingest is disabled"))?;
+        let options = srt_ingest.start(&session).await?;
+        Ok(SrtIngestOptions {
+            port: options.port,
+            passphrase: options.passphrase,
+            audio_producer_id: options.audio_producer.id(),
+            video_producer_id: options.video_producer.id(),
+        })
+    }
+
+    /// Send a command to the Vulcast of the room. Requires the `send-commands`
+    /// permission. Returns the ID of the command.
+    #[graphql(guard = "RoleGuard::new(Permission::SendCommands)")]
+    async fn send_vulcast_command(
+        &self,
+        ctx: &Context<'_>,
+        command: Json<serde_json::Value>,
+    ) -> Result<ID> {
+        let session = session_from_ctx(ctx)?;
+        let id = session
+            .get_room()
+            .send_vulcast_command(session.get_foreign_session_id(), command.0);
+        Ok(ID(id.to_string()))
+    }
+
+    /// Lock input from WebClients in the room, e.g. during cutscenes.
+    /// Requires the `moderate` permission.
+    #[graphql(guard = "RoleGuard::new(Permission::Moderate)")]
+    async fn lock_room_input(&self, ctx: &Context<'_>) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        session.get_room().set_input_locked(true);
+        session.get_room().record_moderation(
+            room::ModerationKind::LockInput,
+            Some(session.get_foreign_session_id()),
+            None,
+            None,
+        );
+        Ok(true)
+    }
+
+    /// Unlock input from WebClients in the room. Requires the `moderate` permission.
+    #[graphql(guard = "RoleGuard::new(Permission::Moderate)")]
+    async fn unlock_room_input(&self, ctx: &Context<'_>) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        session.get_room().set_input_locked(false);
+        session.get_room().record_moderation(
+            room::ModerationKind::UnlockInput,
+            Some(session.get_foreign_session_id()),
+            None,
+            None,
+        );
+        Ok(true)
+    }
+
+    /// Lock the room, so that new web clients cannot connect to it, leaving connected
+    /// clients connected. Requires the `moderate` permission.
+    #[graphql(guard = "RoleGuard::new(Permission::Moderate)")]
+    async fn lock_room(&self, ctx: &Context<'_>) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        let relay_server = ctx
+            .data_opt::<RelayServer>()
+            .ok_or_else(|| anyhow!("relay server is unknown"))?;
+        let frid = relay_server
+            .get_foreign_room_id(&session.get_foreign_session_id())
+            .ok_or_else(|| anyhow!("session is not in a registered room"))?;
+        relay_server.set_room_locked(frid, true)?;
+        Ok(true)
+    }
+
+    /// Unlock the room, letting new web clients connect to it again. Requires the
+    /// `moderate` permission.
+    #[graphql(guard = "RoleGuard::new(Permission::Moderate)")]
+    async fn unlock_room(&self, ctx: &Context<'_>) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        let relay_server = ctx
+            .data_opt::<RelayServer>()
+            .ok_or_else(|| anyhow!("relay server is unknown"))?;
+        let frid = relay_server
+            .get_foreign_room_id(&session.get_foreign_session_id())
+            .ok_or_else(|| anyhow!("session is not in a registered room"))?;
+        relay_server.set_room_locked(frid, false)?;
+        Ok(true)
+    }
+
+    /// Request exclusive control of input to the room. Requires the `control`
+    /// permission. Returns whether control was granted, i.e. no other web client holds it.
+    #[graphql(guard = "RoleGuard::new(Permission::Control)")]
+    async fn request_control(&self, ctx: &Context<'_>) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        let room = session.get_room();
+        match room.controller() {
+            Some(controller) if controller != session.id() => Ok(false),
+            _ => {
+                room.set_controller(Some(&session));
+                Ok(true)
+            }
+        }
+    }
+
+    /// Release control of input to the room. Returns whether control was held.
+    async fn release_control(&self, ctx: &Context<'_>) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        let room = session.get_room();
+        if room.controller() == Some(session.id()) {
+            room.set_controller(None);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Give a web client in the room exclusive control of input. Requires the
+    /// `moderate` permission, and the web client the `control` permission.
+    #[graphql(guard = "RoleGuard::new(Permission::Moderate)")]
+    async fn grant_control(&self, ctx: &Context<'_>, session_id: ID) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        let room = session.get_room();
+        let foreign_session_id = ForeignSessionId(session_id.0);
+        let controller = room
+            .active_sessions()
+            .into_iter()
+            .find(|session| {
+                session.get_foreign_session_id() == foreign_session_id
+                    && session.get_session_options().role() == Role::WebClient
+            })
+            .ok_or_else(|| anyhow!("no web client {} in room", foreign_session_id))?;
+        ctx.data_opt::<RelayServer>()
+            .map(RelayServer::permissions)
+            .unwrap_or_default()
+            .check(controller.get_session_options().role(), Permission::Control)
+            .map_err(|err| err.extend())?;
+        room.set_controller(Some(&controller));
+        Ok(true)
+    }
+
+    /// Pause a producer of another session in the room, e.g. to silence the audio of a
+    /// misbehaving participant. Requires the `moderate` permission. The owner may resume
+    /// the producer.
+    #[graphql(guard = "RoleGuard::new(Permission::Moderate)")]
+    async fn mute_producer(&self, ctx: &Context<'_>, producer_id: ProducerId) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        let owner = session
+            .get_room()
+            .active_sessions()
+            .into_iter()
+            .find(|owner| owner.get_producer(producer_id.0).is_some())
+            .ok_or_else(|| anyhow!("no producer {} in room", producer_id.0))?;
+        if owner.id() == session.id() {
+            return Err("cannot mute producers of this session".into());
+        }
+        log::debug!(
+            "host {} muted producer {} of session {}",
+            session.get_foreign_session_id(),
+            producer_id.0,
+            owner.get_foreign_session_id()
+        );
+        owner.producer_pause(producer_id.0).await?;
+        session.get_room().record_moderation(
+            room::ModerationKind::Mute,
+            Some(session.get_foreign_session_id()),
+            Some(owner.get_foreign_session_id()),
+            Some(producer_id.0),
+        );
+        Ok(true)
+    }
+
+    /// Kick a web client from the room, dropping its session so that other participants
+    /// see it leave. The client may connect again with its token unless it is also
+    /// unregistered, invalidating the token. Requires the `moderate` permission.
+    #[graphql(guard = "RoleGuard::new(Permission::Moderate)")]
+    async fn kick_session(
+        &self,
+        ctx: &Context<'_>,
+        session_id: ID,
+        #[graphql(default = false)] unregister: bool,
+    ) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        let foreign_session_id = ForeignSessionId(session_id.0);
+        let in_room = session.get_room().active_sessions().iter().any(|target| {
+            target.get_foreign_session_id() == foreign_session_id
+                && target.get_session_options().role() == Role::WebClient
+        });
+        if !in_room {
+            return Err(format!("no web client {} in room", foreign_session_id).into());
+        }
+        log::debug!(
+            "host {} kicked session {}",
+            session.get_foreign_session_id(),
+            foreign_session_id
+        );
+        ctx.data_opt::<RelayServer>()
+            .ok_or_else(|| anyhow!("relay server is unknown"))?
+            .kick_session(foreign_session_id.clone(), unregister)?;
+        session.get_room().record_moderation(
+            room::ModerationKind::Kick,
+            Some(session.get_foreign_session_id()),
+            Some(foreign_session_id),
+            None,
+        );
+        Ok(true)
+    }
+
+    /// Undo a moderation action taken in the room, e.g. resume a muted producer. Kicks
+    /// cannot be undone. Requires the `moderate` permission.
+    #[graphql(guard = "RoleGuard::new(Permission::Moderate)")]
+    async fn undo_moderation(&self, ctx: &Context<'_>, id: u64) -> Result<ModerationAction> {
+        let session = session_from_ctx(ctx)?;
+        let action = session
+            .get_room()
+            .undo_moderation(id, Some(session.get_foreign_session_id()))
+            .await?;
+        Ok(action.into())
+    }
+
+    /// Write a key of the state shared by the participants of the room (e.g. the
+    /// scoreboard), or remove it if the value is null. The last write wins.
+    async fn set_room_state(
+        &self,
+        ctx: &Context<'_>,
+        key: String,
+        value: Option<Json<serde_json::Value>>,
+    ) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        session.get_room().set_state(
+            session.get_foreign_session_id(),
+            key,
+            value.map(|value| value.0),
+        )?;
+        Ok(true)
+    }
+
+    /// Acknowledge receipt of a command. Requires the `receive-commands` permission.
+    #[graphql(guard = "RoleGuard::new(Permission::ReceiveCommands)")]
+    async fn acknowledge_vulcast_command(&self, ctx: &Context<'_>, id: ID) -> Result<bool> {
+        let session = session_from_ctx(ctx)?;
+        session
+            .get_room()
+            .acknowledge_message(parse_message_id(&id)?)?;
+        Ok(true)
+    }
+
     /// Request consumption of data stream.
-    #[graphql(guard = "ResourceGuard::new(ResourceType::DataConsumer, 128, 1)")]
+    #[graphql(
+        guard = "RoleGuard::new(Permission::ConsumeData).and(ResourceGuard::new(ResourceType::DataConsumer, 1))"
+    )]
     async fn consume_data(
         &self,
         ctx: &Context<'_>,
@@ -160,7 +1060,9 @@ impl MutationRoot {
     }
 
     /// Request production of data stream.
-    #[graphql(guard = "ResourceGuard::new(ResourceType::DataProducer, 2, 1)")]
+    #[graphql(
+        guard = "RoleGuard::new(Permission::ProduceData).and(ResourceGuard::new(ResourceType::DataProducer, 1))"
+    )]
     async fn produce_data(
         &self,
         ctx: &Context<'_>,
@@ -181,23 +1083,199 @@ impl MutationRoot {
 pub struct SubscriptionRoot;
 #[Subscription]
 impl SubscriptionRoot {
-    /// Notify when new producers are available.
+    /// Notify when new producers are available, if the client auto-consumes their
+    /// role of stream under the stream policy of the room.
     async fn producer_available(
         &self,
         ctx: &Context<'_>,
     ) -> Result<impl Stream<Item = ProducerId>> {
         let session = session_from_ctx(ctx)?;
         let room = session.get_room();
-        Ok(room.available_producers().map(ProducerId))
+        Ok(room
+            .auto_consumed_producers(session.get_session_options().role())
+            .map(|announcement| ProducerId(announcement.producer_id)))
+    }
+    /// Notify when new producers are available, along with the client owning each and
+    /// their kind, label and role, so that clients can decide which producers to consume.
+    /// Only producers the client auto-consumes under the stream policy of the room are
+    /// announced.
+    async fn producer_announced(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<impl Stream<Item = ProducerAnnouncement>> {
+        let session = session_from_ctx(ctx)?;
+        let room = session.get_room();
+        Ok(room
+            .auto_consumed_producers(session.get_session_options().role())
+            .map(ProducerAnnouncement::from))
+    }
+    /// Notify when producers are closed, whether by the sessions which own them, by
+    /// their transports closing, or by their sessions disconnecting.
+    async fn producer_unavailable(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<impl Stream<Item = ProducerId>> {
+        let session = session_from_ctx(ctx)?;
+        let room = session.get_room();
+        Ok(room.closed_producers().map(ProducerId))
+    }
+    /// Notify of the session ID of the web client holding control of input, and
+    /// whenever it changes. Yields null when input is unarbitrated.
+    async fn controller(&self, ctx: &Context<'_>) -> Result<impl Stream<Item = Option<ID>>> {
+        let session = session_from_ctx(ctx)?;
+        let room = session.get_room();
+        Ok(room
+            .controllers()
+            .map(|controller| controller.map(|controller| ID(controller.0))))
+    }
+    /// Notify when the dominant audio producer in the room changes, with its
+    /// audio level in dBov. Yields null when the room falls silent.
+    async fn active_speaker(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<impl Stream<Item = Option<ActiveSpeaker>>> {
+        let session = session_from_ctx(ctx)?;
+        let room = session.get_room();
+        Ok(room.active_speaker().map(|active_speaker| {
+            active_speaker.map(|active_speaker| ActiveSpeaker {
+                producer_id: active_speaker.producer_id,
+                volume: active_speaker.volume,
+            })
+        }))
+    }
+    /// Periodically notify of the audio levels (in dBov) of the loudest audio
+    /// producers in the room, for VU meters. The interval is in milliseconds, from the
+    /// interval at which audio levels are observed (500) up to a minute.
+    async fn audio_levels(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 1000)] interval: u64,
+    ) -> Result<impl Stream<Item = Vec<AudioLevel>>> {
+        let session = session_from_ctx(ctx)?;
+        let min_interval = u64::from(room::AUDIO_LEVEL_INTERVAL_MS);
+        if !(min_interval..=room::MAX_AUDIO_LEVELS_INTERVAL_MS).contains(&interval) {
+            return Err(format!(
+                "interval must be between {} and {} ms",
+                min_interval,
+                room::MAX_AUDIO_LEVELS_INTERVAL_MS
+            )
+            .into());
+        }
+        let room = session.get_room();
+        Ok(room
+            .audio_levels(Duration::from_millis(interval))
+            .map(|audio_levels| {
+                audio_levels
+                    .into_iter()
+                    .map(|audio_level| AudioLevel {
+                        producer_id: audio_level.producer_id,
+                        volume: audio_level.volume,
+                    })
+                    .collect()
+            }))
+    }
+    /// Periodically notify of the bitrate of a producer in the room, in bits per
+    /// second. The interval is in milliseconds, from 100 up to a minute. Completes once
+    /// the producer is closed.
+    async fn producer_bitrate(
+        &self,
+        ctx: &Context<'_>,
+        producer_id: ProducerId,
+        #[graphql(default = 1000)] interval_ms: u64,
+    ) -> Result<impl Stream<Item = u32>> {
+        let session = session_from_ctx(ctx)?;
+        if !(room::MIN_BITRATE_INTERVAL_MS..=room::MAX_BITRATE_INTERVAL_MS).contains(&interval_ms) {
+            return Err(format!(
+                "interval must be between {} and {} ms",
+                room::MIN_BITRATE_INTERVAL_MS,
+                room::MAX_BITRATE_INTERVAL_MS
+            )
+            .into());
+        }
+        let room = session.get_room();
+        Ok(room.producer_bitrate(producer_id.0, Duration::from_millis(interval_ms))?)
+    }
+    /// Notify of the current entries of the shared room state, then of each write.
+    /// Removed keys are notified with a null value.
+    async fn room_state(&self, ctx: &Context<'_>) -> Result<impl Stream<Item = StateEntry>> {
+        let session = session_from_ctx(ctx)?;
+        Ok(session.get_room().state_changes().map(StateEntry::from))
+    }
+    /// Notify of each client in the room, then of each client joining or leaving, e.g.
+    /// to render a live roster.
+    async fn client_state_available(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<impl Stream<Item = ClientStateUpdate>> {
+        let session = session_from_ctx(ctx)?;
+        Ok(session
+            .get_room()
+            .client_state_updates()
+            .map(ClientStateUpdate::from))
+    }
+    /// Notify of each event in the room, as a versioned envelope numbered with its
+    /// sequence in the room. A reconnecting client may pass the sequence of the last
+    /// event it received as resumeFrom, to first receive the events it missed rather
+    /// than resyncing. Audio levels are not replayed. Fails with code
+    /// `RESUME_UNAVAILABLE` if the missed events are no longer retained.
+    /// If the relay buffers messages, a client reconnecting without resumeFrom first
+    /// receives the events buffered while it was disconnected.
+    async fn room_events(
+        &self,
+        ctx: &Context<'_>,
+        resume_from: Option<u64>,
+    ) -> Result<impl Stream<Item = Json<Envelope>>> {
+        let session = session_from_ctx(ctx)?;
+        let room = session.get_room();
+        let buffered = ctx.data_opt::<RelayServer>().and_then(|relay_server| {
+            relay_server.take_buffered_events(&session.get_foreign_session_id())
+        });
+        let (buffered, resume_from) = match (resume_from, buffered) {
+            (Some(resume_from), _) => (Vec::new(), resume_from),
+            (None, Some((buffered, last_sequence))) => (buffered, last_sequence),
+            (None, None) => (Vec::new(), room.last_sequence()),
+        };
+        let live = room
+            .resume_events(resume_from)
+            .map_err(|err| err.extend())?;
+        Ok(futures::stream::iter(buffered).chain(live).map(Json))
+    }
+    /// Notify the Vulcast of commands sent by hosts in its room. Requires the
+    /// `receive-commands` permission. Commands may be acknowledged with
+    /// acknowledgeVulcastCommand.
+    #[graphql(guard = "RoleGuard::new(Permission::ReceiveCommands)")]
+    async fn vulcast_commands(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<impl Stream<Item = VulcastCommand>> {
+        let session = session_from_ctx(ctx)?;
+        let room = session.get_room();
+        Ok(room
+            .vulcast_commands()
+            .map(|vulcast_command| VulcastCommand {
+                id: vulcast_command.id.to_string(),
+                sender: vulcast_command.sender.0,
+                command: vulcast_command.command,
+            }))
+    }
+    /// Notify when new data producers are available.
+    async fn data_producer_available(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<impl Stream<Item = DataProducerId>> {
+        let session = session_from_ctx(ctx)?;
+        let room = session.get_room();
+        Ok(room.available_data_producers().map(DataProducerId))
     }
-    /// Notify when new data producers are available.
-    async fn data_producer_available(
+    /// Notify when data producers available in the room are closed, e.g. so the
+    /// Vulcast can drop the input streams of web clients which left.
+    async fn data_producer_unavailable(
         &self,
         ctx: &Context<'_>,
     ) -> Result<impl Stream<Item = DataProducerId>> {
         let session = session_from_ctx(ctx)?;
         let room = session.get_room();
-        Ok(room.available_data_producers().map(DataProducerId))
+        Ok(room.closed_data_producers().map(DataProducerId))
     }
     /// Notify when client-side transport should close.
     async fn transport_closed(&self, ctx: &Context<'_>) -> Result<impl Stream<Item = TransportId>> {
@@ -239,6 +1317,78 @@ impl SubscriptionRoot {
             })
             .map(ConsumerId))
     }
+    /// Notify shortly before the session reaches the maximum duration of its room, after
+    /// which it is unregistered, e.g. so that clients can prompt users to wrap up.
+    async fn session_expiring_soon(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<impl Stream<Item = SessionExpiringSoon>> {
+        let session = session_from_ctx(ctx)?;
+        Ok(session
+            .expiry_warnings()
+            .map(|remaining| SessionExpiringSoon {
+                expires_in_ms: remaining.as_millis() as u64,
+            }))
+    }
+    /// Notify when the layers forwarded to a simulcast/SVC consumer change, e.g. as
+    /// bandwidth estimates vary, so that clients can adapt their UI.
+    async fn consumer_layers_changed(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<impl Stream<Item = ConsumerLayersChange>> {
+        let session = session_from_ctx(ctx)?;
+        Ok(session
+            .consumer_layers_changes()
+            .map(|(consumer_id, layers)| ConsumerLayersChange {
+                consumer_id,
+                layers,
+            }))
+    }
+    /// Notify of the score of a producer of the session, from 0 (bad) to 10 (perfect)
+    /// per received RTP stream, starting with the current score, e.g. so that the
+    /// Vulcast can react to poor upstream quality.
+    async fn producer_score(
+        &self,
+        ctx: &Context<'_>,
+        producer_id: ProducerId,
+    ) -> Result<impl Stream<Item = ProducerScore>> {
+        let session = session_from_ctx(ctx)?;
+        Ok(session
+            .producer_scores(producer_id.0)?
+            .map(move |scores| ProducerScore {
+                producer_id: producer_id.0,
+                scores,
+            }))
+    }
+    /// Notify of trace events of the given kinds of a producer of the session, e.g. to
+    /// debug frozen video. Trace events are enabled only while subscribed.
+    async fn producer_trace(
+        &self,
+        ctx: &Context<'_>,
+        producer_id: ProducerId,
+        kinds: Vec<TraceKind>,
+    ) -> Result<impl Stream<Item = ProducerTrace>> {
+        let session = session_from_ctx(ctx)?;
+        Ok(session
+            .producer_traces(producer_id.0, kinds.into_iter().map(Into::into).collect())
+            .await?
+            .map(ProducerTrace))
+    }
+    /// Notify of the score of a consumer of the session, from 0 (bad) to 10 (perfect),
+    /// starting with the current score, e.g. for link quality indicators.
+    async fn consumer_score(
+        &self,
+        ctx: &Context<'_>,
+        consumer_id: ConsumerId,
+    ) -> Result<impl Stream<Item = ConsumerScore>> {
+        let session = session_from_ctx(ctx)?;
+        Ok(session
+            .consumer_scores(consumer_id.0)?
+            .map(move |score| ConsumerScore {
+                consumer_id: consumer_id.0,
+                score,
+            }))
+    }
     /// Notify when client-side data producer should close.
     async fn data_producer_closed(
         &self,
@@ -278,38 +1428,147 @@ struct ResourceGuard {
     resource: ResourceType,
     /// Expected count of this resource allocated as a result of this operation.
     expected: usize,
-    /// Maximum allowable count of this resource.
-    limit: usize,
 }
 impl ResourceGuard {
-    fn new(resource: ResourceType, limit: usize, expected: usize) -> Self {
-        ResourceGuard {
-            resource,
-            limit,
-            expected,
-        }
+    fn new(resource: ResourceType, expected: usize) -> Self {
+        ResourceGuard { resource, expected }
     }
 }
 #[async_trait::async_trait]
 impl Guard for ResourceGuard {
     async fn check(&self, ctx: &Context<'_>) -> Result<()> {
         let session = session_from_ctx(ctx)?;
-        if session.get_resource_count(&self.resource) + self.expected <= self.limit {
+        // maximum allowable count of this resource is resolved from the policy
+        let resource_policy = ctx
+            .data_opt::<ResourcePolicy>()
+            .cloned()
+            .unwrap_or_default();
+        let foreign_room_id = ctx.data_opt::<RelayServer>().and_then(|relay_server| {
+            relay_server.get_foreign_room_id(&session.get_foreign_session_id())
+        });
+        let limit = resource_policy.limit(
+            session.get_session_options().role(),
+            foreign_room_id.as_ref(),
+            self.resource,
+        );
+        if session.get_resource_count(&self.resource) + self.expected <= limit {
             Ok(())
         } else {
             Err(format!(
                 "resource limit of {} exceeded (max {})",
-                self.resource, self.limit
+                self.resource, limit
             )
             .into())
         }
     }
 }
 
+/// Rejects operations the role of the session is not permitted to perform.
+struct RoleGuard {
+    permission: Permission,
+}
+impl RoleGuard {
+    fn new(permission: Permission) -> Self {
+        RoleGuard { permission }
+    }
+}
+#[async_trait::async_trait]
+impl Guard for RoleGuard {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        let session = session_from_ctx(ctx)?;
+        let permissions = ctx
+            .data_opt::<RelayServer>()
+            .map(RelayServer::permissions)
+            .unwrap_or_default();
+        permissions
+            .check(session.get_session_options().role(), self.permission)
+            .map_err(|err| err.extend())
+    }
+}
+
 pub type SignalSchema = Schema<QueryRoot, MutationRoot, SubscriptionRoot>;
 
-pub fn schema() -> SignalSchema {
-    SignalSchema::build(QueryRoot, MutationRoot, SubscriptionRoot).finish()
+/// Version of the signal schema, incremented on breaking changes.
+/// See [`crate::schema_compat`] for the compatibility rules.
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub fn schema(
+    relay_server: RelayServer,
+    resource_policy: ResourcePolicy,
+    srt_ingest: Option<SrtIngest>,
+    plain_transport_config: PlainTransportConfig,
+    consumer_config: ConsumerConfig,
+    crypto_policy: CryptoPolicy,
+    deprecation_policy: DeprecationPolicy,
+) -> SignalSchema {
+    let mut builder = SignalSchema::build(QueryRoot, MutationRoot, SubscriptionRoot)
+        .extension(ResolverDelay(relay_server.chaos()))
+        .extension(PayloadLimitCheck(relay_server.payload_limit()))
+        .data(relay_server)
+        .data(resource_policy)
+        .data(plain_transport_config)
+        .data(consumer_config)
+        .data(crypto_policy)
+        .data(deprecation_policy);
+    if let Some(srt_ingest) = srt_ingest {
+        builder = builder.data(srt_ingest);
+    }
+    builder.finish()
+}
+
+/// Role of a client in its room.
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(name = "Role")]
+enum ClientRole {
+    Vulcast,
+    WebClient,
+    Host,
+}
+impl From<Role> for ClientRole {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::Vulcast => ClientRole::Vulcast,
+            Role::WebClient => ClientRole::WebClient,
+            Role::Host => ClientRole::Host,
+        }
+    }
+}
+
+/// Purpose of a media stream, by which the stream policy of the room decides who may
+/// produce it and who is announced it for auto-consumption.
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+enum StreamRole {
+    Game,
+    Camera,
+    Voice,
+    Screen,
+}
+impl From<StreamRole> for stream_policy::StreamRole {
+    fn from(stream_role: StreamRole) -> Self {
+        match stream_role {
+            StreamRole::Game => stream_policy::StreamRole::Game,
+            StreamRole::Camera => stream_policy::StreamRole::Camera,
+            StreamRole::Voice => stream_policy::StreamRole::Voice,
+            StreamRole::Screen => stream_policy::StreamRole::Screen,
+        }
+    }
+}
+
+/// How the MID of a consumer is assigned.
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+enum MidStrategy {
+    /// Sequential MIDs assigned by the transport.
+    Sequential,
+    /// The ID of the consumed producer.
+    ProducerId,
+}
+impl From<MidStrategy> for session::MidStrategy {
+    fn from(mid_strategy: MidStrategy) -> Self {
+        match mid_strategy {
+            MidStrategy::Sequential => session::MidStrategy::Sequential,
+            MidStrategy::ProducerId => session::MidStrategy::ProducerId,
+        }
+    }
 }
 
 // TODO all UUID based types need to be migrated to either:
@@ -370,30 +1629,203 @@ scalar!(RtpCapabilitiesFinalized);
 struct SctpStreamParameters(mediasoup::sctp_parameters::SctpStreamParameters);
 scalar!(SctpStreamParameters);
 
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(transparent)]
+struct ConsumeCheck(crate::negotiation::ConsumeCheck);
+scalar!(ConsumeCheck);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(transparent)]
+struct MessageStatus(crate::room::MessageStatus);
+scalar!(MessageStatus);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(transparent)]
+struct NegotiationReport(crate::negotiation::NegotiationReport);
+scalar!(NegotiationReport);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct TimeSync {
+    /// Time at which the client sent the request, echoed back.
+    client_timestamp: f64,
+    /// Time at which the relay received the request.
+    server_receive_timestamp: f64,
+    /// Time at which the relay sent the response.
+    server_transmit_timestamp: f64,
+}
+scalar!(TimeSync);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(transparent)]
+struct TurnCredentials(crate::turn::TurnCredentials);
+scalar!(TurnCredentials);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(transparent)]
+struct SyncInfo(crate::room::SyncInfo);
+scalar!(SyncInfo);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(transparent)]
+struct ConsumerLayers(mediasoup::consumer::ConsumerLayers);
+scalar!(ConsumerLayers);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(transparent)]
+struct SrtpParameters(mediasoup::srtp_parameters::SrtpParameters);
+scalar!(SrtpParameters);
+
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(transparent)]
 struct TransportTuple(mediasoup::data_structures::TransportTuple);
 scalar!(TransportTuple);
 
-/// Initialization parameters for a transport
 #[derive(Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(transparent)]
+struct SctpParameters(mediasoup::sctp_parameters::SctpParameters);
+scalar!(SctpParameters);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(transparent)]
+struct IceParameters(mediasoup::data_structures::IceParameters);
+scalar!(IceParameters);
+
+/// Initialization parameters for a transport. Selecting every field yields the
+/// options expected by mediasoup-client.
+/// Warning that a session reaches its maximum duration.
+#[derive(SimpleObject)]
+struct SessionExpiringSoon {
+    /// Time until the session is unregistered, in milliseconds.
+    expires_in_ms: u64,
+}
+
+#[derive(SimpleObject)]
 struct WebRtcTransportOptions {
-    id: mediasoup::transport::TransportId,
-    dtls_parameters: mediasoup::data_structures::DtlsParameters,
-    sctp_parameters: mediasoup::sctp_parameters::SctpParameters,
-    ice_candidates: Vec<mediasoup::data_structures::IceCandidate>,
-    ice_parameters: mediasoup::data_structures::IceParameters,
+    id: TransportId,
+    dtls_parameters: DtlsParameters,
+    sctp_parameters: SctpParameters,
+    /// ICE candidates of the transport, one per listen IP and protocol.
+    ice_candidates: Vec<IceCandidate>,
+    ice_parameters: IceParameters,
+}
+impl From<&mediasoup::webrtc_transport::WebRtcTransport> for WebRtcTransportOptions {
+    fn from(transport: &mediasoup::webrtc_transport::WebRtcTransport) -> Self {
+        Self {
+            id: TransportId(transport.id()),
+            dtls_parameters: DtlsParameters(transport.dtls_parameters()),
+            sctp_parameters: SctpParameters(transport.sctp_parameters().unwrap()),
+            ice_candidates: transport
+                .ice_candidates()
+                .iter()
+                .map(IceCandidate::from)
+                .collect(),
+            ice_parameters: IceParameters(transport.ice_parameters().clone()),
+        }
+    }
+}
+
+/// ICE candidate of a transport.
+#[derive(SimpleObject)]
+struct IceCandidate {
+    foundation: String,
+    /// Priority of the candidate, higher is preferred (e.g. UDP over TCP).
+    priority: u32,
+    ip: String,
+    protocol: TransportProtocol,
+    port: u16,
+    #[graphql(name = "type")]
+    candidate_type: IceCandidateType,
+    /// Type of TCP candidate, if the protocol is TCP.
+    tcp_type: Option<IceCandidateTcpType>,
+}
+impl From<&mediasoup::data_structures::IceCandidate> for IceCandidate {
+    fn from(candidate: &mediasoup::data_structures::IceCandidate) -> Self {
+        Self {
+            foundation: candidate.foundation.clone(),
+            priority: candidate.priority,
+            ip: candidate.ip.to_string(),
+            protocol: candidate.protocol.into(),
+            port: candidate.port,
+            candidate_type: candidate.r#type.into(),
+            tcp_type: candidate.tcp_type.map(Into::into),
+        }
+    }
+}
+
+// values are lowercase as in mediasoup-client
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(rename_items = "lowercase")]
+enum TransportProtocol {
+    Udp,
+    Tcp,
+}
+impl From<mediasoup::data_structures::TransportProtocol> for TransportProtocol {
+    fn from(protocol: mediasoup::data_structures::TransportProtocol) -> Self {
+        match protocol {
+            mediasoup::data_structures::TransportProtocol::Udp => TransportProtocol::Udp,
+            mediasoup::data_structures::TransportProtocol::Tcp => TransportProtocol::Tcp,
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(rename_items = "lowercase")]
+enum IceCandidateType {
+    Host,
+}
+impl From<mediasoup::data_structures::IceCandidateType> for IceCandidateType {
+    fn from(candidate_type: mediasoup::data_structures::IceCandidateType) -> Self {
+        match candidate_type {
+            mediasoup::data_structures::IceCandidateType::Host => IceCandidateType::Host,
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(rename_items = "lowercase")]
+enum IceCandidateTcpType {
+    Passive,
+}
+impl From<mediasoup::data_structures::IceCandidateTcpType> for IceCandidateTcpType {
+    fn from(tcp_type: mediasoup::data_structures::IceCandidateTcpType) -> Self {
+        match tcp_type {
+            mediasoup::data_structures::IceCandidateTcpType::Passive => {
+                IceCandidateTcpType::Passive
+            }
+        }
+    }
 }
-scalar!(WebRtcTransportOptions);
 
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 struct PlainTransportOptions {
     id: mediasoup::transport::TransportId,
     tuple: mediasoup::data_structures::TransportTuple,
+    rtcp_tuple: Option<mediasoup::data_structures::TransportTuple>,
+    srtp_parameters: Option<mediasoup::srtp_parameters::SrtpParameters>,
 }
 scalar!(PlainTransportOptions);
+impl From<&mediasoup::plain_transport::PlainTransport> for PlainTransportOptions {
+    fn from(plain_transport: &mediasoup::plain_transport::PlainTransport) -> Self {
+        Self {
+            id: plain_transport.id(),
+            tuple: plain_transport.tuple(),
+            rtcp_tuple: plain_transport.rtcp_tuple(),
+            srtp_parameters: plain_transport.srtp_parameters(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SrtIngestOptions {
+    port: u16,
+    passphrase: String,
+    audio_producer_id: mediasoup::producer::ProducerId,
+    video_producer_id: mediasoup::producer::ProducerId,
+}
+scalar!(SrtIngestOptions);
 
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -402,8 +1834,385 @@ struct ConsumerOptions {
     producer_id: mediasoup::producer::ProducerId,
     kind: mediasoup::rtp_parameters::MediaKind,
     rtp_parameters: mediasoup::rtp_parameters::RtpParameters,
+    /// Layers being forwarded, if the consumer is simulcast/SVC and active.
+    current_layers: Option<mediasoup::consumer::ConsumerLayers>,
+    /// Layers forwarded when available, if the consumer is simulcast/SVC.
+    preferred_layers: Option<mediasoup::consumer::ConsumerLayers>,
 }
 scalar!(ConsumerOptions);
+impl From<&mediasoup::consumer::Consumer> for ConsumerOptions {
+    fn from(consumer: &mediasoup::consumer::Consumer) -> Self {
+        Self {
+            id: consumer.id(),
+            kind: consumer.kind(),
+            rtp_parameters: consumer.rtp_parameters().clone(),
+            producer_id: consumer.producer_id(),
+            current_layers: consumer.current_layers(),
+            preferred_layers: consumer.preferred_layers(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ProducerOptions {
+    id: mediasoup::producer::ProducerId,
+    kind: mediasoup::rtp_parameters::MediaKind,
+    rtp_parameters: mediasoup::rtp_parameters::RtpParameters,
+    paused: bool,
+}
+scalar!(ProducerOptions);
+impl From<&mediasoup::producer::Producer> for ProducerOptions {
+    fn from(producer: &mediasoup::producer::Producer) -> Self {
+        Self {
+            id: producer.id(),
+            kind: producer.kind(),
+            rtp_parameters: producer.rtp_parameters().clone(),
+            paused: producer.paused(),
+        }
+    }
+}
+
+/// Capabilities and policies of a relay.
+#[derive(SimpleObject)]
+struct ServerCapabilities {
+    /// Version of the signal schema, incremented on breaking changes.
+    schema_version: u32,
+    /// Minimum client version of each role which has one. Clients declare their version
+    /// in the `clientVersion` connection param, and are rejected with the code
+    /// `UPGRADE_REQUIRED` if it is lower.
+    min_client_versions: Vec<MinClientVersion>,
+}
+
+/// Identity of a session and the room it is bound to.
+#[derive(SimpleObject)]
+struct WhoAmI {
+    /// ID of the session on this relay.
+    session_id: String,
+    role: ClientRole,
+    /// Session ID the client was registered with, by which other participants know it.
+    foreign_session_id: String,
+    /// ID of the room on this relay.
+    room_id: String,
+    /// Room ID the room was registered with, unless it was unregistered meanwhile.
+    foreign_room_id: Option<String>,
+}
+
+/// Minimum client version of a role.
+#[derive(SimpleObject)]
+struct MinClientVersion {
+    /// Role of the client, e.g. `Vulcast`.
+    role: String,
+    min_version: String,
+}
+
+/// Resources of a session, for clients resynchronizing after resuming the session.
+#[derive(SimpleObject)]
+struct MySession {
+    webrtc_transports: Vec<WebRtcTransportOptions>,
+    plain_transports: Vec<PlainTransportOptions>,
+    producers: Vec<SessionProducer>,
+    consumers: Vec<SessionConsumer>,
+}
+
+/// Producer of a session, with the transport it was created on.
+#[derive(SimpleObject)]
+struct SessionProducer {
+    transport_id: TransportId,
+    producer: ProducerOptions,
+}
+
+/// Consumer of a session, with the transport it was created on.
+#[derive(SimpleObject)]
+struct SessionConsumer {
+    transport_id: TransportId,
+    consumer: ConsumerOptions,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ConsumerLayersChange {
+    consumer_id: mediasoup::consumer::ConsumerId,
+    /// Layers now being forwarded, or null if no layer is being forwarded.
+    layers: Option<mediasoup::consumer::ConsumerLayers>,
+}
+scalar!(ConsumerLayersChange);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ProducerScore {
+    producer_id: mediasoup::producer::ProducerId,
+    /// Score of each RTP stream received by the producer.
+    scores: Vec<mediasoup::producer::ProducerScore>,
+}
+scalar!(ProducerScore);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(transparent)]
+struct ProducerTrace(mediasoup::producer::ProducerTraceEventData);
+scalar!(ProducerTrace);
+
+/// Kind of producer trace event.
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+enum TraceKind {
+    KeyFrame,
+    Fir,
+    Pli,
+    Nack,
+}
+impl From<TraceKind> for session::TraceKind {
+    fn from(kind: TraceKind) -> Self {
+        match kind {
+            TraceKind::KeyFrame => session::TraceKind::KeyFrame,
+            TraceKind::Fir => session::TraceKind::Fir,
+            TraceKind::Pli => session::TraceKind::Pli,
+            TraceKind::Nack => session::TraceKind::Nack,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ConsumerScore {
+    consumer_id: mediasoup::consumer::ConsumerId,
+    /// Score of the RTP stream sent by the consumer, and of the consumed producer.
+    score: mediasoup::consumer::ConsumerScore,
+}
+scalar!(ConsumerScore);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct AudioLevel {
+    producer_id: mediasoup::producer::ProducerId,
+    volume: i8,
+}
+scalar!(AudioLevel);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct VulcastCommand {
+    id: String,
+    /// Session ID of the host which sent the command.
+    sender: String,
+    command: serde_json::Value,
+}
+scalar!(VulcastCommand);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct StateEntry {
+    key: String,
+    /// Value of the key, or null if the key was removed.
+    value: Option<serde_json::Value>,
+    /// Session ID of the session which last wrote the key.
+    session_id: String,
+}
+scalar!(StateEntry);
+
+impl From<crate::room::StateEntry> for StateEntry {
+    fn from(entry: crate::room::StateEntry) -> Self {
+        Self {
+            key: entry.key,
+            value: entry.value,
+            session_id: entry.writer.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ClientStateUpdate {
+    /// Whether the client joined or left, `join` or `leave`.
+    state: crate::room::ClientState,
+    /// Session ID of the client.
+    session_id: String,
+    /// Role of the client, e.g. `WebClient`.
+    role: String,
+    /// Human-readable name of the client, if registered with one.
+    display_name: Option<String>,
+}
+scalar!(ClientStateUpdate);
+
+impl From<crate::room::ClientStateUpdate> for ClientStateUpdate {
+    fn from(update: crate::room::ClientStateUpdate) -> Self {
+        Self {
+            state: update.state,
+            session_id: update.foreign_session_id.0,
+            role: update.role.to_string(),
+            display_name: update.display_name,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct Participant {
+    /// Session ID of the client.
+    session_id: String,
+    /// Role of the client, e.g. `WebClient`.
+    role: String,
+    /// Human-readable name of the client, if registered with one.
+    display_name: Option<String>,
+}
+scalar!(Participant);
+
+impl From<crate::room::Participant> for Participant {
+    fn from(participant: crate::room::Participant) -> Self {
+        Self {
+            session_id: participant.foreign_session_id.0,
+            role: participant.role.to_string(),
+            display_name: participant.display_name,
+        }
+    }
+}
+
+/// Client in a room, along with its current producers.
+#[derive(SimpleObject)]
+struct RoomParticipant {
+    /// Session ID of the client.
+    session_id: String,
+    role: ClientRole,
+    /// Human-readable name of the client, if registered with one.
+    display_name: Option<String>,
+    /// Producers currently held by the client.
+    producer_ids: Vec<ProducerId>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ModerationAction {
+    id: u64,
+    /// One of `mute`, `kick`, `lockInput` or `unlockInput`.
+    kind: ModerationKind,
+    /// Session ID of the host which took the action, if not taken by the backend.
+    actor_session_id: Option<String>,
+    /// Session ID of the client the action was taken against, if any.
+    target_session_id: Option<String>,
+    /// Producer paused by the action, if any.
+    producer_id: Option<mediasoup::producer::ProducerId>,
+    /// Time of the action, in milliseconds since the Unix epoch.
+    timestamp: f64,
+    /// Time at which the action was undone, if it was.
+    undone_at: Option<f64>,
+    /// Session ID of the host which undid the action, if not undone by the backend.
+    undone_by: Option<String>,
+}
+scalar!(ModerationAction);
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+enum ModerationKind {
+    Mute,
+    Kick,
+    LockInput,
+    UnlockInput,
+}
+
+impl From<room::ModerationAction> for ModerationAction {
+    fn from(action: room::ModerationAction) -> Self {
+        Self {
+            id: action.id,
+            kind: match action.kind {
+                room::ModerationKind::Mute => ModerationKind::Mute,
+                room::ModerationKind::Kick => ModerationKind::Kick,
+                room::ModerationKind::LockInput => ModerationKind::LockInput,
+                room::ModerationKind::UnlockInput => ModerationKind::UnlockInput,
+            },
+            actor_session_id: action.actor.map(|actor| actor.0),
+            target_session_id: action.target.map(|target| target.0),
+            producer_id: action.producer_id,
+            timestamp: unix_ms(action.time),
+            undone_at: action.undone.as_ref().map(|(time, _)| unix_ms(*time)),
+            undone_by: action
+                .undone
+                .and_then(|(_, actor)| actor)
+                .map(|actor| actor.0),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ProducerAnnouncement {
+    producer_id: mediasoup::producer::ProducerId,
+    /// Session ID of the client owning the producer.
+    session_id: String,
+    kind: mediasoup::rtp_parameters::MediaKind,
+    /// Label the producer was created with, if any.
+    label: Option<String>,
+    /// Role of the stream declared by the producer, if any.
+    role: Option<stream_policy::StreamRole>,
+}
+scalar!(ProducerAnnouncement);
+
+impl From<crate::room::ProducerAnnouncement> for ProducerAnnouncement {
+    fn from(announcement: crate::room::ProducerAnnouncement) -> Self {
+        Self {
+            producer_id: announcement.producer_id,
+            session_id: announcement.foreign_session_id.0,
+            kind: announcement.kind,
+            label: announcement.label,
+            role: announcement.role,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct AvailableProducer {
+    producer_id: mediasoup::producer::ProducerId,
+    kind: mediasoup::rtp_parameters::MediaKind,
+    /// Session ID of the client owning the producer.
+    session_id: String,
+}
+scalar!(AvailableProducer);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct AvailableDataProducer {
+    data_producer_id: mediasoup::data_producer::DataProducerId,
+    label: String,
+    /// Session ID of the client producing the data, or null for data produced by the
+    /// relay itself.
+    session_id: Option<String>,
+}
+scalar!(AvailableDataProducer);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ActiveSpeaker {
+    producer_id: mediasoup::producer::ProducerId,
+    volume: i8,
+}
+scalar!(ActiveSpeaker);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct JoinInfo {
+    room_id: Option<String>,
+    server_rtp_capabilities: mediasoup::rtp_parameters::RtpCapabilitiesFinalized,
+    producer_ids: Vec<mediasoup::producer::ProducerId>,
+    data_producer_ids: Vec<mediasoup::data_producer::DataProducerId>,
+    participants: Vec<Participant>,
+}
+scalar!(JoinInfo);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ConsumeResult {
+    producer_id: mediasoup::producer::ProducerId,
+    consumer: Option<ConsumerOptions>,
+    error: Option<String>,
+}
+scalar!(ConsumeResult);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ResumeResult {
+    consumer_id: mediasoup::consumer::ConsumerId,
+    error: Option<String>,
+}
+scalar!(ResumeResult);
 
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -0,0 +1,393 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use async_graphql::http::{playground_source, GraphQLPlaygroundConfig};
+use async_graphql::{ErrorExtensions, ServerError};
+use async_graphql_warp::{GraphQLResponse, GraphQLWebSocket};
+use thiserror::Error;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+use warp::http::Response;
+use warp::{Filter, Rejection, Reply};
+
+use crate::diagnostics::{self, AsyncPath};
+use crate::join_throttle::JoinThrottledError;
+use crate::payload_limit;
+use crate::relay_server::{RelayServer, SessionToken};
+use crate::session::Session;
+use crate::signal_schema::SignalSchema;
+use crate::version_gate::UpgradeRequiredError;
+use crate::whep::bearer_token;
+
+/// Longest display name accepted in connection params, in characters.
+const MAX_DISPLAY_NAME_LEN: usize = 64;
+/// Longest client version accepted in connection params, in characters.
+const MAX_CLIENT_VERSION_LEN: usize = 64;
+/// Most capabilities accepted in connection params.
+const MAX_CAPABILITIES: usize = 32;
+
+/// Connection params sent by a signal client on connection init, available to
+/// resolvers as context data. Unknown params are ignored, so that newer clients may
+/// connect to older relays.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectParams {
+    /// Session token, unless presented in the `token` cookie.
+    pub token: Option<SessionToken>,
+    /// Name of the participant, shown to other participants.
+    pub display_name: Option<String>,
+    /// Name and version of the client, e.g. `vulcan-web/1.4.0`, for diagnostics.
+    pub client_version: Option<String>,
+    /// Optional features supported by the client.
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    /// W3C trace context of the client, to correlate its traces with the relay.
+    pub traceparent: Option<String>,
+}
+
+impl ConnectParams {
+    /// Parse and validate connection params. Missing (null) params are empty.
+    pub fn parse(value: &serde_json::Value) -> Result<Self, ConnectParamsError> {
+        let params = match value {
+            serde_json::Value::Null => return Ok(Self::default()),
+            serde_json::Value::Object(params) => params,
+            _ => return Err(ConnectParamsError::NotAnObject),
+        };
+        let connect_params = Self {
+            token: param(params, "token")?,
+            display_name: param(params, "displayName")?,
+            client_version: param(params, "clientVersion")?,
+            capabilities: param(params, "capabilities")?.unwrap_or_default(),
+            traceparent: param(params, "traceparent")?,
+        };
+        connect_params.validate()?;
+        Ok(connect_params)
+    }
+
+    fn validate(&self) -> Result<(), ConnectParamsError> {
+        if let Some(display_name) = &self.display_name {
+            if display_name.trim().is_empty() || display_name.chars().any(char::is_control) {
+                return Err(invalid("displayName", "must be printable and not blank"));
+            }
+            if display_name.chars().count() > MAX_DISPLAY_NAME_LEN {
+                return Err(invalid(
+                    "displayName",
+                    format!("must be at most {} characters", MAX_DISPLAY_NAME_LEN),
+                ));
+            }
+        }
+        if let Some(client_version) = &self.client_version {
+            if client_version.chars().count() > MAX_CLIENT_VERSION_LEN {
+                return Err(invalid(
+                    "clientVersion",
+                    format!("must be at most {} characters", MAX_CLIENT_VERSION_LEN),
+                ));
+            }
+        }
+        if self.capabilities.len() > MAX_CAPABILITIES {
+            return Err(invalid(
+                "capabilities",
+                format!("must list at most {} capabilities", MAX_CAPABILITIES),
+            ));
+        }
+        if let Some(traceparent) = &self.traceparent {
+            if !valid_traceparent(traceparent) {
+                return Err(invalid(
+                    "traceparent",
+                    "must be a W3C traceparent, e.g. 00-<trace id>-<parent id>-<flags>",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn param<T: DeserializeOwned>(
+    params: &serde_json::Map<String, serde_json::Value>,
+    field: &'static str,
+) -> Result<Option<T>, ConnectParamsError> {
+    match params.get(field) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(value) => serde_json::from_value(value.clone())
+            .map(Some)
+            .map_err(|err| invalid(field, err.to_string())),
+    }
+}
+
+fn invalid(field: &'static str, reason: impl Into<String>) -> ConnectParamsError {
+    ConnectParamsError::Invalid {
+        field,
+        reason: reason.into(),
+    }
+}
+
+/// Check the format of a W3C traceparent: `version-traceid-parentid-flags` in lowercase
+/// hex, where neither ID is all zeros.
+fn valid_traceparent(traceparent: &str) -> bool {
+    let parts = traceparent.split('-').collect::<Vec<_>>();
+    let hex = |part: &str, len: usize| {
+        part.len() == len
+            && part
+                .chars()
+                .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+    };
+    let nonzero = |part: &str| part.chars().any(|c| c != '0');
+    match parts.as_slice() {
+        [version, trace_id, parent_id, flags] => {
+            hex(version, 2)
+                && *version != "ff"
+                && hex(trace_id, 32)
+                && nonzero(trace_id)
+                && hex(parent_id, 16)
+                && nonzero(parent_id)
+                && hex(flags, 2)
+        }
+        _ => false,
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ConnectParamsError {
+    #[error("connection params must be an object")]
+    NotAnObject,
+    #[error("invalid connection param {field}: {reason}")]
+    Invalid { field: &'static str, reason: String },
+}
+impl ErrorExtensions for ConnectParamsError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, extensions| {
+            extensions.set("code", "INVALID_CONNECT_PARAMS");
+            if let ConnectParamsError::Invalid { field, .. } = self {
+                extensions.set("field", *field);
+            }
+        })
+    }
+}
+
+impl ErrorExtensions for UpgradeRequiredError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, extensions| {
+            extensions.set("code", "UPGRADE_REQUIRED");
+            extensions.set("minVersion", self.min_version.to_string());
+        })
+    }
+}
+
+/// Selects the session token presented by a signal connection.
+pub trait Authenticator: Send + Sync {
+    /// Select the session token from the `token` cookie of the upgrade request, if
+    /// any, and the connection params sent by the client on connection init.
+    fn authenticate(
+        &self,
+        cookie_token: Option<&str>,
+        connection_params: &serde_json::Value,
+    ) -> Option<SessionToken>;
+}
+
+/// Accepts a session token from the connection params, or else from the cookie.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokenAuthenticator;
+impl Authenticator for TokenAuthenticator {
+    fn authenticate(
+        &self,
+        cookie_token: Option<&str>,
+        connection_params: &serde_json::Value,
+    ) -> Option<SessionToken> {
+        let param_token = connection_params.get("token").and_then(|param_token| {
+            serde_json::from_value::<SessionToken>(param_token.to_owned()).ok()
+        });
+        let cookie_token = cookie_token
+            .and_then(|cookie_token| Uuid::parse_str(cookie_token).ok().map(SessionToken));
+        param_token.or(cookie_token)
+    }
+}
+
+/// Signal GraphQL websocket route. Each connection creates the PHY session of
+/// the token it presents, which is dropped once the connection closes, or once the
+/// reconnect grace period of the relay passes unless the client resumes it. Connections
+/// with the token of a connected session are handled by the duplicate connection policy
+/// of the relay, and rejected during connection init if refused. Connections
+/// are admitted to each room no faster than the join throttle of the relay allows,
+/// and rejected with `retryAfterMs` in the error extensions if the queue is too long.
+/// Connections with invalid connection params are rejected with the `code`
+/// `INVALID_CONNECT_PARAMS`, along with the offending `field`, in the error extensions,
+/// and clients below the minimum version of their role with the `code`
+/// `UPGRADE_REQUIRED`, along with the `minVersion`.
+pub fn routes(
+    relay_server: RelayServer,
+    signal_schema: SignalSchema,
+    authenticator: Arc<dyn Authenticator>,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    warp::ws()
+        .and(warp::filters::cookie::optional("token"))
+        .and(async_graphql_warp::graphql_protocol())
+        .map(
+            move |mut ws: warp::ws::Ws, cookie_token: Option<String>, protocol| {
+                if let Some(max_request_bytes) = relay_server.payload_limit().max_request_bytes() {
+                    ws = ws.max_message_size(max_request_bytes);
+                }
+                let reply = ws.on_upgrade(
+                    enclose! { (relay_server, signal_schema, authenticator) move |websocket| async move {
+                        let (tx, rx) = oneshot::channel();
+                        let connection = GraphQLWebSocket::new(websocket, signal_schema, protocol).on_connection_init(
+                            enclose! { (relay_server, authenticator) move |value| async move {
+                                let mut data = async_graphql::Data::default();
+                                let connect_params = ConnectParams::parse(&value).map_err(|err| err.extend())?;
+                                log::debug!(
+                                    "signal connection init (client {:?}, traceparent {:?})",
+                                    connect_params.client_version,
+                                    connect_params.traceparent
+                                );
+                                let client_version = connect_params.client_version.clone();
+                                data.insert(connect_params);
+                                let token = authenticator.authenticate(cookie_token.as_deref(), &value);
+                                if let Some(token) = token {
+                                    let session =
+                                        connect_session(&relay_server, token, client_version.as_deref())
+                                            .await
+                                            .map_err(|err| err.extend())?;
+                                    if let Some(session) = session {
+                                        tx.send((token, session.id())).unwrap();
+                                        data.insert(session.downgrade());
+                                        data.insert(token);
+                                    }
+                                }
+                                Ok(data)
+                            }
+                        }).serve();
+                        diagnostics::instrument(AsyncPath::Signal, connection).await;
+
+                        if let Ok((token, session_id)) = rx.await {
+                            relay_server.release_session(&token, session_id)
+                        }
+                    }},
+                );
+                warp::reply::with_header(
+                    reply,
+                    "Sec-WebSocket-Protocol",
+                    protocol.sec_websocket_protocol(),
+                )
+            },
+        )
+}
+
+/// Reasons a client presenting a known token is refused its session.
+#[derive(Debug, Error)]
+pub(crate) enum ConnectError {
+    #[error(transparent)]
+    UpgradeRequired(#[from] UpgradeRequiredError),
+    #[error(transparent)]
+    JoinThrottled(#[from] JoinThrottledError),
+    #[error("room is locked")]
+    RoomLocked,
+    #[error("session is already connected")]
+    AlreadyConnected,
+}
+impl ErrorExtensions for ConnectError {
+    fn extend(&self) -> async_graphql::Error {
+        match self {
+            ConnectError::UpgradeRequired(err) => err.extend(),
+            ConnectError::JoinThrottled(err) => {
+                let retry_after_ms = err.retry_after.as_millis() as u64;
+                async_graphql::Error::new(self.to_string())
+                    .extend_with(|_, extensions| extensions.set("retryAfterMs", retry_after_ms))
+            }
+            ConnectError::RoomLocked => async_graphql::Error::new(self.to_string())
+                .extend_with(|_, extensions| extensions.set("code", "ROOM_LOCKED")),
+            ConnectError::AlreadyConnected => async_graphql::Error::new(self.to_string()),
+        }
+    }
+}
+
+/// Admit the client presenting a token to its room and connect the session of the
+/// token, as a client connecting to the signal endpoint. Returns no session if the
+/// token is unknown. The session must be released once the client disconnects.
+pub(crate) async fn connect_session(
+    relay_server: &RelayServer,
+    token: SessionToken,
+    client_version: Option<&str>,
+) -> Result<Option<Session>, ConnectError> {
+    // fence off clients older than the minimum of their role
+    if let Some(role) = relay_server.get_role_by_token(&token) {
+        relay_server.version_gate().check(role, client_version)?;
+    }
+    // queue the join behind others to the same room, or
+    // ask the client to retry once the room is less busy
+    relay_server.admit_session(&token).await?;
+    // create session from the selected token
+    if let Some(session) = relay_server.session_from_token(token) {
+        Ok(Some(session))
+    } else if relay_server.is_locked_out(&token) {
+        Err(ConnectError::RoomLocked)
+    } else if relay_server.get_session_by_token(&token).is_some() {
+        // refused by the duplicate connection policy
+        Err(ConnectError::AlreadyConnected)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Development routes serving a GraphQL playground for the signal schema, along with
+/// queries and mutations over HTTP, as the playground only sends subscriptions over
+/// the websocket. HTTP requests act on behalf of the session whose token is presented
+/// as a bearer token. Unless the session is connected over a websocket, it is admitted
+/// as by the signal endpoint (without a client version) for the request, and released
+/// once the request completes.
+pub fn playground_routes(
+    relay_server: RelayServer,
+    signal_schema: SignalSchema,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let playground = warp::path::end().and(warp::get()).map(|| {
+        Response::builder()
+            .header("content-type", "text/html")
+            .body(playground_source(
+                GraphQLPlaygroundConfig::new("/").subscription_endpoint("/"),
+            ))
+    });
+    let graphql_post = payload_limit::limit_request(relay_server.payload_limit())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(async_graphql_warp::graphql(signal_schema))
+        .and_then(
+            move |authorization: Option<String>,
+                  (schema, mut request): (SignalSchema, async_graphql::Request)| {
+                let relay_server = relay_server.clone();
+                async move {
+                    let token = match authorization.as_deref().and_then(bearer_token) {
+                        Some(token) => token,
+                        None => {
+                            return Ok::<_, Infallible>(GraphQLResponse::from(
+                                schema.execute(request).await,
+                            ))
+                        }
+                    };
+                    let (session, release) = match relay_server.get_session_by_token(&token) {
+                        Some(session) => (Some(session), false),
+                        None => match connect_session(&relay_server, token, None).await {
+                            Ok(session) => (session, true),
+                            Err(err) => {
+                                let err = err.extend();
+                                let mut server_error = ServerError::new(err.message, None);
+                                server_error.extensions = err.extensions;
+                                return Ok(GraphQLResponse::from(
+                                    async_graphql::Response::from_errors(vec![server_error]),
+                                ));
+                            }
+                        },
+                    };
+                    let session_id = session.as_ref().map(Session::id);
+                    if let Some(session) = session {
+                        request = request.data(session.downgrade()).data(token);
+                    }
+                    let response = schema.execute(request).await;
+                    // sessions connected for the request leave their rooms with it
+                    if let (true, Some(session_id)) = (release, session_id) {
+                        relay_server.release_session(&token, session_id);
+                    }
+                    Ok(GraphQLResponse::from(response))
+                }
+            },
+        );
+    playground.or(graphql_post)
+}
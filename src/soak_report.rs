@@ -0,0 +1,140 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use crate::leak_detector;
+use crate::relay_server::RelayServer;
+use crate::subsystems::Subsystem;
+
+static LIVE_SESSIONS: AtomicUsize = AtomicUsize::new(0);
+static LIVE_ROOMS: AtomicUsize = AtomicUsize::new(0);
+
+/// Relay object whose live instances are counted.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Tracked {
+    Session,
+    Room,
+}
+
+impl Tracked {
+    fn counter(self) -> &'static AtomicUsize {
+        match self {
+            Tracked::Session => &LIVE_SESSIONS,
+            Tracked::Room => &LIVE_ROOMS,
+        }
+    }
+    pub(crate) fn created(self) {
+        self.counter().fetch_add(1, Ordering::Relaxed);
+    }
+    pub(crate) fn dropped(self) {
+        self.counter().fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Memory accounting of a relay, for verifying that long-running relays do not
+/// leak sessions, rooms or Mediasoup handles. Live counts which keep growing while
+/// the registered counts stay flat indicate leaked references.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SoakReport {
+    /// Sessions alive in this process, whether or not they are reachable.
+    pub live_sessions: usize,
+    /// Rooms alive in this process, whether or not they are reachable.
+    pub live_rooms: usize,
+    pub registered_sessions: usize,
+    pub registered_rooms: usize,
+    /// Sessions reachable from the relay server.
+    pub connected_sessions: usize,
+    /// Rooms reachable from the relay server.
+    pub active_rooms: usize,
+    /// Mediasoup handles held by the sessions and rooms of the relay.
+    pub handles: HandleCounts,
+    /// Resident memory of the relay process in KiB, if known.
+    pub relay_rss_kib: Option<u64>,
+    /// Peak resident memory of the Mediasoup worker in KiB, unless the worker is overloaded.
+    pub worker_max_rss_kib: Option<u64>,
+    /// Discrepancies found by a leak audit, unless the worker is overloaded.
+    pub leak_discrepancies: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandleCounts {
+    pub webrtc_transports: usize,
+    pub plain_transports: usize,
+    pub producers: usize,
+    pub consumers: usize,
+    pub data_producers: usize,
+    pub data_consumers: usize,
+    /// Data producers relayed through the server, and data producers owned by the server.
+    pub server_data_producers: usize,
+}
+
+/// Account for the objects held by the relay. Requests to the worker are skipped
+/// while it is overloaded.
+pub async fn report(relay_server: &RelayServer) -> SoakReport {
+    let mut report = SoakReport {
+        live_sessions: LIVE_SESSIONS.load(Ordering::Relaxed),
+        live_rooms: LIVE_ROOMS.load(Ordering::Relaxed),
+        registered_sessions: relay_server.registered_session_count(),
+        registered_rooms: relay_server.registered_room_count(),
+        relay_rss_kib: relay_rss_kib(),
+        ..SoakReport::default()
+    };
+
+    let rooms = relay_server.get_rooms();
+    report.active_rooms = rooms.len();
+    for room in &rooms {
+        let handles = &mut report.handles;
+        handles.server_data_producers +=
+            room.get_relayed_data().len() + room.get_server_data_producers().len();
+        for session in room.active_sessions() {
+            report.connected_sessions += 1;
+            handles.webrtc_transports += session.get_webrtc_transports().len();
+            handles.plain_transports += session.get_plain_transports().len();
+            handles.producers += session.get_producers().len();
+            handles.consumers += session.get_consumers().len();
+            handles.data_producers += session.get_data_producers().len();
+            handles.data_consumers += session.get_data_consumers().len();
+        }
+    }
+
+    if !relay_server.worker_monitor().overloaded() {
+        match relay_server.worker().get_resource_usage().await {
+            Ok(usage) => report.worker_max_rss_kib = Some(usage.ru_maxrss),
+            Err(err) => log::error!("worker resource usage failed: {}", err),
+        }
+        match leak_detector::audit(relay_server).await {
+            Ok(leak_report) => report.leak_discrepancies = Some(leak_report.discrepancies()),
+            Err(err) => log::error!("leak audit failed: {}", err),
+        }
+    }
+    report
+}
+
+/// Spawn a background task which logs a soak report at a fixed interval.
+pub fn spawn(relay_server: RelayServer, period: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if !relay_server.subsystems().enabled(Subsystem::SoakReport) {
+                continue;
+            }
+            let report = report(&relay_server).await;
+            match serde_json::to_string(&report) {
+                Ok(report) => log::info!("soak report: {}", report),
+                Err(err) => log::error!("soak report failed: {}", err),
+            }
+        }
+    });
+}
+
+/// Read the resident memory of this process from procfs (Linux only).
+fn relay_rss_kib() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|rss| rss.trim().trim_end_matches("kB").trim().parse().ok())
+}
@@ -0,0 +1,273 @@
+use std::net::{IpAddr, Ipv4Addr};
+use std::num::{NonZeroU32, NonZeroU8};
+use std::ops::RangeInclusive;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use mediasoup::{
+    plain_transport::PlainTransport,
+    producer::Producer,
+    rtp_parameters::{
+        MediaKind, MimeTypeAudio, MimeTypeVideo, RtcpFeedback, RtcpParameters, RtpCodecParameters,
+        RtpCodecParametersParameters, RtpEncodingParameters, RtpParameters,
+    },
+    transport::Transport,
+};
+use tokio::io::AsyncWriteExt;
+use tokio::process::{Child, Command};
+use uuid::Uuid;
+
+use crate::session::Session;
+
+const AUDIO_PAYLOAD_TYPE: u8 = 101;
+const AUDIO_SSRC: u32 = 11111111;
+const VIDEO_PAYLOAD_TYPE: u8 = 102;
+const VIDEO_SSRC: u32 = 22222222;
+
+/// Subsystem which accepts MPEG-TS over SRT from capture hardware and injects
+/// the elementary streams into the room of a Vulcast session.
+///
+/// Each ingest is served by an FFmpeg child process which listens for SRT,
+/// demuxes the transport stream and forwards RTP to plain transports owned
+/// by the Vulcast session. The SRT stream is encrypted with a passphrase generated
+/// for each ingest, so only the session which started the ingest can publish. The
+/// passphrase is handed to FFmpeg over stdin, so it never appears on its command line.
+#[derive(Clone)]
+pub struct SrtIngest {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    listen_ip: IpAddr,
+    ffmpeg_path: String,
+    /// ports available for new SRT listeners
+    free_ports: Mutex<Vec<u16>>,
+}
+
+/// Connection parameters for a started SRT ingest.
+#[derive(Debug, Clone)]
+pub struct SrtIngestOptions {
+    pub port: u16,
+    /// passphrase the SRT stream must be encrypted with
+    pub passphrase: String,
+    pub audio_producer: Producer,
+    pub video_producer: Producer,
+}
+
+impl SrtIngest {
+    pub fn new(listen_ip: IpAddr, ports: RangeInclusive<u16>, ffmpeg_path: String) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                listen_ip,
+                ffmpeg_path,
+                free_ports: Mutex::new(ports.rev().collect()),
+            }),
+        }
+    }
+
+    /// Start an SRT listener which publishes into the room of the given session.
+    /// The listener is torn down when the session is dropped or the stream ends.
+    pub async fn start(&self, session: &Session) -> Result<SrtIngestOptions> {
+        let port = self
+            .shared
+            .free_ports
+            .lock()
+            .unwrap()
+            .pop()
+            .ok_or_else(|| anyhow!("no srt ingest ports available"))?;
+        let passphrase = Uuid::new_v4().to_simple().to_string();
+
+        let audio_transport = session.create_plain_transport().await;
+        let video_transport = session.create_plain_transport().await;
+        let transport_ids = [audio_transport.id(), video_transport.id()];
+        let ingest = self
+            .spawn(
+                session,
+                port,
+                &passphrase,
+                &audio_transport,
+                &video_transport,
+            )
+            .await;
+        drop((audio_transport, video_transport));
+        let (child, audio_producer, video_producer) = match ingest {
+            Ok(ingest) => ingest,
+            Err(err) => {
+                // release everything the failed ingest acquired
+                for transport_id in transport_ids {
+                    let _ = session.close_transport(transport_id);
+                }
+                self.release_port(port);
+                return Err(err);
+            }
+        };
+        log::debug!("+srt ingest :{} (session {})", port, session.id());
+
+        tokio::spawn({
+            let srt_ingest = self.clone();
+            let weak_session = session.downgrade();
+            let mut child = child;
+            async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(1));
+                loop {
+                    tokio::select! {
+                        status = child.wait() => {
+                            log::debug!("srt ingest :{} exited with {:?}", port, status);
+                            break;
+                        }
+                        _ = interval.tick() => {
+                            if weak_session.upgrade().is_none() {
+                                break;
+                            }
+                        }
+                    }
+                }
+                // kill ffmpeg if it is still running and release transports
+                drop(child);
+                if let Some(session) = weak_session.upgrade() {
+                    for transport_id in transport_ids {
+                        let _ = session.close_transport(transport_id);
+                    }
+                }
+                srt_ingest.release_port(port);
+                log::debug!("-srt ingest :{}", port);
+            }
+        });
+
+        Ok(SrtIngestOptions {
+            port,
+            passphrase,
+            audio_producer,
+            video_producer,
+        })
+    }
+
+    /// Produce the elementary streams on the given transports, and spawn FFmpeg to
+    /// forward them from the SRT listener.
+    async fn spawn(
+        &self,
+        session: &Session,
+        port: u16,
+        passphrase: &str,
+        audio_transport: &PlainTransport,
+        video_transport: &PlainTransport,
+    ) -> Result<(Child, Producer, Producer)> {
+        let audio_producer = session
+            .produce_plain(
+                audio_transport.id(),
+                MediaKind::Audio,
+                audio_rtp_parameters(),
+            )
+            .await?;
+        let video_producer = session
+            .produce_plain(
+                video_transport.id(),
+                MediaKind::Video,
+                video_rtp_parameters(),
+            )
+            .await?;
+
+        // plain transports listen on the rtc ip, which may be unspecified
+        let rtp_ip = if self.shared.listen_ip.is_unspecified() {
+            IpAddr::V4(Ipv4Addr::LOCALHOST)
+        } else {
+            self.shared.listen_ip
+        };
+        let audio_port = audio_transport.tuple().local_port();
+        let video_port = video_transport.tuple().local_port();
+        // the srt url is read from a concat script on stdin, so that the passphrase
+        // is not visible to other users of the host in the arguments of ffmpeg
+        let mut child = Command::new(&self.shared.ffmpeg_path)
+            .args(&["-nostdin", "-loglevel", "warning"])
+            .args(&["-f", "concat", "-safe", "0"])
+            .args(&["-protocol_whitelist", "pipe,srt"])
+            .args(&["-i", "pipe:0"])
+            .args(&["-map", "0:v:0", "-c:v", "copy", "-f", "rtp"])
+            .args(&["-payload_type", &VIDEO_PAYLOAD_TYPE.to_string()])
+            .args(&["-ssrc", &VIDEO_SSRC.to_string()])
+            .arg(format!(
+                "rtp://{}:{}?rtcpport={}",
+                rtp_ip, video_port, video_port
+            ))
+            .args(&[
+                "-map", "0:a:0", "-c:a", "libopus", "-ac", "2", "-ar", "48000",
+            ])
+            .args(&["-f", "rtp"])
+            .args(&["-payload_type", &AUDIO_PAYLOAD_TYPE.to_string()])
+            .args(&["-ssrc", &AUDIO_SSRC.to_string()])
+            .arg(format!(
+                "rtp://{}:{}?rtcpport={}",
+                rtp_ip, audio_port, audio_port
+            ))
+            .stdin(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let script = format!(
+            "ffconcat version 1.0\nfile 'srt://{}:{}?mode=listener&passphrase={}'\n",
+            self.shared.listen_ip, port, passphrase
+        );
+        // ffmpeg reads the script until stdin is closed
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("stdin of ffmpeg is unavailable"))?;
+        stdin.write_all(script.as_bytes()).await?;
+        drop(stdin);
+
+        Ok((child, audio_producer, video_producer))
+    }
+
+    fn release_port(&self, port: u16) {
+        self.shared.free_ports.lock().unwrap().push(port);
+    }
+}
+
+fn audio_rtp_parameters() -> RtpParameters {
+    RtpParameters {
+        codecs: vec![RtpCodecParameters::Audio {
+            mime_type: MimeTypeAudio::Opus,
+            payload_type: AUDIO_PAYLOAD_TYPE,
+            clock_rate: NonZeroU32::new(48000).unwrap(),
+            channels: NonZeroU8::new(2).unwrap(),
+            parameters: RtpCodecParametersParameters::from([("sprop-stereo", 1u32.into())]),
+            rtcp_feedback: vec![],
+        }],
+        encodings: vec![RtpEncodingParameters {
+            ssrc: Some(AUDIO_SSRC),
+            ..RtpEncodingParameters::default()
+        }],
+        rtcp: RtcpParameters {
+            cname: Some("srt-ingest".to_string()),
+            ..RtcpParameters::default()
+        },
+        ..RtpParameters::default()
+    }
+}
+
+fn video_rtp_parameters() -> RtpParameters {
+    RtpParameters {
+        codecs: vec![RtpCodecParameters::Video {
+            mime_type: MimeTypeVideo::H264,
+            payload_type: VIDEO_PAYLOAD_TYPE,
+            clock_rate: NonZeroU32::new(90000).unwrap(),
+            parameters: RtpCodecParametersParameters::from([
+                ("packetization-mode", 1u32.into()),
+                ("level-asymmetry-allowed", 1u32.into()),
+                ("profile-level-id", "42e01f".into()),
+            ]),
+            rtcp_feedback: vec![RtcpFeedback::Nack, RtcpFeedback::NackPli],
+        }],
+        encodings: vec![RtpEncodingParameters {
+            ssrc: Some(VIDEO_SSRC),
+            ..RtpEncodingParameters::default()
+        }],
+        rtcp: RtcpParameters {
+            cname: Some("srt-ingest".to_string()),
+            ..RtcpParameters::default()
+        },
+        ..RtpParameters::default()
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use mediasoup::rtp_parameters::MediaKind;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::relay_server::Role;
+
+/// Purpose of a media stream, declared by the producing client in the app data of the
+/// producer, so that the relay can enforce which clients produce and auto-consume
+/// which streams rather than leaving it to convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StreamRole {
+    /// Gameplay captured by the Vulcast, audio and video.
+    Game,
+    /// Webcam video of a participant.
+    Camera,
+    /// Microphone audio of a participant.
+    Voice,
+    /// Screen share, audio and video.
+    Screen,
+}
+
+impl StreamRole {
+    pub const ALL: [StreamRole; 4] = [
+        StreamRole::Game,
+        StreamRole::Camera,
+        StreamRole::Voice,
+        StreamRole::Screen,
+    ];
+
+    /// Whether a stream of this role may carry media of the given kind.
+    pub fn allows(self, kind: MediaKind) -> bool {
+        match self {
+            StreamRole::Camera => kind == MediaKind::Video,
+            StreamRole::Voice => kind == MediaKind::Audio,
+            StreamRole::Game | StreamRole::Screen => true,
+        }
+    }
+}
+
+impl FromStr for StreamRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        StreamRole::ALL
+            .iter()
+            .copied()
+            .find(|stream_role| stream_role.to_string() == s)
+            .ok_or_else(|| s.to_owned())
+    }
+}
+
+impl fmt::Display for StreamRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            StreamRole::Game => "game",
+            StreamRole::Camera => "camera",
+            StreamRole::Voice => "voice",
+            StreamRole::Screen => "screen",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Streams a role of session may produce, and is announced for auto-consumption.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamRules {
+    /// Stream roles which may be produced, or any if unset. Producers must declare
+    /// one of the roles if set.
+    pub produce: Option<Vec<StreamRole>>,
+    /// Stream roles announced for auto-consumption, or all producers if unset.
+    /// Producers which declare no role are not announced if set. Other producers may
+    /// still be consumed explicitly.
+    pub auto_consume: Option<Vec<StreamRole>>,
+}
+
+/// Stream rules of each role of session in a room. Roles of session without rules
+/// may produce and auto-consume any stream.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamPolicy {
+    rules: HashMap<Role, StreamRules>,
+}
+
+impl StreamPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the rules of a role of session, replacing any previous rules.
+    pub fn set_rules(&mut self, role: Role, rules: StreamRules) {
+        self.rules.insert(role, rules);
+    }
+
+    /// Get the rules of a role of session.
+    pub fn rules(&self, role: Role) -> StreamRules {
+        self.rules.get(&role).cloned().unwrap_or_default()
+    }
+
+    /// Check that a session of a role may produce media of a kind in a stream of the
+    /// declared role, if any.
+    pub fn check_produce(
+        &self,
+        role: Role,
+        stream_role: Option<StreamRole>,
+        kind: MediaKind,
+    ) -> Result<(), StreamPolicyError> {
+        if let Some(stream_role) = stream_role {
+            if !stream_role.allows(kind) {
+                return Err(StreamPolicyError::KindMismatch { stream_role, kind });
+            }
+        }
+        let allowed = match self
+            .rules
+            .get(&role)
+            .and_then(|rules| rules.produce.as_ref())
+        {
+            Some(allowed) => allowed,
+            None => return Ok(()),
+        };
+        match stream_role {
+            Some(stream_role) if allowed.contains(&stream_role) => Ok(()),
+            Some(stream_role) => Err(StreamPolicyError::NotAllowed { role, stream_role }),
+            None => Err(StreamPolicyError::RoleRequired { role }),
+        }
+    }
+
+    /// Whether producers of a stream role are announced to sessions of a role for
+    /// auto-consumption.
+    pub fn auto_consumes(&self, role: Role, stream_role: Option<StreamRole>) -> bool {
+        match self
+            .rules
+            .get(&role)
+            .and_then(|rules| rules.auto_consume.as_ref())
+        {
+            Some(auto_consume) => {
+                stream_role.map_or(false, |stream_role| auto_consume.contains(&stream_role))
+            }
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum StreamPolicyError {
+    #[error("{stream_role} streams cannot carry {kind:?} media")]
+    KindMismatch {
+        stream_role: StreamRole,
+        kind: MediaKind,
+    },
+    #[error("{role} sessions must declare the role of their streams")]
+    RoleRequired { role: Role },
+    #[error("{role} sessions may not produce {stream_role} streams")]
+    NotAllowed { role: Role, stream_role: StreamRole },
+}
@@ -0,0 +1,64 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+/// Optional subsystem of the relay, which may be disabled at runtime to shed load
+/// during incidents without restarting the relay or dropping media.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Subsystem {
+    /// Periodic sampling of the latency of requests to the Mediasoup worker.
+    WorkerSampling,
+    /// Periodic audits of Mediasoup objects for leaks.
+    LeakAudit,
+    /// Periodic soak reports.
+    SoakReport,
+    /// New SRT ingest listeners. Existing listeners are unaffected.
+    SrtIngest,
+    /// New WHEP sessions. Existing sessions are unaffected.
+    Whep,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 5] = [
+        Subsystem::WorkerSampling,
+        Subsystem::LeakAudit,
+        Subsystem::SoakReport,
+        Subsystem::SrtIngest,
+        Subsystem::Whep,
+    ];
+}
+
+/// Switches of the optional subsystems of a relay, all enabled initially.
+/// Subsystems check their switch before doing work, so disabling a subsystem
+/// takes effect on its next unit of work (e.g. the next tick of a periodic task).
+#[derive(Debug, Clone, Default)]
+pub struct Subsystems {
+    disabled: Arc<Mutex<HashSet<Subsystem>>>,
+}
+
+impl Subsystems {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn enabled(&self, subsystem: Subsystem) -> bool {
+        !self.disabled.lock().unwrap().contains(&subsystem)
+    }
+
+    /// Enable or disable a subsystem. Returns whether the subsystem changed.
+    pub fn set_enabled(&self, subsystem: Subsystem, enabled: bool) -> bool {
+        let mut disabled = self.disabled.lock().unwrap();
+        let changed = if enabled {
+            disabled.remove(&subsystem)
+        } else {
+            disabled.insert(subsystem)
+        };
+        if changed {
+            log::info!(
+                "{:?} {}",
+                subsystem,
+                if enabled { "enabled" } else { "disabled" }
+            );
+        }
+        changed
+    }
+}
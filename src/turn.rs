@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use ring::hmac;
+
+/// TURN server sharing a secret with the relay, e.g. coturn with `use-auth-secret`.
+/// The relay mints time-limited credentials for sessions following the TURN REST API
+/// convention, so clients need no separate credential service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TurnConfig {
+    /// ICE server URLs of the TURN server (e.g. `turn:turn.example.com:3478?transport=udp`).
+    pub urls: Vec<String>,
+    /// Secret shared with the TURN server (`static-auth-secret` in coturn).
+    pub shared_secret: String,
+    /// Lifetime of minted credentials.
+    pub ttl: Duration,
+}
+
+/// Time-limited credentials for a TURN server, in the shape of an RTCIceServer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TurnCredentials {
+    pub urls: Vec<String>,
+    pub username: String,
+    pub credential: String,
+    /// Expiry of the credentials, in seconds since the Unix epoch.
+    pub expires_at: u64,
+}
+
+impl TurnConfig {
+    /// Mint credentials for a user which expire after the configured lifetime.
+    /// The username is `<expiry>:<user>` and the credential is the base64 encoded
+    /// HMAC-SHA1 of the username keyed by the shared secret.
+    pub fn mint(&self, user: &str, now: SystemTime) -> TurnCredentials {
+        let expires_at = (now + self.ttl)
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let username = format!("{}:{}", expires_at, user);
+        let key = hmac::Key::new(
+            hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+            self.shared_secret.as_bytes(),
+        );
+        let credential = base64::encode(hmac::sign(&key, username.as_bytes()).as_ref());
+        TurnCredentials {
+            urls: self.urls.clone(),
+            username,
+            credential,
+            expires_at,
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use thiserror::Error;
+
+use crate::relay_server::Role;
+
+/// Minimum client versions per role, so that clients (e.g. Vulcast firmware) with known
+/// breaking bugs can be fenced off at the relay. Clients declare their version in the
+/// `clientVersion` connection param, and are rejected on connect if it is below the
+/// minimum of their role, or missing while a minimum applies.
+#[derive(Debug, Clone, Default)]
+pub struct VersionGate {
+    shared: Arc<Mutex<BTreeMap<Role, ClientVersion>>>,
+}
+
+impl VersionGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require clients of a role to be at least the given version. Clients of the role
+    /// are admitted regardless of version if unset.
+    pub fn set_min_version(&self, role: Role, min_version: Option<ClientVersion>) {
+        let mut min_versions = self.shared.lock().unwrap();
+        match min_version {
+            Some(min_version) => min_versions.insert(role, min_version),
+            None => min_versions.remove(&role),
+        };
+    }
+
+    /// Get the minimum version of each role which has one.
+    pub fn min_versions(&self) -> Vec<(Role, ClientVersion)> {
+        let min_versions = self.shared.lock().unwrap();
+        min_versions
+            .iter()
+            .map(|(role, min_version)| (*role, min_version.clone()))
+            .collect()
+    }
+
+    /// Check whether a client of a role may connect with the version it declared.
+    pub fn check(
+        &self,
+        role: Role,
+        client_version: Option<&str>,
+    ) -> Result<(), UpgradeRequiredError> {
+        let min_version = match self.shared.lock().unwrap().get(&role) {
+            Some(min_version) => min_version.clone(),
+            None => return Ok(()),
+        };
+        match client_version.and_then(|client_version| client_version.parse().ok()) {
+            Some(client_version) if client_version >= min_version => Ok(()),
+            _ => Err(UpgradeRequiredError {
+                role,
+                min_version,
+                client_version: client_version.map(str::to_owned),
+            }),
+        }
+    }
+}
+
+/// Dotted numeric version of a client, e.g. `1.4.0`. Parsed from the version of a
+/// `name/version` string, ignoring a leading `v` and any pre-release or build suffix.
+/// Missing components are zero, so `1.4` equals `1.4.0`.
+#[derive(Debug, Clone, Eq)]
+pub struct ClientVersion(Vec<u64>);
+
+impl ClientVersion {
+    fn component(&self, i: usize) -> u64 {
+        self.0.get(i).copied().unwrap_or(0)
+    }
+}
+
+impl FromStr for ClientVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let version = s.rsplit('/').next().unwrap_or(s);
+        let version = version.strip_prefix('v').unwrap_or(version);
+        let version = version.split(&['-', '+'][..]).next().unwrap_or(version);
+        version
+            .split('.')
+            .map(|component| component.parse().map_err(|_| s.to_owned()))
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl fmt::Display for ClientVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let components = self.0.iter().map(u64::to_string).collect::<Vec<_>>();
+        write!(f, "{}", components.join("."))
+    }
+}
+
+impl PartialEq for ClientVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl PartialOrd for ClientVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for ClientVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (0..self.0.len().max(other.0.len()))
+            .map(|i| self.component(i).cmp(&other.component(i)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("{role} clients must be at least version {min_version}, upgrade required")]
+pub struct UpgradeRequiredError {
+    pub role: Role,
+    pub min_version: ClientVersion,
+    /// Version declared by the client, if any.
+    pub client_version: Option<String>,
+}
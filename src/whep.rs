@@ -0,0 +1,710 @@
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::fmt::Write;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use mediasoup::{
+    consumer::Consumer,
+    data_structures::{DtlsFingerprint, DtlsParameters, DtlsRole, DtlsState, IceState},
+    producer::Producer,
+    rtp_parameters::{MediaKind, RtpCapabilities, RtpCodecCapability, RtpHeaderExtension},
+    transport::Transport,
+    webrtc_transport::WebRtcTransport,
+};
+use serde_json::{json, Value};
+use thiserror::Error;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+use warp::http::{Response, StatusCode};
+use warp::hyper::body::Bytes;
+use warp::{Filter, Rejection, Reply};
+
+use crate::permissions::{Permission, PermissionDeniedError};
+use crate::relay_server::{RelayServer, Role, SessionToken};
+use crate::resource_policy::ResourcePolicy;
+use crate::session::{ResourceType, Session, SessionId};
+use crate::signal_server::{connect_session, ConnectError};
+use crate::subsystems::Subsystem;
+
+/// Time within which a WHEP player must connect its transport once answered.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// WHEP (WebRTC-HTTP Egress Protocol) routes, which allow simple players to
+/// consume a room with a single HTTP SDP offer/answer exchange.
+/// The player authenticates by presenting the session token of a web client as a
+/// bearer token, and is admitted as a client connecting to the signal endpoint.
+/// Its session lasts as long as its transport, unless torn down earlier, and it may
+/// only tear down the resource of its own session.
+pub fn routes(
+    relay_server: RelayServer,
+    resource_policy: ResourcePolicy,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let offer = warp::path!("whep")
+        .and(warp::post())
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::bytes())
+        .and_then({
+            let relay_server = relay_server.clone();
+            move |authorization: String, body: Bytes| {
+                let relay_server = relay_server.clone();
+                let resource_policy = resource_policy.clone();
+                async move {
+                    Ok::<_, Infallible>(
+                        match handle_offer(relay_server, resource_policy, &authorization, &body)
+                            .await
+                        {
+                            Ok((session, answer)) => Response::builder()
+                                .status(StatusCode::CREATED)
+                                .header("content-type", "application/sdp")
+                                .header("location", format!("/whep/{}", session.id()))
+                                .body(answer),
+                            Err(err) => error_response(err),
+                        },
+                    )
+                }
+            }
+        });
+    let teardown = warp::path!("whep" / String)
+        .and(warp::delete())
+        .and(warp::header::<String>("authorization"))
+        .map(move |resource: String, authorization: String| {
+            match handle_teardown(&relay_server, &resource, &authorization) {
+                Ok(session) => {
+                    log::debug!("whep teardown (session {})", session.id());
+                    drop(session);
+                    Response::builder()
+                        .status(StatusCode::OK)
+                        .body(String::new())
+                }
+                Err(err) => error_response(err),
+            }
+        });
+    offer.or(teardown)
+}
+
+#[derive(Debug, Error)]
+enum WhepError {
+    #[error("missing or invalid session token")]
+    Unauthorized,
+    #[error("only web clients may use whep")]
+    Forbidden,
+    #[error(transparent)]
+    Refused(#[from] ConnectError),
+    #[error(transparent)]
+    PermissionDenied(#[from] PermissionDeniedError),
+    #[error("resource limit of {resource} exceeded (max {limit})")]
+    LimitExceeded {
+        resource: ResourceType,
+        limit: usize,
+    },
+    #[error("unknown whep resource")]
+    NotFound,
+    #[error("malformed sdp offer: {0}")]
+    BadOffer(String),
+    #[error("whep is disabled")]
+    Disabled,
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+fn error_response(err: WhepError) -> Result<Response<String>, warp::http::Error> {
+    let status = match &err {
+        WhepError::Unauthorized => StatusCode::UNAUTHORIZED,
+        WhepError::Forbidden | WhepError::PermissionDenied(_) | WhepError::LimitExceeded { .. } => {
+            StatusCode::FORBIDDEN
+        }
+        WhepError::Refused(ConnectError::JoinThrottled(_)) => StatusCode::SERVICE_UNAVAILABLE,
+        WhepError::Refused(ConnectError::AlreadyConnected) => StatusCode::CONFLICT,
+        WhepError::Refused(_) => StatusCode::FORBIDDEN,
+        WhepError::NotFound => StatusCode::NOT_FOUND,
+        WhepError::BadOffer(_) => StatusCode::BAD_REQUEST,
+        WhepError::Disabled => StatusCode::SERVICE_UNAVAILABLE,
+        WhepError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    log::debug!("whep request failed: {}", err);
+    let mut response = Response::builder().status(status);
+    if let WhepError::Refused(ConnectError::JoinThrottled(err)) = &err {
+        // in whole seconds, rounded up
+        let retry_after = (err.retry_after.as_millis() + 999) / 1000;
+        response = response.header("retry-after", retry_after.to_string());
+    }
+    response.body(err.to_string())
+}
+
+/// Parse a session token presented as a bearer token.
+pub(crate) fn bearer_token(authorization: &str) -> Option<SessionToken> {
+    let token = authorization.strip_prefix("Bearer ")?;
+    Uuid::parse_str(token.trim()).ok().map(SessionToken)
+}
+
+/// Parse the bearer token of a request, which must be the session token of a web client.
+fn web_client_token(
+    relay_server: &RelayServer,
+    authorization: &str,
+) -> Result<SessionToken, WhepError> {
+    let token = bearer_token(authorization).ok_or(WhepError::Unauthorized)?;
+    match relay_server.get_role_by_token(&token) {
+        Some(Role::WebClient) => Ok(token),
+        Some(_) => Err(WhepError::Forbidden),
+        None => Err(WhepError::Unauthorized),
+    }
+}
+
+async fn handle_offer(
+    relay_server: RelayServer,
+    resource_policy: ResourcePolicy,
+    authorization: &str,
+    body: &[u8],
+) -> Result<(Session, String), WhepError> {
+    if !relay_server.subsystems().enabled(Subsystem::Whep) {
+        return Err(WhepError::Disabled);
+    }
+    let offer = std::str::from_utf8(body)
+        .map_err(|err| WhepError::BadOffer(err.to_string()))
+        .and_then(Offer::parse)?;
+    let token = web_client_token(&relay_server, authorization)?;
+    relay_server
+        .permissions()
+        .check(Role::WebClient, Permission::ConsumeMedia)?;
+    // players do not present a client version
+    let session = connect_session(&relay_server, token, None)
+        .await?
+        .ok_or(WhepError::Unauthorized)?;
+
+    match answer_offer(&relay_server, &resource_policy, &session, token, &offer).await {
+        Ok(answer) => Ok((session, answer)),
+        Err(err) => {
+            // leave no half-built session behind
+            disconnect(&relay_server, &token, session.id());
+            Err(err)
+        }
+    }
+}
+
+/// Consume the room on a new transport of the session of a WHEP player, within
+/// the resource limits of the session, and answer the offer of the player.
+async fn answer_offer(
+    relay_server: &RelayServer,
+    resource_policy: &ResourcePolicy,
+    session: &Session,
+    token: SessionToken,
+    offer: &Offer,
+) -> Result<String, WhepError> {
+    let foreign_room_id = relay_server.get_foreign_room_id(&session.get_foreign_session_id());
+    let limit =
+        |resource| resource_policy.limit(Role::WebClient, foreign_room_id.as_ref(), resource);
+    let transport_limit = limit(ResourceType::WebrtcTransport);
+    if session.get_resource_count(&ResourceType::WebrtcTransport) >= transport_limit {
+        return Err(WhepError::LimitExceeded {
+            resource: ResourceType::WebrtcTransport,
+            limit: transport_limit,
+        });
+    }
+    let consumer_limit = limit(ResourceType::Consumer)
+        .saturating_sub(session.get_resource_count(&ResourceType::Consumer));
+
+    let transport = session.create_webrtc_transport().await;
+    watch_transport(relay_server.clone(), token, session.id(), &transport);
+    session
+        .connect_webrtc_transport(
+            transport.id(),
+            DtlsParameters {
+                role: DtlsRole::Client,
+                fingerprints: offer.fingerprints.clone(),
+            },
+        )
+        .await?;
+    session.set_rtp_capabilities(offer.rtp_capabilities());
+
+    // assign each offered media section to an unclaimed producer of the same kind,
+    // up to the consumer limit of the session
+    let router = session.get_room().get_router().await;
+    let mut producers = session
+        .get_room()
+        .get_producers()
+        .into_iter()
+        .filter(|producer| session.get_producer(producer.id()).is_none())
+        .collect::<Vec<Producer>>();
+    let mut consumers = Vec::new();
+    for media in &offer.media {
+        let position = producers.iter().position(|producer| {
+            producer.kind() == media.kind
+                && router.can_consume(&producer.id(), &offer.rtp_capabilities())
+        });
+        let consumer = match position {
+            Some(position) if consumers.iter().flatten().count() < consumer_limit => {
+                let producer = producers.remove(position);
+                let consumer = session
+                    .consume_with_mid(transport.id(), producer.id(), Some(media.mid.clone()))
+                    .await?;
+                session.consumer_resume(consumer.id()).await?;
+                Some(consumer)
+            }
+            _ => None,
+        };
+        consumers.push(consumer);
+    }
+    log::debug!(
+        "whep offer answered with {} consumers (session {})",
+        consumers.iter().flatten().count(),
+        session.id()
+    );
+
+    Ok(build_answer(offer, &transport, &consumers)?)
+}
+
+enum TransportState {
+    Connected,
+    Closed,
+}
+
+/// Disconnect the session of a WHEP player once its transport closes or fails, or
+/// if it does not connect in time, as players may leave without tearing down.
+fn watch_transport(
+    relay_server: RelayServer,
+    token: SessionToken,
+    session_id: SessionId,
+    transport: &WebRtcTransport,
+) {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    transport
+        .on_dtls_state_change({
+            let tx = tx.clone();
+            Box::new(move |dtls_state: DtlsState| {
+                let state = match dtls_state {
+                    DtlsState::Connected => TransportState::Connected,
+                    DtlsState::Failed | DtlsState::Closed => TransportState::Closed,
+                    _ => return,
+                };
+                let _ = tx.send(state);
+            })
+        })
+        .detach();
+    transport
+        .on_ice_state_change({
+            let tx = tx.clone();
+            Box::new(move |ice_state: IceState| {
+                // ICE consent of the player expired
+                if matches!(ice_state, IceState::Disconnected | IceState::Closed) {
+                    let _ = tx.send(TransportState::Closed);
+                }
+            })
+        })
+        .detach();
+    transport
+        .on_close(Box::new(move || {
+            let _ = tx.send(TransportState::Closed);
+        }))
+        .detach();
+
+    tokio::spawn(async move {
+        let timeout = tokio::time::sleep(CONNECT_TIMEOUT);
+        tokio::pin!(timeout);
+        let mut connected = false;
+        loop {
+            tokio::select! {
+                state = rx.recv() => match state {
+                    Some(TransportState::Connected) => connected = true,
+                    Some(TransportState::Closed) => break,
+                    None => return,
+                },
+                _ = &mut timeout, if !connected => break,
+            }
+        }
+        log::debug!("whep transport closed (session {})", session_id);
+        disconnect(&relay_server, &token, session_id);
+    });
+}
+
+/// Disconnect the session of a WHEP player, unless it was already replaced.
+fn disconnect(relay_server: &RelayServer, token: &SessionToken, session_id: SessionId) {
+    match relay_server.get_session_by_token(token) {
+        Some(session) if session.id() == session_id => {
+            drop(relay_server.disconnect_session_by_token(token));
+        }
+        // parallel sessions are dropped once released
+        _ => relay_server.release_session(token, session_id),
+    }
+}
+
+/// Disconnect the session of a WHEP player, which must own the resource it tears down.
+fn handle_teardown(
+    relay_server: &RelayServer,
+    resource: &str,
+    authorization: &str,
+) -> Result<Session, WhepError> {
+    let token = web_client_token(relay_server, authorization)?;
+    // the resource is the session the answer was created for, see the location header
+    match relay_server.get_session_by_token(&token) {
+        Some(session) if session.id().to_string() == resource => relay_server
+            .disconnect_session_by_token(&token)
+            .ok_or(WhepError::NotFound),
+        _ => Err(WhepError::NotFound),
+    }
+}
+
+/// Subset of an SDP offer from a WHEP player required to produce an answer.
+struct Offer {
+    fingerprints: Vec<DtlsFingerprint>,
+    media: Vec<OfferMedia>,
+}
+
+struct OfferMedia {
+    kind: MediaKind,
+    mid: String,
+    /// codecs as JSON in mediasoup's RtpCodecCapability format
+    codecs: Vec<Value>,
+    /// header extensions as JSON in mediasoup's RtpHeaderExtension format
+    header_extensions: Vec<Value>,
+}
+
+impl Offer {
+    fn parse(sdp: &str) -> Result<Self, WhepError> {
+        let mut fingerprints = Vec::new();
+        let mut media: Vec<OfferMedia> = Vec::new();
+        for line in sdp.lines().map(str::trim) {
+            if let Some(m) = line.strip_prefix("m=") {
+                let kind = match m.split(' ').next() {
+                    Some("audio") => MediaKind::Audio,
+                    Some("video") => MediaKind::Video,
+                    _ => return Err(WhepError::BadOffer(format!("unsupported media `{}`", m))),
+                };
+                media.push(OfferMedia {
+                    kind,
+                    mid: media.len().to_string(),
+                    codecs: Vec::new(),
+                    header_extensions: Vec::new(),
+                });
+            } else if let Some(fingerprint) = line.strip_prefix("a=fingerprint:") {
+                let (algorithm, value) = fingerprint
+                    .split_once(' ')
+                    .ok_or_else(|| WhepError::BadOffer(line.to_owned()))?;
+                let fingerprint = serde_json::from_value::<DtlsFingerprint>(json!({
+                    "algorithm": algorithm,
+                    "value": value,
+                }))
+                .map_err(|_| WhepError::BadOffer(line.to_owned()))?;
+                if !fingerprints.contains(&fingerprint) {
+                    fingerprints.push(fingerprint);
+                }
+            } else if let Some(current) = media.last_mut() {
+                current.parse_attribute(line);
+            }
+        }
+        if fingerprints.is_empty() {
+            return Err(WhepError::BadOffer("missing dtls fingerprint".to_owned()));
+        }
+        if media.is_empty() {
+            return Err(WhepError::BadOffer("missing media sections".to_owned()));
+        }
+        Ok(Offer {
+            fingerprints,
+            media,
+        })
+    }
+
+    /// RTP capabilities of the player, ignoring codecs and extensions unknown to mediasoup.
+    fn rtp_capabilities(&self) -> RtpCapabilities {
+        let mut seen_extensions = HashSet::new();
+        RtpCapabilities {
+            codecs: self
+                .media
+                .iter()
+                .flat_map(|media| media.codecs.iter())
+                .filter_map(|codec| {
+                    serde_json::from_value::<RtpCodecCapability>(codec.clone()).ok()
+                })
+                .collect(),
+            header_extensions: self
+                .media
+                .iter()
+                .flat_map(|media| media.header_extensions.iter())
+                .filter(|extension| seen_extensions.insert(extension.to_string()))
+                .filter_map(|extension| {
+                    serde_json::from_value::<RtpHeaderExtension>(extension.clone()).ok()
+                })
+                .collect(),
+        }
+    }
+}
+
+impl OfferMedia {
+    fn kind_str(&self) -> &'static str {
+        match self.kind {
+            MediaKind::Audio => "audio",
+            MediaKind::Video => "video",
+        }
+    }
+
+    fn codec_mut(&mut self, payload_type: &str) -> Option<&mut Value> {
+        let payload_type = payload_type.parse::<u8>().ok()?;
+        self.codecs
+            .iter_mut()
+            .find(|codec| codec["preferredPayloadType"] == json!(payload_type))
+    }
+
+    fn parse_attribute(&mut self, line: &str) {
+        if let Some(mid) = line.strip_prefix("a=mid:") {
+            self.mid = mid.to_owned();
+        } else if let Some(rtpmap) = line.strip_prefix("a=rtpmap:") {
+            // a=rtpmap:<pt> <name>/<clock rate>[/<channels>]
+            let (payload_type, encoding) = match rtpmap.split_once(' ') {
+                Some(x) => x,
+                None => return,
+            };
+            let mut encoding = encoding.split('/');
+            let (payload_type, name, clock_rate) = match (
+                payload_type.parse::<u8>(),
+                encoding.next(),
+                encoding.next().and_then(|x| x.parse::<u32>().ok()),
+            ) {
+                (Ok(payload_type), Some(name), Some(clock_rate)) => {
+                    (payload_type, name, clock_rate)
+                }
+                _ => return,
+            };
+            let mut codec = json!({
+                "kind": self.kind_str(),
+                "mimeType": format!("{}/{}", self.kind_str(), name),
+                "preferredPayloadType": payload_type,
+                "clockRate": clock_rate,
+                "parameters": {},
+                "rtcpFeedback": [],
+            });
+            if self.kind == MediaKind::Audio {
+                codec["channels"] = json!(encoding
+                    .next()
+                    .and_then(|x| x.parse::<u8>().ok())
+                    .unwrap_or(1));
+            }
+            self.codecs.push(codec);
+        } else if let Some(fmtp) = line.strip_prefix("a=fmtp:") {
+            // a=fmtp:<pt> <key>=<value>;...
+            let (payload_type, parameters) = match fmtp.split_once(' ') {
+                Some(x) => x,
+                None => return,
+            };
+            if let Some(codec) = self.codec_mut(payload_type) {
+                for (key, value) in parameters
+                    .split(';')
+                    .filter_map(|parameter| parameter.trim().split_once('='))
+                {
+                    codec["parameters"][key] = match value.parse::<u32>() {
+                        Ok(value) => json!(value),
+                        Err(_) => json!(value),
+                    };
+                }
+            }
+        } else if let Some(rtcp_fb) = line.strip_prefix("a=rtcp-fb:") {
+            // a=rtcp-fb:<pt> <type>[ <parameter>]
+            let mut rtcp_fb = rtcp_fb.split(' ');
+            let (payload_type, r#type) = match (rtcp_fb.next(), rtcp_fb.next()) {
+                (Some(payload_type), Some(r#type)) => (payload_type, r#type),
+                _ => return,
+            };
+            let parameter = rtcp_fb.next().unwrap_or_default().to_owned();
+            if let Some(codec) = self.codec_mut(payload_type) {
+                codec["rtcpFeedback"]
+                    .as_array_mut()
+                    .unwrap()
+                    .push(json!({ "type": r#type, "parameter": parameter }));
+            }
+        } else if let Some(extmap) = line.strip_prefix("a=extmap:") {
+            // a=extmap:<id>[/<direction>] <uri>
+            let mut extmap = extmap.split(' ');
+            let (id, uri) = match (
+                extmap
+                    .next()
+                    .and_then(|id| id.split('/').next())
+                    .and_then(|id| id.parse::<u16>().ok()),
+                extmap.next(),
+            ) {
+                (Some(id), Some(uri)) => (id, uri),
+                _ => return,
+            };
+            self.header_extensions.push(json!({
+                "kind": self.kind_str(),
+                "uri": uri,
+                "preferredId": id,
+                "preferredEncrypt": false,
+                "direction": "sendrecv",
+            }));
+        }
+    }
+}
+
+/// Build an SDP answer with one section per offered media section. Sections
+/// which could not be assigned a consumer are rejected.
+fn build_answer(
+    offer: &Offer,
+    transport: &WebRtcTransport,
+    consumers: &[Option<Consumer>],
+) -> anyhow::Result<String> {
+    let ice_parameters = transport.ice_parameters();
+    let ice_candidates = serde_json::to_value(transport.ice_candidates())?;
+    let fingerprints = serde_json::to_value(&transport.dtls_parameters().fingerprints)?;
+
+    let mut sdp = String::new();
+    writeln!(sdp, "v=0")?;
+    writeln!(sdp, "o=- {} 2 IN IP4 127.0.0.1", transport.id())?;
+    writeln!(sdp, "s=-")?;
+    writeln!(sdp, "t=0 0")?;
+    writeln!(sdp, "a=ice-lite")?;
+    let bundle = offer
+        .media
+        .iter()
+        .zip(consumers)
+        .filter(|(_, consumer)| consumer.is_some())
+        .map(|(media, _)| media.mid.as_str())
+        .collect::<Vec<&str>>();
+    if !bundle.is_empty() {
+        writeln!(sdp, "a=group:BUNDLE {}", bundle.join(" "))?;
+    }
+    writeln!(sdp, "a=msid-semantic: WMS *")?;
+
+    for (media, consumer) in offer.media.iter().zip(consumers) {
+        let consumer = match consumer {
+            Some(consumer) => consumer,
+            None => {
+                writeln!(sdp, "m={} 0 UDP/TLS/RTP/SAVPF 0", media.kind_str())?;
+                writeln!(sdp, "c=IN IP4 0.0.0.0")?;
+                writeln!(sdp, "a=mid:{}", media.mid)?;
+                writeln!(sdp, "a=inactive")?;
+                continue;
+            }
+        };
+        let rtp_parameters = serde_json::to_value(consumer.rtp_parameters())?;
+        let codecs = rtp_parameters["codecs"]
+            .as_array()
+            .ok_or_else(|| anyhow!("consumer has no codecs"))?;
+        let payload_types = codecs
+            .iter()
+            .map(|codec| codec["payloadType"].to_string())
+            .collect::<Vec<String>>();
+
+        writeln!(
+            sdp,
+            "m={} 9 UDP/TLS/RTP/SAVPF {}",
+            media.kind_str(),
+            payload_types.join(" ")
+        )?;
+        writeln!(sdp, "c=IN IP4 0.0.0.0")?;
+        writeln!(sdp, "a=mid:{}", media.mid)?;
+        writeln!(sdp, "a=sendonly")?;
+        writeln!(sdp, "a=rtcp-mux")?;
+        writeln!(sdp, "a=rtcp-rsize")?;
+        writeln!(sdp, "a=ice-ufrag:{}", ice_parameters.username_fragment)?;
+        writeln!(sdp, "a=ice-pwd:{}", ice_parameters.password)?;
+        for fingerprint in fingerprints.as_array().into_iter().flatten() {
+            writeln!(
+                sdp,
+                "a=fingerprint:{} {}",
+                as_str(&fingerprint["algorithm"]),
+                as_str(&fingerprint["value"])
+            )?;
+        }
+        writeln!(sdp, "a=setup:passive")?;
+
+        for codec in codecs {
+            let payload_type = &codec["payloadType"];
+            let name = as_str(&codec["mimeType"])
+                .split('/')
+                .nth(1)
+                .unwrap_or_default();
+            match codec.get("channels") {
+                Some(channels) if channels.is_u64() => writeln!(
+                    sdp,
+                    "a=rtpmap:{} {}/{}/{}",
+                    payload_type, name, codec["clockRate"], channels
+                )?,
+                _ => writeln!(
+                    sdp,
+                    "a=rtpmap:{} {}/{}",
+                    payload_type, name, codec["clockRate"]
+                )?,
+            }
+            if let Some(parameters) = codec["parameters"].as_object() {
+                if !parameters.is_empty() {
+                    let parameters = parameters
+                        .iter()
+                        .map(|(key, value)| format!("{}={}", key, as_str(value)))
+                        .collect::<Vec<String>>();
+                    writeln!(sdp, "a=fmtp:{} {}", payload_type, parameters.join(";"))?;
+                }
+            }
+            for feedback in codec["rtcpFeedback"].as_array().into_iter().flatten() {
+                match as_str(&feedback["parameter"]).as_str() {
+                    "" => writeln!(
+                        sdp,
+                        "a=rtcp-fb:{} {}",
+                        payload_type,
+                        as_str(&feedback["type"])
+                    )?,
+                    parameter => writeln!(
+                        sdp,
+                        "a=rtcp-fb:{} {} {}",
+                        payload_type,
+                        as_str(&feedback["type"]),
+                        parameter
+                    )?,
+                }
+            }
+        }
+        for extension in rtp_parameters["headerExtensions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+        {
+            writeln!(
+                sdp,
+                "a=extmap:{} {}",
+                extension["id"],
+                as_str(&extension["uri"])
+            )?;
+        }
+
+        let cname = as_str(&rtp_parameters["rtcp"]["cname"]);
+        let msid = format!("{} {}", consumer.producer_id(), consumer.id());
+        for encoding in rtp_parameters["encodings"].as_array().into_iter().flatten() {
+            let ssrc = &encoding["ssrc"];
+            let rtx_ssrc = &encoding["rtx"]["ssrc"];
+            if rtx_ssrc.is_u64() {
+                writeln!(sdp, "a=ssrc-group:FID {} {}", ssrc, rtx_ssrc)?;
+            }
+            for ssrc in [ssrc, rtx_ssrc].into_iter().filter(|ssrc| ssrc.is_u64()) {
+                writeln!(sdp, "a=ssrc:{} cname:{}", ssrc, cname)?;
+                writeln!(sdp, "a=ssrc:{} msid:{}", ssrc, msid)?;
+            }
+        }
+        writeln!(sdp, "a=msid:{}", msid)?;
+
+        for candidate in ice_candidates.as_array().into_iter().flatten() {
+            write!(
+                sdp,
+                "a=candidate:{} 1 {} {} {} {} typ {}",
+                as_str(&candidate["foundation"]),
+                as_str(&candidate["protocol"]),
+                candidate["priority"],
+                as_str(&candidate["ip"]),
+                candidate["port"],
+                as_str(&candidate["type"])
+            )?;
+            if let Some(tcp_type) = candidate["tcpType"].as_str() {
+                write!(sdp, " tcptype {}", tcp_type)?;
+            }
+            writeln!(sdp)?;
+        }
+        writeln!(sdp, "a=end-of-candidates")?;
+    }
+    // SDP lines are CRLF terminated
+    Ok(sdp.replace('\n', "\r\n"))
+}
+
+/// Render a JSON value as a bare string, without quotes.
+fn as_str(value: &Value) -> String {
+    match value {
+        Value::String(x) => x.clone(),
+        x => x.to_string(),
+    }
+}
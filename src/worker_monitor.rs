@@ -0,0 +1,166 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use mediasoup::worker::{RequestError, Worker};
+use thiserror::Error;
+
+use crate::subsystems::{Subsystem, Subsystems};
+
+/// Weight of the latest sample in the moving average of request latency.
+const LATENCY_SMOOTHING: f64 = 0.2;
+
+/// Measures the latency of requests to the Mediasoup worker, which grows as the
+/// worker channel saturates. Once the latency exceeds a threshold, non-essential
+/// requests (e.g. stats and dumps) are shed so that media requests keep succeeding.
+#[derive(Debug, Clone)]
+pub struct WorkerMonitor {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    worker: Worker,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// moving average of request latency, if sampled
+    latency: Option<Duration>,
+    /// latency above which non-essential requests are shed, if any
+    threshold: Option<Duration>,
+    /// CPU time (ms) spent by the worker as of the last sample, and when it was taken
+    cpu_time: Option<(Instant, u64)>,
+    /// CPU usage of the worker between the last two samples, as a fraction of one core
+    cpu_usage: Option<f64>,
+}
+
+impl WorkerMonitor {
+    pub fn new(worker: Worker) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                worker,
+                state: Mutex::new(State::default()),
+            }),
+        }
+    }
+
+    /// Measure the latency of a request to the worker, updating the moving average.
+    /// The CPU usage of the worker is measured along the way.
+    // integer widths of resource usage differ between mediasoup versions, so all
+    // times are widened to u64
+    #[allow(clippy::unnecessary_cast)]
+    pub async fn sample(&self) -> Result<Duration, RequestError> {
+        let start = Instant::now();
+        let usage = self.shared.worker.get_resource_usage().await?;
+        let latency = start.elapsed();
+        self.record(latency);
+        self.record_cpu_time(start, usage.ru_utime as u64 + usage.ru_stime as u64);
+        Ok(latency)
+    }
+
+    /// Update the moving average of request latency with a measured latency.
+    pub fn record(&self, latency: Duration) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.latency = Some(match state.latency {
+            Some(average) => {
+                average.mul_f64(1.0 - LATENCY_SMOOTHING) + latency.mul_f64(LATENCY_SMOOTHING)
+            }
+            None => latency,
+        });
+    }
+
+    /// Update the CPU usage of the worker with the CPU time (ms) it spent as of a sample.
+    pub fn record_cpu_time(&self, time: Instant, cpu_time_ms: u64) {
+        let mut state = self.shared.state.lock().unwrap();
+        if let Some((last_time, last_cpu_time_ms)) = state.cpu_time {
+            let elapsed = time.saturating_duration_since(last_time).as_secs_f64();
+            if elapsed > 0.0 {
+                let cpu_time = cpu_time_ms.saturating_sub(last_cpu_time_ms) as f64 / 1000.0;
+                state.cpu_usage = Some(cpu_time / elapsed);
+            }
+        }
+        state.cpu_time = Some((time, cpu_time_ms));
+    }
+
+    /// Forget all samples, e.g. once they go stale.
+    pub fn clear(&self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.latency = None;
+        state.cpu_time = None;
+        state.cpu_usage = None;
+    }
+
+    /// Periodically sample the latency of requests to the worker, unless sampling
+    /// is disabled. Samples are cleared while sampling is disabled.
+    pub fn spawn(&self, period: Duration, subsystems: Subsystems) {
+        let worker_monitor = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            let mut overloaded = false;
+            loop {
+                interval.tick().await;
+                if !subsystems.enabled(Subsystem::WorkerSampling) {
+                    // the last sample goes stale, so it must not keep shedding requests
+                    worker_monitor.clear();
+                    if overloaded {
+                        log::info!("worker sampling disabled, no longer shedding requests");
+                        overloaded = false;
+                    }
+                    continue;
+                }
+                if let Err(err) = worker_monitor.sample().await {
+                    log::error!("worker latency sample failed: {}", err);
+                }
+                match (overloaded, worker_monitor.overloaded()) {
+                    (false, true) => log::warn!(
+                        "worker overloaded (latency {:?}), shedding non-essential requests",
+                        worker_monitor.latency()
+                    ),
+                    (true, false) => log::info!("worker no longer overloaded"),
+                    _ => {}
+                }
+                overloaded = worker_monitor.overloaded();
+            }
+        });
+    }
+
+    /// Get the moving average of request latency, if sampled.
+    pub fn latency(&self) -> Option<Duration> {
+        self.shared.state.lock().unwrap().latency
+    }
+
+    /// Get the CPU usage of the worker between the last two samples, as a fraction of
+    /// the single core it runs on, if sampled.
+    pub fn cpu_usage(&self) -> Option<f64> {
+        self.shared.state.lock().unwrap().cpu_usage
+    }
+
+    /// Set the latency above which non-essential requests are shed.
+    /// Requests are never shed if unset.
+    pub fn set_threshold(&self, threshold: Option<Duration>) {
+        self.shared.state.lock().unwrap().threshold = threshold;
+    }
+    pub fn threshold(&self) -> Option<Duration> {
+        self.shared.state.lock().unwrap().threshold
+    }
+
+    /// Whether the request latency exceeds the threshold.
+    pub fn overloaded(&self) -> bool {
+        let state = self.shared.state.lock().unwrap();
+        matches!((state.latency, state.threshold), (Some(latency), Some(threshold)) if latency > threshold)
+    }
+
+    /// Check whether a non-essential request may be made to the worker.
+    pub fn check(&self) -> Result<(), WorkerOverloadedError> {
+        if self.overloaded() {
+            Err(WorkerOverloadedError(self.latency().unwrap_or_default()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("the worker is overloaded (latency {0:?}), try again later")]
+pub struct WorkerOverloadedError(pub Duration);
@@ -0,0 +1,47 @@
+use std::time::{Duration, Instant};
+
+use vulcan_relay::capacity;
+use vulcan_relay::relay_server::{ForeignSessionId, SessionOptions};
+use vulcan_relay::resource_policy::ResourcePolicy;
+
+pub mod fixture;
+
+#[tokio::test]
+async fn capacity_is_limited_by_ports_without_consumers() {
+    let relay_server = fixture::relay_server().await;
+    let resource_policy = ResourcePolicy::new();
+
+    // unknown until configured and sampled
+    let capacity = capacity::report(&relay_server, &resource_policy);
+    assert_eq!(capacity.rtc_ports, None);
+    assert_eq!(capacity.worker_cpu_usage, None);
+    assert_eq!(capacity.additional_consumers, None);
+
+    relay_server.set_rtc_ports_range(Some(10000..=10009));
+    let start = Instant::now();
+    let worker_monitor = relay_server.worker_monitor();
+    worker_monitor.record_cpu_time(start, 1000);
+    worker_monitor.record_cpu_time(start + Duration::from_secs(1), 1500);
+    assert_eq!(worker_monitor.cpu_usage(), Some(0.5));
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    vulcast.create_webrtc_transport().await;
+
+    let capacity = capacity::report(&relay_server, &resource_policy);
+    assert_eq!(capacity.rtc_ports, Some(10));
+    assert_eq!(capacity.transports, 1);
+    assert_eq!(capacity.rtc_ports_remaining, Some(9));
+    assert_eq!(capacity.sessions, 1);
+    assert_eq!(capacity.consumers, 0);
+    // CPU usage cannot be extrapolated without consumers
+    assert_eq!(
+        capacity.additional_consumers,
+        Some(9 * capacity.max_consumers_per_web_client)
+    );
+}
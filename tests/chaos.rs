@@ -0,0 +1,81 @@
+// failures are only injected in debug builds
+#![cfg(debug_assertions)]
+
+use std::time::{Duration, Instant};
+
+use async_graphql::Request;
+
+use vulcan_relay::chaos::{Chaos, ChaosError};
+use vulcan_relay::crypto_policy::CryptoPolicy;
+use vulcan_relay::relay_server::{ForeignSessionId, SessionOptions};
+use vulcan_relay::resource_policy::ResourcePolicy;
+use vulcan_relay::schema_compat::DeprecationPolicy;
+use vulcan_relay::session::{ConsumerConfig, PlainTransportConfig, ResourceType};
+use vulcan_relay::signal_schema;
+
+pub mod fixture;
+
+#[test]
+fn transport_creations_fail_at_configured_rate() {
+    let chaos = Chaos::new();
+    assert!((0..100).all(|_| chaos.check_transport_creation().is_ok()));
+
+    chaos.set_transport_failure_percent(100).unwrap();
+    assert!((0..100).all(|_| chaos.check_transport_creation().is_err()));
+
+    assert_eq!(
+        chaos.set_transport_failure_percent(101),
+        Err(ChaosError::InvalidPercent(101))
+    );
+    assert_eq!(chaos.transport_failure_percent(), 100);
+}
+
+#[test]
+fn resolver_delay_can_be_cleared() {
+    let chaos = Chaos::new();
+    chaos
+        .set_resolver_delay(Some(Duration::from_millis(50)))
+        .unwrap();
+    assert_eq!(chaos.resolver_delay(), Some(Duration::from_millis(50)));
+    chaos.set_resolver_delay(None).unwrap();
+    assert_eq!(chaos.resolver_delay(), None);
+}
+
+#[tokio::test]
+async fn resolver_delay_delays_signal_operations() {
+    let relay_server = fixture::relay_server().await;
+    let signal_schema = signal_schema::schema(
+        relay_server.clone(),
+        ResourcePolicy::new(),
+        None,
+        PlainTransportConfig::default(),
+        ConsumerConfig::default(),
+        CryptoPolicy::default(),
+        DeprecationPolicy::default(),
+    );
+    relay_server
+        .chaos()
+        .set_resolver_delay(Some(Duration::from_millis(200)))
+        .unwrap();
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+
+    let start = Instant::now();
+    let response = signal_schema
+        .execute(
+            Request::new("mutation { createWebrtcTransport { id } }").data(vulcast.downgrade()),
+        )
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert_eq!(
+        vulcast.get_resource_count(&ResourceType::WebrtcTransport),
+        1
+    );
+}
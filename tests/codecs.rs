@@ -0,0 +1,159 @@
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+
+use mediasoup::rtp_parameters::{
+    MimeTypeVideo, RtpCodecCapability, RtpCodecParametersParameters,
+    RtpCodecParametersParametersValue,
+};
+use uuid::Uuid;
+
+use vulcan_relay::codecs::{self, CodecConfigError, CodecFilter, OpusConfig};
+
+fn write_codecs(codecs: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("codecs-{}.json", Uuid::new_v4()));
+    std::fs::write(&path, codecs).unwrap();
+    path
+}
+
+#[test]
+fn default_codecs_are_valid() {
+    codecs::validate(&codecs::default_codecs()).unwrap();
+}
+
+#[test]
+fn codecs_are_loaded_from_file() {
+    let default_codecs = codecs::default_codecs();
+    let path = write_codecs(&serde_json::to_string(&default_codecs).unwrap());
+    let codecs = codecs::load(&path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(codecs, default_codecs);
+}
+
+#[test]
+fn invalid_codecs_are_rejected() {
+    let path = write_codecs("[]");
+    assert!(matches!(codecs::load(&path), Err(CodecConfigError::Empty)));
+    std::fs::remove_file(path).unwrap();
+
+    let path = write_codecs("not json");
+    assert!(matches!(
+        codecs::load(&path),
+        Err(CodecConfigError::Parse(_))
+    ));
+    std::fs::remove_file(path).unwrap();
+
+    let rtx = RtpCodecCapability::Video {
+        mime_type: MimeTypeVideo::Rtx,
+        preferred_payload_type: None,
+        clock_rate: NonZeroU32::new(90000).unwrap(),
+        parameters: RtpCodecParametersParameters::default(),
+        rtcp_feedback: vec![],
+    };
+    assert!(matches!(
+        codecs::validate(&[rtx]),
+        Err(CodecConfigError::Rtx)
+    ));
+
+    let mut duplicates = codecs::default_codecs();
+    for codec in &mut duplicates {
+        match codec {
+            RtpCodecCapability::Audio {
+                preferred_payload_type,
+                ..
+            }
+            | RtpCodecCapability::Video {
+                preferred_payload_type,
+                ..
+            } => *preferred_payload_type = Some(100),
+        }
+    }
+    assert!(matches!(
+        codecs::validate(&duplicates),
+        Err(CodecConfigError::DuplicatePayloadType(100))
+    ));
+}
+
+#[test]
+fn video_codecs_are_filtered() {
+    let video_codecs = |codecs: &[RtpCodecCapability]| {
+        codecs
+            .iter()
+            .filter_map(|codec| match codec {
+                RtpCodecCapability::Audio { .. } => None,
+                RtpCodecCapability::Video { mime_type, .. } => Some(*mime_type),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let vp8_only = CodecFilter {
+        only: Some(MimeTypeVideo::Vp8),
+        ..CodecFilter::default()
+    };
+    let codecs = vp8_only.apply(codecs::default_codecs()).unwrap();
+    assert_eq!(video_codecs(&codecs), vec![MimeTypeVideo::Vp8]);
+    assert!(codecs
+        .iter()
+        .any(|codec| matches!(codec, RtpCodecCapability::Audio { .. })));
+
+    let no_h264 = CodecFilter {
+        disabled: vec![MimeTypeVideo::H264],
+        ..CodecFilter::default()
+    };
+    assert_eq!(
+        video_codecs(&no_h264.apply(codecs::default_codecs()).unwrap()),
+        vec![MimeTypeVideo::Vp8]
+    );
+
+    let no_video = CodecFilter {
+        disabled: vec![MimeTypeVideo::H264, MimeTypeVideo::Vp8],
+        ..CodecFilter::default()
+    };
+    assert!(matches!(
+        no_video.apply(codecs::default_codecs()),
+        Err(CodecConfigError::NoVideo)
+    ));
+}
+
+#[test]
+fn opus_config_is_applied() {
+    let opus_parameters = |codecs: &[RtpCodecCapability]| match &codecs[0] {
+        RtpCodecCapability::Audio { parameters, .. } => parameters.clone(),
+        _ => unreachable!("first codec is Opus"),
+    };
+
+    let mut codecs = codecs::default_codecs();
+    OpusConfig::default().apply(&mut codecs).unwrap();
+    assert_eq!(codecs, codecs::default_codecs());
+
+    let opus_config = OpusConfig {
+        stereo: Some(true),
+        inband_fec: Some(false),
+        dtx: Some(true),
+        max_average_bitrate: Some(64000),
+    };
+    opus_config.apply(&mut codecs).unwrap();
+    let parameters = opus_parameters(&codecs);
+    for (key, value) in [
+        ("stereo", 1),
+        ("sprop-stereo", 1),
+        ("useinbandfec", 0),
+        ("usedtx", 1),
+        ("maxaveragebitrate", 64000),
+    ] {
+        assert_eq!(
+            parameters.get(key),
+            Some(&RtpCodecParametersParametersValue::Number(value)),
+            "{}",
+            key
+        );
+    }
+
+    let opus_config = OpusConfig {
+        max_average_bitrate: Some(1_000_000),
+        ..OpusConfig::default()
+    };
+    assert!(matches!(
+        opus_config.apply(&mut codecs::default_codecs()),
+        Err(CodecConfigError::OpusBitrate(1_000_000))
+    ));
+}
@@ -0,0 +1,111 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::oneshot;
+
+use vulcan_relay::control_queue::{ControlQueue, OperationClass};
+
+#[test]
+fn fields_are_classified() {
+    assert_eq!(
+        OperationClass::of_field("registerClientSession"),
+        OperationClass::Registration
+    );
+    assert_eq!(
+        OperationClass::of_field("serverStats"),
+        OperationClass::Stats
+    );
+    assert_eq!(
+        OperationClass::of_field("capacity"),
+        OperationClass::Routine
+    );
+}
+
+#[tokio::test]
+async fn registrations_overtake_queued_stats() {
+    let control_queue = ControlQueue::new();
+    control_queue.set_max_concurrent(Some(1));
+    let served = Arc::new(Mutex::new(Vec::new()));
+
+    // occupy the only slot until released
+    let (release_tx, release_rx) = oneshot::channel::<()>();
+    let blocker = tokio::spawn({
+        let control_queue = control_queue.clone();
+        async move {
+            control_queue
+                .run(OperationClass::Routine, async {
+                    let _ = release_rx.await;
+                })
+                .await
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(control_queue.running(), 1);
+
+    let mut queued = vec![];
+    for class in [
+        OperationClass::Stats,
+        OperationClass::Routine,
+        OperationClass::Registration,
+    ] {
+        let control_queue = control_queue.clone();
+        let served = served.clone();
+        queued.push(tokio::spawn(async move {
+            control_queue
+                .run(class, async move { served.lock().unwrap().push(class) })
+                .await
+        }));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let depths = control_queue.depths();
+    assert!(depths.iter().all(|queue_depth| queue_depth.depth == 1));
+
+    release_tx.send(()).unwrap();
+    blocker.await.unwrap();
+    for queued in queued {
+        queued.await.unwrap();
+    }
+    assert_eq!(
+        *served.lock().unwrap(),
+        vec![
+            OperationClass::Registration,
+            OperationClass::Routine,
+            OperationClass::Stats
+        ]
+    );
+    assert_eq!(control_queue.running(), 0);
+    assert!(control_queue
+        .depths()
+        .iter()
+        .all(|queue_depth| queue_depth.depth == 0 && queue_depth.peak_depth == 1));
+}
+
+#[tokio::test]
+async fn abandoned_operations_release_their_slot() {
+    let control_queue = ControlQueue::new();
+    control_queue.set_max_concurrent(Some(1));
+
+    let (release_tx, release_rx) = oneshot::channel::<()>();
+    let blocker = tokio::spawn({
+        let control_queue = control_queue.clone();
+        async move {
+            control_queue
+                .run(OperationClass::Routine, async {
+                    let _ = release_rx.await;
+                })
+                .await
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    // abandoned while queued
+    let abandoned = control_queue.run(OperationClass::Stats, async {});
+    assert!(tokio::time::timeout(Duration::from_millis(10), abandoned)
+        .await
+        .is_err());
+
+    release_tx.send(()).unwrap();
+    blocker.await.unwrap();
+    assert_eq!(control_queue.running(), 0);
+    control_queue.run(OperationClass::Routine, async {}).await;
+}
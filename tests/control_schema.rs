@@ -0,0 +1,104 @@
+use futures::stream::StreamExt;
+
+use vulcan_relay::control_schema;
+use vulcan_relay::crypto_policy::CryptoPolicy;
+use vulcan_relay::relay_server::{ForeignRoomId, ForeignSessionId, SessionOptions};
+use vulcan_relay::resource_policy::ResourcePolicy;
+use vulcan_relay::schema_compat::DeprecationPolicy;
+
+pub mod fixture;
+
+#[tokio::test]
+async fn stats_stream_completes_once_session_ends() {
+    let relay_server = fixture::relay_server().await;
+    let control_schema = control_schema::schema(
+        relay_server.clone(),
+        ResourcePolicy::new(),
+        CryptoPolicy::default(),
+        DeprecationPolicy::default(),
+    );
+
+    let token = relay_server
+        .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+        .unwrap();
+    let session = relay_server.session_from_token(token).unwrap();
+    session.create_webrtc_transport().await;
+
+    let mut stream = control_schema.execute_stream(
+        r#"subscription {
+            statsStream(sessionId: "vulcast", intervalMs: 100) { transports { transportId } }
+        }"#,
+    );
+    for _ in 0..2 {
+        let response = stream.next().await.unwrap();
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+    }
+
+    drop(session);
+    drop(relay_server.disconnect_session_by_token(&token));
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn stats_stream_rejects_unknown_sessions() {
+    let relay_server = fixture::relay_server().await;
+    let control_schema = control_schema::schema(
+        relay_server,
+        ResourcePolicy::new(),
+        CryptoPolicy::default(),
+        DeprecationPolicy::default(),
+    );
+
+    let response = control_schema
+        .execute_stream(r#"subscription { statsStream(sessionId: "nobody") { __typename } }"#)
+        .next()
+        .await
+        .unwrap();
+    assert!(!response.errors.is_empty());
+}
+
+#[tokio::test]
+async fn rooms_lists_registered_sessions() {
+    let relay_server = fixture::relay_server().await;
+    let control_schema = control_schema::schema(
+        relay_server.clone(),
+        ResourcePolicy::new(),
+        CryptoPolicy::default(),
+        DeprecationPolicy::default(),
+    );
+
+    let vulcast_token = relay_server
+        .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(
+            ForeignRoomId("ayush".into()),
+            ForeignSessionId("vulcast".into()),
+        )
+        .unwrap();
+    relay_server
+        .register_session(
+            ForeignSessionId("web".into()),
+            SessionOptions::WebClient(ForeignRoomId("ayush".into())),
+        )
+        .unwrap();
+    let _vulcast = relay_server.session_from_token(vulcast_token).unwrap();
+
+    let response = control_schema
+        .execute("{ rooms { id vulcastSessionId sessions { id role connected } } }")
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data.into_json().unwrap(),
+        serde_json::json!({
+            "rooms": [{
+                "id": "ayush",
+                "vulcastSessionId": "vulcast",
+                "sessions": [
+                    { "id": "vulcast", "role": "VULCAST", "connected": true },
+                    { "id": "web", "role": "WEB_CLIENT", "connected": false },
+                ],
+            }],
+        })
+    );
+}
@@ -0,0 +1,42 @@
+use mediasoup::srtp_parameters::SrtpCryptoSuite;
+
+use vulcan_relay::crypto_policy::{CryptoPolicy, CryptoPolicyError};
+use vulcan_relay::session::PlainTransportConfig;
+
+#[test]
+fn constrained_mode_requires_tls() {
+    assert_eq!(
+        CryptoPolicy::new(true, false).unwrap_err(),
+        CryptoPolicyError::TlsDisabled
+    );
+    assert!(!CryptoPolicy::new(false, false).unwrap().tls());
+    assert!(CryptoPolicy::new(true, true).unwrap().tls());
+}
+
+#[test]
+fn constrained_mode_requires_approved_srtp() {
+    let crypto_policy = CryptoPolicy::new(true, true).unwrap();
+
+    assert_eq!(
+        crypto_policy
+            .check_plain_transport_config(&PlainTransportConfig::default())
+            .unwrap_err(),
+        CryptoPolicyError::SrtpDisabled
+    );
+    let config = PlainTransportConfig {
+        enable_srtp: true,
+        ..PlainTransportConfig::default()
+    };
+    assert!(crypto_policy.check_plain_transport_config(&config).is_ok());
+    assert!(crypto_policy
+        .check_srtp_crypto_suite(SrtpCryptoSuite::AesCm128HmacSha1_32)
+        .is_err());
+
+    let crypto_policy = CryptoPolicy::default();
+    assert!(crypto_policy
+        .check_plain_transport_config(&PlainTransportConfig::default())
+        .is_ok());
+    assert!(crypto_policy
+        .check_srtp_crypto_suite(SrtpCryptoSuite::AesCm128HmacSha1_32)
+        .is_ok());
+}
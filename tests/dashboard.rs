@@ -0,0 +1,32 @@
+use vulcan_relay::dashboard;
+
+#[tokio::test]
+async fn dashboard_serves_embedded_assets() {
+    let routes = dashboard::routes();
+
+    let index = warp::test::request()
+        .path("/dashboard")
+        .reply(&routes)
+        .await;
+    assert_eq!(index.status(), 200);
+    assert_eq!(index.headers()["content-type"], "text/html; charset=utf-8");
+    assert!(std::str::from_utf8(index.body())
+        .unwrap()
+        .contains("/dashboard/dashboard.js"));
+
+    let script = warp::test::request()
+        .path("/dashboard/dashboard.js")
+        .reply(&routes)
+        .await;
+    assert_eq!(script.status(), 200);
+    assert_eq!(
+        script.headers()["content-type"],
+        "text/javascript; charset=utf-8"
+    );
+
+    let missing = warp::test::request()
+        .path("/dashboard/missing.js")
+        .reply(&routes)
+        .await;
+    assert_eq!(missing.status(), 404);
+}
@@ -0,0 +1,309 @@
+use futures::stream::StreamExt;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+use mediasoup::data_producer::DataProducerId;
+use mediasoup::data_structures::WebRtcMessage;
+use mediasoup::sctp_parameters::SctpStreamParameters;
+use mediasoup::transport::Transport;
+use uuid::Uuid;
+
+use vulcan_relay::data_filter::{DataFilter, FilterChain, RateLimitFilter, SequenceFilter};
+use vulcan_relay::leak_detector;
+use vulcan_relay::relay_server::{
+    ForeignRoomId, ForeignSessionId, Role, SessionOptions, SetRoomDataFilterError,
+};
+use vulcan_relay::room::DataRelayStat;
+
+pub mod fixture;
+
+/// Get a new data producer ID. Filters only key their state by it, so no data
+/// producer need exist.
+fn new_data_producer_id() -> DataProducerId {
+    serde_json::from_value(json!(Uuid::new_v4())).unwrap()
+}
+
+/// Get a web client input message carrying a sequence number.
+fn input(seq: u64) -> WebRtcMessage<'static> {
+    WebRtcMessage::String(json!({ "seq": seq, "button": "a" }).to_string())
+}
+
+/// Get the relay sequence number a sequence filter stamped on a message, if relayed.
+fn relay_seq(message: Option<WebRtcMessage<'_>>) -> Option<u64> {
+    match message {
+        Some(WebRtcMessage::String(string)) => serde_json::from_str::<serde_json::Value>(&string)
+            .unwrap()
+            .get("relaySeq")
+            .and_then(|x| x.as_u64()),
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn data_is_relayed_through_server() {
+    let relay_server = fixture::relay_server().await;
+    relay_server.set_data_filter(Some(Arc::new(RateLimitFilter::new(
+        1,
+        Duration::from_secs(1),
+    ))));
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    let room = vulcast.get_room();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let data_producer = vulcast
+        .produce_data(send_transport.id(), fixture::sctp_stream_parameters())
+        .await
+        .unwrap();
+
+    // only the relayed data producer is available in the room
+    let data_producers = room.get_data_producers();
+    assert_eq!(data_producers.len(), 1);
+    assert_ne!(data_producers[0].id(), data_producer.id());
+    assert!(leak_detector::audit(&relay_server)
+        .await
+        .unwrap()
+        .is_clean());
+
+    // relay is torn down with the session data producer, and its closure announced
+    let mut closed_data_producers = Box::pin(room.closed_data_producers());
+    drop(data_producer);
+    drop(vulcast.close_transport(send_transport.id()));
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while !room.get_data_producers().is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(
+        closed_data_producers.next().await,
+        Some(data_producers[0].id())
+    );
+}
+
+#[tokio::test]
+async fn closed_relayed_data_producers_are_announced_once() {
+    let relay_server = fixture::relay_server().await;
+    relay_server.set_data_filter(Some(Arc::new(RateLimitFilter::new(
+        1,
+        Duration::from_secs(1),
+    ))));
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    let room = vulcast.get_room();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let data_producer_id = vulcast
+        .produce_data(send_transport.id(), fixture::sctp_stream_parameters())
+        .await
+        .unwrap()
+        .id();
+    let relayed_id = room.get_data_producers()[0].id();
+
+    // the relayed data producer the room advertised is announced closed, rather
+    // than the session data producer
+    let mut closed_data_producers = Box::pin(room.closed_data_producers());
+    vulcast.data_producer_close(data_producer_id).unwrap();
+    assert_eq!(closed_data_producers.next().await, Some(relayed_id));
+    assert!(room.get_data_relay_stat(data_producer_id).is_none());
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), closed_data_producers.next())
+            .await
+            .is_err()
+    );
+}
+
+#[test]
+fn rate_limit_filter_drops_excess_messages() {
+    let data_producer_id = new_data_producer_id();
+    let filter = RateLimitFilter::new(2, Duration::from_secs(60));
+    let message = || WebRtcMessage::String("input".to_owned());
+    assert!(filter
+        .filter(data_producer_id, Role::WebClient, message())
+        .is_some());
+    assert!(filter
+        .filter(data_producer_id, Role::WebClient, message())
+        .is_some());
+    assert!(filter
+        .filter(data_producer_id, Role::WebClient, message())
+        .is_none());
+    // each data producer is limited separately
+    assert!(filter
+        .filter(new_data_producer_id(), Role::WebClient, message())
+        .is_some());
+
+    // state is released once the data producer is removed
+    filter.remove(data_producer_id);
+    assert!(filter
+        .filter(data_producer_id, Role::WebClient, message())
+        .is_some());
+}
+
+#[test]
+fn size_limit_drops_oversized_messages() {
+    let data_producer_id = new_data_producer_id();
+    let filter = RateLimitFilter::new(10, Duration::from_secs(60)).with_max_message_size(4);
+    assert!(filter
+        .filter(
+            data_producer_id,
+            Role::WebClient,
+            WebRtcMessage::String("left".to_owned())
+        )
+        .is_some());
+    assert!(filter
+        .filter(
+            data_producer_id,
+            Role::WebClient,
+            WebRtcMessage::String("right".to_owned())
+        )
+        .is_none());
+}
+
+#[tokio::test]
+async fn room_data_filter_relays_new_data_producers() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    assert_eq!(
+        relay_server.set_room_data_filter(foreign_room_id.clone(), None),
+        Err(SetRoomDataFilterError::UnknownRoom(foreign_room_id.clone()))
+    );
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let room = vulcast.get_room();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let direct_data_producer = vulcast
+        .produce_data(send_transport.id(), fixture::sctp_stream_parameters())
+        .await
+        .unwrap();
+    assert!(room
+        .get_data_relay_stat(direct_data_producer.id())
+        .is_none());
+
+    relay_server
+        .set_room_data_filter(
+            foreign_room_id,
+            Some(Arc::new(RateLimitFilter::new(1, Duration::from_secs(1)))),
+        )
+        .unwrap();
+    let relayed_data_producer = vulcast
+        .produce_data(
+            send_transport.id(),
+            SctpStreamParameters::new_unordered_with_life_time(1, 5000),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        room.get_data_relay_stat(relayed_data_producer.id()),
+        Some(DataRelayStat::default())
+    );
+
+    // direct data producers are available alongside relays
+    let data_producer_ids = room
+        .get_data_producers()
+        .into_iter()
+        .map(|data_producer| data_producer.id())
+        .collect::<Vec<_>>();
+    assert_eq!(data_producer_ids.len(), 2);
+    assert!(data_producer_ids.contains(&direct_data_producer.id()));
+    assert!(!data_producer_ids.contains(&relayed_data_producer.id()));
+}
+
+#[test]
+fn sequence_filter_drops_replayed_input() {
+    let data_producer_id = new_data_producer_id();
+    let filter = SequenceFilter::new(10);
+
+    assert_eq!(
+        relay_seq(filter.filter(data_producer_id, Role::WebClient, input(1))),
+        Some(1)
+    );
+    assert_eq!(
+        relay_seq(filter.filter(data_producer_id, Role::WebClient, input(3))),
+        Some(2)
+    );
+    // duplicated, replayed and out-of-window messages are dropped
+    assert!(filter
+        .filter(data_producer_id, Role::WebClient, input(3))
+        .is_none());
+    assert!(filter
+        .filter(data_producer_id, Role::WebClient, input(2))
+        .is_none());
+    assert!(filter
+        .filter(data_producer_id, Role::WebClient, input(14))
+        .is_none());
+    assert!(filter
+        .filter(
+            data_producer_id,
+            Role::WebClient,
+            WebRtcMessage::String("a".to_owned())
+        )
+        .is_none());
+
+    // data from other roles is not sequenced
+    assert!(filter
+        .filter(
+            data_producer_id,
+            Role::Vulcast,
+            WebRtcMessage::String("a".to_owned())
+        )
+        .is_some());
+}
+
+#[test]
+fn filter_chain_passes_messages_through_each_filter() {
+    let data_producer_id = new_data_producer_id();
+    let filter = FilterChain::new(vec![
+        Arc::new(SequenceFilter::new(10)),
+        Arc::new(RateLimitFilter::new(2, Duration::from_secs(60))),
+    ]);
+
+    assert_eq!(
+        relay_seq(filter.filter(data_producer_id, Role::WebClient, input(1))),
+        Some(1)
+    );
+    // messages dropped by a filter are not passed to later filters
+    assert!(filter
+        .filter(data_producer_id, Role::WebClient, input(1))
+        .is_none());
+    assert_eq!(
+        relay_seq(filter.filter(data_producer_id, Role::WebClient, input(2))),
+        Some(2)
+    );
+    assert!(filter
+        .filter(data_producer_id, Role::WebClient, input(3))
+        .is_none());
+
+    // state of every filter is released once the data producer is removed
+    filter.remove(data_producer_id);
+    assert_eq!(
+        relay_seq(filter.filter(data_producer_id, Role::WebClient, input(1))),
+        Some(1)
+    );
+}
@@ -0,0 +1,78 @@
+use futures::stream::StreamExt;
+
+use vulcan_relay::event_log::{EventLog, ResumeError};
+
+fn is_even(item: &u32) -> bool {
+    item % 2 == 0
+}
+
+#[tokio::test]
+async fn items_are_numbered_in_order() {
+    let event_log = EventLog::new(8, 8, |_: &u32| true);
+    let mut stream = Box::pin(event_log.stream());
+    assert_eq!(event_log.send(10), 1);
+    assert_eq!(event_log.send(20), 2);
+    assert_eq!(event_log.last_sequence(), 2);
+
+    let first = stream.next().await.unwrap();
+    assert_eq!((first.sequence, first.item), (1, 10));
+    let second = stream.next().await.unwrap();
+    assert_eq!((second.sequence, second.item), (2, 20));
+}
+
+#[tokio::test]
+async fn deliveries_to_each_subscriber_are_counted() {
+    let event_log = EventLog::new(8, 8, |_: &u32| true);
+    let mut first = Box::pin(event_log.stream());
+    let mut second = Box::pin(event_log.stream());
+    event_log.send(10);
+    first.next().await.unwrap();
+    second.next().await.unwrap();
+    drop(second);
+
+    let fan_out_stats = event_log.fan_out_stats();
+    assert_eq!(fan_out_stats.subscribers, 1);
+    assert_eq!(fan_out_stats.sent, 1);
+    assert_eq!(fan_out_stats.delivered, 2);
+    assert!(fan_out_stats.mean_latency <= fan_out_stats.max_latency);
+    assert!(fan_out_stats.max_latency.is_some());
+}
+
+#[tokio::test]
+async fn resume_replays_retained_items() {
+    let event_log = EventLog::new(8, 8, is_even);
+    for item in 0..4 {
+        event_log.send(item);
+    }
+
+    let mut stream = Box::pin(event_log.resume(1).unwrap());
+    event_log.send(4);
+    let replayed = stream
+        .by_ref()
+        .take(2)
+        .map(|sequenced| (sequenced.sequence, sequenced.item))
+        .collect::<Vec<_>>()
+        .await;
+    // odd items are not retained, so are not replayed
+    assert_eq!(replayed, vec![(3, 2), (5, 4)]);
+}
+
+#[test]
+fn resume_fails_once_items_are_evicted() {
+    let event_log = EventLog::new(2, 8, |_: &u32| true);
+    for item in 0..3 {
+        event_log.send(item);
+    }
+    assert_eq!(
+        event_log.resume(0).err(),
+        Some(ResumeError {
+            resume_from: 0,
+            evicted_through: 1,
+            last_sequence: 3,
+        })
+    );
+    assert!(event_log.resume(1).is_ok());
+    assert!(event_log.resume(3).is_ok());
+    // never reached, e.g. seen in a previous log
+    assert!(event_log.resume(4).is_err());
+}
@@ -0,0 +1,167 @@
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use std::path::Path;
+
+use mediasoup::rtp_parameters::MediaKind;
+
+use vulcan_relay::events::{AudioLevelEvent, Envelope, Event, SCHEMA_VERSION};
+
+const TIMESTAMP: u64 = 1650000000000;
+const PRODUCER_ID: &str = "5b0f5c4e-8a57-4b0e-9b8a-2f1c3c7d9e01";
+const DATA_PRODUCER_ID: &str = "0d2c6b9a-3f4e-4d1a-8c7b-6e5f4a3b2c1d";
+const MESSAGE_ID: &str = "9a8b7c6d-5e4f-4a3b-8c2d-1e0f9a8b7c6d";
+
+fn id<T: DeserializeOwned>(id: &str) -> T {
+    serde_json::from_value(json!(id)).unwrap()
+}
+
+fn golden(name: &str) -> serde_json::Value {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden/events")
+        .join(name);
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+/// Check that an event serializes to, and deserializes from, its golden file.
+fn assert_golden(name: &str, event: Event) {
+    let envelope = Envelope {
+        version: SCHEMA_VERSION,
+        timestamp: TIMESTAMP,
+        sequence: None,
+        event,
+    };
+    let golden = golden(name);
+    assert_eq!(serde_json::to_value(&envelope).unwrap(), golden);
+    assert_eq!(
+        serde_json::from_value::<Envelope>(golden).unwrap(),
+        envelope
+    );
+}
+
+#[test]
+fn events_match_golden_files() {
+    assert_golden(
+        "producer_available.json",
+        Event::ProducerAvailable {
+            producer_id: id(PRODUCER_ID),
+            session_id: Some("vulcast".into()),
+            kind: Some(MediaKind::Video),
+            label: Some("game".into()),
+        },
+    );
+    assert_golden(
+        "producer_closed.json",
+        Event::ProducerClosed {
+            producer_id: id(PRODUCER_ID),
+        },
+    );
+    assert_golden(
+        "data_producer_available.json",
+        Event::DataProducerAvailable {
+            data_producer_id: id(DATA_PRODUCER_ID),
+        },
+    );
+    assert_golden(
+        "data_producer_closed.json",
+        Event::DataProducerClosed {
+            data_producer_id: id(DATA_PRODUCER_ID),
+        },
+    );
+    assert_golden(
+        "active_speaker.json",
+        Event::ActiveSpeaker {
+            speaker: Some(AudioLevelEvent {
+                producer_id: id(PRODUCER_ID),
+                volume: -20,
+            }),
+        },
+    );
+    assert_golden(
+        "audio_levels.json",
+        Event::AudioLevels {
+            levels: vec![AudioLevelEvent {
+                producer_id: id(PRODUCER_ID),
+                volume: -20,
+            }],
+        },
+    );
+    assert_golden(
+        "vulcast_command.json",
+        Event::VulcastCommand {
+            id: MESSAGE_ID.into(),
+            sender: "host".into(),
+            command: json!({"type": "reboot"}),
+        },
+    );
+    assert_golden(
+        "controller_changed.json",
+        Event::ControllerChanged {
+            session_id: Some("webclient".into()),
+        },
+    );
+    assert_golden(
+        "state_changed.json",
+        Event::StateChanged {
+            key: "scoreboard".into(),
+            value: Some(json!({"p1": 3, "p2": 1})),
+            session_id: "webclient".into(),
+        },
+    );
+    assert_golden(
+        "client_joined.json",
+        Event::ClientJoined {
+            session_id: "webclient".into(),
+        },
+    );
+    assert_golden(
+        "client_left.json",
+        Event::ClientLeft {
+            session_id: "webclient".into(),
+        },
+    );
+}
+
+#[test]
+fn unknown_fields_are_ignored() {
+    let mut event = golden("producer_available.json");
+    event["source"] = json!("relay");
+    event["data"]["bitrate"] = json!(1000000);
+    assert_eq!(
+        serde_json::from_value::<Envelope>(event).unwrap().event,
+        Event::ProducerAvailable {
+            producer_id: id(PRODUCER_ID),
+            session_id: Some("vulcast".into()),
+            kind: Some(MediaKind::Video),
+            label: Some("game".into()),
+        }
+    );
+}
+
+#[test]
+fn added_fields_are_optional() {
+    // producers were announced by id alone before their owner, kind and label
+    let event = json!({
+        "version": 1,
+        "timestamp": TIMESTAMP,
+        "type": "producerAvailable",
+        "data": { "producerId": PRODUCER_ID },
+    });
+    assert_eq!(
+        serde_json::from_value::<Envelope>(event).unwrap().event,
+        Event::ProducerAvailable {
+            producer_id: id(PRODUCER_ID),
+            session_id: None,
+            kind: None,
+            label: None,
+        }
+    );
+}
+
+#[test]
+fn sequenced_events_carry_their_sequence() {
+    let mut event = golden("producer_available.json");
+    event["sequence"] = json!(42);
+    let envelope = serde_json::from_value::<Envelope>(event.clone()).unwrap();
+    assert_eq!(envelope.sequence, Some(42));
+    assert_eq!(serde_json::to_value(&envelope).unwrap(), event);
+}
@@ -0,0 +1,60 @@
+use std::time::{Duration, Instant};
+
+use vulcan_relay::join_throttle::{JoinThrottle, JoinThrottledError};
+use vulcan_relay::relay_server::ForeignRoomId;
+
+#[test]
+fn unlimited_rooms_admit_immediately() {
+    let join_throttle = JoinThrottle::new(Duration::from_secs(1));
+    let frid = ForeignRoomId("ayush".into());
+    let now = Instant::now();
+    for _ in 0..100 {
+        assert_eq!(join_throttle.reserve(&frid, now), Ok(Duration::ZERO));
+    }
+}
+
+#[test]
+fn joins_beyond_rate_are_queued_then_rejected() {
+    let join_throttle = JoinThrottle::new(Duration::from_millis(250));
+    join_throttle.set_default_rate(Some(10));
+    let frid = ForeignRoomId("ayush".into());
+    let now = Instant::now();
+
+    for delay_ms in [0, 100, 200] {
+        assert_eq!(
+            join_throttle.reserve(&frid, now),
+            Ok(Duration::from_millis(delay_ms))
+        );
+    }
+    assert_eq!(
+        join_throttle.reserve(&frid, now),
+        Err(JoinThrottledError {
+            frid: frid.clone(),
+            retry_after: Duration::from_millis(50),
+        })
+    );
+
+    // the queue drains over time
+    let later = now + Duration::from_millis(300);
+    assert_eq!(join_throttle.reserve(&frid, later), Ok(Duration::ZERO));
+}
+
+#[test]
+fn room_rates_override_default_rate() {
+    let join_throttle = JoinThrottle::new(Duration::ZERO);
+    join_throttle.set_default_rate(Some(1));
+    let busy = ForeignRoomId("busy".into());
+    let quiet = ForeignRoomId("quiet".into());
+    join_throttle.set_room_rate(busy.clone(), Some(1000));
+    assert_eq!(join_throttle.rate(&busy), Some(1000));
+    assert_eq!(join_throttle.rate(&quiet), Some(1));
+
+    let now = Instant::now();
+    assert!(join_throttle.reserve(&quiet, now).is_ok());
+    assert!(join_throttle.reserve(&quiet, now).is_err());
+    // rooms are throttled independently
+    assert!(join_throttle.reserve(&busy, now).is_ok());
+
+    join_throttle.set_room_rate(busy.clone(), None);
+    assert_eq!(join_throttle.rate(&busy), Some(1));
+}
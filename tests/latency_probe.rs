@@ -0,0 +1,42 @@
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use mediasoup::consumer::ConsumerId;
+use mediasoup::producer::ProducerId;
+
+use vulcan_relay::latency_probe::LatencyProbe;
+
+fn id<T: DeserializeOwned>(id: &str) -> T {
+    serde_json::from_value(json!(id)).unwrap()
+}
+
+#[test]
+fn latency_is_measured_per_frame() {
+    let producer_id: ProducerId = id("5b0f5c4e-8a57-4b0e-9b8a-2f1c3c7d9e01");
+    let consumer_id: ConsumerId = id("0d2c6b9a-3f4e-4d1a-8c7b-6e5f4a3b2c1d");
+    let latency_probe = LatencyProbe::new();
+
+    // packets of a frame share a timestamp, the first arrival is kept
+    latency_probe.record_ingress(producer_id, 3000, 100);
+    latency_probe.record_ingress(producer_id, 3000, 104);
+    latency_probe.record_ingress(producer_id, 6000, 133);
+
+    // packets which never arrived are not measured
+    latency_probe.record_egress(consumer_id, producer_id, 9000, 200);
+    assert!(latency_probe.report().is_empty());
+
+    latency_probe.record_egress(consumer_id, producer_id, 3000, 105);
+    // later packets of a measured frame are ignored
+    latency_probe.record_egress(consumer_id, producer_id, 3000, 109);
+    latency_probe.record_egress(consumer_id, producer_id, 6000, 148);
+
+    let report = latency_probe.report();
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].producer_id, producer_id);
+    assert_eq!(report[0].consumer_id, consumer_id);
+    assert_eq!(report[0].samples, 2);
+    assert_eq!(report[0].min_ms, 5);
+    assert_eq!(report[0].max_ms, 15);
+    assert_eq!(report[0].last_ms, 15);
+    assert_eq!(report[0].mean_ms, 10.0);
+}
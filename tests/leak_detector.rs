@@ -0,0 +1,56 @@
+use vulcan_relay::leak_detector;
+use vulcan_relay::relay_server::{ForeignSessionId, SessionOptions};
+
+pub mod fixture;
+
+#[tokio::test]
+async fn audit_is_clean_after_session_dropped() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    let room = vulcast.get_room();
+    let transport = vulcast.create_webrtc_transport().await;
+    assert!(leak_detector::audit(&relay_server)
+        .await
+        .unwrap()
+        .is_clean());
+
+    // drop all strong references to the session, closing its transport
+    drop(transport);
+    drop(vulcast);
+    drop(relay_server.take_session(&vulcast_session_id));
+    assert!(leak_detector::audit(&relay_server)
+        .await
+        .unwrap()
+        .is_clean());
+    drop(room);
+}
+
+#[tokio::test]
+async fn audit_tracks_server_data_producers() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    let room = vulcast.get_room();
+    room.send_server_data("server", "hello".to_owned())
+        .await
+        .unwrap();
+    assert_eq!(room.get_server_data_producers().len(), 1);
+    assert!(leak_detector::audit(&relay_server)
+        .await
+        .unwrap()
+        .is_clean());
+}
@@ -0,0 +1,197 @@
+use futures::stream::StreamExt;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+use vulcan_relay::events::{Envelope, Event};
+use vulcan_relay::message_store::{MemoryMessageStore, MessageStore};
+use vulcan_relay::relay_server::{ForeignRoomId, ForeignSessionId, SessionOptions};
+use vulcan_relay::room::MessageStatus;
+
+pub mod fixture;
+
+#[tokio::test]
+async fn commands_are_replayed_on_reconnect() {
+    let relay_server = fixture::relay_server().await;
+    let message_store = Arc::new(MemoryMessageStore::new(Duration::from_secs(60), 16));
+    relay_server.set_message_store(Some(message_store.clone()));
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+    let host_session_id = ForeignSessionId("host".into());
+
+    let vulcast_token = relay_server
+        .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    let vulcast = relay_server.session_from_token(vulcast_token).unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id.clone())
+        .unwrap();
+    let host = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    host_session_id.clone(),
+                    SessionOptions::Host(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    // vulcast disconnects, host sends a command in the meantime
+    drop(vulcast);
+    drop(relay_server.take_session(&vulcast_session_id));
+    let room = host.get_room();
+    let id = room.send_vulcast_command(host_session_id, json!({"type": "reboot"}));
+    assert_eq!(message_store.pending(&vulcast_session_id).len(), 1);
+
+    // vulcast reconnects and receives the buffered command
+    let vulcast = relay_server.session_from_token(vulcast_token).unwrap();
+    let mut vulcast_commands = Box::pin(vulcast.get_room().vulcast_commands());
+    let vulcast_command = vulcast_commands.next().await.unwrap();
+    assert_eq!(vulcast_command.id, id);
+    assert_eq!(room.message_status(id), Some(MessageStatus::Delivered));
+    assert!(message_store.pending(&vulcast_session_id).is_empty());
+}
+
+#[test]
+fn expired_commands_are_discarded() {
+    let message_store = MemoryMessageStore::new(Duration::from_millis(0), 16);
+    let fsid = ForeignSessionId("vulcast".into());
+    message_store.push(
+        &fsid,
+        vulcan_relay::room::VulcastCommand {
+            id: vulcan_relay::room::MessageId::new(),
+            sender: ForeignSessionId("host".into()),
+            command: json!({}),
+        },
+    );
+    assert!(message_store.pending(&fsid).is_empty());
+}
+
+#[tokio::test]
+async fn room_events_are_buffered_while_disconnected() {
+    let relay_server = fixture::relay_server().await;
+    let message_store = Arc::new(MemoryMessageStore::new(Duration::from_secs(60), 16));
+    relay_server.set_message_store(Some(message_store.clone()));
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+    let host_session_id = ForeignSessionId("host".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let host_token = relay_server
+        .register_session(
+            host_session_id.clone(),
+            SessionOptions::Host(foreign_room_id),
+        )
+        .unwrap();
+    let host = relay_server.session_from_token(host_token).unwrap();
+    let room = vulcast.get_room();
+
+    // host disconnects, and misses events of its room in the meantime
+    drop(host);
+    relay_server.release_session_by_token(&host_token);
+    let id = room.send_vulcast_command(host_session_id.clone(), json!({"type": "reboot"}));
+    room.report_audio_levels(vec![]);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // host reconnects and replays the events it missed, other than audio levels
+    let _host = relay_server.session_from_token(host_token).unwrap();
+    let (events, last_sequence) = relay_server.take_buffered_events(&host_session_id).unwrap();
+    assert!(events.iter().any(|envelope| envelope.event
+        == Event::VulcastCommand {
+            id: id.to_string(),
+            sender: host_session_id.0.clone(),
+            command: json!({"type": "reboot"}),
+        }));
+    assert!(!events
+        .iter()
+        .any(|envelope| matches!(envelope.event, Event::AudioLevels { .. })));
+    assert_eq!(
+        events.last().and_then(|envelope| envelope.sequence),
+        Some(last_sequence)
+    );
+    assert!(room.resume_events(last_sequence).is_ok());
+
+    // buffering stopped once replayed
+    room.send_vulcast_command(host_session_id.clone(), json!({"type": "reboot"}));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(relay_server
+        .take_buffered_events(&host_session_id)
+        .is_none());
+    assert!(message_store.take_events(&host_session_id).is_empty());
+}
+
+#[tokio::test]
+async fn unregistered_sessions_are_not_buffered() {
+    let relay_server = fixture::relay_server().await;
+    let message_store = Arc::new(MemoryMessageStore::new(Duration::from_secs(60), 16));
+    relay_server.set_message_store(Some(message_store.clone()));
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+    let host_session_id = ForeignSessionId("host".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let host_token = relay_server
+        .register_session(
+            host_session_id.clone(),
+            SessionOptions::Host(foreign_room_id),
+        )
+        .unwrap();
+    drop(relay_server.session_from_token(host_token).unwrap());
+    relay_server.release_session_by_token(&host_token);
+
+    relay_server
+        .unregister_session(host_session_id.clone())
+        .unwrap();
+    vulcast
+        .get_room()
+        .send_vulcast_command(ForeignSessionId("vulcast".into()), json!({}));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(relay_server
+        .take_buffered_events(&host_session_id)
+        .is_none());
+    assert!(message_store.take_events(&host_session_id).is_empty());
+}
+
+#[test]
+fn buffered_events_are_bounded() {
+    let message_store = MemoryMessageStore::new(Duration::from_secs(60), 2);
+    let fsid = ForeignSessionId("host".into());
+    for sequence in 1..=3 {
+        message_store.push_event(
+            &fsid,
+            Envelope::new(Event::ClientJoined {
+                session_id: "player".into(),
+            })
+            .with_sequence(sequence),
+        );
+    }
+    let sequences = message_store
+        .take_events(&fsid)
+        .into_iter()
+        .map(|envelope| envelope.sequence.unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(sequences, vec![2, 3]);
+    assert!(message_store.take_events(&fsid).is_empty());
+}
@@ -0,0 +1,85 @@
+use async_graphql::{Request, Variables};
+use serde_json::json;
+
+use vulcan_relay::control_schema;
+use vulcan_relay::crypto_policy::CryptoPolicy;
+use vulcan_relay::payload_limit::{PayloadLimit, PayloadRejections, PayloadTooLargeError};
+use vulcan_relay::resource_policy::ResourcePolicy;
+use vulcan_relay::schema_compat::DeprecationPolicy;
+
+pub mod fixture;
+
+fn request_with_variable(value: serde_json::Value) -> Request {
+    Request::new("mutation ($id: ID!) { registerVulcastSession(sessionId: $id) { __typename } }")
+        .variables(Variables::from_json(json!({ "id": value })))
+}
+
+#[test]
+fn oversized_requests_are_rejected() {
+    let payload_limit = PayloadLimit::new();
+    assert_eq!(payload_limit.check_request(usize::MAX), Ok(()));
+
+    payload_limit.set_max_request_bytes(Some(1024));
+    assert_eq!(payload_limit.check_request(1024), Ok(()));
+    assert_eq!(
+        payload_limit.check_request(1025),
+        Err(PayloadTooLargeError {
+            variable: None,
+            size: 1025,
+            max_bytes: 1024,
+        })
+    );
+    assert_eq!(
+        payload_limit.rejections(),
+        PayloadRejections {
+            requests: 1,
+            variables: 0,
+        }
+    );
+}
+
+#[test]
+fn oversized_variables_are_rejected() {
+    let payload_limit = PayloadLimit::new();
+    payload_limit.set_max_variable_bytes(Some(64));
+
+    let request = request_with_variable(json!("vulcast"));
+    assert_eq!(payload_limit.check_graphql_request(&request), Ok(()));
+
+    let request = request_with_variable(json!("x".repeat(1 << 20)));
+    let err = payload_limit.check_graphql_request(&request).unwrap_err();
+    assert_eq!(err.variable.as_deref(), Some("id"));
+    assert_eq!(err.max_bytes, 64);
+    // measuring stops soon after the limit
+    assert!(err.size > 64 && err.size < 1 << 20, "{}", err.size);
+    assert_eq!(payload_limit.rejections().variables, 1);
+}
+
+#[tokio::test]
+async fn oversized_variables_are_rejected_before_execution() {
+    let relay_server = fixture::relay_server().await;
+    relay_server
+        .payload_limit()
+        .set_max_variable_bytes(Some(1024));
+    let control_schema = control_schema::schema(
+        relay_server.clone(),
+        ResourcePolicy::new(),
+        CryptoPolicy::default(),
+        DeprecationPolicy::default(),
+    );
+
+    let response = control_schema
+        .execute(request_with_variable(json!("x".repeat(4096))))
+        .await;
+    assert_eq!(response.errors.len(), 1);
+    let extensions = serde_json::to_value(&response.errors[0].extensions).unwrap();
+    assert_eq!(extensions["code"], "PAYLOAD_TOO_LARGE");
+    assert_eq!(extensions["maxBytes"], 1024);
+    assert_eq!(extensions["variable"], "id");
+
+    let response = control_schema
+        .execute(request_with_variable(json!("vulcast")))
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(relay_server.payload_limit().rejections().variables, 1);
+}
@@ -0,0 +1,135 @@
+use async_graphql::Request;
+
+use vulcan_relay::cmdline::PermissionsArg;
+use vulcan_relay::crypto_policy::CryptoPolicy;
+use vulcan_relay::permissions::{Permission, PermissionDeniedError, Permissions};
+use vulcan_relay::relay_server::{ForeignRoomId, ForeignSessionId, Role, SessionOptions};
+use vulcan_relay::resource_policy::ResourcePolicy;
+use vulcan_relay::schema_compat::DeprecationPolicy;
+use vulcan_relay::session::{ConsumerConfig, PlainTransportConfig};
+use vulcan_relay::signal_schema;
+
+pub mod fixture;
+
+#[test]
+fn roles_have_default_permissions() {
+    let permissions = Permissions::new();
+    assert!(permissions.allows(Role::Vulcast, Permission::ProduceMedia));
+    assert!(!permissions.allows(Role::Vulcast, Permission::Moderate));
+    assert!(permissions.allows(Role::WebClient, Permission::ConsumeMedia));
+    assert!(permissions.allows(Role::WebClient, Permission::ProduceData));
+    assert!(!permissions.allows(Role::WebClient, Permission::ProduceMedia));
+    assert!(!permissions.allows(Role::WebClient, Permission::Moderate));
+    assert!(permissions.allows(Role::Host, Permission::Moderate));
+    assert!(!permissions.allows(Role::Host, Permission::ProduceMedia));
+    assert!(permissions.allows(Role::Vulcast, Permission::ReceiveCommands));
+    assert!(permissions.allows(Role::WebClient, Permission::Control));
+    assert!(!permissions.allows(Role::WebClient, Permission::SendCommands));
+    assert!(permissions.allows(Role::Host, Permission::SendCommands));
+    assert!(!permissions.allows(Role::Host, Permission::Control));
+}
+
+#[test]
+fn permissions_can_be_granted_and_revoked() {
+    let permissions = Permissions::new();
+    permissions.grant(Role::WebClient, Permission::ProduceMedia);
+    assert_eq!(
+        permissions.check(Role::WebClient, Permission::ProduceMedia),
+        Ok(())
+    );
+    permissions.revoke(Role::Host, Permission::Moderate);
+    assert_eq!(
+        permissions.check(Role::Host, Permission::Moderate),
+        Err(PermissionDeniedError {
+            role: Role::Host,
+            permission: Permission::Moderate,
+        })
+    );
+    // other roles are unaffected
+    assert!(!permissions.allows(Role::Host, Permission::ProduceMedia));
+}
+
+#[test]
+fn permissions_are_parsed_from_args() {
+    let arg: PermissionsArg = "webclient=produce-media,moderate".parse().unwrap();
+    assert_eq!(arg.role, Role::WebClient);
+    assert_eq!(
+        arg.permissions,
+        vec![Permission::ProduceMedia, Permission::Moderate]
+    );
+    assert!("webclient=produce".parse::<PermissionsArg>().is_err());
+    assert!("viewer=moderate".parse::<PermissionsArg>().is_err());
+}
+
+#[tokio::test]
+async fn operations_are_guarded_by_role() {
+    let relay_server = fixture::relay_server().await;
+    let signal_schema = signal_schema::schema(
+        relay_server.clone(),
+        ResourcePolicy::new(),
+        None,
+        PlainTransportConfig::default(),
+        ConsumerConfig::default(),
+        CryptoPolicy::default(),
+        DeprecationPolicy::default(),
+    );
+    let frid = ForeignRoomId("ayush".into());
+    let vulcast_fsid = ForeignSessionId("vulcast".into());
+    relay_server
+        .register_session(vulcast_fsid.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(frid.clone(), vulcast_fsid)
+        .unwrap();
+    let session = |fsid: &str, session_options| {
+        let token = relay_server
+            .register_session(ForeignSessionId(fsid.into()), session_options)
+            .unwrap();
+        relay_server.session_from_token(token).unwrap()
+    };
+    let web_client = session("webclient", SessionOptions::WebClient(frid.clone()));
+    let host = session("host", SessionOptions::Host(frid.clone()));
+
+    let response = signal_schema
+        .execute(Request::new("mutation { lockRoom }").data(web_client.downgrade()))
+        .await;
+    assert_eq!(response.errors.len(), 1);
+    let extensions = serde_json::to_value(&response.errors[0].extensions).unwrap();
+    assert_eq!(extensions["code"], "FORBIDDEN");
+    assert_eq!(extensions["permission"], "moderate");
+    assert!(!relay_server.room_locked(&frid));
+
+    let response = signal_schema
+        .execute(Request::new("mutation { lockRoom }").data(host.downgrade()))
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert!(relay_server.room_locked(&frid));
+
+    // granted permissions take effect immediately
+    relay_server
+        .permissions()
+        .grant(Role::WebClient, Permission::Moderate);
+    let response = signal_schema
+        .execute(Request::new("mutation { unlockRoom }").data(web_client.downgrade()))
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert!(!relay_server.room_locked(&frid));
+
+    let response = signal_schema
+        .execute(
+            Request::new(r#"mutation { sendVulcastCommand(command: "reboot") }"#)
+                .data(web_client.downgrade()),
+        )
+        .await;
+    assert_eq!(response.errors.len(), 1);
+    let extensions = serde_json::to_value(&response.errors[0].extensions).unwrap();
+    assert_eq!(extensions["code"], "FORBIDDEN");
+    assert_eq!(extensions["permission"], "send-commands");
+
+    let response = signal_schema
+        .execute(Request::new("mutation { requestControl }").data(host.downgrade()))
+        .await;
+    assert_eq!(response.errors.len(), 1);
+    let extensions = serde_json::to_value(&response.errors[0].extensions).unwrap();
+    assert_eq!(extensions["permission"], "control");
+}
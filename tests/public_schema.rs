@@ -0,0 +1,81 @@
+use serde_json::json;
+
+use vulcan_relay::public_schema;
+use vulcan_relay::relay_server::{
+    ForeignRoomId, ForeignSessionId, SessionOptions, SetRoomPublicError,
+};
+
+pub mod fixture;
+
+#[tokio::test]
+async fn public_rooms_are_listed_with_viewers() {
+    let relay_server = fixture::relay_server().await;
+    let public_schema = public_schema::schema(relay_server.clone());
+
+    let vulcast_token = relay_server
+        .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(
+            ForeignRoomId("ayush".into()),
+            ForeignSessionId("vulcast".into()),
+        )
+        .unwrap();
+    let web_token = relay_server
+        .register_session(
+            ForeignSessionId("web".into()),
+            SessionOptions::WebClient(ForeignRoomId("ayush".into())),
+        )
+        .unwrap();
+    let _vulcast = relay_server.session_from_token(vulcast_token).unwrap();
+    let _web = relay_server.session_from_token(web_token).unwrap();
+
+    // rooms are unlisted until made public
+    let response = public_schema
+        .execute("{ publicRooms { id viewers } }")
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data.into_json().unwrap(),
+        json!({ "publicRooms": [] })
+    );
+
+    relay_server
+        .set_room_public(ForeignRoomId("ayush".into()), true)
+        .unwrap();
+    let response = public_schema
+        .execute("{ publicRooms { id viewers } }")
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data.into_json().unwrap(),
+        json!({ "publicRooms": [{ "id": "ayush", "viewers": 1 }] })
+    );
+
+    relay_server
+        .unregister_room(ForeignRoomId("ayush".into()))
+        .unwrap();
+    assert!(relay_server.public_rooms().is_empty());
+}
+
+#[tokio::test]
+async fn unknown_rooms_cannot_be_made_public() {
+    let relay_server = fixture::relay_server().await;
+    assert_eq!(
+        relay_server.set_room_public(ForeignRoomId("nowhere".into()), true),
+        Err(SetRoomPublicError::UnknownRoom(ForeignRoomId(
+            "nowhere".into()
+        )))
+    );
+}
+
+#[tokio::test]
+async fn public_schema_has_no_mutations() {
+    let relay_server = fixture::relay_server().await;
+    let public_schema = public_schema::schema(relay_server);
+
+    let response = public_schema
+        .execute(r#"mutation { registerRoom(roomId: "ayush", vulcastSessionId: "vulcast") { __typename } }"#)
+        .await;
+    assert!(!response.errors.is_empty());
+}
@@ -0,0 +1,35 @@
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::Duration;
+
+use vulcan_relay::reachability::{self, ReachabilityError};
+
+pub mod fixture;
+
+const LOCALHOST: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+#[tokio::test]
+async fn announce_ip_routing_to_listen_ip_is_reachable() {
+    let rtt = reachability::probe(LOCALHOST, LOCALHOST, 0..=0, Duration::from_secs(1))
+        .await
+        .unwrap();
+    assert!(rtt < Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn announce_ip_routing_elsewhere_is_unreachable() {
+    // bound to 127.0.0.1, so probes to another loopback address never arrive
+    let result = reachability::probe(
+        LOCALHOST,
+        IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2)),
+        0..=0,
+        Duration::from_millis(300),
+    )
+    .await;
+    assert!(matches!(result, Err(ReachabilityError::Unreachable(_, _))));
+}
+
+#[tokio::test]
+async fn relays_without_announce_ip_are_not_probed() {
+    let relay_server = fixture::relay_server().await;
+    assert_eq!(reachability::check(&relay_server).await.unwrap(), None);
+}
@@ -1,9 +1,13 @@
+use futures::stream::StreamExt;
+use std::time::Duration;
 use uuid::Uuid;
 
 use vulcan_relay::relay_server::{
-    ForeignRoomId, ForeignSessionId, RegisterRoomError, RegisterSessionError, SessionOptions,
-    SessionToken, UnregisterRoomError, UnregisterSessionError,
+    DisconnectReason, DuplicateConnectionPolicy, ForeignRoomId, ForeignSessionId,
+    RegisterRoomError, RegisterSessionError, SessionOptions, SessionToken, SetRoomLockedError,
+    UnregisterRoomError, UnregisterSessionError,
 };
+use vulcan_relay::room::{ClientState, RoomPreset};
 
 pub mod fixture;
 
@@ -128,3 +132,292 @@ async fn maximum_one_room_per_vulcast() {
         Ok(())
     );
 }
+
+#[tokio::test]
+async fn disconnect_reasons_are_recorded() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+    let webclient_session_id = ForeignSessionId("webclient".into());
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_token = relay_server
+        .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id.clone())
+        .unwrap();
+    let webclient_token = relay_server
+        .register_session(
+            webclient_session_id.clone(),
+            SessionOptions::WebClient(foreign_room_id),
+        )
+        .unwrap();
+    let reason = |fsid: &ForeignSessionId| {
+        relay_server
+            .last_disconnect(fsid)
+            .map(|disconnect| disconnect.reason)
+    };
+
+    // sessions which never connected were never disconnected
+    assert_eq!(reason(&webclient_session_id), None);
+
+    drop(relay_server.session_from_token(webclient_token));
+    drop(relay_server.session_from_token(webclient_token));
+    assert_eq!(
+        reason(&webclient_session_id),
+        Some(DisconnectReason::Replaced)
+    );
+    drop(relay_server.disconnect_session_by_token(&webclient_token));
+    assert_eq!(
+        reason(&webclient_session_id),
+        Some(DisconnectReason::ClientClosed)
+    );
+
+    drop(relay_server.session_from_token(vulcast_token));
+    drop(relay_server.session_from_token(webclient_token));
+    relay_server
+        .unregister_session(vulcast_session_id.clone())
+        .unwrap();
+    assert_eq!(
+        reason(&vulcast_session_id),
+        Some(DisconnectReason::Unregistered)
+    );
+    assert_eq!(
+        reason(&webclient_session_id),
+        Some(DisconnectReason::RoomUnregistered)
+    );
+}
+
+#[tokio::test]
+async fn web_clients_expire_after_max_session_duration() {
+    let relay_server = fixture::relay_server().await;
+    relay_server.set_max_session_duration(RoomPreset::Standard, Some(Duration::from_millis(200)));
+
+    let frid = ForeignRoomId("ayush".into());
+    let vulcast_fsid = ForeignSessionId("vulcast".into());
+    let web_client_fsid = ForeignSessionId("webclient".into());
+    let vulcast_token = relay_server
+        .register_session(vulcast_fsid.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(frid.clone(), vulcast_fsid.clone())
+        .unwrap();
+    let web_client_token = relay_server
+        .register_session(web_client_fsid.clone(), SessionOptions::WebClient(frid))
+        .unwrap();
+    let _vulcast = relay_server.session_from_token(vulcast_token).unwrap();
+    let web_client = relay_server.session_from_token(web_client_token).unwrap();
+
+    // the warning precedes the deadline, which lies within the maximum duration
+    let mut warnings = Box::pin(web_client.expiry_warnings());
+    let remaining = warnings.next().await.unwrap();
+    assert!(remaining <= Duration::from_millis(200));
+    assert!(relay_server.get_session(&web_client_fsid).is_some());
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(relay_server.get_session(&web_client_fsid).is_none());
+    assert_eq!(
+        relay_server
+            .last_disconnect(&web_client_fsid)
+            .map(|disconnect| disconnect.reason),
+        Some(DisconnectReason::Expired)
+    );
+    // vulcasts are never limited
+    assert!(relay_server.get_session(&vulcast_fsid).is_some());
+}
+
+#[tokio::test]
+async fn sessions_resume_within_reconnect_grace() {
+    let relay_server = fixture::relay_server().await;
+    relay_server.set_reconnect_grace(Some(Duration::from_millis(200)));
+
+    let fsid = ForeignSessionId("vulcast".into());
+    let token = relay_server
+        .register_session(fsid.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    let session = relay_server.session_from_token(token).unwrap();
+    let transport = session.create_webrtc_transport().await;
+    let session_id = session.id();
+    drop(session);
+
+    // the session outlives the connection, and is resumed by the reconnect
+    relay_server.release_session_by_token(&token);
+    assert!(relay_server.get_session(&fsid).is_some());
+    let session = relay_server.session_from_token(token).unwrap();
+    assert_eq!(session.id(), session_id);
+    assert!(session.get_webrtc_transport(transport.id()).is_some());
+    assert_eq!(relay_server.last_disconnect(&fsid), None);
+    drop(session);
+
+    // the session is dropped once the grace period passes without a reconnect
+    relay_server.release_session_by_token(&token);
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(relay_server.get_session(&fsid).is_none());
+    assert_eq!(
+        relay_server
+            .last_disconnect(&fsid)
+            .map(|disconnect| disconnect.reason),
+        Some(DisconnectReason::ClientClosed)
+    );
+}
+
+#[tokio::test]
+async fn duplicate_connections_follow_policy() {
+    let relay_server = fixture::relay_server().await;
+
+    let fsid = ForeignSessionId("vulcast".into());
+    let token = relay_server
+        .register_session(fsid.clone(), SessionOptions::Vulcast)
+        .unwrap();
+
+    // by default, the new connection replaces the existing session
+    let first = relay_server.session_from_token(token).unwrap();
+    let second = relay_server.session_from_token(token).unwrap();
+    assert_eq!(
+        relay_server.get_session(&fsid).map(|session| session.id()),
+        Some(second.id())
+    );
+    assert_eq!(
+        relay_server
+            .last_disconnect(&fsid)
+            .map(|disconnect| disconnect.reason),
+        Some(DisconnectReason::Replaced)
+    );
+    // releasing the replaced session leaves the new session connected
+    relay_server.release_session(&token, first.id());
+    assert!(relay_server.get_session(&fsid).is_some());
+
+    relay_server.set_duplicate_connection_policy(DuplicateConnectionPolicy::RejectNew);
+    assert!(relay_server.session_from_token(token).is_none());
+    assert_eq!(
+        relay_server.get_session(&fsid).map(|session| session.id()),
+        Some(second.id())
+    );
+
+    relay_server.set_duplicate_connection_policy(DuplicateConnectionPolicy::AllowParallel(2));
+    let parallel = relay_server.session_from_token(token).unwrap();
+    assert_ne!(parallel.id(), second.id());
+    assert!(relay_server.session_from_token(token).is_none());
+
+    // the parallel session is promoted once the owning session ends
+    relay_server.release_session(&token, second.id());
+    assert_eq!(
+        relay_server.get_session(&fsid).map(|session| session.id()),
+        Some(parallel.id())
+    );
+    relay_server.release_session(&token, parallel.id());
+    assert!(relay_server.get_session(&fsid).is_none());
+}
+
+#[tokio::test]
+async fn kicked_sessions_leave_their_room() {
+    let relay_server = fixture::relay_server().await;
+    let frid = ForeignRoomId("ayush".into());
+    let vulcast_fsid = ForeignSessionId("vulcast".into());
+    let web_client_fsid = ForeignSessionId("web".into());
+
+    let vulcast_token = relay_server
+        .register_session(vulcast_fsid.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(frid.clone(), vulcast_fsid.clone())
+        .unwrap();
+    let web_client_token = relay_server
+        .register_session(web_client_fsid.clone(), SessionOptions::WebClient(frid))
+        .unwrap();
+    let vulcast = relay_server.session_from_token(vulcast_token).unwrap();
+    let web_client_id = relay_server
+        .session_from_token(web_client_token)
+        .unwrap()
+        .id();
+
+    let updates = vulcast.get_room().client_state_updates();
+    let mut updates = Box::pin(updates.skip(2));
+    relay_server
+        .kick_session(web_client_fsid.clone(), false)
+        .unwrap();
+    let update = updates.next().await.unwrap();
+    assert_eq!(
+        (update.state, update.session_id),
+        (ClientState::Leave, web_client_id)
+    );
+    assert!(relay_server.get_session(&web_client_fsid).is_none());
+    assert_eq!(
+        relay_server
+            .last_disconnect(&web_client_fsid)
+            .map(|disconnect| disconnect.reason),
+        Some(DisconnectReason::Kicked)
+    );
+
+    // the client may connect again unless unregistered
+    let _web_client = relay_server.session_from_token(web_client_token).unwrap();
+    relay_server
+        .kick_session(web_client_fsid.clone(), true)
+        .unwrap();
+    assert!(relay_server.session_from_token(web_client_token).is_none());
+    assert_eq!(
+        relay_server.kick_session(web_client_fsid.clone(), false),
+        Err(UnregisterSessionError::UnknownSession(web_client_fsid))
+    );
+}
+
+#[tokio::test]
+async fn locked_rooms_keep_out_new_web_clients() {
+    let relay_server = fixture::relay_server().await;
+    let frid = ForeignRoomId("ayush".into());
+    let vulcast_fsid = ForeignSessionId("vulcast".into());
+
+    assert_eq!(
+        relay_server.set_room_locked(frid.clone(), true),
+        Err(SetRoomLockedError::UnknownRoom(frid.clone()))
+    );
+
+    let vulcast_token = relay_server
+        .register_session(vulcast_fsid.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(frid.clone(), vulcast_fsid)
+        .unwrap();
+    let _vulcast = relay_server.session_from_token(vulcast_token).unwrap();
+    let early_token = relay_server
+        .register_session(
+            ForeignSessionId("early".into()),
+            SessionOptions::WebClient(frid.clone()),
+        )
+        .unwrap();
+    let late_token = relay_server
+        .register_session(
+            ForeignSessionId("late".into()),
+            SessionOptions::WebClient(frid.clone()),
+        )
+        .unwrap();
+    let host_token = relay_server
+        .register_session(
+            ForeignSessionId("host".into()),
+            SessionOptions::Host(frid.clone()),
+        )
+        .unwrap();
+    let early = relay_server.session_from_token(early_token).unwrap();
+
+    relay_server.set_room_locked(frid.clone(), true).unwrap();
+    assert!(relay_server.room_locked(&frid));
+    assert!(relay_server.session_from_token(late_token).is_none());
+    assert!(relay_server.is_locked_out(&late_token));
+    // connected web clients stay connected, and hosts may still connect
+    assert_eq!(
+        relay_server
+            .get_session_by_token(&early_token)
+            .map(|session| session.id()),
+        Some(early.id())
+    );
+    assert!(!relay_server.is_locked_out(&host_token));
+    assert!(relay_server.session_from_token(host_token).is_some());
+
+    relay_server.set_room_locked(frid.clone(), false).unwrap();
+    assert!(relay_server.session_from_token(late_token).is_some());
+
+    // rooms are unlocked when registered again
+    relay_server.set_room_locked(frid.clone(), true).unwrap();
+    relay_server.unregister_room(frid.clone()).unwrap();
+    assert!(!relay_server.room_locked(&frid));
+}
@@ -0,0 +1,49 @@
+use vulcan_relay::relay_server::{ForeignRoomId, Role};
+use vulcan_relay::resource_policy::{LimitScope, ResourcePolicy};
+use vulcan_relay::session::ResourceType;
+
+#[test]
+fn most_specific_limit_applies() {
+    let resource_policy = ResourcePolicy::new();
+    let room = ForeignRoomId("room".into());
+
+    assert_eq!(
+        resource_policy.limit(Role::WebClient, Some(&room), ResourceType::Consumer),
+        2
+    );
+
+    resource_policy.set_limit(
+        LimitScope::Role(Role::WebClient),
+        ResourceType::Consumer,
+        Some(4),
+    );
+    assert_eq!(
+        resource_policy.limit(Role::WebClient, Some(&room), ResourceType::Consumer),
+        4
+    );
+    assert_eq!(
+        resource_policy.limit(Role::Host, Some(&room), ResourceType::Consumer),
+        2
+    );
+
+    resource_policy.set_limit(
+        LimitScope::Room(room.clone()),
+        ResourceType::Consumer,
+        Some(8),
+    );
+    assert_eq!(
+        resource_policy.limit(Role::WebClient, Some(&room), ResourceType::Consumer),
+        8
+    );
+    assert_eq!(
+        resource_policy.limit(Role::WebClient, None, ResourceType::Consumer),
+        4
+    );
+
+    // clearing a limit defers to the less specific limit
+    resource_policy.set_limit(LimitScope::Room(room.clone()), ResourceType::Consumer, None);
+    assert_eq!(
+        resource_policy.limit(Role::WebClient, Some(&room), ResourceType::Consumer),
+        4
+    );
+}
@@ -0,0 +1,1118 @@
+use futures::stream::StreamExt;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+use mediasoup::rtp_parameters::{MediaKind, RtpCodecCapabilityFinalized};
+
+use vulcan_relay::data_filter::RateLimitFilter;
+use vulcan_relay::events::{Event, SCHEMA_VERSION};
+use vulcan_relay::relay_server::{
+    ForeignRoomId, ForeignSessionId, RegisterRoomError, Role, SessionOptions,
+};
+use vulcan_relay::room::{AudioLevel, ClientState, MessageStatus, ModerationKind, RoomPreset};
+use vulcan_relay::session::ProducerAppData;
+
+pub mod fixture;
+
+#[tokio::test]
+async fn vulcast_receives_host_commands() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+    let host_session_id = ForeignSessionId("host".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let host = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    host_session_id.clone(),
+                    SessionOptions::Host(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let mut vulcast_commands = Box::pin(vulcast.get_room().vulcast_commands());
+    let id = host
+        .get_room()
+        .send_vulcast_command(host_session_id.clone(), json!({"type": "reboot"}));
+    assert_eq!(
+        host.get_room().message_status(id),
+        Some(MessageStatus::Pending)
+    );
+
+    let vulcast_command = vulcast_commands.next().await.unwrap();
+    assert_eq!(
+        host.get_room().message_status(id),
+        Some(MessageStatus::Delivered)
+    );
+    vulcast.get_room().acknowledge_message(id).unwrap();
+    assert_eq!(
+        host.get_room().message_status(id),
+        Some(MessageStatus::Acknowledged)
+    );
+
+    assert_eq!(vulcast_command.id, id);
+    assert_eq!(vulcast_command.sender, host_session_id);
+    assert_eq!(vulcast_command.command, json!({"type": "reboot"}));
+}
+
+#[tokio::test]
+async fn room_messages_are_published_as_events() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+    let host_session_id = ForeignSessionId("host".into());
+
+    relay_server
+        .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let host = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    host_session_id.clone(),
+                    SessionOptions::Host(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let mut events = Box::pin(host.get_room().events());
+    let id = host
+        .get_room()
+        .send_vulcast_command(host_session_id, json!({"type": "reboot"}));
+
+    let envelope = events.next().await.unwrap();
+    assert_eq!(envelope.version, SCHEMA_VERSION);
+    assert_eq!(
+        envelope.event,
+        Event::VulcastCommand {
+            id: id.to_string(),
+            sender: "host".into(),
+            command: json!({"type": "reboot"}),
+        }
+    );
+}
+
+#[tokio::test]
+async fn missed_events_are_replayed_on_resume() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+    let host_session_id = ForeignSessionId("host".into());
+
+    relay_server
+        .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let host = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    host_session_id.clone(),
+                    SessionOptions::Host(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+    let room = host.get_room();
+
+    let mut events = Box::pin(room.events());
+    room.send_vulcast_command(host_session_id.clone(), json!({"type": "reboot"}));
+    let last_seen = events.next().await.unwrap().sequence.unwrap();
+    drop(events);
+
+    // missed while disconnected
+    let id = room.send_vulcast_command(host_session_id, json!({"type": "shutdown"}));
+
+    let mut events = Box::pin(room.resume_events(last_seen).unwrap());
+    let envelope = events.next().await.unwrap();
+    assert_eq!(envelope.sequence, Some(last_seen + 1));
+    assert_eq!(
+        envelope.event,
+        Event::VulcastCommand {
+            id: id.to_string(),
+            sender: "host".into(),
+            command: json!({"type": "shutdown"}),
+        }
+    );
+    assert!(room.resume_events(last_seen + 2).is_err());
+}
+
+#[tokio::test]
+async fn closed_producers_are_announced() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let webclient = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    ForeignSessionId("webclient".into()),
+                    SessionOptions::WebClient(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    let producer_id = producer.id();
+    drop(producer);
+
+    let mut closed_producers = Box::pin(webclient.get_room().closed_producers());
+    vulcast.producer_close(producer_id).unwrap();
+    assert_eq!(closed_producers.next().await, Some(producer_id));
+    assert!(vulcast.get_producer(producer_id).is_none());
+    assert!(vulcast.producer_close(producer_id).is_err());
+}
+
+#[tokio::test]
+async fn producers_of_dropped_sessions_are_announced() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id.clone())
+        .unwrap();
+    let webclient = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    ForeignSessionId("webclient".into()),
+                    SessionOptions::WebClient(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let producer_id = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+        )
+        .await
+        .unwrap()
+        .id();
+
+    let mut closed_producers = Box::pin(webclient.get_room().closed_producers());
+    relay_server.unregister_session(vulcast_session_id).unwrap();
+    drop(vulcast);
+    assert_eq!(closed_producers.next().await, Some(producer_id));
+}
+
+#[tokio::test]
+async fn closed_data_producers_are_announced() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let webclient = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    ForeignSessionId("webclient".into()),
+                    SessionOptions::WebClient(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let send_transport = webclient.create_webrtc_transport().await;
+    let data_producer_id = webclient
+        .produce_data(send_transport.id(), fixture::sctp_stream_parameters())
+        .await
+        .unwrap()
+        .id();
+
+    // data producers are announced closed along with their transports
+    let mut closed_data_producers = Box::pin(vulcast.get_room().closed_data_producers());
+    webclient.close_transport(send_transport.id()).unwrap();
+    assert_eq!(closed_data_producers.next().await, Some(data_producer_id));
+    assert!(webclient.get_data_producers().is_empty());
+}
+
+#[tokio::test]
+async fn locked_room_blocks_webclient_input() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let webclient = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    ForeignSessionId("webclient".into()),
+                    SessionOptions::WebClient(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let vulcast_send_transport = vulcast.create_webrtc_transport().await;
+    let webclient_send_transport = webclient.create_webrtc_transport().await;
+    let vulcast_data_producer = vulcast
+        .produce_data(
+            vulcast_send_transport.id(),
+            fixture::sctp_stream_parameters(),
+        )
+        .await
+        .unwrap();
+    let webclient_data_producer = webclient
+        .produce_data(
+            webclient_send_transport.id(),
+            fixture::sctp_stream_parameters(),
+        )
+        .await
+        .unwrap();
+
+    vulcast
+        .consume_data(vulcast_send_transport.id(), webclient_data_producer.id())
+        .await
+        .unwrap();
+
+    // data is sent directly while input is unrestricted
+    let room = webclient.get_room();
+    assert!(room
+        .get_data_relay_stat(webclient_data_producer.id())
+        .is_none());
+    assert!(room
+        .get_data_relay_stat(vulcast_data_producer.id())
+        .is_none());
+
+    // locking leaves data producers open, and only blocks webclient input
+    let mut closed_data_producers = Box::pin(room.closed_data_producers());
+    room.set_input_locked(true);
+    assert!(room.input_locked());
+    assert!(!room.accepts_input(webclient.id(), Role::WebClient));
+    assert!(room.accepts_input(vulcast.id(), Role::Vulcast));
+    assert_eq!(webclient.get_data_producers().len(), 1);
+    assert_eq!(vulcast.get_data_producers().len(), 1);
+
+    // webclient data is relayed from then on, so that it can be dropped, and
+    // sessions consuming it directly are switched over to the relay
+    assert_eq!(
+        closed_data_producers.next().await,
+        Some(webclient_data_producer.id())
+    );
+    assert!(room
+        .get_data_relay_stat(webclient_data_producer.id())
+        .is_some());
+    assert!(room
+        .get_data_relay_stat(vulcast_data_producer.id())
+        .is_none());
+    assert!(vulcast.get_data_consumers().is_empty());
+    let data_producer = webclient
+        .produce_data(
+            webclient_send_transport.id(),
+            fixture::sctp_stream_parameters(),
+        )
+        .await
+        .unwrap();
+    assert!(room.get_data_relay_stat(data_producer.id()).is_some());
+
+    room.set_input_locked(false);
+    assert!(room.accepts_input(webclient.id(), Role::WebClient));
+    assert_eq!(webclient.get_data_producers().len(), 2);
+}
+
+#[tokio::test]
+async fn only_controller_may_send_input() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+    let player1_session_id = ForeignSessionId("player1".into());
+
+    let _vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let player1 = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    player1_session_id.clone(),
+                    SessionOptions::WebClient(foreign_room_id.clone()),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+    let player2 = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    ForeignSessionId("player2".into()),
+                    SessionOptions::WebClient(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let player1_send_transport = player1.create_webrtc_transport().await;
+    let player2_send_transport = player2.create_webrtc_transport().await;
+    player2
+        .produce_data(
+            player2_send_transport.id(),
+            fixture::sctp_stream_parameters(),
+        )
+        .await
+        .unwrap();
+
+    let room = player1.get_room();
+    let mut controllers = Box::pin(room.controllers());
+    assert_eq!(controllers.next().await, Some(None));
+
+    // granting control blocks input from other web clients, leaving their data
+    // producers open
+    room.set_controller(Some(&player1));
+    assert_eq!(
+        controllers.next().await,
+        Some(Some(player1_session_id.clone()))
+    );
+    assert_eq!(room.controller(), Some(player1.id()));
+    assert!(room.accepts_input(player1.id(), Role::WebClient));
+    assert!(!room.accepts_input(player2.id(), Role::WebClient));
+    assert_eq!(player2.get_data_producers().len(), 1);
+    assert!(player1
+        .produce_data(
+            player1_send_transport.id(),
+            fixture::sctp_stream_parameters(),
+        )
+        .await
+        .is_ok());
+
+    // control is released when the controller leaves
+    drop(player1_send_transport);
+    drop(player1);
+    drop(relay_server.take_session(&player1_session_id));
+    assert_eq!(controllers.next().await, Some(None));
+    assert!(room.accepts_input(player2.id(), Role::WebClient));
+    assert_eq!(player2.get_data_producers().len(), 1);
+}
+
+#[tokio::test]
+async fn producer_bitrate_ends_when_producer_closes() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    let room = vulcast.get_room();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let producer_id = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap()
+        .id();
+
+    let mut bitrates = Box::pin(
+        room.producer_bitrate(producer_id, Duration::from_millis(100))
+            .unwrap(),
+    );
+    // no media is sent by the test producer
+    assert_eq!(bitrates.next().await, Some(0));
+
+    vulcast.producer_close(producer_id).unwrap();
+    assert_eq!(bitrates.next().await, None);
+    assert!(room
+        .producer_bitrate(producer_id, Duration::from_millis(100))
+        .is_err());
+}
+
+#[tokio::test]
+async fn active_speaker_is_announced_when_loudest_producer_changes() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    let room = vulcast.get_room();
+
+    // each producer is sent on its own transport, as they share a mid and ssrc
+    let mut producer_ids = Vec::new();
+    for _ in 0..2 {
+        let send_transport = vulcast.create_webrtc_transport().await;
+        let producer = vulcast
+            .produce(
+                send_transport.id(),
+                MediaKind::Audio,
+                fixture::audio_producer_device_parameters(),
+            )
+            .await
+            .unwrap();
+        producer_ids.push(producer.id());
+    }
+    let level = |producer_index: usize, volume: i8| AudioLevel {
+        producer_id: producer_ids[producer_index],
+        volume,
+    };
+
+    let active_speaker = room.active_speaker();
+    let audio_levels = room.audio_levels(Duration::ZERO);
+    tokio::pin!(active_speaker);
+    tokio::pin!(audio_levels);
+
+    room.report_audio_levels(vec![level(0, -20), level(1, -40)]);
+    // louder or quieter, the loudest producer is unchanged
+    room.report_audio_levels(vec![level(0, -10), level(1, -40)]);
+    room.report_audio_levels(vec![level(0, -30)]);
+    room.report_audio_levels(vec![level(1, -20), level(0, -30)]);
+    room.report_audio_levels(vec![]);
+    room.report_audio_levels(vec![]);
+    room.report_audio_levels(vec![level(1, -20)]);
+
+    assert_eq!(active_speaker.next().await, Some(Some(level(0, -20))));
+    assert_eq!(active_speaker.next().await, Some(Some(level(1, -20))));
+    assert_eq!(active_speaker.next().await, Some(None));
+    assert_eq!(active_speaker.next().await, Some(Some(level(1, -20))));
+
+    // audio levels are still reported on every report
+    assert_eq!(
+        audio_levels.next().await,
+        Some(vec![level(0, -20), level(1, -40)])
+    );
+    assert_eq!(
+        audio_levels.next().await,
+        Some(vec![level(0, -10), level(1, -40)])
+    );
+}
+
+#[tokio::test]
+async fn audio_only_room_carries_no_video() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast_token = relay_server
+        .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room_with_preset(foreign_room_id, vulcast_session_id, RoomPreset::AudioOnly)
+        .unwrap();
+    let vulcast = relay_server.session_from_token(vulcast_token).unwrap();
+    assert_eq!(vulcast.get_room().preset(), RoomPreset::AudioOnly);
+
+    let router = vulcast.get_room().get_router().await;
+    assert!(router
+        .rtp_capabilities()
+        .codecs
+        .iter()
+        .all(|codec| matches!(codec, RtpCodecCapabilityFinalized::Audio { .. })));
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    assert!(vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+        )
+        .await
+        .is_err());
+    assert!(vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn preset_cannot_change_after_negotiation() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    vulcast.create_webrtc_transport().await;
+
+    assert_eq!(
+        relay_server.register_room_with_preset(
+            foreign_room_id.clone(),
+            vulcast_session_id.clone(),
+            RoomPreset::AudioOnly,
+        ),
+        Err(RegisterRoomError::PresetUnavailable(
+            foreign_room_id.clone()
+        ))
+    );
+    relay_server
+        .register_room(foreign_room_id, vulcast_session_id)
+        .unwrap();
+}
+
+#[tokio::test]
+async fn sync_group_reports_grouped_producers() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast_token = relay_server
+        .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id, vulcast_session_id)
+        .unwrap();
+    let vulcast = relay_server.session_from_token(vulcast_token).unwrap();
+    let send_transport = vulcast.create_webrtc_transport().await;
+
+    let game = || ProducerAppData {
+        sync_group: Some("game".into()),
+        label: None,
+        role: None,
+    };
+    let audio = vulcast
+        .produce_with_app_data(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+            game(),
+        )
+        .await
+        .unwrap();
+    let video = vulcast
+        .produce_with_app_data(
+            send_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+            game(),
+        )
+        .await
+        .unwrap();
+    vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(ProducerAppData::of(&audio), Some(&game()));
+
+    let mut sync_infos = vulcast.get_room().sync_group("game").await;
+    sync_infos.sort_by_key(|sync_info| sync_info.clock_rate);
+    assert_eq!(
+        sync_infos
+            .iter()
+            .map(|sync_info| (sync_info.producer_id, sync_info.clock_rate))
+            .collect::<Vec<_>>(),
+        vec![(audio.id(), Some(48000)), (video.id(), Some(90000))]
+    );
+    assert!(vulcast.get_room().sync_group("camera").await.is_empty());
+}
+
+#[tokio::test]
+async fn producer_announcements_carry_owner_and_label() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast_token = relay_server
+        .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id, vulcast_session_id.clone())
+        .unwrap();
+    let vulcast = relay_server.session_from_token(vulcast_token).unwrap();
+    let send_transport = vulcast.create_webrtc_transport().await;
+
+    let audio = vulcast
+        .produce_with_app_data(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+            ProducerAppData {
+                sync_group: None,
+                label: Some("mic".into()),
+                role: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    // producers created before subscribing are announced too
+    let announcements = vulcast.get_room().producer_announcements();
+    tokio::pin!(announcements);
+    let announcement = announcements.next().await.unwrap();
+    assert_eq!(announcement.producer_id, audio.id());
+    assert_eq!(announcement.session_id, vulcast.id());
+    assert_eq!(announcement.foreign_session_id, vulcast_session_id);
+    assert_eq!(announcement.kind, MediaKind::Audio);
+    assert_eq!(announcement.label.as_deref(), Some("mic"));
+
+    let video = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    let announcement = announcements.next().await.unwrap();
+    assert_eq!(announcement.producer_id, video.id());
+    assert_eq!(announcement.kind, MediaKind::Video);
+    assert_eq!(announcement.label, None);
+}
+
+#[tokio::test]
+async fn room_state_is_replayed_to_late_joiners() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast_token = relay_server
+        .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id, vulcast_session_id.clone())
+        .unwrap();
+    let vulcast = relay_server.session_from_token(vulcast_token).unwrap();
+    let room = vulcast.get_room();
+
+    room.set_state(
+        vulcast_session_id.clone(),
+        "game".into(),
+        Some(json!("tetris")),
+    )
+    .unwrap();
+    room.set_state(
+        vulcast_session_id.clone(),
+        "scoreboard".into(),
+        Some(json!({"p1": 0})),
+    )
+    .unwrap();
+    room.set_state(
+        vulcast_session_id.clone(),
+        "scoreboard".into(),
+        Some(json!({"p1": 1})),
+    )
+    .unwrap();
+    assert_eq!(
+        room.get_state("scoreboard").unwrap().value,
+        Some(json!({"p1": 1}))
+    );
+
+    // a late joiner sees the latest value of each key, then subsequent writes
+    let mut state_changes = Box::pin(room.state_changes());
+    let replayed = state_changes.next().await.unwrap();
+    assert_eq!(
+        (replayed.key.as_str(), replayed.value),
+        ("game", Some(json!("tetris")))
+    );
+    let replayed = state_changes.next().await.unwrap();
+    assert_eq!(
+        (replayed.key.as_str(), replayed.value),
+        ("scoreboard", Some(json!({"p1": 1})))
+    );
+    room.set_state(vulcast_session_id.clone(), "game".into(), None)
+        .unwrap();
+    let removed = state_changes.next().await.unwrap();
+    assert_eq!((removed.key.as_str(), removed.value), ("game", None));
+    assert_eq!(removed.writer, vulcast_session_id);
+    assert!(room.get_state("game").is_none());
+
+    // the store is bounded
+    assert!(room
+        .set_state(
+            vulcast_session_id.clone(),
+            "big".into(),
+            Some(json!("x".repeat(8192)))
+        )
+        .is_err());
+    for i in 0..63 {
+        room.set_state(
+            vulcast_session_id.clone(),
+            format!("key-{}", i),
+            Some(json!(i)),
+        )
+        .unwrap();
+    }
+    assert!(room
+        .set_state(vulcast_session_id.clone(), "extra".into(), Some(json!(0)))
+        .is_err());
+    // existing keys may still be overwritten
+    room.set_state(vulcast_session_id, "scoreboard".into(), Some(json!({})))
+        .unwrap();
+}
+
+#[tokio::test]
+async fn clients_joining_and_leaving_are_announced() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+    let webclient_session_id = ForeignSessionId("webclient".into());
+
+    let vulcast_token = relay_server
+        .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id.clone())
+        .unwrap();
+    let vulcast = relay_server.session_from_token(vulcast_token).unwrap();
+    let room = vulcast.get_room();
+
+    // clients already in the room are announced first
+    let mut updates = Box::pin(room.client_state_updates());
+    let update = updates.next().await.unwrap();
+    assert_eq!(update.state, ClientState::Join);
+    assert_eq!(update.session_id, vulcast.id());
+    assert_eq!(update.foreign_session_id, vulcast_session_id);
+    assert_eq!(update.role, Role::Vulcast);
+
+    let webclient_token = relay_server
+        .register_session(
+            webclient_session_id.clone(),
+            SessionOptions::WebClient(foreign_room_id),
+        )
+        .unwrap();
+    let webclient = relay_server.session_from_token(webclient_token).unwrap();
+    let webclient_id = webclient.id();
+    let update = updates.next().await.unwrap();
+    assert_eq!(
+        (update.state, update.foreign_session_id, update.role),
+        (
+            ClientState::Join,
+            webclient_session_id.clone(),
+            Role::WebClient
+        )
+    );
+
+    drop(webclient);
+    relay_server
+        .unregister_session(webclient_session_id.clone())
+        .unwrap();
+    let update = updates.next().await.unwrap();
+    assert_eq!(
+        (update.state, update.session_id, update.foreign_session_id),
+        (ClientState::Leave, webclient_id, webclient_session_id)
+    );
+}
+
+#[tokio::test]
+async fn roster_lists_display_names() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+    let webclient_session_id = ForeignSessionId("webclient".into());
+
+    let vulcast_token = relay_server
+        .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id.clone())
+        .unwrap();
+    let vulcast = relay_server.session_from_token(vulcast_token).unwrap();
+    let room = vulcast.get_room();
+    let mut updates = Box::pin(room.client_state_updates());
+    assert_eq!(updates.next().await.unwrap().display_name, None);
+
+    let webclient_token = relay_server
+        .register_session_with_display_name(
+            webclient_session_id.clone(),
+            SessionOptions::WebClient(foreign_room_id),
+            Some("Ayush".into()),
+        )
+        .unwrap();
+    let _webclient = relay_server.session_from_token(webclient_token).unwrap();
+    let update = updates.next().await.unwrap();
+    assert_eq!(
+        (update.foreign_session_id, update.display_name),
+        (webclient_session_id.clone(), Some("Ayush".into()))
+    );
+
+    let mut roster = room
+        .roster()
+        .into_iter()
+        .map(|participant| (participant.foreign_session_id, participant.display_name))
+        .collect::<Vec<_>>();
+    roster.sort();
+    assert_eq!(
+        roster,
+        vec![
+            (vulcast_session_id, None),
+            (webclient_session_id, Some("Ayush".into()))
+        ]
+    );
+}
+
+#[tokio::test]
+async fn moderation_actions_are_logged_and_undone() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+    let host_session_id = ForeignSessionId("host".into());
+
+    let vulcast_token = relay_server
+        .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id, vulcast_session_id)
+        .unwrap();
+    let vulcast = relay_server.session_from_token(vulcast_token).unwrap();
+    let room = vulcast.get_room();
+
+    room.set_input_locked(true);
+    let lock = room.record_moderation(
+        ModerationKind::LockInput,
+        Some(host_session_id.clone()),
+        None,
+        None,
+    );
+    let kick = room.record_moderation(
+        ModerationKind::Kick,
+        Some(host_session_id.clone()),
+        Some(ForeignSessionId("web".into())),
+        None,
+    );
+    assert_eq!(
+        room.moderation_log()
+            .iter()
+            .map(|action| (action.id, action.kind))
+            .collect::<Vec<_>>(),
+        vec![
+            (lock.id, ModerationKind::LockInput),
+            (kick.id, ModerationKind::Kick)
+        ]
+    );
+
+    let undone = room
+        .undo_moderation(lock.id, Some(host_session_id.clone()))
+        .await
+        .unwrap();
+    assert!(!room.input_locked());
+    assert_eq!(
+        undone.undone.map(|(_, actor)| actor),
+        Some(Some(host_session_id))
+    );
+    // actions are undone at most once, and kicks never
+    assert!(room.undo_moderation(lock.id, None).await.is_err());
+    assert!(room.undo_moderation(kick.id, None).await.is_err());
+    assert!(room.undo_moderation(kick.id + 1, None).await.is_err());
+}
+
+#[tokio::test]
+async fn superseded_moderation_actions_are_not_undone() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast_token = relay_server
+        .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id, vulcast_session_id)
+        .unwrap();
+    let vulcast = relay_server.session_from_token(vulcast_token).unwrap();
+    let room = vulcast.get_room();
+
+    room.set_input_locked(true);
+    let lock = room.record_moderation(ModerationKind::LockInput, None, None, None);
+    room.set_input_locked(false);
+    let unlock = room.record_moderation(ModerationKind::UnlockInput, None, None, None);
+
+    // undoing the lock would also revert the later unlock
+    assert!(room.undo_moderation(lock.id, None).await.is_err());
+    assert!(!room.input_locked());
+
+    // once the unlock is undone, the lock is in effect again and may be undone
+    room.undo_moderation(unlock.id, None).await.unwrap();
+    assert!(room.input_locked());
+    room.undo_moderation(lock.id, None).await.unwrap();
+    assert!(!room.input_locked());
+}
+
+#[tokio::test]
+async fn moderation_actions_are_undone_once_concurrently() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast_token = relay_server
+        .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id, vulcast_session_id.clone())
+        .unwrap();
+    let vulcast = relay_server.session_from_token(vulcast_token).unwrap();
+    let room = vulcast.get_room();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    vulcast.producer_pause(producer.id()).await.unwrap();
+    let mute = room.record_moderation(
+        ModerationKind::Mute,
+        None,
+        Some(vulcast_session_id),
+        Some(producer.id()),
+    );
+
+    let (first, second) = futures::join!(
+        room.undo_moderation(mute.id, None),
+        room.undo_moderation(mute.id, None)
+    );
+    assert!(first.is_ok() != second.is_ok());
+    assert!(!producer.paused());
+
+    // a failed undo leaves the action as it was
+    vulcast.producer_pause(producer.id()).await.unwrap();
+    let mute = room.record_moderation(ModerationKind::Mute, None, None, Some(producer.id()));
+    let producer_id = producer.id();
+    drop(producer);
+    vulcast.producer_close(producer_id).unwrap();
+    assert!(room.undo_moderation(mute.id, None).await.is_err());
+    assert!(room
+        .moderation_log()
+        .iter()
+        .any(|action| action.id == mute.id && action.undone.is_none()));
+}
+
+#[tokio::test]
+async fn relayed_data_producers_are_attributed_to_their_session() {
+    let relay_server = fixture::relay_server().await;
+    relay_server.set_data_filter(Some(Arc::new(RateLimitFilter::new(
+        1,
+        Duration::from_secs(1),
+    ))));
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    let room = vulcast.get_room();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let data_producer = vulcast
+        .produce_data(send_transport.id(), fixture::sctp_stream_parameters())
+        .await
+        .unwrap();
+
+    // the relay rather than the session data producer is available, attributed to
+    // the session producing its data
+    let sources = room
+        .get_data_producer_sources()
+        .into_iter()
+        .map(|(data_producer, foreign_session_id)| (data_producer.id(), foreign_session_id))
+        .collect::<Vec<_>>();
+    assert_eq!(sources.len(), 1);
+    assert_ne!(sources[0].0, data_producer.id());
+    assert_eq!(sources[0].1, Some(ForeignSessionId("vulcast".into())));
+}
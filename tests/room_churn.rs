@@ -0,0 +1,116 @@
+use std::time::{Duration, Instant};
+
+use vulcan_relay::relay_server::{
+    ForeignRoomId, ForeignSessionId, RegisterRoomError, SessionOptions, UnregisterRoomError,
+};
+use vulcan_relay::room::RoomPreset;
+use vulcan_relay::room_churn::{RoomChurnLimit, RoomChurnLimitedError};
+
+pub mod fixture;
+
+#[test]
+fn registrations_beyond_limit_are_rejected_until_they_leave_the_window() {
+    let room_churn = RoomChurnLimit::new(Duration::from_secs(60));
+    room_churn.set_max_registrations(Some(2));
+    let vulcast = ForeignSessionId("vulcast".into());
+    let other = ForeignSessionId("other".into());
+    let now = Instant::now();
+
+    assert_eq!(room_churn.register(&vulcast, now), Ok(()));
+    assert_eq!(
+        room_churn.register(&vulcast, now + Duration::from_secs(10)),
+        Ok(())
+    );
+    assert_eq!(
+        room_churn.register(&vulcast, now + Duration::from_secs(20)),
+        Err(RoomChurnLimitedError {
+            vulcast_fsid: vulcast.clone(),
+            retry_after: Duration::from_secs(40),
+        })
+    );
+    // other vulcasts are limited separately
+    assert_eq!(room_churn.register(&other, now), Ok(()));
+    assert_eq!(room_churn.registrations(&vulcast, now), 2);
+    assert_eq!(room_churn.rejections(), 1);
+
+    // the oldest registration leaves the window
+    let later = now + Duration::from_secs(60);
+    assert_eq!(room_churn.registrations(&vulcast, later), 1);
+    assert_eq!(room_churn.register(&vulcast, later), Ok(()));
+    assert!(room_churn.register(&vulcast, later).is_err());
+}
+
+#[test]
+fn unlimited_registrations_are_tracked() {
+    let room_churn = RoomChurnLimit::default();
+    let vulcast = ForeignSessionId("vulcast".into());
+    let now = Instant::now();
+    for _ in 0..100 {
+        assert_eq!(room_churn.register(&vulcast, now), Ok(()));
+    }
+    assert_eq!(room_churn.registrations(&vulcast, now), 100);
+    assert_eq!(room_churn.rejections(), 0);
+}
+
+#[tokio::test]
+async fn churning_vulcasts_cannot_register_rooms() {
+    let relay_server = fixture::relay_server().await;
+    relay_server.room_churn().set_max_registrations(Some(2));
+    let vulcast_fsid = ForeignSessionId("vulcast".into());
+    relay_server
+        .register_session(vulcast_fsid.clone(), SessionOptions::Vulcast)
+        .unwrap();
+
+    for i in 0..2 {
+        let frid = ForeignRoomId(format!("room-{}", i));
+        relay_server
+            .register_room(frid.clone(), vulcast_fsid.clone())
+            .unwrap();
+        relay_server.unregister_room(frid).unwrap();
+    }
+    match relay_server.register_room(ForeignRoomId("room-2".into()), vulcast_fsid.clone()) {
+        Err(RegisterRoomError::ChurnLimited {
+            vulcast_fsid: limited,
+            retry_after,
+        }) => {
+            assert_eq!(limited, vulcast_fsid);
+            assert!(retry_after <= Duration::from_secs(3600));
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+    // rejected registrations do not register the room
+    let frid = ForeignRoomId("room-2".into());
+    assert_eq!(
+        relay_server.unregister_room(frid.clone()),
+        Err(UnregisterRoomError::UnknownRoom(frid))
+    );
+}
+
+#[tokio::test]
+async fn churn_limited_registrations_keep_the_room_preset() {
+    let relay_server = fixture::relay_server().await;
+    relay_server.room_churn().set_max_registrations(Some(1));
+    let vulcast_fsid = ForeignSessionId("vulcast".into());
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_fsid.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+
+    let frid = ForeignRoomId("room-0".into());
+    relay_server
+        .register_room(frid.clone(), vulcast_fsid.clone())
+        .unwrap();
+    relay_server.unregister_room(frid).unwrap();
+    assert!(matches!(
+        relay_server.register_room_with_preset(
+            ForeignRoomId("room-1".into()),
+            vulcast_fsid,
+            RoomPreset::AudioOnly
+        ),
+        Err(RegisterRoomError::ChurnLimited { .. })
+    ));
+    assert_eq!(vulcast.get_room().preset(), RoomPreset::default());
+}
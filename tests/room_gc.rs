@@ -0,0 +1,70 @@
+use futures::stream::StreamExt;
+use std::time::Duration;
+
+use vulcan_relay::relay_server::{ForeignRoomId, ForeignSessionId, SessionOptions};
+use vulcan_relay::room_gc;
+
+pub mod fixture;
+
+#[tokio::test]
+async fn rooms_are_never_collected_by_default() {
+    let relay_server = fixture::relay_server().await;
+    relay_server
+        .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(
+            ForeignRoomId("ayush".into()),
+            ForeignSessionId("vulcast".into()),
+        )
+        .unwrap();
+
+    assert!(room_gc::sweep(&relay_server).is_empty());
+    assert_eq!(relay_server.registered_room_count(), 1);
+}
+
+#[tokio::test]
+async fn idle_rooms_are_collected() {
+    let relay_server = fixture::relay_server().await;
+    relay_server
+        .room_gc()
+        .set_idle_timeout(Some(Duration::from_millis(100)));
+    let mut collected = Box::pin(relay_server.room_gc().collected());
+
+    // the vulcast of the idle room never connects
+    relay_server
+        .register_session(ForeignSessionId("idle".into()), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(
+            ForeignRoomId("idle".into()),
+            ForeignSessionId("idle".into()),
+        )
+        .unwrap();
+    let token = relay_server
+        .register_session(ForeignSessionId("active".into()), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(
+            ForeignRoomId("active".into()),
+            ForeignSessionId("active".into()),
+        )
+        .unwrap();
+    let _session = relay_server.session_from_token(token).unwrap();
+
+    assert!(room_gc::sweep(&relay_server).is_empty());
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    let swept = room_gc::sweep(&relay_server);
+    assert_eq!(swept.len(), 1);
+    assert_eq!(swept[0].frid, ForeignRoomId("idle".into()));
+    assert!(swept[0].idle >= Duration::from_millis(100));
+
+    assert_eq!(collected.next().await, Some(swept[0].clone()));
+    assert_eq!(
+        relay_server.registered_rooms(),
+        vec![(
+            ForeignRoomId("active".into()),
+            ForeignSessionId("active".into())
+        )]
+    );
+}
@@ -0,0 +1,54 @@
+use mediasoup::rtp_parameters::MediaKind;
+
+use vulcan_relay::relay_server::{ForeignRoomId, ForeignSessionId, SessionOptions};
+use vulcan_relay::room_stats;
+
+pub mod fixture;
+
+#[tokio::test]
+async fn room_stats_aggregate_sessions() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let _webclient = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    ForeignSessionId("webclient".into()),
+                    SessionOptions::WebClient(foreign_room_id.clone()),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+
+    let room = relay_server.get_room(&foreign_room_id).unwrap();
+    let stats = room_stats::report(&room).await.unwrap();
+    assert_eq!(stats.vulcasts, 1);
+    assert_eq!(stats.web_clients, 1);
+    assert_eq!(stats.hosts, 0);
+    // no media flows between unconnected transports
+    assert_eq!(stats.packets_received, 0);
+    assert_eq!(stats.round_trip_time_p50, None);
+}
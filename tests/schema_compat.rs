@@ -0,0 +1,183 @@
+use std::path::{Path, PathBuf};
+
+use vulcan_relay::control_schema::{self, ControlSchema};
+use vulcan_relay::public_schema::{self, PublicSchema};
+use vulcan_relay::schema_compat::{self, BreakingChangeKind, CompatError};
+use vulcan_relay::signal_schema::{self, SignalSchema};
+
+const OLD: &str = r#"
+type QueryRoot {
+    stats(sessionId: ID!): String!
+    version: String
+    legacy: String @deprecated(reason: "use version")
+}
+enum Role {
+    VULCAST
+    WEB_CLIENT
+}
+"#;
+
+fn snapshot_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden/schema")
+        .join(name)
+}
+
+/// Check a schema against its snapshot, which starts with a `# version: <n>` line.
+/// The snapshot is only recorded or updated if requested, so that a missing snapshot
+/// cannot pass unnoticed.
+fn assert_snapshot(name: &str, sdl: &str, version: u32) {
+    let path = snapshot_path(name);
+    let header = format!("# version: {}\n", version);
+    let update = std::env::var_os("UPDATE_SCHEMA_SNAPSHOTS").is_some();
+    match std::fs::read_to_string(&path) {
+        Ok(snapshot) => {
+            let snapshot_version = snapshot
+                .lines()
+                .next()
+                .and_then(|line| line.strip_prefix("# version: "))
+                .and_then(|version| version.parse().ok())
+                .expect("snapshot has no version header");
+            if let Err(err) = schema_compat::check(&snapshot, snapshot_version, sdl, version) {
+                panic!("{} is incompatible with its snapshot: {}", name, err);
+            }
+            if snapshot == header.clone() + sdl {
+                return;
+            }
+            assert!(
+                update,
+                "{} changed, update its snapshot with `UPDATE_SCHEMA_SNAPSHOTS=1 cargo test --test schema_compat`",
+                name
+            );
+        }
+        Err(_) => assert!(
+            update,
+            "{} has no snapshot, record it with `UPDATE_SCHEMA_SNAPSHOTS=1 cargo test --test schema_compat`",
+            name
+        ),
+    }
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, header + sdl).unwrap();
+}
+
+#[test]
+fn signal_schema_matches_snapshot() {
+    assert_snapshot(
+        "signal.graphql",
+        &SignalSchema::default().sdl(),
+        signal_schema::SCHEMA_VERSION,
+    );
+}
+
+#[test]
+fn control_schema_matches_snapshot() {
+    assert_snapshot(
+        "control.graphql",
+        &ControlSchema::default().sdl(),
+        control_schema::SCHEMA_VERSION,
+    );
+}
+
+#[test]
+fn public_schema_matches_snapshot() {
+    assert_snapshot(
+        "public.graphql",
+        &PublicSchema::default().sdl(),
+        public_schema::SCHEMA_VERSION,
+    );
+}
+
+#[test]
+fn additions_are_compatible() {
+    let new = r#"
+type QueryRoot {
+    stats(sessionId: ID!, verbose: Boolean): String!
+    version: String!
+    legacy: String @deprecated(reason: "use version")
+    roomStats(roomId: ID!): String
+}
+enum Role {
+    VULCAST
+    WEB_CLIENT
+    HOST
+}
+"#;
+    assert_eq!(schema_compat::breaking_changes(OLD, new).unwrap(), vec![]);
+    assert!(schema_compat::check(OLD, 1, new, 1).unwrap().is_empty());
+}
+
+#[test]
+fn breaking_changes_are_detected() {
+    let new = r#"
+type QueryRoot {
+    stats(sessionId: ID!, roomId: ID!): Int!
+    legacy: String @deprecated(reason: "use version")
+}
+enum Role {
+    VULCAST
+}
+"#;
+    let changes = schema_compat::breaking_changes(OLD, new).unwrap();
+    let kinds = changes
+        .iter()
+        .map(|change| (change.path.as_str(), &change.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            (
+                "QueryRoot.stats",
+                &BreakingChangeKind::TypeChanged {
+                    from: "String!".into(),
+                    to: "Int!".into()
+                }
+            ),
+            ("QueryRoot.version", &BreakingChangeKind::Removed),
+            ("Role.WEB_CLIENT", &BreakingChangeKind::Removed),
+            (
+                "QueryRoot.stats(roomId)",
+                &BreakingChangeKind::RequiredInputAdded
+            ),
+        ]
+    );
+    assert!(matches!(
+        schema_compat::check(OLD, 1, new, 1),
+        Err(CompatError::VersionNotBumped(_))
+    ));
+}
+
+#[test]
+fn only_deprecated_fields_may_be_removed() {
+    let without_legacy = r#"
+type QueryRoot {
+    stats(sessionId: ID!): String!
+    version: String
+}
+enum Role {
+    VULCAST
+    WEB_CLIENT
+}
+"#;
+    let without_version = r#"
+type QueryRoot {
+    stats(sessionId: ID!): String!
+    legacy: String @deprecated(reason: "use version")
+}
+enum Role {
+    VULCAST
+    WEB_CLIENT
+}
+"#;
+    // removals always require a version bump
+    assert!(matches!(
+        schema_compat::check(OLD, 1, without_legacy, 1),
+        Err(CompatError::VersionNotBumped(_))
+    ));
+    let changes = schema_compat::check(OLD, 1, without_legacy, 2).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert!(changes[0].deprecated);
+    assert!(matches!(
+        schema_compat::check(OLD, 1, without_version, 2),
+        Err(CompatError::NotDeprecated(_))
+    ));
+}
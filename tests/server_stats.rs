@@ -0,0 +1,41 @@
+use mediasoup::rtp_parameters::MediaKind;
+
+use vulcan_relay::relay_server::{ForeignRoomId, ForeignSessionId, SessionOptions};
+use vulcan_relay::server_stats;
+
+pub mod fixture;
+
+#[tokio::test]
+async fn server_stats_count_relay_objects() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(ForeignRoomId("ayush".into()), vulcast_session_id)
+        .unwrap();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+
+    let stats = server_stats::report(&relay_server).await.unwrap();
+    assert_eq!(stats.rooms, 1);
+    assert_eq!(stats.sessions, 1);
+    assert_eq!(stats.producers, 1);
+    assert_eq!(stats.consumers, 0);
+    assert_eq!(stats.worker_routers, 1);
+    assert!(stats.worker_max_rss_kib > 0);
+}
@@ -1,8 +1,17 @@
 use futures::stream::StreamExt;
+use std::time::Duration;
 
-use mediasoup::{rtp_parameters::MediaKind, transport::Transport};
+use mediasoup::{
+    consumer::ConsumerLayers,
+    plain_transport::PlainTransportRemoteParameters,
+    rtp_parameters::{MediaKind, RtpCodecParameters},
+    transport::Transport,
+};
 
 use vulcan_relay::relay_server::{ForeignRoomId, ForeignSessionId, SessionOptions};
+use vulcan_relay::session::{
+    ConsumerConfig, MidStrategy, PlainTransportConfig, Resource, ResourceType, TraceKind,
+};
 
 pub mod fixture;
 
@@ -115,3 +124,1045 @@ async fn producer_consumer_connected_after_signalling() {
         .await
         .unwrap();
 }
+
+#[tokio::test]
+async fn closed_consumers_are_removed_from_session() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id.clone())
+        .unwrap();
+    let webclient = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    ForeignSessionId("webclient".into()),
+                    SessionOptions::WebClient(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let vulcast_send_transport = vulcast.create_webrtc_transport().await;
+    let webclient_recv_transport = webclient.create_webrtc_transport().await;
+    webclient.set_rtp_capabilities(fixture::consumer_device_capabilities());
+
+    let producer = vulcast
+        .produce(
+            vulcast_send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    let _consumer = webclient
+        .consume(webclient_recv_transport.id(), producer.id())
+        .await
+        .unwrap();
+    assert_eq!(webclient.get_consumers().len(), 1);
+
+    // closing the vulcast session closes its producer, and the dependent consumer
+    drop(producer);
+    drop(vulcast_send_transport);
+    drop(vulcast);
+    drop(relay_server.take_session(&vulcast_session_id));
+
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while !webclient.get_consumers().is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(webclient.get_resource_count(&ResourceType::Consumer), 0);
+}
+
+#[tokio::test]
+async fn close_transport_closes_dependent_resources() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    let _data_producer = vulcast
+        .produce_data(send_transport.id(), fixture::sctp_stream_parameters())
+        .await
+        .unwrap();
+    assert_eq!(vulcast.get_resource_count(&ResourceType::Producer), 1);
+    assert_eq!(vulcast.get_resource_count(&ResourceType::DataProducer), 1);
+
+    let mut closed_producers = Box::pin(vulcast.get_room().closed_producers());
+    vulcast.close_transport(send_transport.id()).unwrap();
+    assert_eq!(closed_producers.next().await, Some(producer.id()));
+    assert!(vulcast.get_webrtc_transport(send_transport.id()).is_none());
+    assert!(vulcast.get_producer(producer.id()).is_none());
+    assert_eq!(
+        vulcast.get_resource_count(&ResourceType::WebrtcTransport),
+        0
+    );
+    assert_eq!(vulcast.get_resource_count(&ResourceType::Producer), 0);
+    assert_eq!(vulcast.get_resource_count(&ResourceType::DataProducer), 0);
+
+    // closing again fails
+    assert!(vulcast.close_transport(send_transport.id()).is_err());
+}
+
+#[tokio::test]
+async fn producer_negotiation_is_reported() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    let report = vulcast.get_negotiation_report(producer.id()).unwrap();
+    assert_eq!(report.codecs.len(), 2);
+    assert!(report.codecs.iter().all(|codec| codec.accepted));
+    assert_eq!(report.header_extensions.len(), 2);
+
+    // unsupported h264 profile is rejected with a reason
+    let mut rtp_parameters = fixture::video_producer_device_parameters();
+    rtp_parameters.mid = Some("VIDEO2".to_string());
+    if let RtpCodecParameters::Video { parameters, .. } = &mut rtp_parameters.codecs[0] {
+        parameters.insert("profile-level-id", "f4001f".into());
+    }
+    let err = vulcast
+        .produce(send_transport.id(), MediaKind::Video, rtp_parameters)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("rejected codecs"));
+}
+
+#[tokio::test]
+async fn invalid_simulcast_encodings_are_rejected() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    let send_transport = vulcast.create_webrtc_transport().await;
+
+    // audio has a single encoding
+    let mut rtp_parameters = fixture::audio_producer_device_parameters();
+    rtp_parameters
+        .encodings
+        .push(rtp_parameters.encodings[0].clone());
+    let err = vulcast
+        .produce(send_transport.id(), MediaKind::Audio, rtp_parameters)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("single encoding"));
+
+    // simulcast layers are uniquely identified
+    let mut rtp_parameters = fixture::video_producer_device_parameters();
+    rtp_parameters.encodings[1].ssrc = rtp_parameters.encodings[0].ssrc;
+    let err = vulcast
+        .produce(send_transport.id(), MediaKind::Video, rtp_parameters)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("duplicate ssrc"));
+
+    let mut rtp_parameters = fixture::video_producer_device_parameters();
+    rtp_parameters.encodings[1].ssrc = None;
+    let err = vulcast
+        .produce(send_transport.id(), MediaKind::Video, rtp_parameters)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("no rid or ssrc"));
+
+    assert!(vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+        )
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn plain_consumer_created_without_client_capabilities() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let recorder = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    ForeignSessionId("recorder".into()),
+                    SessionOptions::WebClient(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+
+    let plain_transport = recorder.create_plain_transport().await;
+    let consumer = recorder
+        .consume_plain(plain_transport.id(), producer.id())
+        .await
+        .unwrap();
+    assert_eq!(consumer.kind(), MediaKind::Audio);
+    assert_eq!(consumer.producer_id(), producer.id());
+    assert_eq!(recorder.get_resource_count(&ResourceType::Consumer), 1);
+}
+
+#[tokio::test]
+async fn can_consume_reports_missing_capabilities() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let webclient = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    ForeignSessionId("webclient".into()),
+                    SessionOptions::WebClient(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+
+    let check = webclient.can_consume(producer.id()).await;
+    assert!(!check.can_consume);
+    assert_eq!(check.reasons, vec!["missing rtp capabilities".to_owned()]);
+
+    webclient.set_rtp_capabilities(fixture::consumer_device_capabilities());
+    let check = webclient.can_consume(producer.id()).await;
+    assert!(check.can_consume);
+    assert!(check.reasons.is_empty());
+}
+
+#[tokio::test]
+async fn plain_transport_connects_to_explicit_tuple() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+
+    let plain_transport = vulcast
+        .create_plain_transport_with_config(PlainTransportConfig {
+            comedia: false,
+            rtcp_mux: false,
+            enable_srtp: false,
+        })
+        .await;
+    assert!(plain_transport.rtcp_tuple().is_some());
+    vulcast
+        .connect_plain_transport(
+            plain_transport.id(),
+            PlainTransportRemoteParameters {
+                ip: Some("127.0.0.1".parse().unwrap()),
+                port: Some(5004),
+                rtcp_port: Some(5005),
+                srtp_parameters: None,
+            },
+        )
+        .await
+        .unwrap();
+    let tuple = plain_transport.tuple();
+    assert_eq!(tuple.remote_port(), Some(5004));
+}
+
+#[tokio::test]
+async fn plain_transport_with_srtp_returns_parameters() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+
+    let plain_transport = vulcast
+        .create_plain_transport_with_config(PlainTransportConfig {
+            enable_srtp: true,
+            ..PlainTransportConfig::default()
+        })
+        .await;
+    assert!(plain_transport.srtp_parameters().is_some());
+    assert!(vulcast
+        .create_plain_transport()
+        .await
+        .srtp_parameters()
+        .is_none());
+}
+
+#[tokio::test]
+async fn producer_can_be_paused_and_resumed() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+
+    vulcast.producer_pause(producer.id()).await.unwrap();
+    assert!(producer.paused());
+    vulcast.producer_resume(producer.id()).await.unwrap();
+    assert!(!producer.paused());
+}
+
+#[tokio::test]
+async fn resources_report_their_transport() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        vulcast.get_resource_transport(&Resource::Producer(producer.id())),
+        Some(send_transport.id())
+    );
+    vulcast.close_transport(send_transport.id()).unwrap();
+    assert_eq!(
+        vulcast.get_resource_transport(&Resource::Producer(producer.id())),
+        None
+    );
+}
+
+#[tokio::test]
+async fn incoming_bitrate_is_capped() {
+    let relay_server = fixture::relay_server().await;
+    relay_server.set_max_incoming_bitrate(Some(1_000_000));
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    assert_eq!(vulcast.max_incoming_bitrate(), Some(1_000_000));
+
+    let transport = vulcast.create_webrtc_transport().await;
+    vulcast
+        .set_max_incoming_bitrate(transport.id(), 500_000)
+        .await
+        .unwrap();
+    assert!(vulcast
+        .set_max_incoming_bitrate(transport.id(), 2_000_000)
+        .await
+        .is_err());
+    assert!(vulcast
+        .set_max_incoming_bitrate(transport.id(), 0)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn stats_are_cached_within_ttl() {
+    let relay_server = fixture::relay_server().await;
+    relay_server.set_stats_cache_ttl(Some(Duration::from_millis(200)));
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    assert!(vulcast
+        .get_stats()
+        .await
+        .unwrap()
+        .webrtc_transport_stats
+        .is_empty());
+
+    // the snapshot is reused until it expires
+    vulcast.create_webrtc_transport().await;
+    assert!(vulcast
+        .get_stats()
+        .await
+        .unwrap()
+        .webrtc_transport_stats
+        .is_empty());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(
+        vulcast
+            .get_stats()
+            .await
+            .unwrap()
+            .webrtc_transport_stats
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn stats_cover_every_resource() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    let mut transports = Vec::new();
+    for _ in 0..2 {
+        transports.push(vulcast.create_webrtc_transport().await);
+    }
+    for (transport, kind, rtp_parameters) in [
+        (
+            &transports[0],
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+        ),
+        (
+            &transports[1],
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        ),
+    ] {
+        vulcast
+            .produce(transport.id(), kind, rtp_parameters)
+            .await
+            .unwrap();
+    }
+
+    let stats = vulcast.get_stats().await.unwrap();
+    assert_eq!(stats.webrtc_transport_stats.len(), 2);
+    assert_eq!(stats.producer_stats.len(), 2);
+}
+
+#[tokio::test]
+async fn transport_crypto_is_reported() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+
+    let webrtc_transport = vulcast.create_webrtc_transport().await;
+    let plain_transport = vulcast
+        .create_plain_transport_with_config(PlainTransportConfig {
+            enable_srtp: true,
+            ..PlainTransportConfig::default()
+        })
+        .await;
+
+    let crypto = vulcast.get_transport_crypto();
+    assert_eq!(crypto.len(), 2);
+    let webrtc_crypto = crypto
+        .iter()
+        .find(|crypto| crypto.transport_id == webrtc_transport.id())
+        .unwrap();
+    assert!(webrtc_crypto.dtls_state.is_some());
+    assert!(webrtc_crypto.dtls_parameters.is_some());
+    assert!(webrtc_crypto.srtp_crypto_suite.is_none());
+    let plain_crypto = crypto
+        .iter()
+        .find(|crypto| crypto.transport_id == plain_transport.id())
+        .unwrap();
+    assert!(plain_crypto.dtls_state.is_none());
+    assert_eq!(
+        plain_crypto.srtp_crypto_suite,
+        plain_transport
+            .srtp_parameters()
+            .map(|srtp_parameters| srtp_parameters.crypto_suite)
+    );
+}
+
+#[tokio::test]
+async fn consumer_config_is_applied() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let webclient = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    ForeignSessionId("webclient".into()),
+                    SessionOptions::WebClient(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let vulcast_send_transport = vulcast.create_webrtc_transport().await;
+    let webclient_recv_transport = webclient.create_webrtc_transport().await;
+    webclient.set_rtp_capabilities(fixture::consumer_device_capabilities());
+
+    let producer = vulcast
+        .produce(
+            vulcast_send_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+
+    let consumer = webclient
+        .consume(webclient_recv_transport.id(), producer.id())
+        .await
+        .unwrap();
+    assert!(consumer.rtp_parameters().encodings[0].rtx.is_some());
+    webclient.consumer_close(consumer.id()).unwrap();
+
+    let consumer = webclient
+        .consume_with_config(
+            webclient_recv_transport.id(),
+            producer.id(),
+            ConsumerConfig {
+                mid_strategy: MidStrategy::ProducerId,
+                enable_rtx: false,
+                ..ConsumerConfig::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        consumer.rtp_parameters().mid,
+        Some(producer.id().to_string())
+    );
+    assert!(consumer.rtp_parameters().encodings[0].rtx.is_none());
+}
+
+#[tokio::test]
+async fn closed_consumer_is_released() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let webclient = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    ForeignSessionId("webclient".into()),
+                    SessionOptions::WebClient(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let vulcast_send_transport = vulcast.create_webrtc_transport().await;
+    let webclient_recv_transport = webclient.create_webrtc_transport().await;
+    webclient.set_rtp_capabilities(fixture::consumer_device_capabilities());
+
+    let producer = vulcast
+        .produce(
+            vulcast_send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    let consumer = webclient
+        .consume(webclient_recv_transport.id(), producer.id())
+        .await
+        .unwrap();
+    assert_eq!(webclient.get_resource_count(&ResourceType::Consumer), 1);
+
+    webclient.consumer_close(consumer.id()).unwrap();
+    assert_eq!(webclient.get_resource_count(&ResourceType::Consumer), 0);
+    assert!(webclient.consumer_close(consumer.id()).is_err());
+}
+
+#[tokio::test]
+async fn closed_data_resources_are_released() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let recv_transport = vulcast.create_webrtc_transport().await;
+    let data_producer = vulcast
+        .produce_data(send_transport.id(), fixture::sctp_stream_parameters())
+        .await
+        .unwrap();
+    let data_consumer = vulcast
+        .consume_data(recv_transport.id(), data_producer.id())
+        .await
+        .unwrap();
+    assert_eq!(vulcast.get_resource_count(&ResourceType::DataConsumer), 1);
+    assert_eq!(vulcast.get_resource_count(&ResourceType::DataProducer), 1);
+
+    vulcast.data_consumer_close(data_consumer.id()).unwrap();
+    assert_eq!(vulcast.get_resource_count(&ResourceType::DataConsumer), 0);
+    assert!(vulcast.data_consumer_close(data_consumer.id()).is_err());
+
+    vulcast.data_producer_close(data_producer.id()).unwrap();
+    assert_eq!(vulcast.get_resource_count(&ResourceType::DataProducer), 0);
+    assert!(vulcast.data_producer_close(data_producer.id()).is_err());
+}
+
+#[tokio::test]
+async fn simulcast_consumer_layers_can_be_preferred() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let webclient = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    ForeignSessionId("webclient".into()),
+                    SessionOptions::WebClient(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let vulcast_send_transport = vulcast.create_webrtc_transport().await;
+    let webclient_recv_transport = webclient.create_webrtc_transport().await;
+    webclient.set_rtp_capabilities(fixture::consumer_device_capabilities());
+
+    let producer = vulcast
+        .produce(
+            vulcast_send_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    let consumer = webclient
+        .consume(webclient_recv_transport.id(), producer.id())
+        .await
+        .unwrap();
+
+    let layers = webclient
+        .consumer_set_preferred_layers(
+            consumer.id(),
+            ConsumerLayers {
+                spatial_layer: 1,
+                temporal_layer: Some(0),
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(layers.spatial_layer, 1);
+    assert_eq!(consumer.preferred_layers(), Some(layers));
+
+    webclient.consumer_close(consumer.id()).unwrap();
+    assert!(webclient
+        .consumer_set_preferred_layers(
+            consumer.id(),
+            ConsumerLayers {
+                spatial_layer: 0,
+                temporal_layer: None,
+            },
+        )
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn consumer_priority_can_be_set() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let webclient = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    ForeignSessionId("webclient".into()),
+                    SessionOptions::WebClient(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let vulcast_send_transport = vulcast.create_webrtc_transport().await;
+    let webclient_recv_transport = webclient.create_webrtc_transport().await;
+    webclient.set_rtp_capabilities(fixture::consumer_device_capabilities());
+
+    let producer = vulcast
+        .produce(
+            vulcast_send_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    let consumer = webclient
+        .consume(webclient_recv_transport.id(), producer.id())
+        .await
+        .unwrap();
+
+    webclient
+        .consumer_set_priority(consumer.id(), 255)
+        .await
+        .unwrap();
+    assert_eq!(consumer.priority(), 255);
+    assert!(webclient
+        .consumer_set_priority(consumer.id(), 0)
+        .await
+        .is_err());
+
+    webclient.consumer_close(consumer.id()).unwrap();
+    assert!(webclient
+        .consumer_set_priority(consumer.id(), 1)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn scores_are_streamed_until_close() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    let webclient = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    ForeignSessionId("webclient".into()),
+                    SessionOptions::WebClient(foreign_room_id),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let vulcast_send_transport = vulcast.create_webrtc_transport().await;
+    let webclient_recv_transport = webclient.create_webrtc_transport().await;
+    webclient.set_rtp_capabilities(fixture::consumer_device_capabilities());
+
+    let producer = vulcast
+        .produce(
+            vulcast_send_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    let consumer = webclient
+        .consume(webclient_recv_transport.id(), producer.id())
+        .await
+        .unwrap();
+
+    // only resources of the session can be watched
+    assert!(webclient.producer_scores(producer.id()).is_err());
+    assert!(vulcast.consumer_scores(consumer.id()).is_err());
+
+    // the current score is yielded first
+    let mut producer_scores = Box::pin(vulcast.producer_scores(producer.id()).unwrap());
+    assert_eq!(producer_scores.next().await, Some(producer.score()));
+    let mut consumer_scores = Box::pin(webclient.consumer_scores(consumer.id()).unwrap());
+    assert_eq!(consumer_scores.next().await, Some(consumer.score()));
+
+    // streams end once closed
+    webclient.consumer_close(consumer.id()).unwrap();
+    drop(consumer);
+    assert!(
+        tokio::time::timeout(Duration::from_secs(1), consumer_scores.next())
+            .await
+            .unwrap()
+            .is_none()
+    );
+    vulcast.producer_close(producer.id()).unwrap();
+    drop(producer);
+    assert!(
+        tokio::time::timeout(Duration::from_secs(1), producer_scores.next())
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn producer_traces_end_with_producer() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id, vulcast_session_id)
+        .unwrap();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+
+    let mut traces = Box::pin(
+        vulcast
+            .producer_traces(producer.id(), vec![TraceKind::KeyFrame, TraceKind::Pli])
+            .await
+            .unwrap(),
+    );
+    // subscriptions may overlap
+    let other_traces = vulcast
+        .producer_traces(producer.id(), vec![TraceKind::Nack])
+        .await
+        .unwrap();
+    drop(other_traces);
+
+    vulcast.producer_close(producer.id()).unwrap();
+    drop(producer);
+    assert!(tokio::time::timeout(Duration::from_secs(1), traces.next())
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn idle_transports_are_closed() {
+    let relay_server = fixture::relay_server().await;
+    relay_server.set_idle_transport_timeout(Some(Duration::from_millis(200)));
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+
+    let idle_transport = vulcast.create_webrtc_transport().await;
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let _producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+
+    // transports with producers/consumers are kept, however long they carry no traffic
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    assert!(vulcast.get_webrtc_transport(idle_transport.id()).is_none());
+    assert!(vulcast.get_webrtc_transport(send_transport.id()).is_some());
+}
+
+#[tokio::test]
+async fn closed_transports_and_their_resources_are_pruned() {
+    let relay_server = fixture::relay_server().await;
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+
+    let webrtc_transport = vulcast.create_webrtc_transport().await;
+    let plain_transport = vulcast.create_plain_transport().await;
+    let producer = vulcast
+        .produce(
+            webrtc_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    let producer_id = producer.id();
+    vulcast.close_transport(webrtc_transport.id()).unwrap();
+    vulcast.close_transport(plain_transport.id()).unwrap();
+    // the transports close once the last handle is dropped
+    drop((producer, webrtc_transport, plain_transport));
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while vulcast.get_producer(producer_id).is_some() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert!(vulcast.get_webrtc_transports().is_empty());
+    assert!(vulcast.get_plain_transports().is_empty());
+    assert!(vulcast.get_producers().is_empty());
+}
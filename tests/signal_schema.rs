@@ -1 +1,588 @@
-// TODO end-to-end schema tests
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_graphql::Request;
+use futures::stream::StreamExt;
+use mediasoup::consumer::ConsumerId;
+use mediasoup::rtp_parameters::MediaKind;
+use mediasoup::transport::TransportId;
+use uuid::Uuid;
+
+use vulcan_relay::crypto_policy::CryptoPolicy;
+use vulcan_relay::permissions::Permission;
+use vulcan_relay::relay_server::{
+    ForeignRoomId, ForeignSessionId, RelayServer, Role, SessionOptions,
+};
+use vulcan_relay::resource_policy::ResourcePolicy;
+use vulcan_relay::room::AudioLevel;
+use vulcan_relay::schema_compat::DeprecationPolicy;
+use vulcan_relay::session::{ConsumerConfig, PlainTransportConfig, ResourceType, Session};
+use vulcan_relay::signal_schema::{self, SignalSchema};
+
+pub mod fixture;
+
+fn schema(relay_server: &RelayServer) -> SignalSchema {
+    signal_schema::schema(
+        relay_server.clone(),
+        ResourcePolicy::new(),
+        None,
+        PlainTransportConfig::default(),
+        ConsumerConfig::default(),
+        CryptoPolicy::default(),
+        DeprecationPolicy::default(),
+    )
+}
+
+fn session(relay_server: &RelayServer, fsid: &str, session_options: SessionOptions) -> Session {
+    let token = relay_server
+        .register_session(ForeignSessionId(fsid.into()), session_options)
+        .unwrap();
+    relay_server.session_from_token(token).unwrap()
+}
+
+/// Execute a request as a session, returning its data as JSON.
+async fn execute(
+    signal_schema: &SignalSchema,
+    session: &Session,
+    request: &str,
+) -> serde_json::Value {
+    let response = signal_schema
+        .execute(Request::new(request).data(session.downgrade()))
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    response.data.into_json().unwrap()
+}
+
+#[tokio::test]
+async fn webrtc_transport_options_select_ice_fields() {
+    let relay_server = fixture::relay_server().await;
+    let signal_schema = schema(&relay_server);
+    let vulcast = session(&relay_server, "vulcast", SessionOptions::Vulcast);
+
+    let data = execute(
+        &signal_schema,
+        &vulcast,
+        "mutation {
+            createWebrtcTransport {
+                id
+                iceParameters
+                iceCandidates { foundation priority ip protocol port type tcpType }
+            }
+        }",
+    )
+    .await;
+    let options = &data["createWebrtcTransport"];
+    let transport_id: TransportId = serde_json::from_value(options["id"].clone()).unwrap();
+    assert!(vulcast.get_webrtc_transport(transport_id).is_some());
+
+    assert!(options["iceParameters"]["usernameFragment"].is_string());
+    assert!(options["iceParameters"]["password"].is_string());
+    let ice_candidates = options["iceCandidates"].as_array().unwrap();
+    assert!(!ice_candidates.is_empty());
+    for ice_candidate in ice_candidates {
+        assert_eq!(ice_candidate["ip"], "127.0.0.1");
+        assert_eq!(ice_candidate["type"], "host");
+        assert!(ice_candidate["port"].as_u64().unwrap() > 0);
+    }
+    // values are lowercase as expected by mediasoup-client
+    assert!(ice_candidates
+        .iter()
+        .any(|ice_candidate| ice_candidate["protocol"] == "udp"));
+}
+
+#[tokio::test]
+async fn room_participants_list_open_producers() {
+    let relay_server = fixture::relay_server().await;
+    let signal_schema = schema(&relay_server);
+    let foreign_room_id = ForeignRoomId("room".into());
+    let vulcast = session(&relay_server, "vulcast", SessionOptions::Vulcast);
+    relay_server
+        .register_room(foreign_room_id.clone(), ForeignSessionId("vulcast".into()))
+        .unwrap();
+    let host = session(
+        &relay_server,
+        "host",
+        SessionOptions::Host(foreign_room_id.clone()),
+    );
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let audio_producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    let video_producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    let video_producer_id = video_producer.id();
+    drop(video_producer);
+    vulcast.producer_close(video_producer_id).unwrap();
+
+    let data = execute(
+        &signal_schema,
+        &host,
+        "{ roomParticipants { sessionId role displayName producerIds } }",
+    )
+    .await;
+    let mut participants = data["roomParticipants"].as_array().unwrap().clone();
+    participants.sort_by_key(|participant| participant["sessionId"].to_string());
+    assert_eq!(
+        participants,
+        vec![
+            serde_json::json!({
+                "sessionId": "host",
+                "role": "HOST",
+                "displayName": null,
+                "producerIds": [],
+            }),
+            serde_json::json!({
+                "sessionId": "vulcast",
+                "role": "VULCAST",
+                "displayName": null,
+                "producerIds": [audio_producer.id()],
+            }),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn who_am_i_describes_session() {
+    let relay_server = fixture::relay_server().await;
+    let signal_schema = schema(&relay_server);
+    let foreign_room_id = ForeignRoomId("room".into());
+    let vulcast = session(&relay_server, "vulcast", SessionOptions::Vulcast);
+    relay_server
+        .register_room(foreign_room_id.clone(), ForeignSessionId("vulcast".into()))
+        .unwrap();
+    let web_client = session(
+        &relay_server,
+        "webclient",
+        SessionOptions::WebClient(foreign_room_id.clone()),
+    );
+
+    let request = "{ whoAmI { sessionId role foreignSessionId roomId foreignRoomId } }";
+    let data = execute(&signal_schema, &web_client, request).await;
+    assert_eq!(
+        data["whoAmI"],
+        serde_json::json!({
+            "sessionId": web_client.id().to_string(),
+            "role": "WEB_CLIENT",
+            "foreignSessionId": "webclient",
+            "roomId": vulcast.get_room().id().to_string(),
+            "foreignRoomId": "room",
+        })
+    );
+
+    // the foreign room id is gone once the room is unregistered
+    relay_server.unregister_room(foreign_room_id).unwrap();
+    let data = execute(&signal_schema, &vulcast, request).await;
+    assert_eq!(data["whoAmI"]["role"], "VULCAST");
+    assert_eq!(data["whoAmI"]["foreignRoomId"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn hosts_mute_producers_of_other_sessions() {
+    let relay_server = fixture::relay_server().await;
+    let signal_schema = schema(&relay_server);
+    let foreign_room_id = ForeignRoomId("room".into());
+    let vulcast = session(&relay_server, "vulcast", SessionOptions::Vulcast);
+    relay_server
+        .register_room(foreign_room_id.clone(), ForeignSessionId("vulcast".into()))
+        .unwrap();
+    let web_client = session(
+        &relay_server,
+        "webclient",
+        SessionOptions::WebClient(foreign_room_id.clone()),
+    );
+    let host = session(&relay_server, "host", SessionOptions::Host(foreign_room_id));
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    let request = format!(
+        "mutation {{ muteProducer(producerId: {}) }}",
+        serde_json::to_string(&producer.id()).unwrap()
+    );
+
+    // only sessions permitted to moderate may mute
+    let response = signal_schema
+        .execute(Request::new(&request).data(web_client.downgrade()))
+        .await;
+    assert_eq!(response.errors.len(), 1);
+    let extensions = serde_json::to_value(&response.errors[0].extensions).unwrap();
+    assert_eq!(extensions["code"], "FORBIDDEN");
+    assert!(!producer.paused());
+
+    let data = execute(&signal_schema, &host, &request).await;
+    assert_eq!(data["muteProducer"], true);
+    assert!(producer.paused());
+    let log = vulcast.get_room().moderation_log();
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].target, Some(ForeignSessionId("vulcast".into())));
+    assert_eq!(log[0].producer_id, Some(producer.id()));
+
+    // sessions may not mute their own producers, even if permitted to moderate
+    vulcast.producer_resume(producer.id()).await.unwrap();
+    relay_server
+        .permissions()
+        .grant(Role::Vulcast, Permission::Moderate);
+    let response = signal_schema
+        .execute(Request::new(&request).data(vulcast.downgrade()))
+        .await;
+    assert_eq!(response.errors.len(), 1);
+    assert!(!producer.paused());
+    assert_eq!(vulcast.get_room().moderation_log().len(), 1);
+}
+
+#[tokio::test]
+async fn consume_many_reports_each_outcome() {
+    let relay_server = fixture::relay_server().await;
+    let signal_schema = schema(&relay_server);
+    let foreign_room_id = ForeignRoomId("room".into());
+    let vulcast = session(&relay_server, "vulcast", SessionOptions::Vulcast);
+    relay_server
+        .register_room(foreign_room_id.clone(), ForeignSessionId("vulcast".into()))
+        .unwrap();
+    let web_client = session(
+        &relay_server,
+        "webclient",
+        SessionOptions::WebClient(foreign_room_id),
+    );
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    web_client.set_rtp_capabilities(fixture::consumer_device_capabilities());
+    let recv_transport = web_client.create_webrtc_transport().await;
+    let unknown_id = Uuid::new_v4().to_string();
+
+    // consumers are created for known producers, and errors reported for the rest
+    let data = execute(
+        &signal_schema,
+        &web_client,
+        &format!(
+            r#"mutation {{
+                consumeMany(transportId: "{}", producerIds: ["{}", "{}"])
+            }}"#,
+            recv_transport.id(),
+            producer.id(),
+            unknown_id
+        ),
+    )
+    .await;
+    let results = data["consumeMany"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["producerId"], producer.id().to_string());
+    assert!(results[0]["error"].is_null());
+    assert_eq!(results[1]["producerId"], unknown_id);
+    assert!(results[1]["consumer"].is_null());
+    assert!(results[1]["error"].is_string());
+    let consumer_id = results[0]["consumer"]["id"].as_str().unwrap().to_owned();
+    assert_eq!(web_client.get_resource_count(&ResourceType::Consumer), 1);
+
+    // the resource limit applies to all requested consumers at once
+    let response = signal_schema
+        .execute(
+            Request::new(format!(
+                r#"mutation {{
+                    consumeMany(transportId: "{}", producerIds: ["{}", "{}"])
+                }}"#,
+                recv_transport.id(),
+                producer.id(),
+                producer.id()
+            ))
+            .data(web_client.downgrade()),
+        )
+        .await;
+    assert_eq!(response.errors.len(), 1);
+    assert_eq!(web_client.get_resource_count(&ResourceType::Consumer), 1);
+
+    // consumers are resumed individually, and errors reported for unknown consumers
+    let data = execute(
+        &signal_schema,
+        &web_client,
+        &format!(
+            r#"mutation {{ resumeConsumers(consumerIds: ["{}", "{}"]) }}"#,
+            consumer_id, unknown_id
+        ),
+    )
+    .await;
+    let results = data["resumeConsumers"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["consumerId"], consumer_id);
+    assert!(results[0]["error"].is_null());
+    assert_eq!(results[1]["consumerId"], unknown_id);
+    assert!(results[1]["error"].is_string());
+    let consumer_id: ConsumerId = serde_json::from_value(results[0]["consumerId"].clone()).unwrap();
+    let consumer = web_client.get_consumer(consumer_id).unwrap();
+    assert!(!consumer.paused());
+}
+
+#[tokio::test]
+async fn join_info_snapshots_room() {
+    let relay_server = fixture::relay_server().await;
+    let signal_schema = schema(&relay_server);
+    let foreign_room_id = ForeignRoomId("room".into());
+    let vulcast = session(&relay_server, "vulcast", SessionOptions::Vulcast);
+    relay_server
+        .register_room(foreign_room_id.clone(), ForeignSessionId("vulcast".into()))
+        .unwrap();
+    let web_client = session(
+        &relay_server,
+        "webclient",
+        SessionOptions::WebClient(foreign_room_id),
+    );
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    let data_producer = vulcast
+        .produce_data(send_transport.id(), fixture::sctp_stream_parameters())
+        .await
+        .unwrap();
+
+    let data = execute(&signal_schema, &web_client, "{ joinInfo }").await;
+    let join_info = &data["joinInfo"];
+    assert_eq!(join_info["roomId"], "room");
+    assert!(!join_info["serverRtpCapabilities"]["codecs"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+    assert_eq!(join_info["producerIds"], serde_json::json!([producer.id()]));
+    assert_eq!(
+        join_info["dataProducerIds"],
+        serde_json::json!([data_producer.id()])
+    );
+    let mut participants = join_info["participants"].as_array().unwrap().clone();
+    participants.sort_by_key(|participant| participant["sessionId"].to_string());
+    assert_eq!(
+        participants,
+        vec![
+            serde_json::json!({
+                "sessionId": "vulcast",
+                "role": "Vulcast",
+                "displayName": null,
+            }),
+            serde_json::json!({
+                "sessionId": "webclient",
+                "role": "WebClient",
+                "displayName": null,
+            }),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn audio_levels_are_notified_at_valid_intervals() {
+    let relay_server = fixture::relay_server().await;
+    let signal_schema = schema(&relay_server);
+    let vulcast = session(&relay_server, "vulcast", SessionOptions::Vulcast);
+
+    // intervals shorter than observed or longer than a minute are rejected
+    for interval in [0, 60_001] {
+        let response = signal_schema
+            .execute_stream(
+                Request::new(format!(
+                    "subscription {{ audioLevels(interval: {}) }}",
+                    interval
+                ))
+                .data(vulcast.downgrade()),
+            )
+            .next()
+            .await
+            .unwrap();
+        assert_eq!(response.errors.len(), 1);
+    }
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+    let mut audio_levels = signal_schema.execute_stream(
+        Request::new("subscription { audioLevels(interval: 500) }").data(vulcast.downgrade()),
+    );
+    let (response, _) = futures::join!(audio_levels.next(), async {
+        // report once the subscription is established
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        vulcast.get_room().report_audio_levels(vec![AudioLevel {
+            producer_id: producer.id(),
+            volume: -20,
+        }]);
+    });
+    let response = response.unwrap();
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data.into_json().unwrap()["audioLevels"],
+        serde_json::json!([{ "producerId": producer.id(), "volume": -20 }])
+    );
+}
+
+#[tokio::test]
+async fn producer_bitrate_rejects_invalid_intervals() {
+    let relay_server = fixture::relay_server().await;
+    let signal_schema = schema(&relay_server);
+    let vulcast = session(&relay_server, "vulcast", SessionOptions::Vulcast);
+    let send_transport = vulcast.create_webrtc_transport().await;
+    let producer = vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+
+    // intervals shorter than 100ms or longer than a minute are rejected
+    for interval in [99, 60_001] {
+        let response = signal_schema
+            .execute_stream(
+                Request::new(format!(
+                    r#"subscription {{ producerBitrate(producerId: "{}", intervalMs: {}) }}"#,
+                    producer.id(),
+                    interval
+                ))
+                .data(vulcast.downgrade()),
+            )
+            .next()
+            .await
+            .unwrap();
+        assert_eq!(response.errors.len(), 1);
+    }
+
+    let response = signal_schema
+        .execute_stream(
+            Request::new(format!(
+                r#"subscription {{ producerBitrate(producerId: "{}", intervalMs: 100) }}"#,
+                producer.id()
+            ))
+            .data(vulcast.downgrade()),
+        )
+        .next()
+        .await
+        .unwrap();
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(response.data.into_json().unwrap()["producerBitrate"], 0);
+}
+
+#[tokio::test]
+async fn time_sync_echoes_and_timestamps_request() {
+    let relay_server = fixture::relay_server().await;
+    let signal_schema = schema(&relay_server);
+    let vulcast = session(&relay_server, "vulcast", SessionOptions::Vulcast);
+
+    let unix_time_ms = || {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs_f64()
+            * 1000.0
+    };
+    let before = unix_time_ms();
+    let data = execute(
+        &signal_schema,
+        &vulcast,
+        "{ timeSync(clientTimestamp: 1234.5) }",
+    )
+    .await;
+    let after = unix_time_ms();
+
+    let time_sync = &data["timeSync"];
+    assert_eq!(time_sync["clientTimestamp"], 1234.5);
+    let server_receive_timestamp = time_sync["serverReceiveTimestamp"].as_f64().unwrap();
+    let server_transmit_timestamp = time_sync["serverTransmitTimestamp"].as_f64().unwrap();
+    assert!(before <= server_receive_timestamp);
+    assert!(server_receive_timestamp <= server_transmit_timestamp);
+    assert!(server_transmit_timestamp <= after);
+}
+
+#[tokio::test]
+async fn hosts_only_kick_web_clients_in_their_room() {
+    let relay_server = fixture::relay_server().await;
+    let signal_schema = schema(&relay_server);
+    let room = ForeignRoomId("room".into());
+    let other_room = ForeignRoomId("other-room".into());
+    // targets are held by the relay server alone, so that kicks drop them
+    let connect = |fsid: &str, session_options: SessionOptions| {
+        let token = relay_server
+            .register_session(ForeignSessionId(fsid.into()), session_options)
+            .unwrap();
+        drop(relay_server.session_from_token(token).unwrap());
+        token
+    };
+    let vulcast = connect("vulcast", SessionOptions::Vulcast);
+    relay_server
+        .register_room(room.clone(), ForeignSessionId("vulcast".into()))
+        .unwrap();
+    connect("other-vulcast", SessionOptions::Vulcast);
+    relay_server
+        .register_room(other_room.clone(), ForeignSessionId("other-vulcast".into()))
+        .unwrap();
+    let target = connect("target", SessionOptions::WebClient(room.clone()));
+    let other_target = connect("other-target", SessionOptions::WebClient(other_room));
+    let other_host = connect("other-host", SessionOptions::Host(room.clone()));
+    let host = session(&relay_server, "host", SessionOptions::Host(room.clone()));
+    let web = session(&relay_server, "web", SessionOptions::WebClient(room));
+
+    let kick = |session: &Session, fsid: &str| {
+        signal_schema.execute(
+            Request::new(format!(
+                "mutation {{ kickSession(sessionId: \"{}\") }}",
+                fsid
+            ))
+            .data(session.downgrade()),
+        )
+    };
+
+    // web clients may not kick, nor may hosts kick across rooms or kick
+    // anyone other than web clients
+    for (session, fsid) in [
+        (&web, "target"),
+        (&host, "other-target"),
+        (&host, "vulcast"),
+        (&host, "other-host"),
+    ] {
+        let response = kick(session, fsid).await;
+        assert_eq!(response.errors.len(), 1, "{}", fsid);
+    }
+    for token in [&target, &other_target, &vulcast, &other_host] {
+        assert!(relay_server.get_session_by_token(token).is_some());
+    }
+
+    let response = kick(&host, "target").await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data.into_json().unwrap()["kickSession"],
+        serde_json::json!(true)
+    );
+    assert!(relay_server.get_session_by_token(&target).is_none());
+}
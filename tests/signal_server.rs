@@ -0,0 +1,202 @@
+use serde_json::json;
+use uuid::Uuid;
+
+use vulcan_relay::crypto_policy::CryptoPolicy;
+use vulcan_relay::relay_server::{ForeignSessionId, Role, SessionOptions, SessionToken};
+use vulcan_relay::resource_policy::ResourcePolicy;
+use vulcan_relay::schema_compat::DeprecationPolicy;
+use vulcan_relay::session::{ConsumerConfig, PlainTransportConfig};
+use vulcan_relay::signal_schema;
+use vulcan_relay::signal_server::{
+    self, Authenticator, ConnectParams, ConnectParamsError, TokenAuthenticator,
+};
+
+pub mod fixture;
+
+const PARAM_TOKEN: &str = "7f1d1b2e-4a7c-4f0e-9d3b-2c5a6e8f9a10";
+const COOKIE_TOKEN: &str = "c3a9e0f4-1b2d-4e6f-8a7b-9c0d1e2f3a4b";
+
+fn token(token: &str) -> Option<SessionToken> {
+    Some(SessionToken(Uuid::parse_str(token).unwrap()))
+}
+
+#[test]
+fn param_token_takes_precedence_over_cookie() {
+    let authenticator = TokenAuthenticator;
+    assert_eq!(
+        authenticator.authenticate(Some(COOKIE_TOKEN), &json!({ "token": PARAM_TOKEN })),
+        token(PARAM_TOKEN)
+    );
+}
+
+#[test]
+fn cookie_token_is_used_without_param() {
+    let authenticator = TokenAuthenticator;
+    assert_eq!(
+        authenticator.authenticate(Some(COOKIE_TOKEN), &json!({})),
+        token(COOKIE_TOKEN)
+    );
+    // malformed params fall back to the cookie
+    assert_eq!(
+        authenticator.authenticate(Some(COOKIE_TOKEN), &json!({ "token": "not a token" })),
+        token(COOKIE_TOKEN)
+    );
+}
+
+#[test]
+fn missing_or_malformed_tokens_are_rejected() {
+    let authenticator = TokenAuthenticator;
+    assert_eq!(authenticator.authenticate(None, &json!({})), None);
+    assert_eq!(authenticator.authenticate(None, &json!(null)), None);
+    assert_eq!(
+        authenticator.authenticate(Some("not a token"), &json!({})),
+        None
+    );
+}
+
+#[test]
+fn connect_params_are_parsed() {
+    let connect_params = ConnectParams::parse(&json!({
+        "token": PARAM_TOKEN,
+        "displayName": "ayush",
+        "clientVersion": "vulcan-web/1.4.0",
+        "capabilities": ["simulcast"],
+        "traceparent": "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        "unknown": true,
+    }))
+    .unwrap();
+    assert_eq!(
+        connect_params,
+        ConnectParams {
+            token: token(PARAM_TOKEN),
+            display_name: Some("ayush".into()),
+            client_version: Some("vulcan-web/1.4.0".into()),
+            capabilities: vec!["simulcast".into()],
+            traceparent: Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".into()),
+        }
+    );
+    assert_eq!(
+        ConnectParams::parse(&json!(null)).unwrap(),
+        ConnectParams::default()
+    );
+}
+
+#[test]
+fn invalid_connect_params_name_the_field() {
+    let field = |value| match ConnectParams::parse(&value) {
+        Err(ConnectParamsError::Invalid { field, .. }) => Some(field),
+        _ => None,
+    };
+    assert_eq!(field(json!({ "token": "not a token" })), Some("token"));
+    assert_eq!(field(json!({ "displayName": "  " })), Some("displayName"));
+    assert_eq!(
+        field(json!({ "displayName": "x".repeat(65) })),
+        Some("displayName")
+    );
+    assert_eq!(
+        field(json!({ "capabilities": "simulcast" })),
+        Some("capabilities")
+    );
+    assert_eq!(
+        field(json!({ "traceparent": "00-00000000000000000000000000000000-00f067aa0ba902b7-01" })),
+        Some("traceparent")
+    );
+    assert_eq!(
+        ConnectParams::parse(&json!("token")),
+        Err(ConnectParamsError::NotAnObject)
+    );
+}
+
+#[tokio::test]
+async fn playground_requests_act_as_bearer_session() {
+    let relay_server = fixture::relay_server().await;
+    let signal_schema = signal_schema::schema(
+        relay_server.clone(),
+        ResourcePolicy::new(),
+        None,
+        PlainTransportConfig::default(),
+        ConsumerConfig::default(),
+        CryptoPolicy::default(),
+        DeprecationPolicy::default(),
+    );
+    let routes = signal_server::playground_routes(relay_server.clone(), signal_schema);
+    let token = relay_server
+        .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+        .unwrap();
+
+    let playground = warp::test::request().path("/").reply(&routes).await;
+    assert_eq!(playground.status(), 200);
+
+    let query = json!({ "query": "{ timeSync(clientTimestamp: 0) }" });
+    let anonymous = warp::test::request()
+        .method("POST")
+        .path("/")
+        .json(&query)
+        .reply(&routes)
+        .await;
+    let anonymous: serde_json::Value = serde_json::from_slice(anonymous.body()).unwrap();
+    assert!(anonymous["errors"].is_array());
+
+    let authorized = warp::test::request()
+        .method("POST")
+        .path("/")
+        .header("authorization", format!("Bearer {}", token.0))
+        .json(&query)
+        .reply(&routes)
+        .await;
+    let authorized: serde_json::Value = serde_json::from_slice(authorized.body()).unwrap();
+    assert!(authorized["errors"].is_null(), "{}", authorized);
+    // the session connected for the request is released with it
+    assert!(relay_server.get_session_by_token(&token).is_none());
+
+    // sessions connected over a websocket are acted on, and left connected
+    let session = relay_server.session_from_token(token).unwrap();
+    let authorized = warp::test::request()
+        .method("POST")
+        .path("/")
+        .header("authorization", format!("Bearer {}", token.0))
+        .json(&json!({ "query": "{ whoAmI { sessionId } }" }))
+        .reply(&routes)
+        .await;
+    let authorized: serde_json::Value = serde_json::from_slice(authorized.body()).unwrap();
+    assert_eq!(
+        authorized["data"]["whoAmI"]["sessionId"],
+        session.id().to_string()
+    );
+    assert!(relay_server.get_session_by_token(&token).is_some());
+}
+
+#[tokio::test]
+async fn playground_requests_are_version_gated() {
+    let relay_server = fixture::relay_server().await;
+    let signal_schema = signal_schema::schema(
+        relay_server.clone(),
+        ResourcePolicy::new(),
+        None,
+        PlainTransportConfig::default(),
+        ConsumerConfig::default(),
+        CryptoPolicy::default(),
+        DeprecationPolicy::default(),
+    );
+    let routes = signal_server::playground_routes(relay_server.clone(), signal_schema);
+    let token = relay_server
+        .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .version_gate()
+        .set_min_version(Role::Vulcast, Some("1.0.0".parse().unwrap()));
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/")
+        .header("authorization", format!("Bearer {}", token.0))
+        .json(&json!({ "query": "{ timeSync(clientTimestamp: 0) }" }))
+        .reply(&routes)
+        .await;
+    let response: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        "UPGRADE_REQUIRED"
+    );
+    assert!(relay_server.get_session_by_token(&token).is_none());
+}
@@ -0,0 +1,54 @@
+use mediasoup::rtp_parameters::MediaKind;
+
+use vulcan_relay::relay_server::{ForeignRoomId, ForeignSessionId, SessionOptions};
+use vulcan_relay::soak_report;
+
+pub mod fixture;
+
+#[tokio::test]
+async fn soak_report_accounts_for_relay_objects() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id, vulcast_session_id.clone())
+        .unwrap();
+
+    let send_transport = vulcast.create_webrtc_transport().await;
+    vulcast
+        .produce(
+            send_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+
+    let report = soak_report::report(&relay_server).await;
+    assert_eq!(report.registered_sessions, 1);
+    assert_eq!(report.registered_rooms, 1);
+    assert_eq!(report.connected_sessions, 1);
+    assert_eq!(report.active_rooms, 1);
+    assert!(report.live_sessions >= 1);
+    assert!(report.live_rooms >= 1);
+    assert_eq!(report.handles.webrtc_transports, 1);
+    assert_eq!(report.handles.producers, 1);
+    assert!(report.worker_max_rss_kib.is_some());
+    assert_eq!(report.leak_discrepancies, Some(0));
+
+    drop((send_transport, vulcast));
+    relay_server.unregister_session(vulcast_session_id).unwrap();
+    let report = soak_report::report(&relay_server).await;
+    assert_eq!(report.registered_sessions, 0);
+    assert_eq!(report.connected_sessions, 0);
+    assert_eq!(report.handles.producers, 0);
+}
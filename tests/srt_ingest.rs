@@ -0,0 +1,140 @@
+use std::net::Ipv4Addr;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use uuid::Uuid;
+
+use vulcan_relay::relay_server::{ForeignSessionId, RelayServer, SessionOptions, SessionToken};
+use vulcan_relay::session::{ResourceType, Session};
+use vulcan_relay::srt_ingest::SrtIngest;
+
+pub mod fixture;
+
+/// Write an executable script standing in for ffmpeg, which records its arguments
+/// and stdin next to itself before running the given commands.
+fn fake_ffmpeg(commands: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("srt-ingest-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("ffmpeg");
+    std::fs::write(
+        &path,
+        format!(
+            "#!/bin/sh\necho \"$@\" > \"$0.args\"\ncat > \"$0.stdin\"\n{}\n",
+            commands
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+async fn read_when_written(path: &Path) -> String {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            match std::fs::read_to_string(path) {
+                Ok(contents) if contents.ends_with('\n') => return contents,
+                _ => tokio::time::sleep(Duration::from_millis(10)).await,
+            }
+        }
+    })
+    .await
+    .unwrap()
+}
+
+fn vulcast(relay_server: &RelayServer) -> (Session, SessionToken) {
+    let token = relay_server
+        .register_session(ForeignSessionId("vulcast".into()), SessionOptions::Vulcast)
+        .unwrap();
+    (relay_server.session_from_token(token).unwrap(), token)
+}
+
+#[tokio::test]
+async fn passphrase_is_passed_over_stdin() {
+    let relay_server = fixture::relay_server().await;
+    let (vulcast, token) = vulcast(&relay_server);
+    let ffmpeg = fake_ffmpeg("exec sleep 30");
+    let srt_ingest = SrtIngest::new(
+        Ipv4Addr::LOCALHOST.into(),
+        20000..=20000,
+        ffmpeg.to_string_lossy().into_owned(),
+    );
+
+    let options = srt_ingest.start(&vulcast).await.unwrap();
+    assert_eq!(options.port, 20000);
+    assert!(vulcast.get_producer(options.audio_producer.id()).is_some());
+    assert!(vulcast.get_producer(options.video_producer.id()).is_some());
+    assert_eq!(vulcast.get_resource_count(&ResourceType::PlainTransport), 2);
+
+    // the passphrase is generated per ingest, and only handed to ffmpeg over stdin
+    assert_ne!(options.passphrase, token.to_string());
+    let args = read_when_written(&ffmpeg.with_extension("args")).await;
+    let stdin = read_when_written(&ffmpeg.with_extension("stdin")).await;
+    assert!(!args.contains(&options.passphrase));
+    assert!(args.contains("pipe:0"));
+    assert!(stdin.contains(&format!(
+        "srt://127.0.0.1:20000?mode=listener&passphrase={}",
+        options.passphrase
+    )));
+}
+
+#[tokio::test]
+async fn start_fails_without_free_ports() {
+    let relay_server = fixture::relay_server().await;
+    let (vulcast, _) = vulcast(&relay_server);
+    let srt_ingest = SrtIngest::new(
+        Ipv4Addr::LOCALHOST.into(),
+        20001..=20001,
+        fake_ffmpeg("exec sleep 30").to_string_lossy().into_owned(),
+    );
+
+    let _options = srt_ingest.start(&vulcast).await.unwrap();
+    assert!(srt_ingest.start(&vulcast).await.is_err());
+    // nothing is acquired by the failed ingest
+    assert_eq!(vulcast.get_resource_count(&ResourceType::PlainTransport), 2);
+    assert_eq!(vulcast.get_resource_count(&ResourceType::Producer), 2);
+}
+
+#[tokio::test]
+async fn failed_spawn_releases_resources() {
+    let relay_server = fixture::relay_server().await;
+    let (vulcast, _) = vulcast(&relay_server);
+    let srt_ingest = SrtIngest::new(
+        Ipv4Addr::LOCALHOST.into(),
+        20002..=20002,
+        "/nonexistent/ffmpeg".into(),
+    );
+
+    for _ in 0..2 {
+        // the port is released, so the second attempt fails the same way
+        let err = srt_ingest.start(&vulcast).await.unwrap_err();
+        assert!(!err.to_string().contains("no srt ingest ports"));
+        assert_eq!(vulcast.get_resource_count(&ResourceType::PlainTransport), 0);
+        assert_eq!(vulcast.get_resource_count(&ResourceType::Producer), 0);
+    }
+}
+
+#[tokio::test]
+async fn exited_ingest_releases_resources() {
+    let relay_server = fixture::relay_server().await;
+    let (vulcast, _) = vulcast(&relay_server);
+    let srt_ingest = SrtIngest::new(
+        Ipv4Addr::LOCALHOST.into(),
+        20003..=20003,
+        fake_ffmpeg("exit 1").to_string_lossy().into_owned(),
+    );
+
+    let options = srt_ingest.start(&vulcast).await.unwrap();
+    drop(options);
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while vulcast.get_resource_count(&ResourceType::PlainTransport) > 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(vulcast.get_resource_count(&ResourceType::Producer), 0);
+
+    // the port is released once ffmpeg exits
+    assert!(srt_ingest.start(&vulcast).await.is_ok());
+}
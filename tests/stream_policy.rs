@@ -0,0 +1,228 @@
+use futures::stream::StreamExt;
+use std::time::Duration;
+
+use mediasoup::rtp_parameters::MediaKind;
+
+use vulcan_relay::cmdline::StreamRolesArg;
+use vulcan_relay::relay_server::{
+    ForeignRoomId, ForeignSessionId, Role, SessionOptions, SetRoomStreamPolicyError,
+};
+use vulcan_relay::session::ProducerAppData;
+use vulcan_relay::stream_policy::{StreamPolicy, StreamPolicyError, StreamRole, StreamRules};
+
+pub mod fixture;
+
+fn web_client_rules() -> StreamRules {
+    StreamRules {
+        produce: Some(vec![StreamRole::Camera, StreamRole::Voice]),
+        auto_consume: Some(vec![StreamRole::Game]),
+    }
+}
+
+fn app_data(role: Option<StreamRole>) -> ProducerAppData {
+    ProducerAppData {
+        role,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn stream_roles_carry_their_kinds() {
+    assert!(StreamRole::Camera.allows(MediaKind::Video));
+    assert!(!StreamRole::Camera.allows(MediaKind::Audio));
+    assert!(StreamRole::Voice.allows(MediaKind::Audio));
+    assert!(!StreamRole::Voice.allows(MediaKind::Video));
+    assert!(StreamRole::Game.allows(MediaKind::Audio));
+    assert!(StreamRole::Screen.allows(MediaKind::Video));
+}
+
+#[test]
+fn roles_without_rules_are_unrestricted() {
+    let policy = StreamPolicy::new();
+    assert_eq!(
+        policy.check_produce(Role::WebClient, None, MediaKind::Video),
+        Ok(())
+    );
+    assert!(policy.auto_consumes(Role::WebClient, None));
+    // the kind of a declared role is still enforced
+    assert_eq!(
+        policy.check_produce(Role::Vulcast, Some(StreamRole::Voice), MediaKind::Video),
+        Err(StreamPolicyError::KindMismatch {
+            stream_role: StreamRole::Voice,
+            kind: MediaKind::Video,
+        })
+    );
+}
+
+#[test]
+fn rules_restrict_production_and_auto_consumption() {
+    let mut policy = StreamPolicy::new();
+    policy.set_rules(Role::WebClient, web_client_rules());
+
+    assert_eq!(
+        policy.check_produce(Role::WebClient, Some(StreamRole::Camera), MediaKind::Video),
+        Ok(())
+    );
+    assert_eq!(
+        policy.check_produce(Role::WebClient, Some(StreamRole::Game), MediaKind::Video),
+        Err(StreamPolicyError::NotAllowed {
+            role: Role::WebClient,
+            stream_role: StreamRole::Game,
+        })
+    );
+    assert_eq!(
+        policy.check_produce(Role::WebClient, None, MediaKind::Audio),
+        Err(StreamPolicyError::RoleRequired {
+            role: Role::WebClient
+        })
+    );
+
+    assert!(policy.auto_consumes(Role::WebClient, Some(StreamRole::Game)));
+    assert!(!policy.auto_consumes(Role::WebClient, Some(StreamRole::Camera)));
+    assert!(!policy.auto_consumes(Role::WebClient, None));
+    // other roles are unaffected
+    assert!(policy.auto_consumes(Role::Host, Some(StreamRole::Camera)));
+}
+
+#[test]
+fn stream_roles_are_parsed_from_args() {
+    let arg: StreamRolesArg = "webclient=camera,voice".parse().unwrap();
+    assert_eq!(arg.role, Role::WebClient);
+    assert_eq!(
+        arg.stream_roles,
+        vec![StreamRole::Camera, StreamRole::Voice]
+    );
+
+    let arg: StreamRolesArg = "host=".parse().unwrap();
+    assert!(arg.stream_roles.is_empty());
+
+    assert!("webclient=camera,mic".parse::<StreamRolesArg>().is_err());
+    assert!("viewer=game".parse::<StreamRolesArg>().is_err());
+}
+
+#[tokio::test]
+async fn room_stream_policy_is_enforced() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+
+    assert_eq!(
+        relay_server.set_room_stream_rules(
+            foreign_room_id.clone(),
+            Role::WebClient,
+            web_client_rules()
+        ),
+        Err(SetRoomStreamPolicyError::UnknownRoom(
+            foreign_room_id.clone()
+        ))
+    );
+
+    let vulcast = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+                .unwrap(),
+        )
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id)
+        .unwrap();
+    relay_server
+        .set_room_stream_rules(foreign_room_id.clone(), Role::WebClient, web_client_rules())
+        .unwrap();
+    let web_client = relay_server
+        .session_from_token(
+            relay_server
+                .register_session(
+                    ForeignSessionId("webclient".into()),
+                    SessionOptions::WebClient(foreign_room_id.clone()),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+
+    let vulcast_transport = vulcast.create_webrtc_transport().await;
+    let game = vulcast
+        .produce_with_app_data(
+            vulcast_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+            app_data(Some(StreamRole::Game)),
+        )
+        .await
+        .unwrap();
+    // untagged producers of unrestricted roles are still accepted
+    vulcast
+        .produce(
+            vulcast_transport.id(),
+            MediaKind::Audio,
+            fixture::audio_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+
+    let web_client_transport = web_client.create_webrtc_transport().await;
+    assert!(web_client
+        .produce(
+            web_client_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+        )
+        .await
+        .is_err());
+    assert!(web_client
+        .produce_with_app_data(
+            web_client_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+            app_data(Some(StreamRole::Screen)),
+        )
+        .await
+        .is_err());
+    let camera = web_client
+        .produce_with_app_data(
+            web_client_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+            app_data(Some(StreamRole::Camera)),
+        )
+        .await
+        .unwrap();
+
+    // web clients are only announced the game
+    let room = web_client.get_room();
+    let announcements = room.auto_consumed_producers(Role::WebClient);
+    tokio::pin!(announcements);
+    let announcement = announcements.next().await.unwrap();
+    assert_eq!(announcement.producer_id, game.id());
+    assert_eq!(announcement.role, Some(StreamRole::Game));
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), announcements.next())
+            .await
+            .is_err()
+    );
+
+    // hosts are announced everything
+    let announced = room
+        .auto_consumed_producers(Role::Host)
+        .take(3)
+        .map(|announcement| announcement.producer_id)
+        .collect::<Vec<_>>()
+        .await;
+    assert!(announced.contains(&camera.id()));
+
+    // restoring the default policy lifts the restrictions
+    relay_server
+        .set_room_stream_policy(foreign_room_id, None)
+        .unwrap();
+    assert_eq!(room.stream_policy(), StreamPolicy::new());
+    web_client
+        .produce(
+            web_client_transport.id(),
+            MediaKind::Video,
+            fixture::video_producer_device_parameters(),
+        )
+        .await
+        .unwrap();
+}
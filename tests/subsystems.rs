@@ -0,0 +1,23 @@
+use vulcan_relay::subsystems::Subsystem;
+
+pub mod fixture;
+
+#[tokio::test]
+async fn subsystems_are_toggled_at_runtime() {
+    let relay_server = fixture::relay_server().await;
+    let subsystems = relay_server.subsystems();
+
+    // all subsystems are enabled initially
+    assert!(Subsystem::ALL
+        .into_iter()
+        .all(|subsystem| subsystems.enabled(subsystem)));
+
+    assert!(subsystems.set_enabled(Subsystem::LeakAudit, false));
+    assert!(!subsystems.set_enabled(Subsystem::LeakAudit, false));
+    // switches are shared by all handles to the relay
+    assert!(!relay_server.subsystems().enabled(Subsystem::LeakAudit));
+    assert!(relay_server.subsystems().enabled(Subsystem::Whep));
+
+    assert!(subsystems.set_enabled(Subsystem::LeakAudit, true));
+    assert!(relay_server.subsystems().enabled(Subsystem::LeakAudit));
+}
@@ -0,0 +1,89 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use vulcan_relay::relay_server::{
+    ForeignRoomId, ForeignSessionId, SessionOptions, SetRoomTurnConfigError,
+};
+use vulcan_relay::turn::TurnConfig;
+
+pub mod fixture;
+
+fn turn_config(url: &str) -> TurnConfig {
+    TurnConfig {
+        urls: vec![url.into()],
+        shared_secret: "north".into(),
+        ttl: Duration::from_secs(86400),
+    }
+}
+
+#[test]
+fn credentials_follow_turn_rest_api() {
+    let credentials = turn_config("turn:turn.example.com:3478")
+        .mint("webclient", UNIX_EPOCH + Duration::from_secs(1650000000));
+    assert_eq!(credentials.urls, vec!["turn:turn.example.com:3478"]);
+    assert_eq!(credentials.username, "1650086400:webclient");
+    assert_eq!(credentials.credential, "UPUMOiFgB0XziraYyrK7J/3T8TE=");
+    assert_eq!(credentials.expires_at, 1650086400);
+}
+
+#[tokio::test]
+async fn room_turn_server_overrides_default() {
+    let relay_server = fixture::relay_server().await;
+
+    let foreign_room_id = ForeignRoomId("ayush".into());
+    let vulcast_session_id = ForeignSessionId("vulcast".into());
+    let webclient_session_id = ForeignSessionId("webclient".into());
+
+    relay_server
+        .register_session(vulcast_session_id.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    relay_server
+        .register_room(foreign_room_id.clone(), vulcast_session_id.clone())
+        .unwrap();
+    relay_server
+        .register_session(
+            webclient_session_id.clone(),
+            SessionOptions::WebClient(foreign_room_id.clone()),
+        )
+        .unwrap();
+
+    // no turn server is offered by default
+    assert!(relay_server
+        .turn_credentials(&webclient_session_id)
+        .is_none());
+
+    relay_server.set_turn_config(Some(turn_config("turn:default")));
+    assert_eq!(
+        relay_server
+            .turn_credentials(&webclient_session_id)
+            .unwrap()
+            .urls,
+        vec!["turn:default"]
+    );
+
+    relay_server
+        .set_room_turn_config(foreign_room_id.clone(), Some(turn_config("turn:room")))
+        .unwrap();
+    for fsid in [&vulcast_session_id, &webclient_session_id] {
+        let credentials = relay_server.turn_credentials(fsid).unwrap();
+        assert_eq!(credentials.urls, vec!["turn:room"]);
+        assert!(credentials.username.ends_with(&format!(":{}", fsid.0)));
+    }
+
+    relay_server
+        .set_room_turn_config(foreign_room_id, None)
+        .unwrap();
+    assert_eq!(
+        relay_server
+            .turn_credentials(&webclient_session_id)
+            .unwrap()
+            .urls,
+        vec!["turn:default"]
+    );
+
+    assert_eq!(
+        relay_server.set_room_turn_config(ForeignRoomId("unknownroom".into()), None),
+        Err(SetRoomTurnConfigError::UnknownRoom(ForeignRoomId(
+            "unknownroom".into()
+        )))
+    );
+}
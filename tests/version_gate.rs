@@ -0,0 +1,46 @@
+use vulcan_relay::relay_server::Role;
+use vulcan_relay::version_gate::{ClientVersion, VersionGate};
+
+fn version(version: &str) -> ClientVersion {
+    version.parse().unwrap()
+}
+
+#[test]
+fn versions_are_parsed_leniently() {
+    assert_eq!(version("vulcast-firmware/v1.4"), version("1.4.0"));
+    assert_eq!(version("1.4.0-beta.2+build.7"), version("1.4"));
+    assert!(version("1.10.0") > version("1.9.3"));
+    assert!("".parse::<ClientVersion>().is_err());
+    assert!("vulcan-web/latest".parse::<ClientVersion>().is_err());
+}
+
+#[test]
+fn clients_below_minimum_version_are_rejected() {
+    let version_gate = VersionGate::new();
+    assert!(version_gate.check(Role::Vulcast, None).is_ok());
+
+    version_gate.set_min_version(Role::Vulcast, Some(version("1.4.0")));
+    assert!(version_gate
+        .check(Role::Vulcast, Some("vulcast-firmware/1.4.1"))
+        .is_ok());
+    let err = version_gate
+        .check(Role::Vulcast, Some("vulcast-firmware/1.3.9"))
+        .unwrap_err();
+    assert_eq!(err.min_version, version("1.4.0"));
+    assert_eq!(
+        err.client_version.as_deref(),
+        Some("vulcast-firmware/1.3.9")
+    );
+    // undeclared and unparseable versions are assumed outdated
+    assert!(version_gate.check(Role::Vulcast, None).is_err());
+    assert!(version_gate.check(Role::Vulcast, Some("dev")).is_err());
+    // other roles are unaffected
+    assert!(version_gate.check(Role::WebClient, None).is_ok());
+    assert_eq!(
+        version_gate.min_versions(),
+        vec![(Role::Vulcast, version("1.4.0"))]
+    );
+
+    version_gate.set_min_version(Role::Vulcast, None);
+    assert!(version_gate.check(Role::Vulcast, None).is_ok());
+}
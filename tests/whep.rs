@@ -0,0 +1,398 @@
+use std::time::Duration;
+
+use mediasoup::rtp_parameters::MediaKind;
+use warp::http::{Response, StatusCode};
+use warp::hyper::body::Bytes;
+
+use vulcan_relay::permissions::Permission;
+use vulcan_relay::relay_server::{
+    ForeignRoomId, ForeignSessionId, RelayServer, Role, SessionOptions, SessionToken,
+};
+use vulcan_relay::resource_policy::{LimitScope, ResourcePolicy};
+use vulcan_relay::session::{ResourceType, Session};
+use vulcan_relay::whep;
+
+pub mod fixture;
+
+/// Receive-only offer of a browser player, with an audio and a video section.
+const OFFER: &str = "v=0\r
+o=- 4611731400430051336 2 IN IP4 127.0.0.1\r
+s=-\r
+t=0 0\r
+a=group:BUNDLE 0 1\r
+a=extmap-allow-mixed\r
+a=msid-semantic: WMS\r
+m=audio 9 UDP/TLS/RTP/SAVPF 111 63 9\r
+c=IN IP4 0.0.0.0\r
+a=rtcp:9 IN IP4 0.0.0.0\r
+a=ice-ufrag:pX9b\r
+a=ice-pwd:k0ZcMgXlJz2GtBl5ygk0B7xT\r
+a=ice-options:trickle\r
+a=fingerprint:sha-256 82:5A:68:3D:36:C3:0A:DE:AF:E7:32:43:D2:88:83:57:AC:2D:65:E5:80:C4:B6:FB:AF:1A:A0:21:9F:6D:0C:AD\r
+a=setup:actpass\r
+a=mid:0\r
+a=extmap:1 urn:ietf:params:rtp-hdrext:ssrc-audio-level\r
+a=extmap:2 http://www.webrtc.org/experiments/rtp-hdrext/abs-send-time\r
+a=extmap:4 urn:ietf:params:rtp-hdrext:sdes:mid\r
+a=recvonly\r
+a=rtcp-mux\r
+a=rtpmap:111 opus/48000/2\r
+a=rtcp-fb:111 transport-cc\r
+a=fmtp:111 minptime=10;useinbandfec=1\r
+a=rtpmap:63 red/48000/2\r
+a=fmtp:63 111/111\r
+a=rtpmap:9 G722/8000\r
+m=video 9 UDP/TLS/RTP/SAVPF 96 97 102 103\r
+c=IN IP4 0.0.0.0\r
+a=rtcp:9 IN IP4 0.0.0.0\r
+a=ice-ufrag:pX9b\r
+a=ice-pwd:k0ZcMgXlJz2GtBl5ygk0B7xT\r
+a=ice-options:trickle\r
+a=fingerprint:sha-256 82:5A:68:3D:36:C3:0A:DE:AF:E7:32:43:D2:88:83:57:AC:2D:65:E5:80:C4:B6:FB:AF:1A:A0:21:9F:6D:0C:AD\r
+a=setup:actpass\r
+a=mid:1\r
+a=extmap:2 http://www.webrtc.org/experiments/rtp-hdrext/abs-send-time\r
+a=extmap:3 http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01\r
+a=extmap:4 urn:ietf:params:rtp-hdrext:sdes:mid\r
+a=recvonly\r
+a=rtcp-mux\r
+a=rtcp-rsize\r
+a=rtpmap:96 VP8/90000\r
+a=rtcp-fb:96 goog-remb\r
+a=rtcp-fb:96 transport-cc\r
+a=rtcp-fb:96 ccm fir\r
+a=rtcp-fb:96 nack\r
+a=rtcp-fb:96 nack pli\r
+a=rtpmap:97 rtx/90000\r
+a=fmtp:97 apt=96\r
+a=rtpmap:102 H264/90000\r
+a=rtcp-fb:102 goog-remb\r
+a=rtcp-fb:102 transport-cc\r
+a=rtcp-fb:102 ccm fir\r
+a=rtcp-fb:102 nack\r
+a=rtcp-fb:102 nack pli\r
+a=fmtp:102 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=4d001f\r
+a=rtpmap:103 rtx/90000\r
+a=fmtp:103 apt=102\r
+";
+
+/// Register a room whose Vulcast produces the given kinds of media.
+async fn room(relay_server: &RelayServer, kinds: &[MediaKind]) -> (Session, SessionToken) {
+    let vulcast_fsid = ForeignSessionId("vulcast".into());
+    let token = relay_server
+        .register_session(vulcast_fsid.clone(), SessionOptions::Vulcast)
+        .unwrap();
+    let vulcast = relay_server.session_from_token(token).unwrap();
+    relay_server
+        .register_room(ForeignRoomId("room".into()), vulcast_fsid)
+        .unwrap();
+
+    let transport = vulcast.create_webrtc_transport().await;
+    vulcast
+        .connect_webrtc_transport(transport.id(), fixture::dtls_parameters())
+        .await
+        .unwrap();
+    for kind in kinds {
+        let rtp_parameters = match kind {
+            MediaKind::Audio => fixture::audio_producer_device_parameters(),
+            MediaKind::Video => fixture::video_producer_device_parameters(),
+        };
+        vulcast
+            .produce(transport.id(), *kind, rtp_parameters)
+            .await
+            .unwrap();
+    }
+    (vulcast, token)
+}
+
+fn web_client(relay_server: &RelayServer, fsid: &str) -> SessionToken {
+    relay_server
+        .register_session(
+            ForeignSessionId(fsid.into()),
+            SessionOptions::WebClient(ForeignRoomId("room".into())),
+        )
+        .unwrap()
+}
+
+async fn offer(relay_server: &RelayServer, token: SessionToken, sdp: &str) -> Response<Bytes> {
+    offer_with_policy(relay_server, &ResourcePolicy::new(), token, sdp).await
+}
+
+async fn offer_with_policy(
+    relay_server: &RelayServer,
+    resource_policy: &ResourcePolicy,
+    token: SessionToken,
+    sdp: &str,
+) -> Response<Bytes> {
+    warp::test::request()
+        .method("POST")
+        .path("/whep")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Bytes::copy_from_slice(sdp.as_bytes()))
+        .reply(&whep::routes(relay_server.clone(), resource_policy.clone()))
+        .await
+}
+
+/// Offer as a web client, returning the SDP answer.
+async fn answer(relay_server: &RelayServer, token: SessionToken, sdp: &str) -> String {
+    let response = offer(relay_server, token, sdp).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(response.headers()["content-type"], "application/sdp");
+    String::from_utf8(response.body().to_vec()).unwrap()
+}
+
+async fn teardown(relay_server: &RelayServer, token: SessionToken, resource: &str) -> StatusCode {
+    warp::test::request()
+        .method("DELETE")
+        .path(resource)
+        .header("authorization", format!("Bearer {}", token))
+        .reply(&whep::routes(relay_server.clone(), ResourcePolicy::new()))
+        .await
+        .status()
+}
+
+#[tokio::test]
+async fn offer_is_answered_with_consumers() {
+    let relay_server = fixture::relay_server().await;
+    let _vulcast = room(&relay_server, &[MediaKind::Audio, MediaKind::Video]).await;
+    let token = web_client(&relay_server, "player");
+
+    let sdp = answer(&relay_server, token, OFFER).await;
+    let session = relay_server.get_session_by_token(&token).unwrap();
+    assert_eq!(
+        session.get_resource_count(&ResourceType::WebrtcTransport),
+        1
+    );
+    assert_eq!(session.get_resource_count(&ResourceType::Consumer), 2);
+
+    let lines = sdp.split("\r\n").collect::<Vec<&str>>();
+    assert!(sdp.ends_with("\r\n"));
+    assert!(lines.contains(&"a=ice-lite"));
+    assert!(lines.contains(&"a=group:BUNDLE 0 1"));
+    assert!(lines.contains(&"a=setup:passive"));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("a=fingerprint:sha-")));
+    assert!(lines.iter().any(|line| line.starts_with("a=ice-ufrag:")));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("a=candidate:") && line.contains(" udp ")));
+
+    // the sections are answered in order, keeping the mids of the offer
+    let sections = sdp.split("m=").skip(1).collect::<Vec<&str>>();
+    assert_eq!(sections.len(), 2);
+    assert!(sections[0].starts_with("audio 9 UDP/TLS/RTP/SAVPF "));
+    assert!(sections[0].contains("a=mid:0\r\n"));
+    assert!(sections[0].contains("a=sendonly\r\n"));
+    assert!(sections[0].contains(" opus/48000/2\r\n"));
+    assert!(sections[0].contains("a=ssrc:"));
+    assert!(sections[1].starts_with("video 9 UDP/TLS/RTP/SAVPF "));
+    assert!(sections[1].contains("a=mid:1\r\n"));
+    assert!(sections[1].contains(" H264/90000\r\n"));
+    assert!(sections[1].contains("packetization-mode=1"));
+    assert!(sections[1].contains("a=ssrc-group:FID "));
+}
+
+#[tokio::test]
+async fn unassigned_sections_are_rejected() {
+    let relay_server = fixture::relay_server().await;
+    let _vulcast = room(&relay_server, &[MediaKind::Audio]).await;
+    let token = web_client(&relay_server, "player");
+
+    let sdp = answer(&relay_server, token, OFFER).await;
+    let session = relay_server.get_session_by_token(&token).unwrap();
+    assert_eq!(session.get_resource_count(&ResourceType::Consumer), 1);
+
+    let sections = sdp.split("m=").skip(1).collect::<Vec<&str>>();
+    assert_eq!(sections.len(), 2);
+    assert!(sdp.contains("a=group:BUNDLE 0\r\n"));
+    assert!(sections[0].contains("a=sendonly\r\n"));
+    assert!(sections[1].starts_with("video 0 "));
+    assert!(sections[1].contains("a=mid:1\r\n"));
+    assert!(sections[1].contains("a=inactive\r\n"));
+}
+
+#[tokio::test]
+async fn malformed_offers_are_rejected() {
+    let relay_server = fixture::relay_server().await;
+    let _vulcast = room(&relay_server, &[MediaKind::Audio, MediaKind::Video]).await;
+    let token = web_client(&relay_server, "player");
+
+    let without_fingerprint = OFFER
+        .split_inclusive("\r\n")
+        .filter(|line| !line.starts_with("a=fingerprint:"))
+        .collect::<String>();
+    let with_application = format!(
+        "{}m=application 9 UDP/DTLS/SCTP webrtc-datachannel\r\n",
+        OFFER
+    );
+    let without_media = OFFER.split("m=").next().unwrap();
+    for sdp in [
+        without_fingerprint.as_str(),
+        with_application.as_str(),
+        without_media,
+    ] {
+        let response = offer(&relay_server, token, sdp).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+    // nothing is allocated for malformed offers
+    assert!(relay_server.get_session_by_token(&token).is_none());
+}
+
+#[tokio::test]
+async fn only_web_clients_may_offer() {
+    let relay_server = fixture::relay_server().await;
+    let _vulcast = room(&relay_server, &[MediaKind::Audio, MediaKind::Video]).await;
+    let host = relay_server
+        .register_session(
+            ForeignSessionId("host".into()),
+            SessionOptions::Host(ForeignRoomId("room".into())),
+        )
+        .unwrap();
+
+    let response = offer(&relay_server, host, OFFER).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(relay_server.get_session_by_token(&host).is_none());
+
+    let response = offer(&relay_server, SessionToken::new(), OFFER).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn offers_are_admitted_like_signal_connections() {
+    let relay_server = fixture::relay_server().await;
+    let _vulcast = room(&relay_server, &[MediaKind::Audio, MediaKind::Video]).await;
+    let token = web_client(&relay_server, "player");
+
+    // players present no client version
+    relay_server
+        .version_gate()
+        .set_min_version(Role::WebClient, Some("1.0.0".parse().unwrap()));
+    let response = offer(&relay_server, token, OFFER).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(relay_server.get_session_by_token(&token).is_none());
+    relay_server
+        .version_gate()
+        .set_min_version(Role::WebClient, None);
+
+    relay_server
+        .permissions()
+        .revoke(Role::WebClient, Permission::ConsumeMedia);
+    let response = offer(&relay_server, token, OFFER).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(relay_server.get_session_by_token(&token).is_none());
+}
+
+#[tokio::test]
+async fn offers_are_limited_by_resource_policy() {
+    let relay_server = fixture::relay_server().await;
+    let _vulcast = room(&relay_server, &[MediaKind::Audio, MediaKind::Video]).await;
+    let token = web_client(&relay_server, "player");
+    let resource_policy = ResourcePolicy::new();
+
+    // sections beyond the consumer limit are rejected
+    resource_policy.set_limit(
+        LimitScope::Role(Role::WebClient),
+        ResourceType::Consumer,
+        Some(1),
+    );
+    let response = offer_with_policy(&relay_server, &resource_policy, token, OFFER).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let sdp = String::from_utf8(response.body().to_vec()).unwrap();
+    let session = relay_server.get_session_by_token(&token).unwrap();
+    assert_eq!(session.get_resource_count(&ResourceType::Consumer), 1);
+    assert!(sdp.contains("a=group:BUNDLE 0\r\n"));
+    drop(session);
+    drop(relay_server.disconnect_session_by_token(&token));
+
+    // the session is disconnected if the offer cannot be answered
+    resource_policy.set_limit(
+        LimitScope::Role(Role::WebClient),
+        ResourceType::WebrtcTransport,
+        Some(0),
+    );
+    let response = offer_with_policy(&relay_server, &resource_policy, token, OFFER).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(relay_server.get_session_by_token(&token).is_none());
+}
+
+#[tokio::test]
+async fn session_is_disconnected_once_transport_closes() {
+    let relay_server = fixture::relay_server().await;
+    let _vulcast = room(&relay_server, &[MediaKind::Audio, MediaKind::Video]).await;
+    let token = web_client(&relay_server, "player");
+
+    answer(&relay_server, token, OFFER).await;
+    let session = relay_server.get_session_by_token(&token).unwrap();
+    let transport = session.get_webrtc_transports().pop().unwrap();
+    session.close_transport(transport.id()).unwrap();
+    drop(transport);
+    drop(session);
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(relay_server.get_session_by_token(&token).is_none());
+}
+
+#[tokio::test]
+async fn teardown_requires_owning_session() {
+    let relay_server = fixture::relay_server().await;
+    let (vulcast, _) = room(&relay_server, &[MediaKind::Audio, MediaKind::Video]).await;
+    let player = web_client(&relay_server, "player");
+    let other = web_client(&relay_server, "other");
+
+    let response = offer(&relay_server, player, OFFER).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let resource = response.headers()["location"].to_str().unwrap();
+    let session = relay_server.get_session_by_token(&player).unwrap();
+    assert_eq!(resource, format!("/whep/{}", session.id()));
+    let _other_session = relay_server.session_from_token(other).unwrap();
+
+    // the resource of another session, or of no session, is not torn down
+    assert_eq!(
+        teardown(&relay_server, other, resource).await,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        teardown(&relay_server, player, &format!("/whep/{}", vulcast.id())).await,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        teardown(&relay_server, player, "/whep/unknown").await,
+        StatusCode::NOT_FOUND
+    );
+    assert!(relay_server.get_session_by_token(&other).is_some());
+    assert!(relay_server.get_session_by_token(&player).is_some());
+
+    assert_eq!(
+        teardown(&relay_server, player, resource).await,
+        StatusCode::OK
+    );
+    assert!(relay_server.get_session_by_token(&player).is_none());
+    assert!(relay_server.get_session_by_token(&other).is_some());
+}
+
+#[tokio::test]
+async fn only_web_clients_may_teardown() {
+    let relay_server = fixture::relay_server().await;
+    let (vulcast, vulcast_token) = room(&relay_server, &[MediaKind::Audio, MediaKind::Video]).await;
+
+    assert_eq!(
+        teardown(
+            &relay_server,
+            vulcast_token,
+            &format!("/whep/{}", vulcast.id())
+        )
+        .await,
+        StatusCode::FORBIDDEN
+    );
+    assert!(relay_server.get_session_by_token(&vulcast_token).is_some());
+    assert_eq!(
+        teardown(
+            &relay_server,
+            SessionToken::new(),
+            &format!("/whep/{}", vulcast.id())
+        )
+        .await,
+        StatusCode::UNAUTHORIZED
+    );
+}
@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use vulcan_relay::subsystems::Subsystem;
+
+pub mod fixture;
+
+#[tokio::test]
+async fn overloaded_worker_sheds_requests() {
+    let relay_server = fixture::relay_server().await;
+    let worker_monitor = relay_server.worker_monitor();
+
+    // never overloaded without a threshold
+    assert!(worker_monitor.latency().is_none());
+    worker_monitor.sample().await.unwrap();
+    assert!(worker_monitor.latency().is_some());
+    assert!(worker_monitor.check().is_ok());
+
+    worker_monitor.set_threshold(Some(Duration::from_millis(50)));
+    worker_monitor.record(Duration::from_secs(10));
+    assert!(worker_monitor.overloaded());
+    assert!(worker_monitor.check().is_err());
+
+    worker_monitor.set_threshold(None);
+    assert!(worker_monitor.check().is_ok());
+}
+
+#[tokio::test]
+async fn disabled_sampling_clears_stale_overload() {
+    let relay_server = fixture::relay_server().await;
+    let worker_monitor = relay_server.worker_monitor();
+    let subsystems = relay_server.subsystems();
+
+    worker_monitor.set_threshold(Some(Duration::from_millis(50)));
+    worker_monitor.record(Duration::from_secs(10));
+    assert!(worker_monitor.overloaded());
+
+    subsystems.set_enabled(Subsystem::WorkerSampling, false);
+    worker_monitor.spawn(Duration::from_millis(10), subsystems);
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while worker_monitor.overloaded() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert!(worker_monitor.latency().is_none());
+    assert!(worker_monitor.check().is_ok());
+}